@@ -0,0 +1,673 @@
+//! Outbound notification channels: SMTP, Telegram, Discord, Slack and signed
+//! JSON webhooks.
+//!
+//! The config in `alerts.channel_config` only holds plain settings and refers
+//! to credentials by secret id. SMTP passwords, bot tokens, webhook URLs and
+//! signing keys stay in `secrets` and are read when a notification is sent.
+//! A channel only accepts secrets stored with its own provider (`smtp`,
+//! `telegram`, ...), so a config can never send an API key to a mail server.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use sha2::Sha256;
+use tauri_plugin_http::reqwest::{header, Url};
+use zeroize::Zeroizing;
+
+use super::{AlertChannel, AlertEvent, AlertEventKind};
+use crate::error::{Error, Result};
+use crate::{http, secrets};
+
+const TELEGRAM_API: &str = "https://api.telegram.org";
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+pub const SIGNATURE_HEADER: &str = "X-Panoptic-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Panoptic-Timestamp";
+
+/// What every channel sends, also the body of generic webhooks.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+    pub alert: String,
+    pub kind: AlertEventKind,
+    pub value: Option<f64>,
+    pub created_at: i64,
+}
+
+impl Notification {
+    pub fn for_event(alert: &str, event: &AlertEvent) -> Self {
+        let title = match event.kind {
+            AlertEventKind::Firing => format!("Alert: {alert}"),
+            AlertEventKind::Resolved => format!("Entwarnung: {alert}"),
+        };
+        Self {
+            title,
+            message: event.message.clone(),
+            alert: alert.to_owned(),
+            kind: event.kind,
+            value: event.value,
+            created_at: event.created_at,
+        }
+    }
+
+    pub fn test() -> Self {
+        Self {
+            title: "Panoptic: Testbenachrichtigung".into(),
+            message: "Dieser Kanal ist richtig eingerichtet.".into(),
+            alert: "Test".into(),
+            kind: AlertEventKind::Firing,
+            value: None,
+            created_at: Utc::now().timestamp(),
+        }
+    }
+}
+
+pub trait NotificationChannel {
+    async fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SmtpSecurity {
+    /// Plain connection upgraded with STARTTLS, usually port 587.
+    #[default]
+    Starttls,
+    /// Implicit TLS, usually port 465.
+    Tls,
+    /// No encryption, only for relays on the local machine or network.
+    None,
+}
+
+impl SmtpSecurity {
+    fn default_port(self) -> u16 {
+        match self {
+            Self::Starttls => 587,
+            Self::Tls => 465,
+            Self::None => 25,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EmailConfig {
+    host: String,
+    port: Option<u16>,
+    #[serde(default)]
+    security: SmtpSecurity,
+    username: Option<String>,
+    password_secret_id: Option<String>,
+    from: String,
+    to: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TelegramConfig {
+    bot_token_secret_id: String,
+    chat_id: String,
+}
+
+/// Discord and Slack: the webhook URL itself is the credential.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChatWebhookConfig {
+    webhook_secret_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookConfig {
+    url: String,
+    signing_secret_id: Option<String>,
+}
+
+pub struct Email {
+    host: String,
+    port: u16,
+    security: SmtpSecurity,
+    credentials: Option<Credentials>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+pub struct Telegram {
+    api_base: String,
+    token: Zeroizing<String>,
+    chat_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatFormat {
+    Discord,
+    Slack,
+}
+
+pub struct ChatWebhook {
+    format: ChatFormat,
+    url: Url,
+}
+
+pub struct Webhook {
+    url: Url,
+    signing_key: Option<Zeroizing<String>>,
+}
+
+/// A channel with its credentials loaded.
+pub enum Outbound {
+    Email(Email),
+    Telegram(Telegram),
+    Chat(ChatWebhook),
+    Webhook(Webhook),
+}
+
+fn invalid_config(e: impl std::fmt::Display) -> Error {
+    Error::InvalidInput(format!("Ungültige Kanal-Konfiguration: {e}"))
+}
+
+fn parse_config<T: for<'de> Deserialize<'de>>(config: Option<&JsonValue>) -> Result<T> {
+    let config = config.ok_or_else(|| invalid_config("fehlt"))?;
+    serde_json::from_value(config.clone()).map_err(invalid_config)
+}
+
+/// Reads the secret `id`, which must be stored with `provider`.
+fn secret_value(conn: &Connection, id: &str, provider: &str) -> Result<Zeroizing<String>> {
+    let secret = secrets::use_secret(conn, id)?
+        .ok_or_else(|| Error::InvalidInput("Das Secret des Kanals wurde nicht gefunden".into()))?;
+    if !secret.provider.eq_ignore_ascii_case(provider) {
+        return Err(invalid_config(format!(
+            "'{}' ist kein Secret mit Provider „{provider}“",
+            secret.name
+        )));
+    }
+    Ok(Zeroizing::new(secret.value))
+}
+
+/// Parses a webhook URL. Plain HTTP is only allowed for the local machine.
+fn webhook_url(raw: &str) -> Result<Url> {
+    let url = Url::parse(raw.trim()).map_err(|_| invalid_config("ungültige URL"))?;
+    let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    match url.scheme() {
+        "https" => Ok(url),
+        "http" if loopback => Ok(url),
+        _ => Err(invalid_config("die URL muss mit https:// beginnen")),
+    }
+}
+
+fn mailbox(address: &str) -> Result<Mailbox> {
+    address
+        .trim()
+        .parse()
+        .map_err(|_| invalid_config(format!("ungültige Adresse '{address}'")))
+}
+
+impl Outbound {
+    /// Loads the channel of a rule, `None` for desktop notifications.
+    pub fn resolve(
+        conn: &Connection,
+        channel: AlertChannel,
+        config: Option<&JsonValue>,
+    ) -> Result<Option<Self>> {
+        let outbound = match channel {
+            AlertChannel::System => return Ok(None),
+            AlertChannel::Email => {
+                let config: EmailConfig = parse_config(config)?;
+                if config.to.is_empty() {
+                    return Err(invalid_config("kein Empfänger"));
+                }
+                let credentials = match (config.username, config.password_secret_id) {
+                    (Some(user), Some(secret_id)) => Some(Credentials::new(
+                        user,
+                        secret_value(conn, &secret_id, "smtp")?.to_string(),
+                    )),
+                    _ => None,
+                };
+                Self::Email(Email {
+                    port: config
+                        .port
+                        .unwrap_or_else(|| config.security.default_port()),
+                    host: config.host,
+                    security: config.security,
+                    credentials,
+                    from: mailbox(&config.from)?,
+                    to: config
+                        .to
+                        .iter()
+                        .map(|to| mailbox(to))
+                        .collect::<Result<_>>()?,
+                })
+            }
+            AlertChannel::Telegram => {
+                let config: TelegramConfig = parse_config(config)?;
+                Self::Telegram(Telegram {
+                    api_base: TELEGRAM_API.into(),
+                    token: secret_value(conn, &config.bot_token_secret_id, "telegram")?,
+                    chat_id: config.chat_id,
+                })
+            }
+            AlertChannel::Discord | AlertChannel::Slack => {
+                let config: ChatWebhookConfig = parse_config(config)?;
+                let (format, provider) = if channel == AlertChannel::Discord {
+                    (ChatFormat::Discord, "discord")
+                } else {
+                    (ChatFormat::Slack, "slack")
+                };
+                let url = secret_value(conn, &config.webhook_secret_id, provider)?;
+                Self::Chat(ChatWebhook {
+                    format,
+                    url: webhook_url(&url)?,
+                })
+            }
+            AlertChannel::Webhook => {
+                let config: WebhookConfig = parse_config(config)?;
+                Self::Webhook(Webhook {
+                    url: webhook_url(&config.url)?,
+                    signing_key: config
+                        .signing_secret_id
+                        .map(|id| secret_value(conn, &id, "webhook"))
+                        .transpose()?,
+                })
+            }
+        };
+        Ok(Some(outbound))
+    }
+}
+
+impl NotificationChannel for Outbound {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        match self {
+            Self::Email(email) => email.send(notification).await,
+            Self::Telegram(telegram) => telegram.send(notification).await,
+            Self::Chat(chat) => chat.send(notification).await,
+            Self::Webhook(webhook) => webhook.send(notification).await,
+        }
+    }
+}
+
+impl NotificationChannel for Email {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let failed = |e: &dyn std::fmt::Display| Error::Notification(e.to_string());
+        let builder = match self.security {
+            SmtpSecurity::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)
+                    .map_err(|e| failed(&e))?
+            }
+            SmtpSecurity::Tls => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host).map_err(|e| failed(&e))?
+            }
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host)
+            }
+        };
+        let mut builder = builder.port(self.port).timeout(Some(SMTP_TIMEOUT));
+        if let Some(credentials) = &self.credentials {
+            builder = builder.credentials(credentials.clone());
+        }
+
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(&notification.title)
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message
+            .body(notification.message.clone())
+            .map_err(|e| failed(&e))?;
+        builder
+            .build()
+            .send(message)
+            .await
+            .map_err(|e| failed(&e))?;
+        Ok(())
+    }
+}
+
+/// Posts `body` as JSON. Webhook URLs and bot tokens are credentials, so
+/// they are stripped from network errors.
+async fn post_json(url: &Url, body: String, headers: &[(&str, String)]) -> Result<String> {
+    let response = http::send(url, false, |client| {
+        let mut request = client
+            .post(url.clone())
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
+        request
+    })
+    .await
+    .map_err(|e| match e {
+        Error::Network(e) => Error::Network(e.without_url()),
+        e => e,
+    })?;
+    let status = response.status();
+    let text = response.text().await.map_err(|e| e.without_url())?;
+    if status.is_success() {
+        Ok(text)
+    } else {
+        Err(Error::from_status(
+            status.as_u16(),
+            error_message(&text),
+            None,
+        ))
+    }
+}
+
+/// The error text of a response: Telegram sends `description`, Discord
+/// `message` and Slack plain text.
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<JsonValue>(body)
+        .ok()
+        .and_then(|value| {
+            ["description", "message"]
+                .iter()
+                .find_map(|field| value[field].as_str().map(str::to_owned))
+        })
+        .unwrap_or_else(|| body.trim().chars().take(200).collect())
+}
+
+impl NotificationChannel for Telegram {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let url = Url::parse(&format!(
+            "{}/bot{}/sendMessage",
+            self.api_base,
+            self.token.as_str()
+        ))
+        .map_err(|_| invalid_config("ungültiger Bot-Token"))?;
+        let body = json!({
+            "chat_id": self.chat_id,
+            "text": format!("{}\n{}", notification.title, notification.message),
+        });
+        post_json(&url, body.to_string(), &[]).await?;
+        Ok(())
+    }
+}
+
+impl NotificationChannel for ChatWebhook {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let body = match self.format {
+            ChatFormat::Discord => json!({
+                "content": format!("**{}**\n{}", notification.title, notification.message),
+            }),
+            ChatFormat::Slack => json!({
+                "text": format!("*{}*\n{}", notification.title, notification.message),
+            }),
+        };
+        post_json(&self.url, body.to_string(), &[]).await?;
+        Ok(())
+    }
+}
+
+/// `sha256=<hex>` of the HMAC-SHA256 over `<timestamp>.<body>`.
+pub fn signature(key: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+impl NotificationChannel for Webhook {
+    async fn send(&self, notification: &Notification) -> Result<()> {
+        let body = serde_json::to_string(notification).expect("serializable notification");
+        let timestamp = Utc::now().timestamp();
+        let mut headers = vec![(TIMESTAMP_HEADER, timestamp.to_string())];
+        if let Some(key) = &self.signing_key {
+            headers.push((SIGNATURE_HEADER, signature(key, timestamp, &body)));
+        }
+        post_json(&self.url, body, &headers).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    use super::*;
+
+    struct Captured {
+        head: String,
+        body: String,
+    }
+
+    impl Captured {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.head.lines().find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.eq_ignore_ascii_case(name).then(|| value.trim())
+            })
+        }
+    }
+
+    /// Answers one HTTP request with `status` and returns what it received.
+    async fn http_stand_in(
+        status: &'static str,
+        response: &'static str,
+    ) -> (Url, JoinHandle<Captured>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                head.push_str(&line);
+            }
+            let captured = Captured {
+                head,
+                body: String::new(),
+            };
+            let length: usize = captured
+                .header("content-length")
+                .map_or(0, |v| v.parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).await.unwrap();
+            let reply = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            );
+            reader
+                .into_inner()
+                .write_all(reply.as_bytes())
+                .await
+                .unwrap();
+            Captured {
+                body: String::from_utf8(body).unwrap(),
+                ..captured
+            }
+        });
+        (url, handle)
+    }
+
+    /// A minimal SMTP server without TLS that accepts one message and
+    /// returns its DATA section.
+    async fn smtp_stand_in() -> (u16, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            write.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+            let mut data = String::new();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250-localhost\r\n250 8BITMIME\r\n"
+                } else if command.starts_with("DATA") {
+                    write.write_all(b"354 go ahead\r\n").await.unwrap();
+                    while let Some(line) = lines.next_line().await.unwrap() {
+                        if line == "." {
+                            break;
+                        }
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    b"250 queued\r\n"
+                } else if command.starts_with("QUIT") {
+                    write.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                write.write_all(reply).await.unwrap();
+            }
+            data
+        });
+        (port, handle)
+    }
+
+    fn notification() -> Notification {
+        Notification {
+            title: "Alert: Tageslimit".into(),
+            message: "OpenAI: $12.00 Kosten heute (Grenze $10.00)".into(),
+            alert: "Tageslimit".into(),
+            kind: AlertEventKind::Firing,
+            value: Some(12.0),
+            created_at: 1_741_600_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn webhooks_are_signed_with_hmac() {
+        let (url, server) = http_stand_in("200 OK", "").await;
+        let webhook = Webhook {
+            url,
+            signing_key: Some(Zeroizing::new("geheim".into())),
+        };
+        webhook.send(&notification()).await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.head.starts_with("POST /hook "));
+        let timestamp: i64 = request.header(TIMESTAMP_HEADER).unwrap().parse().unwrap();
+        assert_eq!(
+            request.header(SIGNATURE_HEADER),
+            Some(signature("geheim", timestamp, &request.body).as_str())
+        );
+        let body: JsonValue = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["alert"], "Tageslimit");
+        assert_eq!(body["kind"], "firing");
+        assert_eq!(body["value"], 12.0);
+    }
+
+    #[tokio::test]
+    async fn telegram_posts_to_the_bot_api() {
+        let (url, server) = http_stand_in("200 OK", r#"{"ok":true}"#).await;
+        let telegram = Telegram {
+            api_base: url.origin().ascii_serialization(),
+            token: Zeroizing::new("123:abc".into()),
+            chat_id: "-1001".into(),
+        };
+        telegram.send(&notification()).await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.head.starts_with("POST /bot123:abc/sendMessage "));
+        let body: JsonValue = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(body["chat_id"], "-1001");
+        assert!(body["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Alert: Tageslimit\n"));
+    }
+
+    #[tokio::test]
+    async fn chat_webhooks_use_the_service_format_and_report_errors() {
+        let (url, server) = http_stand_in("200 OK", "ok").await;
+        let slack = ChatWebhook {
+            format: ChatFormat::Slack,
+            url,
+        };
+        slack.send(&notification()).await.unwrap();
+        let body: JsonValue = serde_json::from_str(&server.await.unwrap().body).unwrap();
+        assert!(body["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("*Alert: Tageslimit*"));
+
+        let (url, server) = http_stand_in(
+            "401 Unauthorized",
+            r#"{"message": "Invalid Webhook Token"}"#,
+        )
+        .await;
+        let discord = ChatWebhook {
+            format: ChatFormat::Discord,
+            url,
+        };
+        let error = discord.send(&notification()).await.unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(ref m) if m == "Invalid Webhook Token"));
+        let body: JsonValue = serde_json::from_str(&server.await.unwrap().body).unwrap();
+        assert!(body["content"]
+            .as_str()
+            .unwrap()
+            .starts_with("**Alert: Tageslimit**"));
+    }
+
+    #[tokio::test]
+    async fn email_is_delivered_over_smtp() {
+        let (port, server) = smtp_stand_in().await;
+        let email = Email {
+            host: "127.0.0.1".into(),
+            port,
+            security: SmtpSecurity::None,
+            credentials: None,
+            from: mailbox("Panoptic <alerts@example.com>").unwrap(),
+            to: vec![mailbox("ops@example.com").unwrap()],
+        };
+        email.send(&notification()).await.unwrap();
+
+        let data = server.await.unwrap();
+        assert!(data.contains("Subject: Alert: Tageslimit"));
+        assert!(data.contains("To: ops@example.com"));
+        assert!(data.contains("OpenAI: $12.00 Kosten heute"));
+    }
+
+    #[test]
+    fn webhook_urls_need_https_outside_localhost() {
+        assert!(webhook_url("https://hooks.slack.com/services/x").is_ok());
+        assert!(webhook_url("http://127.0.0.1:8080/hook").is_ok());
+        assert!(webhook_url("http://example.com/hook").is_err());
+        assert!(webhook_url("ftp://example.com").is_err());
+    }
+
+    #[test]
+    fn channels_only_read_secrets_of_their_provider() {
+        let key = crate::audit::ChainKey::derive("test");
+        let conn = crate::db::open_in_memory(&key);
+        let api_key =
+            secrets::insert(&conn, &key, "OpenAI", "llm", "openai", "sk-admin-1").unwrap();
+        let password = secrets::insert(&conn, &key, "Mail", "app", "smtp", "geheim").unwrap();
+        let email = |secret_id: &str| {
+            json!({
+                "host": "mail.example.com",
+                "username": "alerts",
+                "passwordSecretId": secret_id,
+                "from": "alerts@example.com",
+                "to": ["ops@example.com"],
+            })
+        };
+
+        let stolen = Outbound::resolve(&conn, AlertChannel::Email, Some(&email(&api_key)));
+        assert!(matches!(stolen, Err(Error::InvalidInput(_))));
+        let resolved = Outbound::resolve(&conn, AlertChannel::Email, Some(&email(&password)));
+        assert!(matches!(resolved, Ok(Some(Outbound::Email(_)))));
+        let telegram = json!({ "botTokenSecretId": password, "chatId": "1" });
+        assert!(Outbound::resolve(&conn, AlertChannel::Telegram, Some(&telegram)).is_err());
+    }
+}
@@ -0,0 +1,567 @@
+//! Background evaluation of the alert rules.
+//!
+//! Every [`CHECK_INTERVAL`] the enabled rules are evaluated against the usage
+//! summary, which refreshes open days in `usage_cache` and reports the last
+//! fetch error of each account from `sync_errors`, even while no fetch was
+//! due. The plan limits of the infrastructure services and the DNS
+//! records of zones with a snapshot are only fetched while a rule watches
+//! them. Nothing is checked while the vault is
+//! locked.
+
+use std::time::Duration;
+
+use chrono::{NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use super::channels::{Notification, NotificationChannel, Outbound};
+use super::{
+    insert_event, load_rule, load_rules, save_state, AlertChannel, AlertEvent, AlertEventKind,
+    AlertKind, AlertPeriod, AlertRule, AlertState,
+};
+use crate::audit;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::infra::cloudflare::{self, ZoneDrift};
+use crate::infra::{self, LimitUsage, Service};
+use crate::providers::usage::{self, DateRange, UsageSummary, SUMMARY_DAYS, WEEK_DAYS};
+use crate::providers::Provider;
+use crate::vault::VaultState;
+
+pub const ALERT_EVENT: &str = "alert-event";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Serializes evaluations, so a manual check never overlaps the scheduled one.
+#[derive(Default)]
+pub struct AlertEngine {
+    running: tokio::sync::Mutex<()>,
+}
+
+/// The value a rule watches and a description of it for notifications.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    pub value: f64,
+    pub message: String,
+}
+
+impl Observation {
+    pub fn is_firing(&self, rule: &AlertRule) -> bool {
+        match rule.kind {
+            AlertKind::Status | AlertKind::Dns => self.value > 0.0,
+            _ => rule.threshold.is_some_and(|t| self.value >= t),
+        }
+    }
+}
+
+/// The event an evaluation causes, if the rule changes its state.
+pub fn transition(current: AlertState, firing: bool) -> Option<AlertEventKind> {
+    match (current, firing) {
+        (AlertState::Ok, true) => Some(AlertEventKind::Firing),
+        (AlertState::Firing, false) => Some(AlertEventKind::Resolved),
+        _ => None,
+    }
+}
+
+fn period_label(period: AlertPeriod) -> &'static str {
+    match period {
+        AlertPeriod::Day => "heute",
+        AlertPeriod::Week => "in 7 Tagen",
+        AlertPeriod::Month => "in 30 Tagen",
+    }
+}
+
+fn scope_label(provider: Option<Provider>) -> &'static str {
+    provider.map_or("Alle Provider", Provider::label)
+}
+
+/// What the infrastructure rules are evaluated against.
+#[derive(Debug, Default)]
+pub struct InfraState {
+    pub limits: Vec<LimitUsage>,
+    /// Only zones with changes.
+    pub dns: Vec<ZoneDrift>,
+}
+
+/// Evaluates `rule` against `summary`, or `infra` for limit and DNS rules.
+pub fn observe(
+    rule: &AlertRule,
+    summary: &UsageSummary,
+    infra: &InfraState,
+    today: NaiveDate,
+) -> Observation {
+    let provider = rule.provider.as_deref().and_then(Provider::from_name);
+    let matches = |p: Provider| provider.is_none_or(|wanted| wanted == p);
+    let projects = summary.projects.iter().filter(|p| matches(p.provider));
+    let threshold = rule.threshold.unwrap_or_default();
+
+    match rule.kind {
+        AlertKind::Cost => {
+            let value: f64 = projects
+                .map(|p| match rule.period {
+                    AlertPeriod::Day => p.cost_today,
+                    AlertPeriod::Week => p.cost_week,
+                    AlertPeriod::Month => p.cost_month,
+                })
+                .sum();
+            Observation {
+                value,
+                message: format!(
+                    "{}: ${value:.2} Kosten {} (Grenze ${threshold:.2})",
+                    scope_label(provider),
+                    period_label(rule.period)
+                ),
+            }
+        }
+        AlertKind::Quota => {
+            let days = match rule.period {
+                AlertPeriod::Day => 1,
+                AlertPeriod::Week => WEEK_DAYS,
+                AlertPeriod::Month => SUMMARY_DAYS,
+            };
+            let window = DateRange::last_days(today, days);
+            let value = projects
+                .flat_map(|p| &p.daily_usage)
+                .filter(|day| day.date.parse().is_ok_and(|date| window.contains(date)))
+                .map(|day| day.total_tokens)
+                .sum::<u64>() as f64;
+            Observation {
+                value,
+                message: format!(
+                    "{}: {value:.0} Tokens {} (Grenze {threshold:.0})",
+                    scope_label(provider),
+                    period_label(rule.period)
+                ),
+            }
+        }
+        AlertKind::Status => {
+            let failing: Vec<String> = summary
+                .providers
+                .iter()
+                .filter(|status| matches(status.provider))
+                .filter_map(|status| {
+                    let error = status.error.as_deref()?;
+                    Some(format!("{}: {error}", status.provider.label()))
+                })
+                .collect();
+            Observation {
+                value: failing.len() as f64,
+                message: if failing.is_empty() {
+                    format!("{}: alle Anfragen erfolgreich", scope_label(provider))
+                } else {
+                    failing.join("; ")
+                },
+            }
+        }
+        AlertKind::Limit => {
+            let service = rule.provider.as_deref().and_then(Service::from_name);
+            let highest = infra
+                .limits
+                .iter()
+                .filter(|limit| service.is_none_or(|wanted| wanted == limit.service))
+                .max_by(|a, b| a.percent().total_cmp(&b.percent()));
+            match highest {
+                Some(limit) => Observation {
+                    value: limit.percent(),
+                    message: format!("{} (Grenze {threshold:.0}%)", limit.describe()),
+                },
+                None => Observation {
+                    value: 0.0,
+                    message: format!(
+                        "{}: keine Plan-Limits bekannt",
+                        service.map_or("Alle Dienste", Service::label)
+                    ),
+                },
+            }
+        }
+        AlertKind::Dns => {
+            let service = rule.provider.as_deref().and_then(Service::from_name);
+            let zones: &[ZoneDrift] = if service.is_none_or(|s| s == Service::Cloudflare) {
+                &infra.dns
+            } else {
+                &[]
+            };
+            let changes: usize = zones.iter().map(|zone| zone.changes.len()).sum();
+            Observation {
+                value: changes as f64,
+                message: if zones.is_empty() {
+                    "DNS entspricht den gespeicherten Snapshots".into()
+                } else {
+                    zones
+                        .iter()
+                        .map(ZoneDrift::describe)
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                },
+            }
+        }
+    }
+}
+
+fn show_desktop(app: &AppHandle, notification: &Notification) -> Result<()> {
+    app.notification()
+        .builder()
+        .title(&notification.title)
+        .body(&notification.message)
+        .show()
+        .map_err(|e| Error::Notification(e.to_string()))
+}
+
+/// Sends `notification` through `channel`.
+async fn deliver(
+    app: &AppHandle,
+    channel: AlertChannel,
+    config: Option<&JsonValue>,
+    notification: &Notification,
+) -> Result<()> {
+    let outbound = app
+        .state::<Database>()
+        .with_conn(|conn| Outbound::resolve(conn, channel, config))?;
+    match outbound {
+        Some(outbound) => outbound.send(notification).await,
+        None => show_desktop(app, notification),
+    }
+}
+
+/// Delivers the event of `rule`. A failed delivery is reported on the
+/// desktop instead, so an alert is never lost silently.
+async fn notify(app: &AppHandle, rule: &AlertRule, event: &AlertEvent) {
+    let notification = Notification::for_event(&rule.name, event);
+    let Err(e) = deliver(
+        app,
+        rule.channel,
+        rule.channel_config.as_ref(),
+        &notification,
+    )
+    .await
+    else {
+        return;
+    };
+    eprintln!("Failed to deliver alert {}: {e}", rule.id);
+    let fallback = Notification {
+        message: format!("{}\n\nVersand fehlgeschlagen: {e}", notification.message),
+        ..notification
+    };
+    if let Err(e) = show_desktop(app, &fallback) {
+        eprintln!("Failed to show alert notification: {e}");
+    }
+}
+
+/// Evaluates all enabled rules once and returns the events it recorded.
+pub async fn check(app: &AppHandle) -> Result<Vec<AlertEvent>> {
+    let engine = app.state::<AlertEngine>();
+    let _running = engine.running.lock().await;
+    app.state::<VaultState>().ensure_unlocked()?;
+
+    let db = app.state::<Database>();
+    let rules: Vec<AlertRule> = db
+        .with_conn(load_rules)?
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .collect();
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let summary = usage::load_summary(&db).await?;
+    let watches = |kind: AlertKind| rules.iter().any(|rule| rule.kind == kind);
+    let mut services = InfraState::default();
+    if watches(AlertKind::Limit) {
+        services.limits = infra::collect_limits(&db).await;
+    }
+    if watches(AlertKind::Dns) {
+        services.dns = cloudflare::dns_drift(&db).await;
+    }
+    let now = Utc::now();
+    let today = now.date_naive();
+    let now = now.timestamp();
+
+    let mut events = Vec::new();
+    for rule in rules {
+        let observation = observe(&rule, &summary, &services, today);
+        let next = transition(rule.state, observation.is_firing(&rule));
+        let event = next.map(|kind| AlertEvent {
+            id: audit::new_id(),
+            alert_id: rule.id.clone(),
+            alert_name: Some(rule.name.clone()),
+            kind,
+            value: Some(observation.value),
+            message: observation.message.clone(),
+            created_at: now,
+        });
+        db.with_conn(|conn| {
+            save_state(
+                conn,
+                &rule.id,
+                next.map_or(rule.state, AlertEventKind::state),
+                observation.value,
+                now,
+            )?;
+            if let Some(event) = &event {
+                insert_event(conn, event)?;
+            }
+            Ok(())
+        })?;
+        if let Some(event) = event {
+            notify(app, &rule, &event).await;
+            if let Err(e) = app.emit(ALERT_EVENT, &event) {
+                eprintln!("Failed to emit {ALERT_EVENT}: {e}");
+            }
+            events.push(event);
+        }
+    }
+    Ok(events)
+}
+
+/// Starts the background task that evaluates the rules.
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if !app.state::<VaultState>().is_unlocked() {
+                continue;
+            }
+            if let Err(e) = check(&app).await {
+                eprintln!("Alert check failed: {e}");
+            }
+        }
+    });
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertCheck {
+    events: Vec<AlertEvent>,
+    checked_at: i64,
+}
+
+/// Evaluates the rules right away instead of waiting for the scheduler.
+#[tauri::command]
+pub async fn check_alerts_now(app: AppHandle, vault: State<'_, VaultState>) -> Result<AlertCheck> {
+    vault.ensure_unlocked()?;
+    Ok(AlertCheck {
+        events: check(&app).await?,
+        checked_at: Utc::now().timestamp(),
+    })
+}
+
+/// Sends a test notification through the saved channel of the rule `id`.
+#[tauri::command]
+pub async fn send_test_notification(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    id: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let rule = db.with_conn(|conn| load_rule(conn, &id))?;
+    deliver(
+        &app,
+        rule.channel,
+        rule.channel_config.as_ref(),
+        &Notification::test(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::cloudflare::{DnsChange, DnsChangeKind, DnsRecord};
+    use crate::infra::LimitMetric;
+    use crate::providers::usage::{
+        summarize, AccountData, CostRecord, ProviderStatus, UsageRecord,
+    };
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn rule(
+        kind: AlertKind,
+        provider: Option<&str>,
+        threshold: f64,
+        period: AlertPeriod,
+    ) -> AlertRule {
+        AlertRule {
+            id: "rule".into(),
+            name: "Test".into(),
+            kind,
+            provider: provider.map(str::to_owned),
+            threshold: Some(threshold),
+            period,
+            channel: AlertChannel::System,
+            channel_config: None,
+            enabled: true,
+            state: AlertState::Ok,
+            last_value: None,
+            last_checked_at: None,
+            created_at: 0,
+        }
+    }
+
+    fn account(provider: Provider, day: &str, tokens: u64, cost: f64) -> AccountData {
+        AccountData {
+            provider,
+            account_name: "Key".into(),
+            projects: vec![],
+            usage: vec![UsageRecord {
+                date: date(day),
+                project_id: None,
+                model: None,
+                input_tokens: tokens,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                requests: 1,
+                batch: false,
+            }],
+            costs: vec![CostRecord {
+                date: date(day),
+                project_id: None,
+                amount_usd: cost,
+                estimated: false,
+            }],
+        }
+    }
+
+    fn status(provider: Provider, error: Option<&str>) -> ProviderStatus {
+        ProviderStatus {
+            provider,
+            keys: 1,
+            error: error.map(str::to_owned),
+            error_kind: None,
+            last_synced_at: None,
+        }
+    }
+
+    #[test]
+    fn cost_and_quota_rules_respect_provider_and_period() {
+        let today = date("2025-03-10");
+        let summary = summarize(
+            today,
+            &[
+                account(Provider::OpenAi, "2025-03-10", 1_000, 4.0),
+                account(Provider::OpenAi, "2025-03-01", 5_000, 20.0),
+                account(Provider::Anthropic, "2025-03-10", 2_000, 3.0),
+            ],
+            vec![],
+        );
+
+        let day = rule(AlertKind::Cost, Some("openai"), 5.0, AlertPeriod::Day);
+        let observation = observe(&day, &summary, &InfraState::default(), today);
+        assert_eq!(observation.value, 4.0);
+        assert!(!observation.is_firing(&day));
+
+        let month = rule(AlertKind::Cost, None, 25.0, AlertPeriod::Month);
+        let observation = observe(&month, &summary, &InfraState::default(), today);
+        assert_eq!(observation.value, 27.0);
+        assert!(observation.is_firing(&month));
+
+        let tokens = rule(AlertKind::Quota, Some("openai"), 1_000.0, AlertPeriod::Week);
+        let observation = observe(&tokens, &summary, &InfraState::default(), today);
+        assert_eq!(observation.value, 1_000.0);
+        assert!(observation.is_firing(&tokens));
+    }
+
+    #[test]
+    fn status_rules_fire_on_failing_providers() {
+        let today = date("2025-03-10");
+        let summary = summarize(
+            today,
+            &[],
+            vec![
+                status(Provider::OpenAi, None),
+                status(Provider::Anthropic, Some("Rate-Limit erreicht")),
+            ],
+        );
+        let all = rule(AlertKind::Status, None, 0.0, AlertPeriod::Day);
+        let observation = observe(&all, &summary, &InfraState::default(), today);
+        assert!(observation.is_firing(&all));
+        assert_eq!(observation.message, "Anthropic: Rate-Limit erreicht");
+
+        let openai = rule(AlertKind::Status, Some("openai"), 0.0, AlertPeriod::Day);
+        assert!(!observe(&openai, &summary, &InfraState::default(), today).is_firing(&openai));
+    }
+
+    #[test]
+    fn limit_rules_watch_the_highest_share() {
+        let today = date("2025-03-10");
+        let summary = summarize(today, &[], vec![]);
+        let limit = |resource: &str, used: f64| LimitUsage {
+            service: Service::Neon,
+            resource: resource.into(),
+            metric: LimitMetric::Storage,
+            used,
+            limit: 100.0,
+        };
+        let infra = InfraState {
+            limits: vec![limit("shop/main", 84.0), limit("blog/main", 12.0)],
+            dns: vec![],
+        };
+
+        let storage = rule(AlertKind::Limit, Some("neondb"), 80.0, AlertPeriod::Day);
+        let observation = observe(&storage, &summary, &infra, today);
+        assert_eq!(observation.value, 84.0);
+        assert!(observation.is_firing(&storage));
+        assert!(observation
+            .message
+            .starts_with("NeonDB · shop/main: Storage"));
+
+        let observation = observe(&storage, &summary, &InfraState::default(), today);
+        assert!(!observation.is_firing(&storage));
+        assert_eq!(observation.message, "NeonDB: keine Plan-Limits bekannt");
+    }
+
+    #[test]
+    fn dns_rules_fire_on_changed_zones() {
+        let today = date("2025-03-10");
+        let summary = summarize(today, &[], vec![]);
+        let record = DnsRecord {
+            id: "1".into(),
+            record_type: "A".into(),
+            name: "shop.example".into(),
+            content: "192.0.2.1".into(),
+            proxied: false,
+            ttl: 1,
+            priority: None,
+            comment: None,
+        };
+        let infra = InfraState {
+            limits: vec![],
+            dns: vec![ZoneDrift {
+                zone_name: "shop.example".into(),
+                changes: vec![DnsChange {
+                    kind: DnsChangeKind::Removed,
+                    before: Some(record),
+                    after: None,
+                }],
+            }],
+        };
+
+        let dns = rule(AlertKind::Dns, None, 0.0, AlertPeriod::Day);
+        let observation = observe(&dns, &summary, &infra, today);
+        assert!(observation.is_firing(&dns));
+        assert_eq!(
+            observation.message,
+            "shop.example: 1 DNS-Änderungen (entfernt A shop.example 192.0.2.1)"
+        );
+        assert!(!observe(&dns, &summary, &InfraState::default(), today).is_firing(&dns));
+    }
+
+    #[test]
+    fn only_state_changes_produce_events() {
+        assert_eq!(
+            transition(AlertState::Ok, true),
+            Some(AlertEventKind::Firing)
+        );
+        assert_eq!(transition(AlertState::Firing, true), None);
+        assert_eq!(
+            transition(AlertState::Firing, false),
+            Some(AlertEventKind::Resolved)
+        );
+        assert_eq!(transition(AlertState::Ok, false), None);
+    }
+}
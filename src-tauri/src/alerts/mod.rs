@@ -0,0 +1,418 @@
+//! Alert rules stored in `alerts` and their history in `alert_events`.
+//!
+//! A rule watches one value (costs, tokens, the provider status, the plan
+//! limits of an infrastructure service or DNS changes) and fires
+//! once it reaches its threshold. [`engine`] evaluates the enabled rules in
+//! the background, records every change between `ok` and `firing` as an
+//! event and sends a notification for it.
+
+pub mod channels;
+pub mod engine;
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tauri::State;
+
+use self::channels::Outbound;
+use crate::audit;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::infra::Service;
+use crate::providers::Provider;
+use crate::vault::VaultState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertKind {
+    /// Costs in USD within the period.
+    Cost,
+    /// Input and output tokens within the period.
+    Quota,
+    /// Providers whose last request failed; the threshold is not used.
+    Status,
+    /// Highest share of a plan limit of an infrastructure service in percent,
+    /// e.g. the storage of a NeonDB branch; the period is not used.
+    Limit,
+    /// DNS records that differ from the saved snapshot of their zone; the
+    /// threshold and period are not used.
+    Dns,
+}
+
+/// Time window of cost and quota rules, matching the Costs page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertPeriod {
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertChannel {
+    /// Desktop notification through `tauri-plugin-notification`.
+    System,
+    Email,
+    Telegram,
+    Discord,
+    Slack,
+    /// JSON POST, signed with HMAC-SHA256 when a signing secret is set.
+    Webhook,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertState {
+    Ok,
+    Firing,
+}
+
+/// What an event records: a rule started or stopped firing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertEventKind {
+    Firing,
+    Resolved,
+}
+
+impl AlertEventKind {
+    /// The rule state after the event.
+    pub fn state(self) -> AlertState {
+        match self {
+            Self::Firing => AlertState::Firing,
+            Self::Resolved => AlertState::Ok,
+        }
+    }
+}
+
+macro_rules! sql_enum {
+    ($ty:ty { $($variant:ident => $name:literal),+ $(,)? }) => {
+        impl $ty {
+            fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)+
+                }
+            }
+
+            fn parse(value: &str) -> Option<Self> {
+                match value {
+                    $($name => Some(Self::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+sql_enum!(AlertKind {
+    Cost => "cost",
+    Quota => "quota",
+    Status => "status",
+    Limit => "limit",
+    Dns => "dns",
+});
+sql_enum!(AlertPeriod { Day => "day", Week => "week", Month => "month" });
+sql_enum!(AlertChannel {
+    System => "system",
+    Email => "email",
+    Telegram => "telegram",
+    Discord => "discord",
+    Slack => "slack",
+    Webhook => "webhook",
+});
+sql_enum!(AlertState { Ok => "ok", Firing => "firing" });
+sql_enum!(AlertEventKind { Firing => "firing", Resolved => "resolved" });
+
+/// A rule as edited in the UI.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleInput {
+    pub name: String,
+    pub kind: AlertKind,
+    /// Provider id like `openai`, or a service id like `neondb` for limit
+    /// and DNS rules; `None` watches all of them.
+    pub provider: Option<String>,
+    pub threshold: Option<f64>,
+    pub period: AlertPeriod,
+    pub channel: AlertChannel,
+    pub channel_config: Option<JsonValue>,
+    pub enabled: bool,
+}
+
+impl AlertRuleInput {
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(Error::InvalidInput("Der Name darf nicht leer sein".into()));
+        }
+        if let Some(provider) = &self.provider {
+            let known = match self.kind {
+                AlertKind::Limit | AlertKind::Dns => Service::from_name(provider).is_some(),
+                _ => Provider::from_name(provider).is_some(),
+            };
+            if !known {
+                return Err(Error::InvalidInput(format!(
+                    "Unbekannter Provider '{provider}'"
+                )));
+            }
+        }
+        match (self.kind, self.threshold) {
+            (AlertKind::Status | AlertKind::Dns, _) => Ok(()),
+            (_, Some(threshold)) if threshold.is_finite() && threshold > 0.0 => Ok(()),
+            _ => Err(Error::InvalidInput(
+                "Der Schwellwert muss größer als 0 sein".into(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRule {
+    pub id: String,
+    pub name: String,
+    pub kind: AlertKind,
+    pub provider: Option<String>,
+    pub threshold: Option<f64>,
+    pub period: AlertPeriod,
+    pub channel: AlertChannel,
+    pub channel_config: Option<JsonValue>,
+    pub enabled: bool,
+    pub state: AlertState,
+    /// Value of the last evaluation.
+    pub last_value: Option<f64>,
+    pub last_checked_at: Option<i64>,
+    pub created_at: i64,
+}
+
+const RULE_COLUMNS: &str = "id, name, type, provider_id, threshold, period, channel,
+    channel_config, enabled, state, last_value, last_checked_at, created_at";
+
+/// Rows with unknown enum values (e.g. written by the old frontend) are
+/// skipped instead of failing the whole list.
+fn rule_from_row(row: &Row<'_>) -> rusqlite::Result<Option<AlertRule>> {
+    let kind: Option<String> = row.get(2)?;
+    let period: String = row.get(5)?;
+    let channel: Option<String> = row.get(6)?;
+    let state: String = row.get(9)?;
+    let (Some(kind), Some(period), Some(channel), Some(state)) = (
+        kind.as_deref().and_then(AlertKind::parse),
+        AlertPeriod::parse(&period),
+        AlertChannel::parse(channel.as_deref().unwrap_or("system")),
+        AlertState::parse(&state),
+    ) else {
+        return Ok(None);
+    };
+    let channel_config: Option<String> = row.get(7)?;
+    Ok(Some(AlertRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        kind,
+        provider: row.get(3)?,
+        threshold: row.get(4)?,
+        period,
+        channel,
+        channel_config: channel_config.and_then(|c| serde_json::from_str(&c).ok()),
+        enabled: row.get::<_, Option<bool>>(8)?.unwrap_or(true),
+        state,
+        last_value: row.get(10)?,
+        last_checked_at: row.get(11)?,
+        created_at: row.get::<_, Option<i64>>(12)?.unwrap_or_default(),
+    }))
+}
+
+pub fn load_rules(conn: &Connection) -> Result<Vec<AlertRule>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {RULE_COLUMNS} FROM alerts ORDER BY created_at"
+    ))?;
+    let rows = stmt.query_map([], rule_from_row)?;
+    Ok(rows
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+fn load_rule(conn: &Connection, id: &str) -> Result<AlertRule> {
+    conn.query_row(
+        &format!("SELECT {RULE_COLUMNS} FROM alerts WHERE id = ?1"),
+        [id],
+        rule_from_row,
+    )
+    .optional()?
+    .flatten()
+    .ok_or_else(|| Error::NotFound(format!("Alert {id}")))
+}
+
+/// Stores the outcome of an evaluation.
+fn save_state(conn: &Connection, id: &str, state: AlertState, value: f64, at: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE alerts SET state = ?2, last_value = ?3, last_checked_at = ?4 WHERE id = ?1",
+        params![id, state.as_str(), value, at],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertEvent {
+    pub id: String,
+    pub alert_id: String,
+    pub alert_name: Option<String>,
+    pub kind: AlertEventKind,
+    pub value: Option<f64>,
+    pub message: String,
+    pub created_at: i64,
+}
+
+fn insert_event(conn: &Connection, event: &AlertEvent) -> Result<()> {
+    conn.execute(
+        "INSERT INTO alert_events (id, alert_id, state, value, message, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            event.id,
+            event.alert_id,
+            event.kind.as_str(),
+            event.value,
+            event.message,
+            event.created_at
+        ],
+    )?;
+    Ok(())
+}
+
+fn to_sql_json(value: &Option<JsonValue>) -> Option<String> {
+    value.as_ref().map(JsonValue::to_string)
+}
+
+#[tauri::command]
+pub async fn list_alert_rules(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<Vec<AlertRule>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(load_rules)
+}
+
+#[tauri::command]
+pub async fn create_alert_rule(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    rule: AlertRuleInput,
+) -> Result<AlertRule> {
+    vault.ensure_unlocked()?;
+    rule.validate()?;
+    let id = audit::new_id();
+    db.with_conn(|conn| {
+        Outbound::resolve(conn, rule.channel, rule.channel_config.as_ref())?;
+        conn.execute(
+            "INSERT INTO alerts (id, name, type, provider_id, threshold, period, channel,
+                channel_config, enabled)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                id,
+                rule.name.trim(),
+                rule.kind.as_str(),
+                rule.provider,
+                rule.threshold,
+                rule.period.as_str(),
+                rule.channel.as_str(),
+                to_sql_json(&rule.channel_config),
+                rule.enabled
+            ],
+        )?;
+        load_rule(conn, &id)
+    })
+}
+
+/// Updates a rule. Changing what it watches resets it to `ok`, so the next
+/// evaluation fires again if the new condition is met.
+#[tauri::command]
+pub async fn update_alert_rule(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    id: String,
+    rule: AlertRuleInput,
+) -> Result<AlertRule> {
+    vault.ensure_unlocked()?;
+    rule.validate()?;
+    db.with_conn(|conn| {
+        Outbound::resolve(conn, rule.channel, rule.channel_config.as_ref())?;
+        let current = load_rule(conn, &id)?;
+        let condition_changed = current.kind != rule.kind
+            || current.provider != rule.provider
+            || current.threshold != rule.threshold
+            || current.period != rule.period;
+        let state = if condition_changed || !rule.enabled {
+            AlertState::Ok
+        } else {
+            current.state
+        };
+        conn.execute(
+            "UPDATE alerts SET name = ?2, type = ?3, provider_id = ?4, threshold = ?5,
+                period = ?6, channel = ?7, channel_config = ?8, enabled = ?9, state = ?10
+             WHERE id = ?1",
+            params![
+                id,
+                rule.name.trim(),
+                rule.kind.as_str(),
+                rule.provider,
+                rule.threshold,
+                rule.period.as_str(),
+                rule.channel.as_str(),
+                to_sql_json(&rule.channel_config),
+                rule.enabled,
+                state.as_str()
+            ],
+        )?;
+        load_rule(conn, &id)
+    })
+}
+
+/// Deletes a rule together with its events.
+#[tauri::command]
+pub async fn delete_alert_rule(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    id: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| {
+        conn.execute("DELETE FROM alert_events WHERE alert_id = ?1", [&id])?;
+        conn.execute("DELETE FROM alerts WHERE id = ?1", [&id])?;
+        Ok(())
+    })
+}
+
+/// Events of all rules or of `alert_id`, newest first.
+#[tauri::command]
+pub async fn list_alert_events(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    alert_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<AlertEvent>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| {
+        let mut stmt = conn.prepare(
+            "SELECT e.id, e.alert_id, a.name, e.state, e.value, e.message, e.created_at
+             FROM alert_events e LEFT JOIN alerts a ON a.id = e.alert_id
+             WHERE ?1 IS NULL OR e.alert_id = ?1
+             ORDER BY e.created_at DESC, e.rowid DESC
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![alert_id, limit.unwrap_or(100)], |row| {
+            let kind: String = row.get(3)?;
+            Ok(AlertEvent {
+                id: row.get(0)?,
+                alert_id: row.get(1)?,
+                alert_name: row.get(2)?,
+                kind: AlertEventKind::parse(&kind).unwrap_or(AlertEventKind::Firing),
+                value: row.get(4)?,
+                message: row.get(5)?,
+                created_at: row.get::<_, Option<i64>>(6)?.unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    })
+}
@@ -0,0 +1,457 @@
+//! Export of the audit log for review outside the app, as JSON Lines, CSV or
+//! ArcSight CEF. Every export is itself audited.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Jsonl,
+    Csv,
+    Cef,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Csv => "csv",
+            Self::Cef => "cef",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Jsonl => "JSON Lines",
+            Self::Csv => "CSV",
+            Self::Cef => "CEF",
+        }
+    }
+}
+
+/// Same filters as the audit log view. Dates are Unix seconds, inclusive.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFilter {
+    pub start_date: Option<i64>,
+    pub end_date: Option<i64>,
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+}
+
+impl AuditFilter {
+    fn where_clause(&self) -> (String, Vec<SqlValue>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(start) = self.start_date {
+            conditions.push("created_at >= ?");
+            values.push(SqlValue::Integer(start));
+        }
+        if let Some(end) = self.end_date {
+            conditions.push("created_at <= ?");
+            values.push(SqlValue::Integer(end));
+        }
+        for (column, value) in [
+            ("action = ?", &self.action),
+            ("resource_type = ?", &self.resource_type),
+            ("resource_id = ?", &self.resource_id),
+        ] {
+            if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+                conditions.push(column);
+                values.push(SqlValue::Text(value.to_owned()));
+            }
+        }
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!("WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+}
+
+/// One `audit_log` row as it leaves the app. Rows inserted around the chain
+/// have no `seq` and `hash`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Record {
+    pub seq: Option<i64>,
+    pub id: String,
+    pub action: String,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<String>,
+    pub details: Option<String>,
+    pub created_at: i64,
+    pub hash: Option<String>,
+}
+
+impl Record {
+    const COLUMNS: &'static str =
+        "seq, id, action, resource_type, resource_id, details, created_at, hash";
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            seq: row.get(0)?,
+            id: row.get(1)?,
+            action: row.get(2)?,
+            resource_type: row.get(3)?,
+            resource_id: row.get(4)?,
+            details: row.get(5)?,
+            created_at: row.get(6)?,
+            hash: row.get(7)?,
+        })
+    }
+
+    fn timestamp(&self) -> String {
+        DateTime::<Utc>::from_timestamp(self.created_at, 0)
+            .unwrap_or_default()
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// Details as JSON, or the raw text if a row holds something else.
+    fn details_json(&self) -> JsonValue {
+        self.details.as_deref().map_or(JsonValue::Null, |d| {
+            serde_json::from_str(d).unwrap_or_else(|_| d.into())
+        })
+    }
+}
+
+/// Matching rows, oldest first.
+pub fn query(conn: &Connection, filter: &AuditFilter) -> Result<Vec<Record>> {
+    let (where_clause, values) = filter.where_clause();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM audit_log {where_clause} ORDER BY created_at, seq",
+        Record::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params_from_iter(values), Record::from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+/// One page of matching rows, newest first, for the audit log view.
+pub fn page(
+    conn: &Connection,
+    filter: &AuditFilter,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<Record>> {
+    let (where_clause, mut values) = filter.where_clause();
+    values.push(SqlValue::Integer(limit.into()));
+    values.push(SqlValue::Integer(offset.into()));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM audit_log {where_clause}
+         ORDER BY created_at DESC, seq DESC LIMIT ? OFFSET ?",
+        Record::COLUMNS
+    ))?;
+    let rows = stmt.query_map(params_from_iter(values), Record::from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+pub fn count(conn: &Connection, filter: &AuditFilter) -> Result<i64> {
+    let (where_clause, values) = filter.where_clause();
+    Ok(conn.query_row(
+        &format!("SELECT COUNT(*) FROM audit_log {where_clause}"),
+        params_from_iter(values),
+        |row| row.get(0),
+    )?)
+}
+
+/// Chained rows after `seq`, in chain order.
+pub fn after(conn: &Connection, seq: i64, limit: u32) -> Result<Vec<Record>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM audit_log WHERE seq > ?1 ORDER BY seq LIMIT ?2",
+        Record::COLUMNS
+    ))?;
+    let rows = stmt.query_map((seq, limit), Record::from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+pub fn render(format: ExportFormat, records: &[Record]) -> String {
+    let mut out = String::new();
+    if format == ExportFormat::Csv {
+        out.push_str("seq,id,created_at,action,resource_type,resource_id,details,hash\n");
+    }
+    for record in records {
+        out.push_str(&match format {
+            ExportFormat::Jsonl => to_json_line(record),
+            ExportFormat::Csv => to_csv_line(record),
+            ExportFormat::Cef => to_cef(record),
+        });
+        out.push('\n');
+    }
+    out
+}
+
+fn to_json_line(record: &Record) -> String {
+    json!({
+        "seq": record.seq,
+        "id": record.id,
+        "createdAt": record.timestamp(),
+        "action": record.action,
+        "resourceType": record.resource_type,
+        "resourceId": record.resource_id,
+        "details": record.details_json(),
+        "hash": record.hash,
+    })
+    .to_string()
+}
+
+fn to_csv_line(record: &Record) -> String {
+    let seq = record.seq.map(|s| s.to_string()).unwrap_or_default();
+    [
+        seq.as_str(),
+        &record.id,
+        &record.timestamp(),
+        &record.action,
+        record.resource_type.as_deref().unwrap_or_default(),
+        record.resource_id.as_deref().unwrap_or_default(),
+        record.details.as_deref().unwrap_or_default(),
+        record.hash.as_deref().unwrap_or_default(),
+    ]
+    .into_iter()
+    .map(csv_field)
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Quotes a CSV field when needed. Leading formula characters are
+/// neutralised so spreadsheets do not evaluate logged text.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{value}")
+    } else {
+        value.to_owned()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// CEF severity (0–10) of an action.
+pub fn severity(action: &str) -> u8 {
+    match action {
+        "api_error" => 5,
+        a if a.ends_with("_deleted") => 5,
+        "audit_pruned" | "data_exported" => 4,
+        a if a.starts_with("secret_") => 3,
+        _ => 1,
+    }
+}
+
+/// One ArcSight CEF line. Also the message body of forwarded syslog entries.
+pub fn to_cef(record: &Record) -> String {
+    let header = |value: &str| value.replace('\\', "\\\\").replace('|', "\\|");
+    let mut extension = vec![
+        format!("rt={}", record.created_at * 1000),
+        format!("act={}", cef_value(&record.action)),
+        format!("externalId={}", cef_value(&record.id)),
+    ];
+    if let Some(seq) = record.seq {
+        extension.push(format!("cn1Label=seq cn1={seq}"));
+    }
+    for (i, (label, value)) in [
+        ("resourceType", &record.resource_type),
+        ("resourceId", &record.resource_id),
+        ("hash", &record.hash),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if let Some(value) = value {
+            let n = i + 1;
+            extension.push(format!("cs{n}Label={label} cs{n}={}", cef_value(value)));
+        }
+    }
+    if let Some(details) = &record.details {
+        extension.push(format!("msg={}", cef_value(details)));
+    }
+    format!(
+        "CEF:0|Panoptic|Panoptic|{}|{}|{}|{}|{}",
+        header(env!("CARGO_PKG_VERSION")),
+        header(&record.action),
+        header(&record.action.replace('_', " ")),
+        severity(&record.action),
+        extension.join(" ")
+    )
+}
+
+fn cef_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+async fn pick_path(app: &AppHandle, format: ExportFormat) -> Result<Option<PathBuf>> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Audit-Log exportieren")
+        .set_file_name(format!(
+            "panoptic-audit-{}.{}",
+            Utc::now().format("%Y-%m-%d"),
+            format.extension()
+        ))
+        .add_filter(format.label(), &[format.extension()])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    path.into_path()
+        .map(Some)
+        .map_err(|_| Error::InvalidInput("Ungültiger Speicherort".into()))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportResult {
+    path: String,
+    count: usize,
+}
+
+/// Writes the matching entries to a file the user picks in a save dialog.
+/// Returns `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_audit_log(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    format: ExportFormat,
+    filter: AuditFilter,
+) -> Result<Option<ExportResult>> {
+    vault.ensure_unlocked()?;
+    let Some(path) = pick_path(&app, format).await? else {
+        return Ok(None);
+    };
+
+    let records = db.with_conn(|conn| query(conn, &filter))?;
+    std::fs::write(&path, render(format, &records))?;
+    db.with_audit(|conn, key| {
+        super::log(
+            conn,
+            key,
+            super::DATA_EXPORTED,
+            Some("audit_log"),
+            None,
+            Some(&json!({
+                "format": format,
+                "count": records.len(),
+                "filter": filter,
+                "path": path.to_string_lossy(),
+            })),
+        )
+    })?;
+    Ok(Some(ExportResult {
+        path: path.to_string_lossy().into_owned(),
+        count: records.len(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{self, ChainKey};
+    use crate::db;
+
+    fn record(action: &str, details: Option<&str>) -> Record {
+        Record {
+            seq: Some(7),
+            id: "id-1".into(),
+            action: action.into(),
+            resource_type: Some("secret".into()),
+            resource_id: Some("abc".into()),
+            details: details.map(str::to_owned),
+            created_at: 1_741_600_000,
+            hash: Some("ff".into()),
+        }
+    }
+
+    #[test]
+    fn filters_by_date_action_and_resource() {
+        let key = ChainKey::derive("test");
+        let conn = db::open_in_memory(&key);
+        for (action, resource) in [
+            ("secret_created", "a"),
+            ("secret_accessed", "a"),
+            ("secret_accessed", "b"),
+        ] {
+            audit::log(&conn, &key, action, Some("secret"), Some(resource), None).unwrap();
+        }
+        conn.execute(
+            "UPDATE audit_log SET created_at = 1000 WHERE action = 'secret_created'",
+            [],
+        )
+        .unwrap();
+
+        let filter = AuditFilter {
+            action: Some("secret_accessed".into()),
+            resource_id: Some("a".into()),
+            ..Default::default()
+        };
+        let found = query(&conn, &filter).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].resource_id.as_deref(), Some("a"));
+
+        let filter = AuditFilter {
+            end_date: Some(1000),
+            ..Default::default()
+        };
+        let found = query(&conn, &filter).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].action, "secret_created");
+
+        let all = AuditFilter {
+            resource_type: Some("secret".into()),
+            ..Default::default()
+        };
+        assert_eq!(count(&conn, &all).unwrap(), 3);
+        let newest: Vec<_> = page(&conn, &all, 2, 0)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.resource_id.unwrap())
+            .collect();
+        assert_eq!(newest, ["b", "a"]);
+        assert_eq!(page(&conn, &all, 2, 2).unwrap()[0].action, "secret_created");
+    }
+
+    #[test]
+    fn renders_each_format_with_escaping() {
+        let records = [record("secret_copied", Some(r#"{"name":"A,B \"x\""}"#))];
+
+        let jsonl = render(ExportFormat::Jsonl, &records);
+        let line: JsonValue = serde_json::from_str(jsonl.trim_end()).unwrap();
+        assert_eq!(line["details"]["name"], "A,B \"x\"");
+        assert_eq!(line["createdAt"], "2025-03-10T09:46:40Z");
+
+        let csv = render(ExportFormat::Csv, &records);
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("seq,id,created_at"));
+        assert_eq!(
+            lines.next().unwrap(),
+            r#"7,id-1,2025-03-10T09:46:40Z,secret_copied,secret,abc,"{""name"":""A,B \""x\""""}",ff"#
+        );
+        assert_eq!(csv_field("=HYPERLINK(1)"), "'=HYPERLINK(1)");
+
+        let cef = to_cef(&record("api|error", Some("a=b\nc")));
+        assert!(cef.starts_with("CEF:0|Panoptic|Panoptic|"));
+        assert!(cef.contains("|api\\|error|api\\|error|1|rt=1741600000000 "));
+        assert!(cef.ends_with("msg=a\\=b\\nc"));
+        assert!(cef.contains("cs1Label=resourceType cs1=secret"));
+    }
+}
@@ -0,0 +1,719 @@
+//! Tamper-evident audit log. The backend writes entries directly, the
+//! webview through `log_audit`; it reads them with `list_audit_log`.
+//!
+//! Every row carries a sequence number and is chained to its predecessor:
+//! `hash` is an HMAC-SHA256 over the row and the `prev_hash` it links to,
+//! keyed with a key derived from the vault key. Editing, deleting or
+//! reordering rows breaks the chain from that row on. Pruning leaves a signed
+//! checkpoint with the hash of the last deleted row, so the remaining rows
+//! still verify.
+
+pub mod export;
+pub mod syslog;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use sha2::Sha256;
+use tauri::State;
+use zeroize::Zeroizing;
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+pub const API_CALL: &str = "api_call";
+pub const API_ERROR: &str = "api_error";
+pub const SCHEMA_MIGRATED: &str = "schema_migrated";
+pub const SECRET_CREATED: &str = "secret_created";
+pub const SECRET_COPIED: &str = "secret_copied";
+pub const SECRET_DELETED: &str = "secret_deleted";
+pub const SECRET_REVEALED: &str = "secret_revealed";
+pub const SECRET_UPDATED: &str = "secret_updated";
+pub const SECRET_ROTATED: &str = "secret_rotated";
+pub const SECRET_REVERTED: &str = "secret_reverted";
+pub const SECRETS_EXPORTED: &str = "secrets_exported";
+pub const SECRETS_IMPORTED: &str = "secrets_imported";
+pub const SETTINGS_UPDATED: &str = "settings_updated";
+pub const DEPLOYMENT_REDEPLOYED: &str = "deployment_redeployed";
+pub const DEPLOYMENT_PROMOTED: &str = "deployment_promoted";
+pub const DEPLOYMENT_CANCELLED: &str = "deployment_cancelled";
+pub const PROJECT_PAUSED: &str = "project_paused";
+pub const PROJECT_RESTORED: &str = "project_restored";
+pub const DNS_SNAPSHOT_SAVED: &str = "dns_snapshot_saved";
+pub const DNS_SNAPSHOT_DELETED: &str = "dns_snapshot_deleted";
+pub const DATA_EXPORTED: &str = "data_exported";
+pub const AUDIT_PRUNED: &str = "audit_pruned";
+
+/// `prev_hash` of the first row when nothing was pruned yet.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub fn new_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// HMAC key of the chain, derived from the vault key of the session.
+pub struct ChainKey(Zeroizing<[u8; 32]>);
+
+impl ChainKey {
+    pub fn derive(vault_key: &str) -> Self {
+        let mut mac =
+            HmacSha256::new_from_slice(vault_key.as_bytes()).expect("HMAC accepts any key length");
+        mac.update(b"panoptic audit chain v1");
+        Self(Zeroizing::new(mac.finalize().into_bytes().into()))
+    }
+
+    fn sign(&self, message: &JsonValue) -> String {
+        let mut mac =
+            HmacSha256::new_from_slice(self.0.as_slice()).expect("HMAC accepts any key length");
+        mac.update(message.to_string().as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+struct Entry {
+    seq: i64,
+    id: String,
+    action: String,
+    resource_type: Option<String>,
+    resource_id: Option<String>,
+    details: Option<String>,
+    created_at: i64,
+    prev_hash: String,
+}
+
+impl Entry {
+    const COLUMNS: &'static str =
+        "seq, id, action, resource_type, resource_id, details, created_at, prev_hash, hash";
+
+    /// Reads [`Self::COLUMNS`], returning the stored hash alongside.
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<(Self, String)> {
+        Ok((
+            Self {
+                seq: row.get(0)?,
+                id: row.get(1)?,
+                action: row.get(2)?,
+                resource_type: row.get(3)?,
+                resource_id: row.get(4)?,
+                details: row.get(5)?,
+                created_at: row.get(6)?,
+                prev_hash: row.get(7)?,
+            },
+            row.get(8)?,
+        ))
+    }
+
+    fn hash(&self, key: &ChainKey) -> String {
+        key.sign(&json!([
+            "entry",
+            self.seq,
+            self.prev_hash,
+            self.id,
+            self.action,
+            self.resource_type,
+            self.resource_id,
+            self.details,
+            self.created_at,
+        ]))
+    }
+}
+
+struct Checkpoint {
+    /// Sequence number of the last pruned row.
+    seq: i64,
+    /// Hash of the last pruned row, the `prev_hash` of the next one.
+    hash: String,
+    pruned: i64,
+    created_at: i64,
+    signature: String,
+}
+
+impl Checkpoint {
+    fn sign(&self, key: &ChainKey) -> String {
+        key.sign(&json!([
+            "checkpoint",
+            self.seq,
+            self.hash,
+            self.pruned,
+            self.created_at,
+        ]))
+    }
+}
+
+fn latest_checkpoint(conn: &Connection) -> Result<Option<Checkpoint>> {
+    Ok(conn
+        .query_row(
+            "SELECT seq, hash, pruned, created_at, signature FROM audit_checkpoints
+             ORDER BY seq DESC LIMIT 1",
+            [],
+            |row| {
+                Ok(Checkpoint {
+                    seq: row.get(0)?,
+                    hash: row.get(1)?,
+                    pruned: row.get(2)?,
+                    created_at: row.get(3)?,
+                    signature: row.get(4)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+/// Sequence number and hash the next row links to.
+fn head(conn: &Connection) -> Result<(i64, String)> {
+    let last = conn
+        .query_row(
+            "SELECT seq, hash FROM audit_log WHERE seq IS NOT NULL ORDER BY seq DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    if let Some(last) = last {
+        return Ok(last);
+    }
+    Ok(latest_checkpoint(conn)?.map_or_else(|| (0, GENESIS.to_owned()), |cp| (cp.seq, cp.hash)))
+}
+
+pub fn log(
+    conn: &Connection,
+    key: &ChainKey,
+    action: &str,
+    resource_type: Option<&str>,
+    resource_id: Option<&str>,
+    details: Option<&JsonValue>,
+) -> Result<()> {
+    let (seq, prev_hash) = head(conn)?;
+    let entry = Entry {
+        seq: seq + 1,
+        id: new_id(),
+        action: action.to_owned(),
+        resource_type: resource_type.map(str::to_owned),
+        resource_id: resource_id.map(str::to_owned),
+        details: details.map(JsonValue::to_string),
+        created_at: Utc::now().timestamp(),
+        prev_hash,
+    };
+    conn.execute(
+        &format!(
+            "INSERT INTO audit_log ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            Entry::COLUMNS
+        ),
+        params![
+            entry.seq,
+            entry.id,
+            entry.action,
+            entry.resource_type,
+            entry.resource_id,
+            entry.details,
+            entry.created_at,
+            entry.prev_hash,
+            entry.hash(key),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Plain insert for the migrations that run before `audit_log` has the
+/// chain columns. [`chain_existing`] links these rows later.
+pub fn log_unchained(
+    conn: &Connection,
+    action: &str,
+    resource_type: Option<&str>,
+    resource_id: Option<&str>,
+    details: Option<&JsonValue>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO audit_log (id, action, resource_type, resource_id, details)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            new_id(),
+            action,
+            resource_type,
+            resource_id,
+            details.map(JsonValue::to_string)
+        ],
+    )?;
+    Ok(())
+}
+
+/// Links the rows written before the chain existed, oldest first. Only the
+/// migration that introduces the chain calls this.
+pub fn chain_existing(conn: &Connection, key: &ChainKey) -> Result<usize> {
+    let legacy = {
+        let mut stmt = conn.prepare(
+            "SELECT rowid, id, action, resource_type, resource_id, details, created_at
+             FROM audit_log WHERE hash IS NULL ORDER BY created_at, rowid",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                Entry {
+                    seq: 0,
+                    id: row.get(1)?,
+                    action: row.get(2)?,
+                    resource_type: row.get(3)?,
+                    resource_id: row.get(4)?,
+                    details: row.get(5)?,
+                    created_at: row.get(6)?,
+                    prev_hash: String::new(),
+                },
+            ))
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    let count = legacy.len();
+    let (mut seq, mut prev_hash) = head(conn)?;
+    for (rowid, mut entry) in legacy {
+        seq += 1;
+        entry.seq = seq;
+        entry.prev_hash = prev_hash;
+        let hash = entry.hash(key);
+        conn.execute(
+            "UPDATE audit_log SET seq = ?1, prev_hash = ?2, hash = ?3 WHERE rowid = ?4",
+            params![seq, entry.prev_hash, hash, rowid],
+        )?;
+        prev_hash = hash;
+    }
+    Ok(count)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakReason {
+    /// The row was changed after it was written.
+    Modified,
+    /// The row does not link to its predecessor: rows before it were deleted
+    /// or reordered.
+    Missing,
+    /// The row was inserted without going through the chain.
+    Unchained,
+    /// The signature of the pruning checkpoint does not match.
+    Checkpoint,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenLink {
+    pub seq: Option<i64>,
+    pub id: Option<String>,
+    pub action: Option<String>,
+    pub created_at: Option<i64>,
+    pub reason: BreakReason,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckpointInfo {
+    pub seq: i64,
+    pub pruned: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainReport {
+    pub intact: bool,
+    /// Rows verified before the first broken link.
+    pub checked: u64,
+    pub checkpoint: Option<CheckpointInfo>,
+    pub first_broken: Option<BrokenLink>,
+}
+
+/// Walks the chain from the latest checkpoint and reports the first row that
+/// does not verify.
+pub fn verify(conn: &Connection, key: &ChainKey) -> Result<ChainReport> {
+    let checkpoint = latest_checkpoint(conn)?;
+    let mut report = ChainReport {
+        intact: false,
+        checked: 0,
+        checkpoint: checkpoint.as_ref().map(|cp| CheckpointInfo {
+            seq: cp.seq,
+            pruned: cp.pruned,
+            created_at: cp.created_at,
+        }),
+        first_broken: None,
+    };
+
+    let (mut seq, mut prev_hash) = match &checkpoint {
+        Some(cp) if cp.signature != cp.sign(key) => {
+            report.first_broken = Some(BrokenLink {
+                seq: Some(cp.seq),
+                id: None,
+                action: None,
+                created_at: Some(cp.created_at),
+                reason: BreakReason::Checkpoint,
+            });
+            return Ok(report);
+        }
+        Some(cp) => (cp.seq, cp.hash.clone()),
+        None => (0, GENESIS.to_owned()),
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM audit_log WHERE seq > ?1 AND hash IS NOT NULL ORDER BY seq",
+        Entry::COLUMNS
+    ))?;
+    let mut rows = stmt.query([seq])?;
+    while let Some(row) = rows.next()? {
+        let (entry, hash) = Entry::from_row(row)?;
+        let reason = if entry.seq != seq + 1 || entry.prev_hash != prev_hash {
+            Some(BreakReason::Missing)
+        } else if entry.hash(key) != hash {
+            Some(BreakReason::Modified)
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            report.first_broken = Some(BrokenLink {
+                seq: Some(entry.seq),
+                id: Some(entry.id),
+                action: Some(entry.action),
+                created_at: Some(entry.created_at),
+                reason,
+            });
+            return Ok(report);
+        }
+        report.checked += 1;
+        seq = entry.seq;
+        prev_hash = hash;
+    }
+
+    report.first_broken = conn
+        .query_row(
+            "SELECT id, action, created_at FROM audit_log
+             WHERE seq IS NULL OR hash IS NULL ORDER BY created_at LIMIT 1",
+            [],
+            |row| {
+                Ok(BrokenLink {
+                    seq: None,
+                    id: row.get(0)?,
+                    action: row.get(1)?,
+                    created_at: row.get(2)?,
+                    reason: BreakReason::Unchained,
+                })
+            },
+        )
+        .optional()?;
+    report.intact = report.first_broken.is_none();
+    Ok(report)
+}
+
+/// Deletes the rows written before `before` (Unix seconds) and leaves a
+/// checkpoint behind. Refuses to prune a chain that no longer verifies, so
+/// tampering cannot be covered up by retention. Returns the number of rows
+/// deleted.
+pub fn prune(conn: &Connection, key: &ChainKey, before: i64) -> Result<usize> {
+    if !verify(conn, key)?.intact {
+        return Err(Error::AuditChainBroken);
+    }
+    let last: Option<(i64, String)> = conn
+        .query_row(
+            "SELECT seq, hash FROM audit_log WHERE created_at < ?1 ORDER BY seq DESC LIMIT 1",
+            [before],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    let Some((seq, hash)) = last else {
+        return Ok(0);
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    let deleted = tx.execute("DELETE FROM audit_log WHERE seq <= ?1", [seq])?;
+    let mut checkpoint = Checkpoint {
+        seq,
+        hash,
+        pruned: deleted as i64,
+        created_at: Utc::now().timestamp(),
+        signature: String::new(),
+    };
+    checkpoint.signature = checkpoint.sign(key);
+    tx.execute(
+        "INSERT INTO audit_checkpoints (seq, hash, pruned, created_at, signature)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            checkpoint.seq,
+            checkpoint.hash,
+            checkpoint.pruned,
+            checkpoint.created_at,
+            checkpoint.signature
+        ],
+    )?;
+    log(
+        &tx,
+        key,
+        AUDIT_PRUNED,
+        Some("audit_log"),
+        None,
+        Some(&json!({ "throughSeq": seq, "deleted": deleted, "before": before })),
+    )?;
+    tx.commit()?;
+    Ok(deleted)
+}
+
+/// Re-signs the chain after the vault key changed. Rows from the first broken
+/// link on keep their old hashes, so verification still stops there. Callers
+/// run it in the transaction of the re-key.
+pub fn resign(conn: &Connection, old: &ChainKey, new: &ChainKey) -> Result<()> {
+    let report = verify(conn, old)?;
+    let stop = match &report.first_broken {
+        Some(BrokenLink {
+            reason: BreakReason::Checkpoint,
+            ..
+        }) => return Ok(()),
+        Some(BrokenLink { seq: Some(seq), .. }) => *seq,
+        _ => i64::MAX,
+    };
+
+    let mut prev_hash = GENESIS.to_owned();
+    let mut from = 0;
+    if let Some(mut cp) = latest_checkpoint(conn)? {
+        cp.signature = cp.sign(new);
+        conn.execute(
+            "UPDATE audit_checkpoints SET signature = ?1 WHERE seq = ?2",
+            params![cp.signature, cp.seq],
+        )?;
+        prev_hash = cp.hash;
+        from = cp.seq;
+    }
+
+    let entries = {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM audit_log WHERE seq > ?1 AND seq < ?2 AND hash IS NOT NULL ORDER BY seq",
+            Entry::COLUMNS
+        ))?;
+        let rows = stmt.query_map([from, stop], Entry::from_row)?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    for (mut entry, _) in entries {
+        entry.prev_hash = prev_hash;
+        let hash = entry.hash(new);
+        conn.execute(
+            "UPDATE audit_log SET prev_hash = ?1, hash = ?2 WHERE seq = ?3",
+            params![entry.prev_hash, hash, entry.seq],
+        )?;
+        prev_hash = hash;
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditInput {
+    action: String,
+    resource_type: Option<String>,
+    resource_id: Option<String>,
+    details: Option<JsonValue>,
+}
+
+/// Appends an entry for an action taken in the webview.
+#[tauri::command]
+pub async fn log_audit(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    entry: AuditInput,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    if entry.action.trim().is_empty() {
+        return Err(Error::InvalidInput(
+            "Die Aktion darf nicht leer sein".into(),
+        ));
+    }
+    db.with_audit(|conn, key| {
+        log(
+            conn,
+            key,
+            &entry.action,
+            entry.resource_type.as_deref(),
+            entry.resource_id.as_deref(),
+            entry.details.as_ref(),
+        )
+    })
+}
+
+const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Entries matching `filter`, newest first.
+#[tauri::command]
+pub async fn list_audit_log(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    filter: export::AuditFilter,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<export::Record>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| export::page(conn, &filter, limit.min(MAX_PAGE_SIZE), offset))
+}
+
+#[tauri::command]
+pub async fn count_audit_log(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    filter: export::AuditFilter,
+) -> Result<i64> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| export::count(conn, &filter))
+}
+
+#[tauri::command]
+pub async fn verify_audit_chain(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<ChainReport> {
+    vault.ensure_unlocked()?;
+    db.with_audit(verify)
+}
+
+/// Deletes entries older than `days_to_keep` days. Returns the number of
+/// deleted rows.
+#[tauri::command]
+pub async fn prune_audit_log(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    days_to_keep: u32,
+) -> Result<usize> {
+    vault.ensure_unlocked()?;
+    let before = Utc::now().timestamp() - i64::from(days_to_keep) * DAY_SECS;
+    db.with_audit(|conn, key| prune(conn, key, before))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    fn conn_with_entries(key: &ChainKey, count: usize) -> Connection {
+        let conn = db::open_in_memory(key);
+        for i in 0..count {
+            log(
+                &conn,
+                key,
+                "secret_accessed",
+                Some("secret"),
+                Some(&i.to_string()),
+                Some(&json!({ "name": format!("KEY_{i}") })),
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn broken(conn: &Connection, key: &ChainKey) -> BrokenLink {
+        let report = verify(conn, key).unwrap();
+        assert!(!report.intact);
+        report.first_broken.unwrap()
+    }
+
+    fn seq_of(conn: &Connection, resource_id: &str) -> i64 {
+        conn.query_row(
+            "SELECT seq FROM audit_log WHERE resource_id = ?1",
+            [resource_id],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fresh_chain_including_migrations_verifies() {
+        let key = ChainKey::derive("passwort");
+        let conn = conn_with_entries(&key, 5);
+        let report = verify(&conn, &key).unwrap();
+        assert!(report.intact, "{:?}", report.first_broken);
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_log", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(report.checked, rows as u64);
+
+        // A different vault key does not verify the same chain
+        assert_eq!(
+            broken(&conn, &ChainKey::derive("anderes")).reason,
+            BreakReason::Modified
+        );
+    }
+
+    #[test]
+    fn reports_the_first_edited_deleted_or_foreign_row() {
+        let key = ChainKey::derive("passwort");
+
+        let conn = conn_with_entries(&key, 5);
+        conn.execute(
+            "UPDATE audit_log SET details = '{}' WHERE resource_id IN ('1', '3')",
+            [],
+        )
+        .unwrap();
+        let link = broken(&conn, &key);
+        assert_eq!(link.reason, BreakReason::Modified);
+        assert_eq!(link.seq, Some(seq_of(&conn, "1")));
+
+        let conn = conn_with_entries(&key, 5);
+        let next = seq_of(&conn, "3");
+        conn.execute("DELETE FROM audit_log WHERE resource_id = '2'", [])
+            .unwrap();
+        let link = broken(&conn, &key);
+        assert_eq!(link.reason, BreakReason::Missing);
+        assert_eq!(link.seq, Some(next));
+
+        let conn = conn_with_entries(&key, 2);
+        conn.execute(
+            "INSERT INTO audit_log (id, action) VALUES ('raw', 'secret_deleted')",
+            [],
+        )
+        .unwrap();
+        let link = broken(&conn, &key);
+        assert_eq!(link.reason, BreakReason::Unchained);
+        assert_eq!(link.id.as_deref(), Some("raw"));
+    }
+
+    #[test]
+    fn pruning_leaves_a_signed_checkpoint() {
+        let key = ChainKey::derive("passwort");
+        let conn = conn_with_entries(&key, 4);
+        conn.execute(
+            "UPDATE audit_log SET created_at = 1000 WHERE resource_id IN ('0', '1')",
+            [],
+        )
+        .unwrap();
+        // Backdating the rows broke them, so pruning refuses
+        assert!(matches!(
+            prune(&conn, &key, 2000),
+            Err(Error::AuditChainBroken)
+        ));
+
+        let conn = conn_with_entries(&key, 4);
+        let cutoff = Utc::now().timestamp() + 1;
+        let deleted = prune(&conn, &key, cutoff).unwrap();
+        assert!(deleted > 4);
+        let report = verify(&conn, &key).unwrap();
+        assert!(report.intact, "{:?}", report.first_broken);
+        assert_eq!(report.checkpoint.unwrap().pruned, deleted as i64);
+        // Only the entry about the pruning itself is left
+        assert_eq!(report.checked, 1);
+
+        log(&conn, &key, "app_locked", None, None, None).unwrap();
+        assert!(verify(&conn, &key).unwrap().intact);
+
+        conn.execute("UPDATE audit_checkpoints SET pruned = 1", [])
+            .unwrap();
+        assert_eq!(broken(&conn, &key).reason, BreakReason::Checkpoint);
+    }
+
+    #[test]
+    fn resigning_moves_the_chain_to_the_new_key() {
+        let old = ChainKey::derive("alt");
+        let new = ChainKey::derive("neu");
+        let conn = conn_with_entries(&old, 3);
+        prune(&conn, &old, Utc::now().timestamp() + 1).unwrap();
+        log(&conn, &old, "app_locked", None, None, None).unwrap();
+
+        resign(&conn, &old, &new).unwrap();
+        assert!(verify(&conn, &new).unwrap().intact);
+        assert!(!verify(&conn, &old).unwrap().intact);
+    }
+}
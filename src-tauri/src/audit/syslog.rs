@@ -0,0 +1,358 @@
+//! Forwarding of new audit entries to a syslog collector.
+//!
+//! Messages follow RFC 5424 with the CEF line of the entry as body, over UDP
+//! (one datagram each) or TCP (octet-counting framing, RFC 6587). A cursor
+//! in the settings remembers the last forwarded `seq`, so entries written
+//! while the collector is unreachable are sent once it is back.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{lookup_host, TcpStream, UdpSocket};
+
+use super::export::{self, Record};
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+const CONFIG_SETTING: &str = "auditSyslog";
+const CURSOR_SETTING: &str = "auditSyslogCursor";
+const FORWARD_INTERVAL: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const BATCH_SIZE: u32 = 200;
+
+/// Facility 13, "log audit".
+const FACILITY: u8 = 13;
+/// Structured data ID, under the enterprise number reserved for examples
+/// and documentation (RFC 5612).
+const SD_ID: &str = "panoptic@32473";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyslogProtocol {
+    Udp,
+    Tcp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyslogConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub protocol: SyslogProtocol,
+}
+
+impl SyslogConfig {
+    fn validate(&self) -> Result<()> {
+        if self.host.trim().is_empty() {
+            return Err(Error::InvalidInput("Der Syslog-Host fehlt".into()));
+        }
+        if self.port == 0 {
+            return Err(Error::InvalidInput("Ungültiger Syslog-Port".into()));
+        }
+        Ok(())
+    }
+}
+
+fn load_config(conn: &rusqlite::Connection) -> Result<Option<SyslogConfig>> {
+    Ok(db::get_setting(conn, CONFIG_SETTING)?.and_then(|value| serde_json::from_str(&value).ok()))
+}
+
+/// Printable ASCII without spaces, as RFC 5424 requires for header fields.
+fn header_field(value: &str, max: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max)
+        .collect();
+    if field.is_empty() {
+        "-".into()
+    } else {
+        field
+    }
+}
+
+fn sd_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace(']', "\\]")
+}
+
+/// Syslog severity: warning for the entries CEF rates 5 or higher,
+/// informational otherwise.
+fn syslog_severity(action: &str) -> u8 {
+    if export::severity(action) >= 5 {
+        4
+    } else {
+        6
+    }
+}
+
+fn format_message(record: &Record, hostname: &str) -> String {
+    let mut params = Vec::new();
+    if let Some(seq) = record.seq {
+        params.push(format!("seq=\"{seq}\""));
+    }
+    for (name, value) in [
+        ("resourceType", &record.resource_type),
+        ("resourceId", &record.resource_id),
+        ("hash", &record.hash),
+    ] {
+        if let Some(value) = value {
+            params.push(format!("{name}=\"{}\"", sd_value(value)));
+        }
+    }
+    let structured_data = if params.is_empty() {
+        format!("[{SD_ID}]")
+    } else {
+        format!("[{SD_ID} {}]", params.join(" "))
+    };
+
+    format!(
+        "<{}>1 {} {} panoptic {} {} {} {}",
+        FACILITY * 8 + syslog_severity(&record.action),
+        chrono::DateTime::<chrono::Utc>::from_timestamp(record.created_at, 0)
+            .unwrap_or_default()
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        header_field(hostname, 255),
+        std::process::id(),
+        header_field(&record.action, 32),
+        structured_data,
+        export::to_cef(record)
+    )
+}
+
+async fn send(config: &SyslogConfig, messages: &[String]) -> Result<()> {
+    let target = (config.host.trim(), config.port);
+    match config.protocol {
+        SyslogProtocol::Udp => {
+            let addr = lookup_host(target).await?.next().ok_or_else(|| {
+                Error::InvalidInput(format!("Syslog-Host {} nicht gefunden", config.host))
+            })?;
+            let local = if addr.is_ipv4() {
+                "0.0.0.0:0"
+            } else {
+                "[::]:0"
+            };
+            let socket = UdpSocket::bind(local).await?;
+            for message in messages {
+                socket.send_to(message.as_bytes(), addr).await?;
+            }
+        }
+        SyslogProtocol::Tcp => {
+            let mut stream = tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(target))
+                .await
+                .map_err(|_| {
+                    Error::Io(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        "Zeitüberschreitung beim Verbinden mit dem Syslog-Server",
+                    ))
+                })??;
+            for message in messages {
+                stream
+                    .write_all(format!("{} {message}", message.len()).as_bytes())
+                    .await?;
+            }
+            stream.flush().await?;
+        }
+    }
+    Ok(())
+}
+
+/// Sends the entries written since the last run. Returns how many were sent.
+async fn forward(app: &AppHandle) -> Result<usize> {
+    let db = app.state::<Database>();
+    let Some(config) = db.with_conn(load_config)?.filter(|c| c.enabled) else {
+        return Ok(0);
+    };
+    let hostname = tauri_plugin_os::hostname();
+
+    let mut sent = 0;
+    loop {
+        let records = db.with_conn(|conn| {
+            let cursor = match db::get_setting(conn, CURSOR_SETTING)?.and_then(|c| c.parse().ok()) {
+                Some(cursor) => cursor,
+                None => init_cursor(conn)?,
+            };
+            export::after(conn, cursor, BATCH_SIZE)
+        })?;
+        let Some(last) = records.last().and_then(|r| r.seq) else {
+            return Ok(sent);
+        };
+        let messages: Vec<String> = records
+            .iter()
+            .map(|r| format_message(r, &hostname))
+            .collect();
+        send(&config, &messages).await?;
+        db.with_conn(|conn| db::set_setting(conn, CURSOR_SETTING, &last.to_string()))?;
+        sent += records.len();
+        if records.len() < BATCH_SIZE as usize {
+            return Ok(sent);
+        }
+    }
+}
+
+/// Starts forwarding at the current end of the chain, so enabling syslog
+/// does not replay the whole history.
+fn init_cursor(conn: &rusqlite::Connection) -> Result<i64> {
+    let head: i64 = conn.query_row("SELECT COALESCE(MAX(seq), 0) FROM audit_log", [], |row| {
+        row.get(0)
+    })?;
+    db::set_setting(conn, CURSOR_SETTING, &head.to_string())?;
+    Ok(head)
+}
+
+/// Starts the background task that forwards new entries.
+pub fn spawn_forwarder(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(FORWARD_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if !app.state::<VaultState>().is_unlocked() {
+                continue;
+            }
+            if let Err(e) = forward(&app).await {
+                eprintln!("Syslog forwarding failed: {e}");
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_audit_syslog(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<Option<SyslogConfig>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(load_config)
+}
+
+#[tauri::command]
+pub async fn set_audit_syslog(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    config: SyslogConfig,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    config.validate()?;
+    let value = serde_json::to_string(&config).expect("serializable config");
+    db.with_audit(|conn, key| {
+        db::set_setting(conn, CONFIG_SETTING, &value)?;
+        if db::get_setting(conn, CURSOR_SETTING)?.is_none() {
+            init_cursor(conn)?;
+        }
+        super::log(
+            conn,
+            key,
+            super::SETTINGS_UPDATED,
+            Some("settings"),
+            Some(CONFIG_SETTING),
+            Some(&json!(config)),
+        )
+    })
+}
+
+/// Sends one test message to a collector before the configuration is saved.
+#[tauri::command]
+pub async fn test_audit_syslog(vault: State<'_, VaultState>, config: SyslogConfig) -> Result<()> {
+    vault.ensure_unlocked()?;
+    config.validate()?;
+    let record = Record {
+        seq: None,
+        id: super::new_id(),
+        action: "syslog_test".into(),
+        resource_type: None,
+        resource_id: None,
+        details: Some(json!({ "message": "Testnachricht von Panoptic" }).to_string()),
+        created_at: chrono::Utc::now().timestamp(),
+        hash: None,
+    };
+    send(
+        &config,
+        &[format_message(&record, &tauri_plugin_os::hostname())],
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    fn record() -> Record {
+        Record {
+            seq: Some(42),
+            id: "id-1".into(),
+            action: "secret_deleted".into(),
+            resource_type: Some("secret".into()),
+            resource_id: Some("a\"b]".into()),
+            details: Some(r#"{"name":"X"}"#.into()),
+            created_at: 1_741_600_000,
+            hash: Some("ff".into()),
+        }
+    }
+
+    fn config(protocol: SyslogProtocol, port: u16) -> SyslogConfig {
+        SyslogConfig {
+            enabled: true,
+            host: "127.0.0.1".into(),
+            port,
+            protocol,
+        }
+    }
+
+    #[test]
+    fn formats_rfc5424_with_structured_data() {
+        let message = format_message(&record(), "my host");
+        let prefix = format!(
+            "<108>1 2025-03-10T09:46:40Z myhost panoptic {} secret_deleted \
+             [panoptic@32473 seq=\"42\" resourceType=\"secret\" resourceId=\"a\\\"b\\]\" hash=\"ff\"] CEF:0|",
+            std::process::id()
+        );
+        assert!(message.starts_with(&prefix), "{message}");
+    }
+
+    #[tokio::test]
+    async fn sends_datagrams_over_udp() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        send(
+            &config(SyslogProtocol::Udp, port),
+            &["<110>1 a".into(), "<110>1 b".into()],
+        )
+        .await
+        .unwrap();
+
+        let mut buf = [0; 64];
+        let n = server.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"<110>1 a");
+        let n = server.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"<110>1 b");
+    }
+
+    #[tokio::test]
+    async fn frames_tcp_messages_with_octet_counts() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = String::new();
+            socket.read_to_string(&mut received).await.unwrap();
+            received
+        });
+
+        send(
+            &config(SyslogProtocol::Tcp, port),
+            &["<110>1 ä".into(), "<110>1 b".into()],
+        )
+        .await
+        .unwrap();
+        assert_eq!(server.await.unwrap(), "9 <110>1 ä8 <110>1 b");
+    }
+}
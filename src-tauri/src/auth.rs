@@ -0,0 +1,214 @@
+//! Master password hashing with Argon2id.
+//!
+//! Hashes are stored as PHC strings in the `masterPasswordHash` setting. Hashes
+//! written by the old webview code (SHA-256 with a static salt, "v1") are still
+//! accepted and replaced by an Argon2id hash after the next successful unlock.
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2, Params, Version};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::State;
+use zeroize::Zeroizing;
+
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+pub const PASSWORD_HASH_SETTING: &str = "masterPasswordHash";
+
+/// Optional override of the Argon2id cost parameters (JSON, see [`HashParams`]).
+pub const PASSWORD_HASH_PARAMS_SETTING: &str = "passwordHashParams";
+
+/// Static salt of the SHA-256 "v1" hashes computed by the webview.
+const LEGACY_PASSWORD_SALT: &str = "panoptic-salt-v1";
+
+/// Salt of the vault key. SQLCipher adds the random salt of the database file
+/// on top, so a fixed one is enough here.
+const VAULT_KEY_SALT: &[u8] = b"panoptic vault key v1";
+
+/// Cost of the vault key. Fixed, since other parameters would derive another
+/// key and lock existing databases out.
+const VAULT_KEY_PARAMS: HashParams = HashParams {
+    memory_kib: 64 * 1024,
+    iterations: 3,
+    parallelism: 1,
+};
+
+/// Argon2id cost parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for HashParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 1,
+        }
+    }
+}
+
+impl HashParams {
+    /// Reads the configured parameters, falling back to the defaults.
+    pub fn load(conn: &Connection) -> Result<Self> {
+        Ok(db::get_setting(conn, PASSWORD_HASH_PARAMS_SETTING)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, None)
+            .map_err(|e| Error::InvalidInput(format!("Ungültige Argon2-Parameter: {e}")))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+/// Derives the database key from the master password (Argon2id, hex). The
+/// vault keeps this key for the session instead of the password.
+pub fn derive_vault_key(password: &str) -> Result<Zeroizing<String>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    VAULT_KEY_PARAMS
+        .argon2()?
+        .hash_password_into(password.as_bytes(), VAULT_KEY_SALT, key.as_mut_slice())
+        .map_err(|e| Error::InvalidInput(format!("Schlüsselableitung fehlgeschlagen: {e}")))?;
+    Ok(Zeroizing::new(hex::encode(key.as_slice())))
+}
+
+/// Outcome of checking a password against a stored hash.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verification {
+    Invalid,
+    /// The password matches; `needs_rehash` is set for v1 hashes and for
+    /// Argon2id hashes with outdated cost parameters.
+    Valid {
+        needs_rehash: bool,
+    },
+}
+
+/// Hashes `password` into a PHC string with a fresh random salt.
+pub fn hash_password(password: &str, params: &HashParams) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = params
+        .argon2()?
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|e| Error::InvalidInput(format!("Passwort-Hashing fehlgeschlagen: {e}")))?;
+    Ok(hash.to_string())
+}
+
+/// Checks `password` against a stored v1 or Argon2id hash.
+pub fn verify_password(password: &str, stored: &str, params: &HashParams) -> Verification {
+    if is_legacy_hash(stored) {
+        return if legacy_hash(password) == stored.to_ascii_lowercase() {
+            Verification::Valid { needs_rehash: true }
+        } else {
+            Verification::Invalid
+        };
+    }
+
+    let Ok(hash) = PasswordHash::new(stored) else {
+        return Verification::Invalid;
+    };
+    if Argon2::default()
+        .verify_password(password.as_bytes(), &hash)
+        .is_err()
+    {
+        return Verification::Invalid;
+    }
+
+    let current = Params::try_from(&hash).ok();
+    let needs_rehash = hash.algorithm != Algorithm::Argon2id.ident()
+        || current.is_none_or(|p| {
+            (p.m_cost(), p.t_cost(), p.p_cost())
+                != (params.memory_kib, params.iterations, params.parallelism)
+        });
+    Verification::Valid { needs_rehash }
+}
+
+/// v1 hashes are bare hex SHA-256 digests, PHC strings start with `$`.
+fn is_legacy_hash(stored: &str) -> bool {
+    stored.len() == 64 && stored.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn legacy_hash(password: &str) -> String {
+    hex::encode(Sha256::digest(
+        format!("{password}{LEGACY_PASSWORD_SALT}").as_bytes(),
+    ))
+}
+
+pub fn stored_hash(conn: &Connection) -> Result<Option<String>> {
+    db::get_setting(conn, PASSWORD_HASH_SETTING)
+}
+
+/// Hashes `password` with the configured parameters and stores it.
+pub fn store_password(conn: &Connection, password: &str) -> Result<()> {
+    let hash = hash_password(password, &HashParams::load(conn)?)?;
+    db::set_setting(conn, PASSWORD_HASH_SETTING, &hash)
+}
+
+/// Verifies `password` against the stored hash and upgrades v1 or outdated
+/// hashes in place. Returns `true` when no hash has been stored yet.
+pub fn verify_and_upgrade(conn: &Connection, password: &str) -> Result<bool> {
+    let Some(stored) = stored_hash(conn)? else {
+        return Ok(true);
+    };
+    let params = HashParams::load(conn)?;
+    match verify_password(password, &stored, &params) {
+        Verification::Invalid => Ok(false),
+        Verification::Valid { needs_rehash } => {
+            if needs_rehash {
+                db::set_setting(
+                    conn,
+                    PASSWORD_HASH_SETTING,
+                    &hash_password(password, &params)?,
+                )?;
+            }
+            Ok(true)
+        }
+    }
+}
+
+/// Stores the hash of the initial master password. The database is re-keyed
+/// if it was unlocked with a different password. Changing an existing password
+/// goes through `change_database_key`.
+#[tauri::command]
+pub async fn set_master_password(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    password: String,
+) -> Result<()> {
+    let password = Zeroizing::new(password);
+    if password.is_empty() {
+        return Err(Error::InvalidInput(
+            "Das Passwort darf nicht leer sein".into(),
+        ));
+    }
+
+    db.with_conn(|conn| {
+        if stored_hash(conn)?.is_some() {
+            return Err(Error::InvalidInput(
+                "Es ist bereits ein Master-Passwort gesetzt".into(),
+            ));
+        }
+        Ok(())
+    })?;
+    db.rekey(&vault, &password)
+}
+
+#[tauri::command]
+pub async fn verify_master_password(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    password: String,
+) -> Result<bool> {
+    vault.ensure_unlocked()?;
+    let password = Zeroizing::new(password);
+    db.with_conn(|conn| verify_and_upgrade(conn, &password))
+}
@@ -0,0 +1,137 @@
+//! SQLCipher helpers for opening, converting and rekeying `panoptic.db`.
+
+use std::fs::{self, File};
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection, ErrorCode, OpenFlags, OptionalExtension};
+
+use crate::auth::{self, HashParams, Verification};
+use crate::error::{Error, Result};
+
+pub const DB_FILE_NAME: &str = "panoptic.db";
+
+/// First 16 bytes of every plaintext SQLite database.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+pub fn db_file(data_path: &Path) -> PathBuf {
+    data_path.join(DB_FILE_NAME)
+}
+
+/// Returns `true` if `file` exists and is not a plaintext SQLite database.
+pub fn is_encrypted(file: &Path) -> Result<bool> {
+    let mut header = Vec::with_capacity(SQLITE_HEADER.len());
+    match File::open(file) {
+        Ok(f) => f
+            .take(SQLITE_HEADER.len() as u64)
+            .read_to_end(&mut header)?,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    // SQLite creates empty files lazily, so a zero-length file is still plaintext.
+    Ok(!header.is_empty() && header.as_slice() != SQLITE_HEADER)
+}
+
+/// Opens an encrypted (or not yet existing) database with `key`.
+pub fn open_encrypted(file: &Path, key: &str) -> Result<Connection> {
+    let conn = Connection::open(file)?;
+    conn.pragma_update(None, "key", key)?;
+    check_key(&conn)?;
+    // A single file without -wal/-shm companions syncs cleanly through OneDrive.
+    conn.pragma_update(None, "journal_mode", "DELETE")?;
+    conn.pragma_update(None, "foreign_keys", true)?;
+    Ok(conn)
+}
+
+/// Opens a plaintext database without modifying it.
+pub fn open_plaintext_readonly(file: &Path) -> Result<Connection> {
+    Ok(Connection::open_with_flags(
+        file,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+/// SQLCipher only notices a wrong key on the first read.
+fn check_key(conn: &Connection) -> Result<()> {
+    match conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    }) {
+        Ok(_) => Ok(()),
+        Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == ErrorCode::NotADatabase => {
+            Err(Error::InvalidPassword)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Converts a plaintext database into a SQLCipher database keyed with `key`.
+///
+/// The encrypted copy is written next to the original and renamed over it, so an
+/// interrupted conversion never leaves a half-written `panoptic.db` behind.
+pub fn encrypt_in_place(file: &Path, key: &str) -> Result<()> {
+    let tmp = file.with_extension("db.encrypting");
+    if tmp.exists() {
+        fs::remove_file(&tmp)?;
+    }
+
+    {
+        let conn = Connection::open(file)?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            params![tmp.to_string_lossy(), key],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+    }
+
+    fs::rename(&tmp, file)?;
+
+    // Journal files of the plaintext database must not be replayed on the new one.
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let companion = PathBuf::from(format!("{}{}", file.display(), suffix));
+        if companion.exists() {
+            fs::remove_file(companion)?;
+        }
+    }
+    Ok(())
+}
+
+/// Re-encrypts an open database with `new_key`.
+pub fn rekey(conn: &Connection, new_key: &str) -> Result<()> {
+    conn.pragma_update(None, "rekey", new_key)?;
+    Ok(())
+}
+
+/// Reads the `masterPasswordHash` setting of a plaintext database, if any.
+pub fn legacy_password_hash(conn: &Connection) -> Result<Option<String>> {
+    let has_settings: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'settings')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_settings {
+        return Ok(None);
+    }
+    Ok(conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = 'masterPasswordHash'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Checks `password` against the hash stored in a plaintext database.
+/// Databases without a stored hash accept any password, databases with one
+/// reject a missing password.
+pub fn verify_legacy_password(file: &Path, password: Option<&str>) -> Result<bool> {
+    let conn = open_plaintext_readonly(file)?;
+    Ok(match (legacy_password_hash(&conn)?, password) {
+        (Some(stored), Some(password)) => {
+            auth::verify_password(password, &stored, &HashParams::default())
+                != Verification::Invalid
+        }
+        (Some(_), None) => false,
+        (None, _) => true,
+    })
+}
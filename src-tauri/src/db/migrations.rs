@@ -0,0 +1,370 @@
+//! Numbered schema migrations, tracked in `schema_version`.
+//!
+//! Every migration runs in its own transaction together with its
+//! `schema_version` row and audit entry, so a failing migration leaves the
+//! database at the previous version. Migrations are append-only: a released
+//! migration is never edited, changes go into a new one.
+
+use rusqlite::{params, Connection};
+use serde_json::json;
+
+use crate::audit::{self, ChainKey};
+use crate::error::{Error, Result};
+
+#[derive(Clone, Copy)]
+struct Migration {
+    version: u32,
+    description: &'static str,
+    sql: &'static str,
+}
+
+/// Version 1 is the schema from before versioning. It keeps `IF NOT EXISTS`
+/// so that it applies cleanly to databases created back then.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Ausgangsschema",
+        sql: r#"
+-- App-Einstellungen
+CREATE TABLE IF NOT EXISTS settings (
+  key TEXT PRIMARY KEY,
+  value TEXT NOT NULL,
+  updated_at INTEGER DEFAULT (unixepoch())
+);
+
+-- Gespeicherte API-Keys
+CREATE TABLE IF NOT EXISTS secrets (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  category TEXT NOT NULL,
+  provider TEXT NOT NULL,
+  value TEXT NOT NULL,
+  created_at INTEGER DEFAULT (unixepoch()),
+  rotated_at INTEGER,
+  last_used_at INTEGER
+);
+
+-- Provider-Konfigurationen
+CREATE TABLE IF NOT EXISTS providers (
+  id TEXT PRIMARY KEY,
+  type TEXT NOT NULL,
+  name TEXT NOT NULL,
+  enabled INTEGER DEFAULT 1,
+  config TEXT,
+  secret_id TEXT REFERENCES secrets(id),
+  created_at INTEGER DEFAULT (unixepoch())
+);
+
+-- Gecachte Usage-Daten
+CREATE TABLE IF NOT EXISTS usage_cache (
+  id TEXT PRIMARY KEY,
+  provider_id TEXT NOT NULL,
+  date TEXT NOT NULL,
+  data TEXT NOT NULL,
+  fetched_at INTEGER DEFAULT (unixepoch())
+);
+
+-- Audit Log
+CREATE TABLE IF NOT EXISTS audit_log (
+  id TEXT PRIMARY KEY,
+  action TEXT NOT NULL,
+  resource_type TEXT,
+  resource_id TEXT,
+  details TEXT,
+  created_at INTEGER DEFAULT (unixepoch())
+);
+
+-- Alert-Konfigurationen
+CREATE TABLE IF NOT EXISTS alerts (
+  id TEXT PRIMARY KEY,
+  name TEXT NOT NULL,
+  type TEXT,
+  provider_id TEXT,
+  threshold REAL,
+  channel TEXT,
+  channel_config TEXT,
+  enabled INTEGER DEFAULT 1,
+  created_at INTEGER DEFAULT (unixepoch())
+);
+
+-- Indizes
+CREATE INDEX IF NOT EXISTS idx_secrets_category ON secrets(category);
+CREATE INDEX IF NOT EXISTS idx_secrets_provider ON secrets(provider);
+CREATE INDEX IF NOT EXISTS idx_usage_cache_provider_date ON usage_cache(provider_id, date);
+CREATE INDEX IF NOT EXISTS idx_audit_log_created ON audit_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_action ON audit_log(action);
+"#,
+    },
+    Migration {
+        version: 2,
+        description: "Alert-Zustand und alert_events",
+        sql: r#"
+ALTER TABLE alerts ADD COLUMN period TEXT NOT NULL DEFAULT 'day';
+ALTER TABLE alerts ADD COLUMN state TEXT NOT NULL DEFAULT 'ok';
+ALTER TABLE alerts ADD COLUMN last_value REAL;
+ALTER TABLE alerts ADD COLUMN last_checked_at INTEGER;
+
+-- Auslösungen und Entwarnungen der Alerts
+CREATE TABLE alert_events (
+  id TEXT PRIMARY KEY,
+  alert_id TEXT NOT NULL,
+  state TEXT NOT NULL,
+  value REAL,
+  message TEXT NOT NULL,
+  created_at INTEGER DEFAULT (unixepoch())
+);
+
+CREATE INDEX idx_alert_events_alert ON alert_events(alert_id, created_at);
+CREATE INDEX idx_alert_events_created ON alert_events(created_at);
+"#,
+    },
+    Migration {
+        version: 3,
+        description: "Hash-Kette im Audit-Log",
+        sql: r#"
+ALTER TABLE audit_log ADD COLUMN seq INTEGER;
+ALTER TABLE audit_log ADD COLUMN prev_hash TEXT;
+ALTER TABLE audit_log ADD COLUMN hash TEXT;
+
+CREATE UNIQUE INDEX idx_audit_log_seq ON audit_log(seq);
+
+-- Signierte Anker nach dem Löschen alter Audit-Einträge
+CREATE TABLE audit_checkpoints (
+  seq INTEGER PRIMARY KEY,
+  hash TEXT NOT NULL,
+  pruned INTEGER NOT NULL,
+  created_at INTEGER NOT NULL,
+  signature TEXT NOT NULL
+);
+"#,
+    },
+    Migration {
+        version: 4,
+        description: "Versionen von Secrets",
+        sql: r#"
+-- Frühere Werte rotierter Secrets, neueste zuerst behalten
+CREATE TABLE secret_versions (
+  id TEXT PRIMARY KEY,
+  secret_id TEXT NOT NULL REFERENCES secrets(id) ON DELETE CASCADE,
+  version INTEGER NOT NULL,
+  value TEXT NOT NULL,
+  created_at INTEGER,
+  retired_at INTEGER NOT NULL DEFAULT (unixepoch()),
+  UNIQUE (secret_id, version)
+);
+"#,
+    },
+    Migration {
+        version: 5,
+        description: "Rotationsrichtlinien für Secrets",
+        sql: r#"
+ALTER TABLE secrets ADD COLUMN max_age_days INTEGER;
+ALTER TABLE secrets ADD COLUMN expires_at INTEGER;
+ALTER TABLE secrets ADD COLUMN rotation_state TEXT NOT NULL DEFAULT 'ok';
+"#,
+    },
+    Migration {
+        version: 6,
+        description: "DNS-Snapshots",
+        sql: r#"
+-- Gespeicherter Stand der DNS-Records einer Zone, gegen den Änderungen gemeldet werden
+CREATE TABLE dns_snapshots (
+  zone_id TEXT PRIMARY KEY,
+  zone_name TEXT NOT NULL,
+  secret_id TEXT NOT NULL REFERENCES secrets(id) ON DELETE CASCADE,
+  records TEXT NOT NULL,
+  created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+"#,
+    },
+    Migration {
+        version: 7,
+        description: "Letzter Abruffehler je Konto",
+        sql: r#"
+-- Fehler des letzten Abrufs, bis ein Abruf wieder gelingt
+CREATE TABLE sync_errors (
+  account_id TEXT PRIMARY KEY,
+  error TEXT NOT NULL,
+  error_kind TEXT,
+  failed_at INTEGER NOT NULL
+);
+"#,
+    },
+];
+
+/// From this version on audit entries are hash-chained. Its migration links
+/// the entries written before.
+const AUDIT_CHAIN_VERSION: u32 = 3;
+
+fn current_version(conn: &Connection) -> Result<u32> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+           version INTEGER PRIMARY KEY,
+           description TEXT NOT NULL,
+           applied_at INTEGER DEFAULT (unixepoch())
+         )",
+    )?;
+    Ok(conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Brings the database up to the last of [`MIGRATIONS`], refusing databases written by a
+/// newer version of the app. Returns the versions it applied.
+pub fn run(conn: &mut Connection, chain_key: &ChainKey) -> Result<Vec<u32>> {
+    apply(conn, chain_key, MIGRATIONS)
+}
+
+fn apply(
+    conn: &mut Connection,
+    chain_key: &ChainKey,
+    migrations: &[Migration],
+) -> Result<Vec<u32>> {
+    let current = current_version(conn)?;
+    let supported = migrations.last().map_or(0, |m| m.version);
+    if current > supported {
+        return Err(Error::SchemaTooNew {
+            found: current,
+            supported,
+        });
+    }
+
+    let mut applied = Vec::new();
+    for migration in migrations.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql)?;
+        tx.execute(
+            "INSERT INTO schema_version (version, description) VALUES (?1, ?2)",
+            params![migration.version, migration.description],
+        )?;
+        let resource_id = migration.version.to_string();
+        let details = json!({
+            "from": applied.last().copied().unwrap_or(current),
+            "to": migration.version,
+            "description": migration.description,
+        });
+        if migration.version < AUDIT_CHAIN_VERSION {
+            audit::log_unchained(
+                &tx,
+                audit::SCHEMA_MIGRATED,
+                Some("database"),
+                Some(&resource_id),
+                Some(&details),
+            )?;
+        } else {
+            if migration.version == AUDIT_CHAIN_VERSION {
+                audit::chain_existing(&tx, chain_key)?;
+            }
+            audit::log(
+                &tx,
+                chain_key,
+                audit::SCHEMA_MIGRATED,
+                Some("database"),
+                Some(&resource_id),
+                Some(&details),
+            )?;
+        }
+        tx.commit()?;
+        applied.push(migration.version);
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> ChainKey {
+        ChainKey::derive("test")
+    }
+
+    fn latest() -> u32 {
+        MIGRATIONS.last().unwrap().version
+    }
+
+    fn audit_entries(conn: &Connection) -> i64 {
+        conn.query_row(
+            "SELECT COUNT(*) FROM audit_log WHERE action = ?1",
+            [audit::SCHEMA_MIGRATED],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn applies_pending_migrations_once_with_audit_entries() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(
+            run(&mut conn, &key()).unwrap(),
+            (1..=latest()).collect::<Vec<_>>()
+        );
+        assert_eq!(current_version(&conn).unwrap(), latest());
+        assert_eq!(audit_entries(&conn), i64::from(latest()));
+
+        assert!(run(&mut conn, &key()).unwrap().is_empty());
+        assert_eq!(audit_entries(&conn), i64::from(latest()));
+    }
+
+    #[test]
+    fn chains_audit_entries_written_before_the_chain() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        apply(&mut conn, &key(), &MIGRATIONS[..2]).unwrap();
+        conn.execute(
+            "INSERT INTO audit_log (id, action, created_at) VALUES ('old', 'app_unlocked', 1000)",
+            [],
+        )
+        .unwrap();
+
+        run(&mut conn, &key()).unwrap();
+        let report = audit::verify(&conn, &key()).unwrap();
+        assert!(report.intact);
+        // One entry per migration plus the old one
+        assert_eq!(report.checked, u64::from(latest()) + 1);
+        let first: String = conn
+            .query_row("SELECT id FROM audit_log WHERE seq = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(first, "old");
+    }
+
+    #[test]
+    fn refuses_databases_from_newer_versions() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn, &key()).unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (version, description) VALUES (?1, 'Zukunft')",
+            [latest() + 1],
+        )
+        .unwrap();
+        assert!(matches!(
+            run(&mut conn, &key()),
+            Err(Error::SchemaTooNew { found, supported }) if found == latest() + 1 && supported == latest()
+        ));
+    }
+
+    #[test]
+    fn failed_migrations_roll_back() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrations = [
+            MIGRATIONS[0],
+            Migration {
+                version: 2,
+                description: "kaputt",
+                sql: "CREATE TABLE half_done (id TEXT); SELECT * FROM missing_table;",
+            },
+        ];
+        assert!(apply(&mut conn, &key(), &migrations).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        let half_done: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'half_done'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(half_done, 0);
+    }
+}
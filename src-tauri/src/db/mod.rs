@@ -0,0 +1,498 @@
+//! Rust-owned connection to `panoptic.db`, encrypted at rest with SQLCipher.
+//!
+//! The webview never opens the database file itself and cannot run SQL: it
+//! unlocks the database with the master password and then goes through typed
+//! commands, which keep secrets and the audit log out of its reach. The
+//! database is keyed with [`auth::derive_vault_key`] of the password, and only
+//! that key stays in the vault.
+
+mod cipher;
+mod migrations;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
+
+use crate::audit::{self, ChainKey};
+use crate::auth;
+use crate::error::{Error, Result};
+use crate::recovery;
+use crate::vault::{self, VaultState};
+
+/// Managed state holding the open connection. The key lives in
+/// [`VaultState`] and is only present while the app is unlocked.
+#[derive(Default)]
+pub struct Database {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    conn: Option<Connection>,
+    path: Option<PathBuf>,
+    chain_key: Option<ChainKey>,
+}
+
+impl Database {
+    /// Runs `f` against the open connection, failing with [`Error::Locked`]
+    /// while the database is locked or closed.
+    pub fn with_conn<T>(&self, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        let inner = self.lock();
+        let conn = inner.conn.as_ref().ok_or(Error::Locked)?;
+        f(conn)
+    }
+
+    /// Like [`Self::with_conn`], with the key of the audit chain for
+    /// [`audit::log`].
+    pub fn with_audit<T>(&self, f: impl FnOnce(&Connection, &ChainKey) -> Result<T>) -> Result<T> {
+        let inner = self.lock();
+        match (&inner.conn, &inner.chain_key) {
+            (Some(conn), Some(key)) => f(conn, key),
+            _ => Err(Error::Locked),
+        }
+    }
+
+    /// Directory of the open database.
+    pub fn data_path(&self) -> Result<PathBuf> {
+        let inner = self.lock();
+        match (&inner.conn, &inner.path) {
+            (Some(_), Some(path)) => Ok(path.clone()),
+            _ => Err(Error::Locked),
+        }
+    }
+
+    /// Re-encrypts the open database with the key of `new_password` unless it
+    /// already uses it, stores the hash of the new password and hands the key
+    /// to the vault.
+    pub fn rekey(&self, vault: &VaultState, new_password: &str) -> Result<()> {
+        let mut inner = self.lock();
+        let conn = inner.conn.as_ref().ok_or(Error::Locked)?;
+        let old_key = vault.key()?;
+        let new_key = auth::derive_vault_key(new_password)?;
+        if old_key == new_key {
+            return auth::store_password(conn, new_password);
+        }
+        inner.chain_key = Some(switch_key(
+            conn,
+            inner.path.as_deref(),
+            &old_key,
+            &new_key,
+            new_password,
+        )?);
+        vault.unlock(new_key);
+        Ok(())
+    }
+
+    /// Opens the database in `data_path` with the key recovered from the
+    /// recovery phrase, re-encrypts it with the key of `new_password` and
+    /// unlocks the vault with it.
+    pub fn recover(
+        &self,
+        vault: &VaultState,
+        data_path: &Path,
+        recovered_key: &str,
+        new_password: &str,
+    ) -> Result<()> {
+        let conn = open(data_path, recovered_key, None)?;
+        let new_key = auth::derive_vault_key(new_password)?;
+        let chain_key = switch_key(
+            &conn,
+            Some(data_path),
+            recovered_key,
+            &new_key,
+            new_password,
+        )?;
+
+        *self.lock() = Inner {
+            conn: Some(conn),
+            path: Some(data_path.to_path_buf()),
+            chain_key: Some(chain_key),
+        };
+        vault.unlock(new_key);
+        Ok(())
+    }
+
+    /// Drops the connection, e.g. when the vault locks or while the database
+    /// file is being copied to a new data path.
+    pub fn close(&self) {
+        *self.lock() = Inner::default();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Re-keys `conn` from `old_key` to `new_key`, re-signs the audit chain,
+/// stores the hash of `password` (the one `new_key` was derived from) and
+/// re-seals the recovery file. If any step after the re-key fails, the
+/// database goes back to `old_key` with its previous contents, so the password
+/// and recovery phrase that opened it keep working.
+fn switch_key(
+    conn: &Connection,
+    data_path: Option<&Path>,
+    old_key: &str,
+    new_key: &str,
+    password: &str,
+) -> Result<ChainKey> {
+    let chain_key = ChainKey::derive(new_key);
+    cipher::rekey(conn, new_key)?;
+    let mut resealed = false;
+    let result = (|| {
+        let tx = conn.unchecked_transaction()?;
+        audit::resign(&tx, &ChainKey::derive(old_key), &chain_key)?;
+        auth::store_password(&tx, password)?;
+        if let Some(path) = data_path {
+            recovery::reseal(path, new_key)?;
+            resealed = true;
+        }
+        tx.commit()?;
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        if let (true, Some(path)) = (resealed, data_path) {
+            recovery::reseal(path, old_key)?;
+        }
+        cipher::rekey(conn, old_key)?;
+        return Err(e);
+    }
+    Ok(chain_key)
+}
+
+/// Opens the database in `data_path` with `key`, creating it or converting a
+/// plaintext database in place when needed, and migrates it to the current
+/// schema. A plaintext database is only converted if `password` matches its
+/// stored hash.
+fn open(data_path: &Path, key: &str, password: Option<&str>) -> Result<Connection> {
+    std::fs::create_dir_all(data_path)?;
+    let file = cipher::db_file(data_path);
+
+    if !cipher::is_encrypted(&file)? && file.exists() {
+        if !cipher::verify_legacy_password(&file, password)? {
+            return Err(Error::InvalidPassword);
+        }
+        cipher::encrypt_in_place(&file, key)?;
+    }
+
+    let mut conn = cipher::open_encrypted(&file, key)?;
+    migrations::run(&mut conn, &ChainKey::derive(key))?;
+    Ok(conn)
+}
+
+/// In-memory database at the current schema, for tests.
+#[cfg(test)]
+pub fn open_in_memory(chain_key: &ChainKey) -> Connection {
+    let mut conn = Connection::open_in_memory().unwrap();
+    conn.pragma_update(None, "foreign_keys", true).unwrap();
+    migrations::run(&mut conn, chain_key).unwrap();
+    conn
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .optional()?)
+}
+
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value, updated_at) VALUES (?1, ?2, unixepoch())
+         ON CONFLICT(key) DO UPDATE SET value = ?2, updated_at = unixepoch()",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStatus {
+    path: String,
+    exists: bool,
+    encrypted: bool,
+    password_set: bool,
+    unlocked: bool,
+}
+
+#[tauri::command]
+pub fn database_status(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    data_path: String,
+) -> Result<DatabaseStatus> {
+    let data_path = PathBuf::from(data_path);
+    let file = cipher::db_file(&data_path);
+    let exists = file.exists();
+    let encrypted = cipher::is_encrypted(&file)?;
+    let password_set = encrypted
+        || (exists
+            && cipher::legacy_password_hash(&cipher::open_plaintext_readonly(&file)?)?.is_some());
+
+    let inner = db.lock();
+    let unlocked = vault.is_unlocked()
+        && inner.conn.is_some()
+        && inner.path.as_deref() == Some(data_path.as_path());
+
+    Ok(DatabaseStatus {
+        path: data_path.to_string_lossy().into_owned(),
+        exists,
+        encrypted,
+        password_set,
+        unlocked,
+    })
+}
+
+/// Opens the database with a key derived from the master password. Creates a
+/// new encrypted database or migrates a plaintext one on first use.
+#[tauri::command]
+pub async fn unlock_database(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    data_path: String,
+    password: String,
+) -> Result<()> {
+    let password = Zeroizing::new(password);
+    if password.is_empty() {
+        return Err(Error::InvalidPassword);
+    }
+    unlock(&db, &vault, PathBuf::from(data_path), &password)?;
+    vault::unlocked(&app);
+    Ok(())
+}
+
+/// [`unlock_database`] without the app handle. Databases still keyed with
+/// the password itself are re-keyed with the derived key.
+fn unlock(db: &Database, vault: &VaultState, data_path: PathBuf, password: &str) -> Result<()> {
+    let key = auth::derive_vault_key(password)?;
+    let conn = match open(&data_path, &key, Some(password)) {
+        Err(Error::InvalidPassword) if cipher::is_encrypted(&cipher::db_file(&data_path))? => {
+            let conn = open(&data_path, password, None)?;
+            switch_key(&conn, Some(&data_path), password, &key, password)?;
+            conn
+        }
+        result => result?,
+    };
+
+    // The database key is authoritative: upgrade v1 hashes and replace stale ones
+    if !auth::verify_and_upgrade(&conn, password)? {
+        auth::store_password(&conn, password)?;
+    }
+
+    *db.lock() = Inner {
+        conn: Some(conn),
+        path: Some(data_path),
+        chain_key: Some(ChainKey::derive(&key)),
+    };
+    vault.unlock(key);
+    Ok(())
+}
+
+/// Closes the connection but keeps the vault unlocked, e.g. while the
+/// database file is being copied to a new data path.
+#[tauri::command]
+pub fn close_database(db: State<'_, Database>) {
+    db.close();
+}
+
+/// (Re)opens the database in `data_path` with the key of the current session.
+#[tauri::command]
+pub async fn open_database(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    data_path: String,
+) -> Result<()> {
+    let key = vault.key()?;
+    let data_path = PathBuf::from(data_path);
+    let mut inner = db.lock();
+    if inner.conn.is_some() && inner.path.as_deref() == Some(data_path.as_path()) {
+        return Ok(());
+    }
+    let conn = open(&data_path, &key, None)?;
+    inner.conn = Some(conn);
+    inner.path = Some(data_path);
+    inner.chain_key = Some(ChainKey::derive(&key));
+    Ok(())
+}
+
+/// Re-encrypts the open database with the key of a new master password and
+/// stores the Argon2id hash of the new password.
+#[tauri::command]
+pub async fn change_database_key(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    current_password: String,
+    new_password: String,
+) -> Result<()> {
+    let current_password = Zeroizing::new(current_password);
+    let new_password = Zeroizing::new(new_password);
+    if new_password.is_empty() {
+        return Err(Error::InvalidInput(
+            "Das neue Passwort darf nicht leer sein".into(),
+        ));
+    }
+
+    if vault.key()? != auth::derive_vault_key(&current_password)? {
+        return Err(Error::InvalidPassword);
+    }
+    db.rekey(&vault, &new_password)
+}
+
+/// Accepts either a data directory or the path of a database file.
+#[tauri::command]
+pub fn is_database_encrypted(path: String) -> Result<bool> {
+    let path = PathBuf::from(path);
+    let file = if path.is_dir() {
+        cipher::db_file(&path)
+    } else {
+        path
+    };
+    cipher::is_encrypted(&file)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseInfo {
+    path: String,
+    exists: bool,
+    encrypted: bool,
+    secrets_count: i64,
+    settings_count: i64,
+}
+
+/// Describes the database in `data_path` without switching to it.
+#[tauri::command]
+pub async fn database_info_at(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    data_path: String,
+) -> Result<DatabaseInfo> {
+    let data_path = PathBuf::from(data_path);
+    let file = cipher::db_file(&data_path);
+    let mut info = DatabaseInfo {
+        path: data_path.to_string_lossy().into_owned(),
+        exists: file.exists(),
+        encrypted: cipher::is_encrypted(&file)?,
+        secrets_count: 0,
+        settings_count: 0,
+    };
+    if !info.exists {
+        return Ok(info);
+    }
+
+    let inner = db.lock();
+    let temp;
+    let conn = match &inner.conn {
+        Some(conn) if inner.path.as_deref() == Some(data_path.as_path()) => conn,
+        _ if info.encrypted => {
+            temp = cipher::open_encrypted(&file, &vault.key()?)?;
+            &temp
+        }
+        _ => {
+            temp = cipher::open_plaintext_readonly(&file)?;
+            &temp
+        }
+    };
+
+    // The target may not have any tables yet.
+    let count = |table: &str| {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get(0)
+        })
+        .unwrap_or(0)
+    };
+    info.secrets_count = count("secrets");
+    info.settings_count = count("settings");
+    Ok(info)
+}
+
+/// Settings the webview reads and writes. The others (password hash, syslog
+/// target, policies, ...) belong to their Rust commands.
+const WEBVIEW_SETTINGS: [&str; 5] = [
+    "dataPath",
+    "autoLockMinutes",
+    "theme",
+    "notificationsEnabled",
+    "setupCompleted",
+];
+
+/// The webview's settings with their stored values (JSON or plain strings).
+#[tauri::command]
+pub async fn list_settings(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<BTreeMap<String, String>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| {
+        let mut settings = BTreeMap::new();
+        for key in WEBVIEW_SETTINGS {
+            if let Some(value) = get_setting(conn, key)? {
+                settings.insert(key.to_owned(), value);
+            }
+        }
+        Ok(settings)
+    })
+}
+
+#[tauri::command]
+pub async fn save_setting(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    key: String,
+    value: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    if !WEBVIEW_SETTINGS.contains(&key.as_str()) {
+        return Err(Error::InvalidInput(format!(
+            "Unbekannte Einstellung: {key}"
+        )));
+    }
+    db.with_conn(|conn| set_setting(conn, &key, &value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_derived_key_and_rekeys_password_keyed_databases() {
+        let dir = std::env::temp_dir().join(format!("panoptic-db-{}", audit::new_id()));
+        // A database from before the derived key, keyed with the password itself
+        // and with its migrations chained under that password
+        drop(open(&dir, "passwort", None).unwrap());
+
+        let db = Database::default();
+        let vault = VaultState::default();
+        assert!(matches!(
+            unlock(&db, &vault, dir.clone(), "falsch"),
+            Err(Error::InvalidPassword)
+        ));
+        unlock(&db, &vault, dir.clone(), "passwort").unwrap();
+        let key = auth::derive_vault_key("passwort").unwrap();
+        assert_eq!(vault.key().unwrap(), key);
+        assert_ne!(vault.key().unwrap().as_str(), "passwort");
+        db.with_audit(|conn, chain_key| {
+            assert!(audit::verify(conn, chain_key)?.intact);
+            Ok(())
+        })
+        .unwrap();
+        db.close();
+
+        // Re-keyed: the derived key opens it, the password itself no longer does
+        assert!(cipher::open_encrypted(&cipher::db_file(&dir), &key).is_ok());
+        assert!(matches!(
+            cipher::open_encrypted(&cipher::db_file(&dir), "passwort"),
+            Err(Error::InvalidPassword)
+        ));
+        unlock(&db, &vault, dir.clone(), "passwort").unwrap();
+        db.close();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
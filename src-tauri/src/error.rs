@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Errors returned by Panoptic commands. Serialized as plain message strings
+/// so the frontend can show them directly.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Netzwerkfehler: {0}")]
+    Network(#[from] tauri_plugin_http::reqwest::Error),
+    #[error("Authentifizierung fehlgeschlagen: {0}")]
+    Unauthorized(String),
+    #[error("Keine Berechtigung: {0}")]
+    Forbidden(String),
+    #[error("Rate-Limit erreicht: {message}")]
+    RateLimited {
+        /// Seconds the provider asked to wait, if it said so.
+        retry_after: Option<u64>,
+        message: String,
+    },
+    #[error("Nicht gefunden: {0}")]
+    NotFound(String),
+    #[error("API-Fehler {status}: {message}")]
+    Api { status: u16, message: String },
+    #[error("Benachrichtigung fehlgeschlagen: {0}")]
+    Notification(String),
+    #[error("Zwischenablage nicht verfügbar: {0}")]
+    Clipboard(String),
+    #[error("Die Datenbank ist gesperrt")]
+    Locked,
+    #[error("Falsches Passwort")]
+    InvalidPassword,
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error(
+        "Die Datenbank stammt von einer neueren Panoptic-Version (Schema {found}, \
+         unterstützt bis {supported}). Bitte aktualisiere die App."
+    )]
+    SchemaTooNew { found: u32, supported: u32 },
+    #[error(
+        "Die Hash-Kette des Audit-Logs ist unterbrochen. Prüfe das Audit-Log, \
+         bevor alte Einträge gelöscht werden."
+    )]
+    AuditChainBroken,
+}
+
+/// Class of a failed provider request, for the UI to explain it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiErrorKind {
+    Auth,
+    Permission,
+    RateLimited,
+    NotFound,
+    Network,
+    Api,
+}
+
+impl Error {
+    /// Maps a non-2xx response to its typed error.
+    pub fn from_status(status: u16, message: String, retry_after: Option<u64>) -> Self {
+        match status {
+            401 => Self::Unauthorized(message),
+            403 => Self::Forbidden(message),
+            404 => Self::NotFound(message),
+            429 => Self::RateLimited {
+                retry_after,
+                message,
+            },
+            _ => Self::Api { status, message },
+        }
+    }
+
+    /// The class of a provider error, `None` for local errors.
+    pub fn api_kind(&self) -> Option<ApiErrorKind> {
+        match self {
+            Self::Network(_) => Some(ApiErrorKind::Network),
+            Self::Unauthorized(_) => Some(ApiErrorKind::Auth),
+            Self::Forbidden(_) => Some(ApiErrorKind::Permission),
+            Self::RateLimited { .. } => Some(ApiErrorKind::RateLimited),
+            Self::NotFound(_) => Some(ApiErrorKind::NotFound),
+            Self::Api { .. } => Some(ApiErrorKind::Api),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
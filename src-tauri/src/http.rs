@@ -0,0 +1,223 @@
+//! The one HTTP client for all outgoing requests.
+//!
+//! Requests share a connection pool with fixed timeouts, at most
+//! [`MAX_PER_HOST`] run against the same host at a time, and rate-limited or
+//! transient failures are retried with exponential backoff and jitter. A
+//! `Retry-After` sent by the server takes precedence over the backoff.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value as JsonValue;
+use tauri_plugin_http::reqwest::{header, Client, RequestBuilder, Response, StatusCode, Url};
+use tokio::sync::Semaphore;
+
+use crate::error::{Error, Result};
+
+const MAX_PER_HOST: usize = 4;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_RETRIES: u32 = 4;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Safety limit for paginated endpoints.
+pub const MAX_PAGES: usize = 10;
+
+struct Shared {
+    client: Client,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+fn shared() -> &'static Shared {
+    static SHARED: OnceLock<Shared> = OnceLock::new();
+    SHARED.get_or_init(|| Shared {
+        client: Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("valid HTTP client configuration"),
+        hosts: Mutex::default(),
+    })
+}
+
+fn host_limit(url: &Url) -> Arc<Semaphore> {
+    let host = url.host_str().unwrap_or_default().to_owned();
+    shared()
+        .hosts
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(host)
+        .or_insert_with(|| Arc::new(Semaphore::new(MAX_PER_HOST)))
+        .clone()
+}
+
+/// Statuses worth another attempt. 529 is Anthropic's "overloaded".
+fn is_transient(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 500 | 502 | 503 | 504 | 529)
+}
+
+/// The `Retry-After` of a response, given in seconds or as an HTTP date.
+pub fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+    let value = value.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Resolves `path` against the API at `base`. Only paths on the same host
+/// are accepted, so a key can never be sent anywhere else.
+pub fn api_url(base: &str, path: &str) -> Result<Url> {
+    let base = Url::parse(base).expect("valid API base URL");
+    if !path.starts_with('/') || path.starts_with("//") {
+        return Err(Error::InvalidInput(format!("Ungültiger API-Pfad: {path}")));
+    }
+    let url = base
+        .join(path)
+        .map_err(|e| Error::InvalidInput(format!("Ungültiger API-Pfad: {e}")))?;
+    if url.host_str() != base.host_str() || url.scheme() != "https" {
+        return Err(Error::InvalidInput(format!("Ungültiger API-Pfad: {path}")));
+    }
+    Ok(url)
+}
+
+/// Extracts the message of the common JSON error formats, or falls back to
+/// the start of the body.
+pub fn error_message(body: &JsonValue) -> String {
+    ["/error/message", "/errors/0/message", "/message"]
+        .iter()
+        .find_map(|pointer| body.pointer(pointer).and_then(JsonValue::as_str))
+        .map(str::to_owned)
+        .unwrap_or_else(|| {
+            let text = match body {
+                JsonValue::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            text.chars().take(200).collect()
+        })
+}
+
+/// Exponential backoff for `attempt` (starting at 0) with equal jitter: half
+/// of the delay is fixed, the other half random.
+fn backoff(attempt: u32) -> Duration {
+    let delay = BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF);
+    let half = delay.as_millis() as u64 / 2;
+    Duration::from_millis(half + fastrand::u64(0..=half))
+}
+
+/// Sends the request built by `build` against `url`, retrying 429 responses
+/// and, for `idempotent` requests, server and network errors. The last
+/// response is returned whatever its status.
+pub async fn send(
+    url: &Url,
+    idempotent: bool,
+    build: impl Fn(&Client) -> RequestBuilder,
+) -> Result<Response> {
+    let limit = host_limit(url);
+    let mut attempt = 0;
+    loop {
+        let result = {
+            let _permit = limit.acquire().await.expect("semaphore is never closed");
+            build(&shared().client).send().await
+        };
+        let wait = match &result {
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                retry_after(response).map(|wait| wait.min(MAX_BACKOFF))
+            }
+            Ok(response) if idempotent && is_transient(response.status()) => {
+                retry_after(response).map(|wait| wait.min(MAX_BACKOFF))
+            }
+            Err(e) if idempotent && (e.is_timeout() || e.is_connect()) => None,
+            _ => return Ok(result?),
+        };
+        if attempt == MAX_RETRIES {
+            return Ok(result?);
+        }
+        tokio::time::sleep(wait.unwrap_or_else(|| backoff(attempt))).await;
+        attempt += 1;
+    }
+}
+
+/// One page of a paginated response.
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_page: Option<String>,
+}
+
+/// Calls `fetch` with the cursor of the previous page until there is none.
+/// Fails when a cursor is still left after [`MAX_PAGES`] instead of returning
+/// a partial result.
+pub async fn paginate<T, F, Fut>(mut fetch: F) -> Result<Vec<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let page = fetch(cursor.take()).await?;
+        items.extend(page.items);
+        cursor = page.next_page;
+        if cursor.is_none() {
+            return Ok(items);
+        }
+    }
+    // A failed fetch, not bad input, for the UI and status alerts
+    Err(Error::Api {
+        status: 200,
+        message: format!("Mehr als {MAX_PAGES} Seiten – das Ergebnis wäre unvollständig"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiErrorKind;
+
+    #[test]
+    fn backoff_grows_with_jitter_and_is_capped() {
+        for attempt in 0..4 {
+            let full = BASE_BACKOFF * 2u32.pow(attempt);
+            let delay = backoff(attempt);
+            assert!(delay >= full / 2 && delay <= full, "{attempt}: {delay:?}");
+        }
+        assert!(backoff(30) <= MAX_BACKOFF);
+    }
+
+    async fn pages(total: usize) -> (Result<Vec<usize>>, usize) {
+        let mut calls = 0;
+        let result = paginate(|cursor| {
+            calls += 1;
+            let index = cursor.map_or(0, |c| c.parse::<usize>().unwrap());
+            async move {
+                Ok(Page {
+                    items: vec![index],
+                    next_page: (index + 1 < total).then(|| (index + 1).to_string()),
+                })
+            }
+        })
+        .await;
+        (result, calls)
+    }
+
+    #[tokio::test]
+    async fn pagination_fails_instead_of_truncating() {
+        let (result, calls) = pages(MAX_PAGES).await;
+        assert_eq!(result.unwrap(), (0..MAX_PAGES).collect::<Vec<_>>());
+        assert_eq!(calls, MAX_PAGES);
+
+        // The 11th page is never fetched, and the first ten are not returned
+        let (result, calls) = pages(MAX_PAGES + 1).await;
+        let error = result.unwrap_err();
+        assert_eq!(error.api_kind(), Some(ApiErrorKind::Api), "{error:?}");
+        assert_eq!(calls, MAX_PAGES);
+    }
+}
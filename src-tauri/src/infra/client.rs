@@ -0,0 +1,123 @@
+//! HTTP client bound to one service token.
+
+use serde_json::{json, Value as JsonValue};
+use tauri_plugin_http::reqwest::{header, Method};
+use zeroize::Zeroizing;
+
+use super::Service;
+use crate::error::{Error, Result};
+use crate::http;
+
+pub struct ServiceClient {
+    service: Service,
+    token: Zeroizing<String>,
+}
+
+impl ServiceClient {
+    pub fn new(service: Service, token: Zeroizing<String>) -> Self {
+        Self { service, token }
+    }
+
+    /// Sends a request through the shared client. Non-2xx responses fail with
+    /// their typed [`Error`]; network errors are stripped of the URL.
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&JsonValue>,
+        idempotent: bool,
+    ) -> Result<JsonValue> {
+        let url = self.service.url(path)?;
+        let body = body.map(JsonValue::to_string);
+        let response = http::send(&url, idempotent, |client| {
+            let mut request = client.request(method.clone(), url.clone()).query(query);
+            if let Some(body) = &body {
+                request = request
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(body.clone());
+            }
+            // Every service takes its token as a bearer token
+            request.bearer_auth(self.token.as_str())
+        })
+        .await
+        .map_err(|e| match e {
+            Error::Network(e) => Error::Network(e.without_url()),
+            e => e,
+        })?;
+
+        let status = response.status().as_u16();
+        let retry_after = http::retry_after(&response).map(|wait| wait.as_secs());
+        let text = response.text().await.map_err(|e| e.without_url())?;
+        let body = serde_json::from_str(&text).unwrap_or(JsonValue::String(text));
+        if (200..300).contains(&status) {
+            Ok(body)
+        } else {
+            Err(Error::from_status(
+                status,
+                http::error_message(&body),
+                retry_after,
+            ))
+        }
+    }
+
+    pub async fn get(&self, path: &str, query: &[(&str, String)]) -> Result<JsonValue> {
+        self.request(Method::GET, path, query, None, true).await
+    }
+
+    /// Sends a request that changes something; it is never retried.
+    pub async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&JsonValue>,
+    ) -> Result<JsonValue> {
+        self.request(method, path, query, body, false).await
+    }
+
+    /// Runs a GraphQL `query` and returns its `data`. Queries are retried
+    /// like GETs; pass `mutation` so a change is never sent twice.
+    pub async fn graphql(
+        &self,
+        path: &str,
+        query: &str,
+        variables: JsonValue,
+        mutation: bool,
+    ) -> Result<JsonValue> {
+        let body = json!({ "query": query, "variables": variables });
+        let response = self
+            .request(Method::POST, path, &[], Some(&body), !mutation)
+            .await?;
+        graphql_data(response)
+    }
+}
+
+/// GraphQL APIs answer errors with status 200 and an `errors` array.
+pub fn graphql_data(mut body: JsonValue) -> Result<JsonValue> {
+    if let Some(message) = body
+        .pointer("/errors/0/message")
+        .and_then(JsonValue::as_str)
+    {
+        let lower = message.to_ascii_lowercase();
+        return Err(
+            if lower.contains("not authorized") || lower.contains("authentication") {
+                Error::Unauthorized(message.to_owned())
+            } else if lower.contains("not found") {
+                Error::NotFound(message.to_owned())
+            } else {
+                Error::Api {
+                    status: 200,
+                    message: message.to_owned(),
+                }
+            },
+        );
+    }
+    match body.get_mut("data").map(JsonValue::take) {
+        Some(data) if !data.is_null() => Ok(data),
+        _ => Err(Error::Api {
+            status: 200,
+            message: "Antwort ohne Daten".into(),
+        }),
+    }
+}
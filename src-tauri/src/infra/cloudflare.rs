@@ -0,0 +1,784 @@
+//! Cloudflare zones, DNS records and Workers (API v4 and GraphQL analytics).
+//!
+//! The records of a zone can be saved as a snapshot in `dns_snapshots`. Later
+//! differences are shown in the webview and fire `dns` alert rules until the
+//! snapshot is saved again.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, SecondsFormat, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tauri::State;
+
+use super::client::ServiceClient;
+use super::{connect, logged, optional_str, write_audit, Service};
+use crate::audit;
+use crate::db::Database;
+use crate::error::{ApiErrorKind, Error, Result};
+use crate::http::{paginate, Page};
+use crate::vault::VaultState;
+
+const GRAPHQL_PATH: &str = "/client/v4/graphql";
+/// The largest `per_page` the zones endpoint accepts.
+const ZONES_PAGE_SIZE: u32 = 50;
+const DNS_PAGE_SIZE: u32 = 100;
+/// Changes listed per zone in an alert message.
+const MAX_LISTED_CHANGES: usize = 3;
+
+const WORKERS_QUERY: &str = r#"
+query WorkerInvocations($accountTag: string!, $since: Time!, $until: Time!) {
+  viewer {
+    accounts(filter: { accountTag: $accountTag }) {
+      workersInvocationsAdaptive(
+        limit: 1000
+        filter: { datetime_geq: $since, datetime_leq: $until }
+      ) {
+        sum { requests errors subrequests }
+        dimensions { scriptName }
+      }
+    }
+  }
+}
+"#;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudflareAccount {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudflareZone {
+    pub id: String,
+    pub name: String,
+    /// `active`, `pending`, `initializing`, `moved`, ...
+    pub status: String,
+    pub paused: bool,
+    pub plan: Option<String>,
+    pub account_id: Option<String>,
+    /// When the DNS snapshot of the zone was saved, if there is one.
+    pub snapshot_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsRecord {
+    pub id: String,
+    /// A, AAAA, CNAME, MX, TXT, ...
+    pub record_type: String,
+    pub name: String,
+    pub content: String,
+    pub proxied: bool,
+    /// 1 means automatic.
+    pub ttl: u64,
+    pub priority: Option<u64>,
+    pub comment: Option<String>,
+}
+
+impl DnsRecord {
+    /// Whether `other` resolves differently; comments do not count.
+    fn differs(&self, other: &Self) -> bool {
+        (
+            &self.record_type,
+            &self.name,
+            &self.content,
+            self.proxied,
+            self.ttl,
+            self.priority,
+        ) != (
+            &other.record_type,
+            &other.name,
+            &other.content,
+            other.proxied,
+            other.ttl,
+            other.priority,
+        )
+    }
+
+    fn value(&self) -> String {
+        if self.proxied {
+            format!("{} (Proxy)", self.content)
+        } else {
+            self.content.clone()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DnsChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A record that differs from the snapshot.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsChange {
+    pub kind: DnsChangeKind,
+    /// `None` for added records.
+    pub before: Option<DnsRecord>,
+    /// `None` for removed records.
+    pub after: Option<DnsRecord>,
+}
+
+impl DnsChange {
+    /// E.g. `CNAME api.example.com: a.example.net → b.example.net`.
+    pub fn describe(&self) -> String {
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => format!(
+                "{} {}: {} → {}",
+                after.record_type,
+                after.name,
+                before.value(),
+                after.value()
+            ),
+            (None, Some(record)) => {
+                format!(
+                    "neu {} {} {}",
+                    record.record_type,
+                    record.name,
+                    record.value()
+                )
+            }
+            (Some(record), None) => format!(
+                "entfernt {} {} {}",
+                record.record_type,
+                record.name,
+                record.value()
+            ),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsOverview {
+    pub records: Vec<DnsRecord>,
+    pub snapshot_at: Option<i64>,
+    /// Empty without a snapshot.
+    pub changes: Vec<DnsChange>,
+}
+
+/// The changes of one zone against its snapshot.
+#[derive(Debug, Clone)]
+pub struct ZoneDrift {
+    pub zone_name: String,
+    pub changes: Vec<DnsChange>,
+}
+
+impl ZoneDrift {
+    pub fn describe(&self) -> String {
+        let listed: Vec<String> = self
+            .changes
+            .iter()
+            .take(MAX_LISTED_CHANGES)
+            .map(DnsChange::describe)
+            .collect();
+        let more = self.changes.len().saturating_sub(MAX_LISTED_CHANGES);
+        let suffix = if more > 0 {
+            format!(" und {more} weitere")
+        } else {
+            String::new()
+        };
+        format!(
+            "{}: {} DNS-Änderungen ({}{suffix})",
+            self.zone_name,
+            self.changes.len(),
+            listed.join("; ")
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CloudflareWorker {
+    pub name: String,
+    pub created_on: Option<String>,
+    pub modified_on: Option<String>,
+    /// Within the last 24 hours; `None` if the token may not read analytics.
+    pub requests: Option<u64>,
+    pub errors: Option<u64>,
+}
+
+/// Unwraps the `result` of the v4 envelope, which can report errors with
+/// status 200.
+pub fn result(mut body: JsonValue) -> Result<JsonValue> {
+    if body["success"].as_bool() == Some(false) {
+        return Err(Error::Api {
+            status: 200,
+            message: body
+                .pointer("/errors/0/message")
+                .and_then(JsonValue::as_str)
+                .unwrap_or("Anfrage fehlgeschlagen")
+                .to_owned(),
+        });
+    }
+    match body.get_mut("result").map(JsonValue::take) {
+        Some(result) if !result.is_null() => Ok(result),
+        _ => Err(Service::Cloudflare.unexpected()),
+    }
+}
+
+/// One page of a list endpoint. The cursor is the number of the next page,
+/// taken from `result_info`.
+pub fn list_page<T>(body: JsonValue, parse: fn(&JsonValue) -> Result<Vec<T>>) -> Result<Page<T>> {
+    let info = &body["result_info"];
+    let next_page = match (info["page"].as_u64(), info["total_pages"].as_u64()) {
+        (Some(page), Some(total)) if page < total => Some((page + 1).to_string()),
+        _ => None,
+    };
+    Ok(Page {
+        items: parse(&result(body)?)?,
+        next_page,
+    })
+}
+
+/// Fetches every page of a list endpoint.
+async fn get_all<T>(
+    client: &ServiceClient,
+    path: &str,
+    per_page: u32,
+    parse: fn(&JsonValue) -> Result<Vec<T>>,
+) -> Result<Vec<T>> {
+    paginate(|page| {
+        let query = [
+            ("per_page", per_page.to_string()),
+            ("page", page.unwrap_or_else(|| "1".into())),
+        ];
+        async move { list_page(client.get(path, &query).await?, parse) }
+    })
+    .await
+}
+
+fn entries(result: &JsonValue) -> Result<&Vec<JsonValue>> {
+    result
+        .as_array()
+        .ok_or_else(|| Service::Cloudflare.unexpected())
+}
+
+pub fn parse_accounts(result: &JsonValue) -> Result<Vec<CloudflareAccount>> {
+    Ok(entries(result)?
+        .iter()
+        .filter_map(|account| {
+            Some(CloudflareAccount {
+                id: account["id"].as_str()?.to_owned(),
+                name: account["name"].as_str().unwrap_or_default().to_owned(),
+            })
+        })
+        .collect())
+}
+
+pub fn parse_zones(result: &JsonValue) -> Result<Vec<CloudflareZone>> {
+    Ok(entries(result)?
+        .iter()
+        .filter_map(|zone| {
+            Some(CloudflareZone {
+                id: zone["id"].as_str()?.to_owned(),
+                name: zone["name"].as_str()?.to_owned(),
+                status: zone["status"].as_str().unwrap_or("unknown").to_owned(),
+                paused: zone["paused"].as_bool().unwrap_or(false),
+                plan: optional_str(&zone["plan"]["name"]),
+                account_id: optional_str(&zone["account"]["id"]),
+                snapshot_at: None,
+            })
+        })
+        .collect())
+}
+
+/// Records sorted by name and type, so snapshots and lists read alike.
+pub fn parse_dns_records(result: &JsonValue) -> Result<Vec<DnsRecord>> {
+    let mut records: Vec<DnsRecord> = entries(result)?
+        .iter()
+        .filter_map(|record| {
+            Some(DnsRecord {
+                id: record["id"].as_str()?.to_owned(),
+                record_type: record["type"].as_str()?.to_owned(),
+                name: record["name"].as_str()?.to_owned(),
+                content: record["content"].as_str().unwrap_or_default().to_owned(),
+                proxied: record["proxied"].as_bool().unwrap_or(false),
+                ttl: record["ttl"].as_u64().unwrap_or(1),
+                priority: record["priority"].as_u64(),
+                comment: optional_str(&record["comment"]),
+            })
+        })
+        .collect();
+    records.sort_by(|a, b| (&a.name, &a.record_type).cmp(&(&b.name, &b.record_type)));
+    Ok(records)
+}
+
+/// Joins the scripts with their invocations of the analytics query.
+/// `analytics` is `None` if it could not be read.
+pub fn parse_workers(
+    result: &JsonValue,
+    analytics: Option<&JsonValue>,
+) -> Result<Vec<CloudflareWorker>> {
+    let mut totals: HashMap<&str, (u64, u64)> = HashMap::new();
+    let groups = analytics
+        .and_then(|data| data.pointer("/viewer/accounts/0/workersInvocationsAdaptive"))
+        .and_then(JsonValue::as_array);
+    for group in groups.into_iter().flatten() {
+        let Some(script) = group["dimensions"]["scriptName"].as_str() else {
+            continue;
+        };
+        let total = totals.entry(script).or_default();
+        total.0 += group["sum"]["requests"].as_u64().unwrap_or_default();
+        total.1 += group["sum"]["errors"].as_u64().unwrap_or_default();
+    }
+    Ok(entries(result)?
+        .iter()
+        .filter_map(|script| {
+            let name = script["id"].as_str()?;
+            let total = analytics.map(|_| totals.get(name).copied().unwrap_or_default());
+            Some(CloudflareWorker {
+                name: name.to_owned(),
+                created_on: optional_str(&script["created_on"]),
+                modified_on: optional_str(&script["modified_on"]),
+                requests: total.map(|t| t.0),
+                errors: total.map(|t| t.1),
+            })
+        })
+        .collect())
+}
+
+/// Records added, removed or changed since `snapshot`, matched by id.
+pub fn diff(snapshot: &[DnsRecord], current: &[DnsRecord]) -> Vec<DnsChange> {
+    let before: BTreeMap<&str, &DnsRecord> = snapshot.iter().map(|r| (r.id.as_str(), r)).collect();
+    let after: BTreeMap<&str, &DnsRecord> = current.iter().map(|r| (r.id.as_str(), r)).collect();
+    let mut changes: Vec<DnsChange> = current
+        .iter()
+        .filter_map(|record| match before.get(record.id.as_str()) {
+            None => Some(DnsChange {
+                kind: DnsChangeKind::Added,
+                before: None,
+                after: Some(record.clone()),
+            }),
+            Some(old) if old.differs(record) => Some(DnsChange {
+                kind: DnsChangeKind::Changed,
+                before: Some((*old).clone()),
+                after: Some(record.clone()),
+            }),
+            Some(_) => None,
+        })
+        .collect();
+    changes.extend(
+        snapshot
+            .iter()
+            .filter(|record| !after.contains_key(record.id.as_str()))
+            .map(|record| DnsChange {
+                kind: DnsChangeKind::Removed,
+                before: Some(record.clone()),
+                after: None,
+            }),
+    );
+    changes
+}
+
+async fn fetch_dns_records(client: &ServiceClient, zone_id: &str) -> Result<Vec<DnsRecord>> {
+    let path = format!("/client/v4/zones/{zone_id}/dns_records");
+    get_all(client, &path, DNS_PAGE_SIZE, parse_dns_records).await
+}
+
+struct Snapshot {
+    zone_name: String,
+    secret_id: String,
+    records: Vec<DnsRecord>,
+    created_at: i64,
+}
+
+fn load_snapshot(conn: &Connection, zone_id: &str) -> Result<Option<Snapshot>> {
+    let row = conn
+        .query_row(
+            "SELECT zone_name, secret_id, records, created_at FROM dns_snapshots WHERE zone_id = ?1",
+            [zone_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            },
+        )
+        .optional()?;
+    Ok(
+        row.map(|(zone_name, secret_id, records, created_at)| Snapshot {
+            zone_name,
+            secret_id,
+            // An unreadable snapshot reports every record as added
+            records: serde_json::from_str(&records).unwrap_or_default(),
+            created_at,
+        }),
+    )
+}
+
+fn snapshot_zones(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT zone_id FROM dns_snapshots ORDER BY zone_name")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+fn store_snapshot(
+    conn: &Connection,
+    zone_id: &str,
+    zone_name: &str,
+    secret_id: &str,
+    records: &[DnsRecord],
+) -> Result<i64> {
+    let records = serde_json::to_string(records).expect("serializable records");
+    Ok(conn.query_row(
+        "INSERT OR REPLACE INTO dns_snapshots (zone_id, zone_name, secret_id, records, created_at)
+         VALUES (?1, ?2, ?3, ?4, unixepoch())
+         RETURNING created_at",
+        params![zone_id, zone_name, secret_id, records],
+        |row| row.get(0),
+    )?)
+}
+
+/// Compares every zone with a snapshot against its current records. Zones
+/// whose token fails are skipped.
+pub async fn dns_drift(db: &Database) -> Vec<ZoneDrift> {
+    let zones = match db.with_conn(snapshot_zones) {
+        Ok(zones) => zones,
+        Err(e) => {
+            eprintln!("Failed to load DNS snapshots: {e}");
+            return Vec::new();
+        }
+    };
+    let mut drifts = Vec::new();
+    for zone_id in zones {
+        let Ok(Some(snapshot)) = db.with_conn(|conn| load_snapshot(conn, &zone_id)) else {
+            continue;
+        };
+        let current = async {
+            let client = connect(db, Service::Cloudflare, &snapshot.secret_id)?;
+            logged(
+                db,
+                Service::Cloudflare,
+                &snapshot.secret_id,
+                "dns_records",
+                fetch_dns_records(&client, &zone_id),
+            )
+            .await
+        };
+        match current.await {
+            Ok(records) => {
+                let changes = diff(&snapshot.records, &records);
+                if !changes.is_empty() {
+                    drifts.push(ZoneDrift {
+                        zone_name: snapshot.zone_name,
+                        changes,
+                    });
+                }
+            }
+            Err(e) => eprintln!("Failed to check DNS of {}: {e}", snapshot.zone_name),
+        }
+    }
+    drifts
+}
+
+#[tauri::command]
+pub async fn list_cloudflare_accounts(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+) -> Result<Vec<CloudflareAccount>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Cloudflare, &secret_id)?;
+    logged(&db, Service::Cloudflare, &secret_id, "accounts", async {
+        parse_accounts(&result(client.get("/client/v4/accounts", &[]).await?)?)
+    })
+    .await
+}
+
+/// Zones of the token with the time of their DNS snapshot.
+#[tauri::command]
+pub async fn list_cloudflare_zones(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+) -> Result<Vec<CloudflareZone>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Cloudflare, &secret_id)?;
+    let mut zones = logged(
+        &db,
+        Service::Cloudflare,
+        &secret_id,
+        "zones",
+        get_all(&client, "/client/v4/zones", ZONES_PAGE_SIZE, parse_zones),
+    )
+    .await?;
+    db.with_conn(|conn| {
+        for zone in &mut zones {
+            zone.snapshot_at = load_snapshot(conn, &zone.id)?.map(|s| s.created_at);
+        }
+        Ok(())
+    })?;
+    Ok(zones)
+}
+
+/// DNS records of a zone and their differences to its snapshot.
+#[tauri::command]
+pub async fn get_cloudflare_dns(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    zone_id: String,
+) -> Result<DnsOverview> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Cloudflare, &secret_id)?;
+    let records = logged(
+        &db,
+        Service::Cloudflare,
+        &secret_id,
+        "dns_records",
+        fetch_dns_records(&client, &zone_id),
+    )
+    .await?;
+    let snapshot = db.with_conn(|conn| load_snapshot(conn, &zone_id))?;
+    Ok(DnsOverview {
+        changes: snapshot
+            .as_ref()
+            .map(|s| diff(&s.records, &records))
+            .unwrap_or_default(),
+        snapshot_at: snapshot.map(|s| s.created_at),
+        records,
+    })
+}
+
+/// Saves the current records of a zone as its snapshot, accepting all
+/// changes since the last one. Returns when it was saved.
+#[tauri::command]
+pub async fn save_dns_snapshot(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    zone_id: String,
+    zone_name: String,
+) -> Result<i64> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Cloudflare, &secret_id)?;
+    let records = logged(
+        &db,
+        Service::Cloudflare,
+        &secret_id,
+        "dns_records",
+        fetch_dns_records(&client, &zone_id),
+    )
+    .await?;
+    let saved_at =
+        db.with_conn(|conn| store_snapshot(conn, &zone_id, &zone_name, &secret_id, &records))?;
+    let details = json!({
+        "provider": Service::Cloudflare.id(),
+        "name": zone_name,
+        "zoneId": zone_id,
+        "records": records.len(),
+    });
+    write_audit(&db, audit::DNS_SNAPSHOT_SAVED, &secret_id, &details)?;
+    Ok(saved_at)
+}
+
+/// Stops watching a zone.
+#[tauri::command]
+pub async fn delete_dns_snapshot(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    zone_id: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let snapshot = db
+        .with_conn(|conn| {
+            let snapshot = load_snapshot(conn, &zone_id)?;
+            conn.execute("DELETE FROM dns_snapshots WHERE zone_id = ?1", [&zone_id])?;
+            Ok(snapshot)
+        })?
+        .ok_or_else(|| Error::NotFound(format!("DNS-Snapshot {zone_id}")))?;
+    let details = json!({
+        "provider": Service::Cloudflare.id(),
+        "name": snapshot.zone_name,
+        "zoneId": zone_id,
+    });
+    write_audit(
+        &db,
+        audit::DNS_SNAPSHOT_DELETED,
+        &snapshot.secret_id,
+        &details,
+    )
+}
+
+/// Workers scripts of an account with their requests and errors of the
+/// last 24 hours.
+#[tauri::command]
+pub async fn list_cloudflare_workers(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    account_id: String,
+) -> Result<Vec<CloudflareWorker>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Cloudflare, &secret_id)?;
+    let now = Utc::now();
+    let variables = json!({
+        "accountTag": account_id,
+        "since": (now - Duration::hours(24)).to_rfc3339_opts(SecondsFormat::Secs, true),
+        "until": now.to_rfc3339_opts(SecondsFormat::Secs, true),
+    });
+    logged(&db, Service::Cloudflare, &secret_id, "workers", async {
+        let scripts = client
+            .get(
+                &format!("/client/v4/accounts/{account_id}/workers/scripts"),
+                &[],
+            )
+            .await?;
+        let analytics = match client
+            .graphql(GRAPHQL_PATH, WORKERS_QUERY, variables, false)
+            .await
+        {
+            Ok(data) => Some(data),
+            Err(e)
+                if matches!(
+                    e.api_kind(),
+                    Some(ApiErrorKind::Auth | ApiErrorKind::Permission)
+                ) =>
+            {
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        parse_workers(&result(scripts)?, analytics.as_ref())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::client::graphql_data;
+    use super::super::fixture;
+    use super::*;
+
+    fn records() -> Vec<DnsRecord> {
+        parse_dns_records(&result(fixture("cloudflare", "dns_records")).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn follows_result_info_across_pages() {
+        let mut pages = Vec::new();
+        let all = paginate(|page| {
+            let page = page.unwrap_or_else(|| "1".into());
+            pages.push(page.clone());
+            let body = fixture("cloudflare", &format!("dns_records_page_{page}"));
+            async move { list_page(body, parse_dns_records) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(pages, ["1", "2"]);
+        let ids = |records: &[DnsRecord]| -> Vec<String> {
+            records.iter().map(|r| r.id.clone()).collect()
+        };
+        assert_eq!(ids(&all), ids(&records()));
+    }
+
+    #[test]
+    fn parses_zones_records_and_envelope_errors() {
+        let zones = parse_zones(&result(fixture("cloudflare", "zones")).unwrap()).unwrap();
+        let names: Vec<_> = zones.iter().map(|z| (z.name.as_str(), z.paused)).collect();
+        assert_eq!(names, [("panoptic.dev", false), ("old-shop.de", true)]);
+        assert_eq!(zones[0].plan.as_deref(), Some("Free Website"));
+
+        let accounts = parse_accounts(&result(fixture("cloudflare", "accounts")).unwrap()).unwrap();
+        assert_eq!(accounts[0].name, "Panoptic");
+
+        let records = records();
+        let listed: Vec<_> = records
+            .iter()
+            .map(|r| format!("{} {}", r.record_type, r.name))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                "CNAME api.panoptic.dev",
+                "A panoptic.dev",
+                "MX panoptic.dev"
+            ]
+        );
+        assert_eq!(records[2].priority, Some(10));
+
+        let error = result(fixture("cloudflare", "error")).unwrap_err();
+        assert!(
+            error.to_string().contains("Authentication error"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn flags_added_removed_and_changed_records() {
+        let snapshot = records();
+        let mut current = snapshot.clone();
+        current[0].content = "panoptic-api.vercel.app".into();
+        current[1].comment = Some("Landing page".into());
+        let removed = current.remove(2);
+        current.push(DnsRecord {
+            id: "new".into(),
+            record_type: "TXT".into(),
+            name: "_verify.panoptic.dev".into(),
+            content: "token=abc".into(),
+            proxied: false,
+            ttl: 1,
+            priority: None,
+            comment: None,
+        });
+
+        let changes = diff(&snapshot, &current);
+        let kinds: Vec<_> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                DnsChangeKind::Changed,
+                DnsChangeKind::Added,
+                DnsChangeKind::Removed
+            ]
+        );
+        assert_eq!(changes[2].before.as_ref(), Some(&removed));
+        assert_eq!(
+            changes[0].describe(),
+            "CNAME api.panoptic.dev: panoptic-api.up.railway.app → panoptic-api.vercel.app"
+        );
+
+        let drift = ZoneDrift {
+            zone_name: "panoptic.dev".into(),
+            changes,
+        };
+        assert!(drift
+            .describe()
+            .starts_with("panoptic.dev: 3 DNS-Änderungen"));
+        assert!(diff(&snapshot, &snapshot).is_empty());
+    }
+
+    #[test]
+    fn joins_workers_with_their_invocations() {
+        let scripts = result(fixture("cloudflare", "workers")).unwrap();
+        let analytics = graphql_data(fixture("cloudflare", "analytics")).unwrap();
+
+        let workers = parse_workers(&scripts, Some(&analytics)).unwrap();
+        let counts: Vec<_> = workers
+            .iter()
+            .map(|w| (w.name.as_str(), w.requests, w.errors))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("og-image", Some(18_300), Some(37)),
+                ("nightly-cleanup", Some(0), Some(0))
+            ]
+        );
+
+        let without = parse_workers(&scripts, None).unwrap();
+        assert_eq!(without[0].requests, None);
+    }
+}
@@ -0,0 +1,403 @@
+//! Infrastructure services: hosting, databases and DNS.
+//!
+//! As with the LLM providers, the webview only passes the id of a secret. The
+//! token is read from the database and attached to requests in Rust. Reads
+//! are logged as `api_call`/`api_error`, actions that change something (a
+//! redeploy, ...) under their own audit action.
+//!
+//! Services with plan limits report their consumption as [`LimitUsage`], which
+//! `limit` alert rules are evaluated against, and keep a daily snapshot of it
+//! in `usage_cache`.
+
+mod client;
+pub mod cloudflare;
+pub mod neon;
+pub mod railway;
+pub mod supabase;
+pub mod vercel;
+
+use std::future::Future;
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tauri::State;
+use tauri_plugin_http::reqwest::Url;
+use zeroize::Zeroizing;
+
+use self::client::ServiceClient;
+use crate::audit;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::http;
+use crate::secrets;
+use crate::vault::VaultState;
+
+/// Services with an integration, matched by the `provider` of a secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Service {
+    Railway,
+    Vercel,
+    #[serde(rename = "neondb")]
+    Neon,
+    Supabase,
+    Cloudflare,
+}
+
+impl Service {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "railway" => Some(Self::Railway),
+            "vercel" => Some(Self::Vercel),
+            "neondb" | "neon" => Some(Self::Neon),
+            "supabase" => Some(Self::Supabase),
+            "cloudflare" => Some(Self::Cloudflare),
+            _ => None,
+        }
+    }
+
+    /// The `provider` value stored with secrets of this service.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Railway => "railway",
+            Self::Vercel => "vercel",
+            Self::Neon => "neondb",
+            Self::Supabase => "supabase",
+            Self::Cloudflare => "cloudflare",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Railway => "Railway",
+            Self::Vercel => "Vercel",
+            Self::Neon => "NeonDB",
+            Self::Supabase => "Supabase",
+            Self::Cloudflare => "Cloudflare",
+        }
+    }
+
+    fn base_url(self) -> &'static str {
+        match self {
+            Self::Railway => "https://backboard.railway.app",
+            Self::Vercel => "https://api.vercel.com",
+            Self::Neon => "https://console.neon.tech",
+            Self::Supabase => "https://api.supabase.com",
+            Self::Cloudflare => "https://api.cloudflare.com",
+        }
+    }
+
+    fn url(self, path: &str) -> Result<Url> {
+        http::api_url(self.base_url(), path)
+    }
+
+    /// For a response that lacks the fields its documentation promises.
+    fn unexpected(self) -> Error {
+        Error::InvalidInput(format!("Unerwartete Antwort von {}", self.label()))
+    }
+
+    fn parse(name: &str) -> Result<Self> {
+        Self::from_name(name)
+            .ok_or_else(|| Error::InvalidInput(format!("Dienst '{name}' wird nicht unterstützt")))
+    }
+}
+
+/// A string field of a response, `None` if it is missing or empty.
+fn optional_str(value: &JsonValue) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(str::to_owned)
+}
+
+/// A file from `tests/fixtures/<dir>`.
+#[cfg(test)]
+fn fixture_text(dir: &str, name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{dir}/{name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(path).unwrap()
+}
+
+/// A JSON response from `tests/fixtures/<dir>/<name>.json`.
+#[cfg(test)]
+fn fixture(dir: &str, name: &str) -> JsonValue {
+    serde_json::from_str(&fixture_text(dir, &format!("{name}.json"))).unwrap()
+}
+
+/// Builds a client for `secret_id`, which must be a token of `service`.
+fn connect(db: &Database, service: Service, secret_id: &str) -> Result<ServiceClient> {
+    let secret = db
+        .with_conn(|conn| secrets::use_secret(conn, secret_id))?
+        .ok_or_else(|| Error::InvalidInput("Secret nicht gefunden".into()))?;
+    if Service::from_name(&secret.provider) != Some(service) {
+        return Err(Error::InvalidInput(format!(
+            "'{}' ist kein {}-Token",
+            secret.name,
+            service.label()
+        )));
+    }
+    Ok(ServiceClient::new(service, Zeroizing::new(secret.value)))
+}
+
+fn write_audit(db: &Database, action: &str, secret_id: &str, details: &JsonValue) -> Result<()> {
+    db.with_audit(|conn, key| {
+        audit::log(
+            conn,
+            key,
+            action,
+            Some("infrastructure"),
+            Some(secret_id),
+            Some(details),
+        )
+    })
+}
+
+/// Runs `request` and logs `action` with `details`, or `api_error` if it
+/// fails.
+async fn audited<T>(
+    db: &Database,
+    secret_id: &str,
+    action: &str,
+    details: JsonValue,
+    request: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let result = request.await;
+    let (action, details) = match &result {
+        Ok(_) => (action, details),
+        Err(e) => (
+            audit::API_ERROR,
+            json!({ "request": details, "error": e.to_string() }),
+        ),
+    };
+    if let Err(e) = write_audit(db, action, secret_id, &details) {
+        eprintln!("Failed to write audit log: {e}");
+    }
+    result
+}
+
+/// Runs a read request and logs it as `api_call` or `api_error`.
+async fn logged<T>(
+    db: &Database,
+    service: Service,
+    secret_id: &str,
+    operation: &str,
+    request: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let details = json!({ "provider": service.id(), "operation": operation });
+    audited(db, secret_id, audit::API_CALL, details, request).await
+}
+
+/// What a plan limit restricts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitMetric {
+    /// Bytes.
+    Storage,
+    /// Seconds of compute within the billing period.
+    ComputeTime,
+}
+
+/// Consumption of one plan limit.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitUsage {
+    pub service: Service,
+    /// Project, branch or account the limit applies to.
+    pub resource: String,
+    pub metric: LimitMetric,
+    pub used: f64,
+    pub limit: f64,
+}
+
+impl LimitUsage {
+    pub fn percent(&self) -> f64 {
+        self.used / self.limit * 100.0
+    }
+
+    /// E.g. `NeonDB · shop/main: Storage 0.41 GiB von 0.50 GiB (82%)`.
+    pub fn describe(&self) -> String {
+        let (label, used, limit) = match self.metric {
+            LimitMetric::Storage => {
+                const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+                (
+                    "Storage",
+                    format!("{:.2} GiB", self.used / GIB),
+                    format!("{:.2} GiB", self.limit / GIB),
+                )
+            }
+            LimitMetric::ComputeTime => (
+                "Compute-Zeit",
+                format!("{:.1} h", self.used / 3600.0),
+                format!("{:.1} h", self.limit / 3600.0),
+            ),
+        };
+        format!(
+            "{} · {}: {label} {used} von {limit} ({:.0}%)",
+            self.service.label(),
+            self.resource,
+            self.percent()
+        )
+    }
+}
+
+/// Fetches the plan limits of every stored token of a service that has
+/// them. A failing token is skipped, so one revoked key does not hide the
+/// others.
+pub async fn collect_limits(db: &Database) -> Vec<LimitUsage> {
+    let secrets = match db.with_conn(|conn| secrets::secrets_by_provider(conn, Service::Neon.id()))
+    {
+        Ok(secrets) => secrets,
+        Err(e) => {
+            eprintln!("Failed to load service keys: {e}");
+            return Vec::new();
+        }
+    };
+    let mut limits = Vec::new();
+    for secret in secrets {
+        match neon::refresh(db, &secret.id).await {
+            Ok(overview) => limits.extend(overview.limits),
+            Err(e) => eprintln!("Failed to fetch limits of {}: {e}", secret.name),
+        }
+    }
+    limits
+}
+
+/// `usage_cache.provider_id` of a service token. The prefix is no LLM
+/// provider, so `providers::cache` skips these rows.
+fn snapshot_account(service: Service, secret_id: &str) -> String {
+    format!("{}:{secret_id}", service.id())
+}
+
+/// Stores the snapshot of `date`, replacing an earlier one of the same day.
+fn store_snapshot(
+    conn: &Connection,
+    service: Service,
+    secret_id: &str,
+    date: NaiveDate,
+    data: &impl Serialize,
+    fetched_at: i64,
+) -> Result<()> {
+    let account = snapshot_account(service, secret_id);
+    let data = serde_json::to_string(data).expect("serializable snapshot");
+    conn.execute(
+        "INSERT OR REPLACE INTO usage_cache (id, provider_id, date, data, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            format!("{account}:{date}"),
+            account,
+            date.to_string(),
+            data,
+            fetched_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// Snapshots since `since`, oldest first. Unreadable rows are skipped.
+fn load_snapshots<T: DeserializeOwned>(
+    conn: &Connection,
+    service: Service,
+    secret_id: &str,
+    since: NaiveDate,
+) -> Result<Vec<(NaiveDate, T)>> {
+    let mut stmt = conn.prepare(
+        "SELECT date, data FROM usage_cache
+         WHERE provider_id = ?1 AND date >= ?2 ORDER BY date",
+    )?;
+    let rows = stmt.query_map(
+        params![snapshot_account(service, secret_id), since.to_string()],
+        |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+    )?;
+    let mut snapshots = Vec::new();
+    for row in rows {
+        let (date, data) = row?;
+        if let (Ok(date), Ok(data)) = (date.parse(), serde_json::from_str(&data)) {
+            snapshots.push((date, data));
+        }
+    }
+    Ok(snapshots)
+}
+
+/// A token of an infrastructure service, without its value.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceKey {
+    id: String,
+    name: String,
+    provider: String,
+}
+
+/// Lists the tokens stored for `service` so the webview can pick one by id.
+#[tauri::command]
+pub async fn list_service_keys(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    service: String,
+) -> Result<Vec<ServiceKey>> {
+    vault.ensure_unlocked()?;
+    let service = Service::parse(&service)?;
+    let rows = db.with_conn(|conn| secrets::secrets_by_provider(conn, service.id()))?;
+    Ok(rows
+        .into_iter()
+        .map(|secret| ServiceKey {
+            id: secret.id,
+            name: secret.name,
+            provider: secret.provider,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_urls_stay_on_the_service_host() {
+        let url = Service::Railway.url("/graphql/v2").unwrap();
+        assert_eq!(url.as_str(), "https://backboard.railway.app/graphql/v2");
+        assert!(Service::Railway.url("//evil.example/graphql").is_err());
+        assert!(Service::Railway.url("https://evil.example").is_err());
+    }
+
+    #[test]
+    fn snapshots_are_kept_apart_from_llm_usage() {
+        let conn = crate::db::open_in_memory(&crate::audit::ChainKey::derive("test"));
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        store_snapshot(
+            &conn,
+            Service::Neon,
+            "key",
+            day("2025-03-09"),
+            &json!({ "v": 1 }),
+            1,
+        )
+        .unwrap();
+        store_snapshot(
+            &conn,
+            Service::Neon,
+            "key",
+            day("2025-03-10"),
+            &json!({ "v": 2 }),
+            2,
+        )
+        .unwrap();
+        store_snapshot(
+            &conn,
+            Service::Neon,
+            "key",
+            day("2025-03-10"),
+            &json!({ "v": 3 }),
+            3,
+        )
+        .unwrap();
+
+        let snapshots: Vec<(NaiveDate, JsonValue)> =
+            load_snapshots(&conn, Service::Neon, "key", day("2025-03-01")).unwrap();
+        let values: Vec<_> = snapshots
+            .iter()
+            .map(|(_, data)| data["v"].as_i64())
+            .collect();
+        assert_eq!(values, [Some(1), Some(3)]);
+        // providers::cache skips rows whose prefix is no LLM provider
+        assert!(crate::providers::Provider::from_name(Service::Neon.id()).is_none());
+    }
+}
@@ -0,0 +1,458 @@
+//! NeonDB projects, branches, compute endpoints and consumption (API v2).
+//!
+//! Works with personal API keys; organization keys cannot read `/users/me`
+//! and report no account limit. Every refresh stores the consumption of the
+//! day in `usage_cache`, so the storage trend can be charted.
+
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tauri::State;
+
+use super::client::ServiceClient;
+use super::{
+    connect, load_snapshots, logged, optional_str, store_snapshot, LimitMetric, LimitUsage, Service,
+};
+use crate::db::Database;
+use crate::error::{ApiErrorKind, Result};
+use crate::http::{paginate, Page};
+use crate::vault::VaultState;
+
+const PROJECTS_PAGE_SIZE: usize = 100;
+const MAX_HISTORY_DAYS: u32 = 90;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeonBranch {
+    pub id: String,
+    pub name: String,
+    pub default: bool,
+    /// `ready` or `init`.
+    pub state: Option<String>,
+    pub logical_size_bytes: Option<u64>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeonEndpoint {
+    pub id: String,
+    pub branch_id: String,
+    /// `read_write` or `read_only`.
+    pub kind: String,
+    /// `active`, `idle` or `init`.
+    pub state: String,
+    /// Idle endpoints are scaled to zero and wake on the next connection.
+    pub suspended: bool,
+    pub host: Option<String>,
+    pub min_cu: Option<f64>,
+    pub max_cu: Option<f64>,
+    pub last_active: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeonProject {
+    pub id: String,
+    pub name: String,
+    pub region: Option<String>,
+    pub pg_version: Option<u64>,
+    /// Billed storage including history.
+    pub storage_bytes: u64,
+    /// Limit of the logical size of each branch.
+    pub branch_size_limit_bytes: Option<u64>,
+    /// Within the current billing period, like the values below.
+    pub compute_seconds: u64,
+    pub compute_limit_seconds: Option<u64>,
+    pub active_seconds: u64,
+    pub written_bytes: u64,
+    pub transfer_bytes: u64,
+    pub period_end: Option<String>,
+    pub branches: Vec<NeonBranch>,
+    pub endpoints: Vec<NeonEndpoint>,
+}
+
+/// The account behind a key as of `/users/me`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeonPlan {
+    /// `free`, `launch`, `scale`, `business`, ...
+    pub name: Option<String>,
+    pub projects_limit: Option<u64>,
+    pub branches_limit: Option<u64>,
+    /// Active compute time included per month.
+    pub active_seconds_limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeonOverview {
+    pub plan: NeonPlan,
+    pub projects: Vec<NeonProject>,
+    pub limits: Vec<LimitUsage>,
+}
+
+/// Consumption of one project in a daily snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeonProjectUsage {
+    pub id: String,
+    pub name: String,
+    pub storage_bytes: u64,
+    pub compute_seconds: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NeonSnapshot {
+    projects: Vec<NeonProjectUsage>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NeonHistoryDay {
+    pub date: String,
+    pub projects: Vec<NeonProjectUsage>,
+}
+
+/// Neon reports unset limits as 0.
+fn limit(value: &JsonValue) -> Option<u64> {
+    value.as_u64().filter(|&limit| limit > 0)
+}
+
+pub fn parse_plan(body: &JsonValue) -> NeonPlan {
+    NeonPlan {
+        name: optional_str(&body["plan"]),
+        projects_limit: limit(&body["projects_limit"]),
+        branches_limit: limit(&body["branches_limit"]),
+        active_seconds_limit: limit(&body["active_seconds_limit"]),
+    }
+}
+
+/// Neon always returns the id of the last project as the cursor; only a
+/// full page can be followed by another one.
+pub fn parse_project_ids(body: &JsonValue, page_size: usize) -> Result<Page<String>> {
+    let projects = body["projects"]
+        .as_array()
+        .ok_or_else(|| Service::Neon.unexpected())?;
+    let items: Vec<String> = projects
+        .iter()
+        .filter_map(|project| project["id"].as_str().map(str::to_owned))
+        .collect();
+    let next_page = if projects.len() >= page_size {
+        optional_str(&body["pagination"]["cursor"])
+    } else {
+        None
+    };
+    Ok(Page { items, next_page })
+}
+
+pub fn parse_branches(body: &JsonValue) -> Vec<NeonBranch> {
+    body["branches"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|branch| {
+            Some(NeonBranch {
+                id: branch["id"].as_str()?.to_owned(),
+                name: branch["name"].as_str().unwrap_or_default().to_owned(),
+                // `primary` is the older name of `default`
+                default: branch["default"]
+                    .as_bool()
+                    .or_else(|| branch["primary"].as_bool())
+                    .unwrap_or(false),
+                state: optional_str(&branch["current_state"]),
+                logical_size_bytes: branch["logical_size"].as_u64(),
+                updated_at: optional_str(&branch["updated_at"]),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_endpoints(body: &JsonValue) -> Vec<NeonEndpoint> {
+    body["endpoints"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|endpoint| {
+            let state = endpoint["current_state"].as_str().unwrap_or("init");
+            Some(NeonEndpoint {
+                id: endpoint["id"].as_str()?.to_owned(),
+                branch_id: endpoint["branch_id"].as_str()?.to_owned(),
+                kind: endpoint["type"].as_str().unwrap_or("read_write").to_owned(),
+                state: state.to_owned(),
+                suspended: state == "idle",
+                host: optional_str(&endpoint["host"]),
+                min_cu: endpoint["autoscaling_limit_min_cu"].as_f64(),
+                max_cu: endpoint["autoscaling_limit_max_cu"].as_f64(),
+                last_active: optional_str(&endpoint["last_active"]),
+            })
+        })
+        .collect()
+}
+
+/// Parses `/projects/{id}`, which carries the consumption and quotas the
+/// project list lacks.
+pub fn parse_project(
+    body: &JsonValue,
+    branches: Vec<NeonBranch>,
+    endpoints: Vec<NeonEndpoint>,
+) -> Result<NeonProject> {
+    let project = &body["project"];
+    let quota = &project["settings"]["quota"];
+    let branch_limit = limit(&project["branch_logical_size_limit_bytes"]);
+    let count = |key: &str| project[key].as_u64().unwrap_or_default();
+    Ok(NeonProject {
+        id: project["id"]
+            .as_str()
+            .ok_or_else(|| Service::Neon.unexpected())?
+            .to_owned(),
+        name: project["name"].as_str().unwrap_or_default().to_owned(),
+        region: optional_str(&project["region_id"]),
+        pg_version: project["pg_version"].as_u64(),
+        storage_bytes: count("synthetic_storage_size"),
+        // A project quota can only lower the limit of the plan
+        branch_size_limit_bytes: match (branch_limit, limit(&quota["logical_size_bytes"])) {
+            (Some(plan), Some(quota)) => Some(plan.min(quota)),
+            (plan, quota) => plan.or(quota),
+        },
+        compute_seconds: count("compute_time_seconds"),
+        compute_limit_seconds: limit(&quota["compute_time_seconds"]),
+        active_seconds: count("active_time_seconds"),
+        written_bytes: count("written_data_bytes"),
+        transfer_bytes: count("data_transfer_bytes"),
+        period_end: optional_str(&project["consumption_period_end"]),
+        branches,
+        endpoints,
+    })
+}
+
+/// The limits of the plan and of project quotas with their consumption: the
+/// logical size of every branch, the compute time of projects with a quota
+/// and the active time of the whole account.
+pub fn limits(plan: &NeonPlan, projects: &[NeonProject]) -> Vec<LimitUsage> {
+    let usage = |resource: String, metric, used: u64, limit: u64| LimitUsage {
+        service: Service::Neon,
+        resource,
+        metric,
+        used: used as f64,
+        limit: limit as f64,
+    };
+    let mut limits = Vec::new();
+    for project in projects {
+        if let Some(limit) = project.branch_size_limit_bytes {
+            limits.extend(project.branches.iter().filter_map(|branch| {
+                Some(usage(
+                    format!("{}/{}", project.name, branch.name),
+                    LimitMetric::Storage,
+                    branch.logical_size_bytes?,
+                    limit,
+                ))
+            }));
+        }
+        if let Some(limit) = project.compute_limit_seconds {
+            limits.push(usage(
+                project.name.clone(),
+                LimitMetric::ComputeTime,
+                project.compute_seconds,
+                limit,
+            ));
+        }
+    }
+    if let Some(limit) = plan.active_seconds_limit {
+        let active = projects.iter().map(|p| p.active_seconds).sum();
+        limits.push(usage(
+            "Konto".into(),
+            LimitMetric::ComputeTime,
+            active,
+            limit,
+        ));
+    }
+    limits
+}
+
+async fn fetch_overview(client: &ServiceClient) -> Result<NeonOverview> {
+    let plan = match client.get("/api/v2/users/me", &[]).await {
+        Ok(body) => parse_plan(&body),
+        Err(e)
+            if matches!(
+                e.api_kind(),
+                Some(ApiErrorKind::NotFound | ApiErrorKind::Permission)
+            ) =>
+        {
+            NeonPlan::default()
+        }
+        Err(e) => return Err(e),
+    };
+    let ids = paginate(|cursor| {
+        let mut query = vec![("limit", PROJECTS_PAGE_SIZE.to_string())];
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+        async move {
+            let body = client.get("/api/v2/projects", &query).await?;
+            parse_project_ids(&body, PROJECTS_PAGE_SIZE)
+        }
+    })
+    .await?;
+    let mut projects = Vec::with_capacity(ids.len());
+    for id in ids {
+        let detail = client.get(&format!("/api/v2/projects/{id}"), &[]).await?;
+        let branches = client
+            .get(&format!("/api/v2/projects/{id}/branches"), &[])
+            .await?;
+        let endpoints = client
+            .get(&format!("/api/v2/projects/{id}/endpoints"), &[])
+            .await?;
+        projects.push(parse_project(
+            &detail,
+            parse_branches(&branches),
+            parse_endpoints(&endpoints),
+        )?);
+    }
+    let limits = limits(&plan, &projects);
+    Ok(NeonOverview {
+        plan,
+        projects,
+        limits,
+    })
+}
+
+/// Fetches the overview of `secret_id` and stores the consumption of today.
+/// The alert engine calls this for `limit` rules.
+pub async fn refresh(db: &Database, secret_id: &str) -> Result<NeonOverview> {
+    let client = connect(db, Service::Neon, secret_id)?;
+    let overview = logged(
+        db,
+        Service::Neon,
+        secret_id,
+        "projects",
+        fetch_overview(&client),
+    )
+    .await?;
+    let snapshot = NeonSnapshot {
+        projects: overview
+            .projects
+            .iter()
+            .map(|project| NeonProjectUsage {
+                id: project.id.clone(),
+                name: project.name.clone(),
+                storage_bytes: project.storage_bytes,
+                compute_seconds: project.compute_seconds,
+            })
+            .collect(),
+    };
+    let now = Utc::now();
+    db.with_conn(|conn| {
+        store_snapshot(
+            conn,
+            Service::Neon,
+            secret_id,
+            now.date_naive(),
+            &snapshot,
+            now.timestamp(),
+        )
+    })?;
+    Ok(overview)
+}
+
+/// Projects with branches, endpoints and consumption against the limits.
+#[tauri::command]
+pub async fn get_neon_overview(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+) -> Result<NeonOverview> {
+    vault.ensure_unlocked()?;
+    refresh(&db, &secret_id).await
+}
+
+/// Daily consumption of the last `days` days from `usage_cache`, oldest
+/// first. Days without a refresh are missing.
+#[tauri::command]
+pub async fn get_neon_storage_history(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    days: u32,
+) -> Result<Vec<NeonHistoryDay>> {
+    vault.ensure_unlocked()?;
+    let days = days.clamp(1, MAX_HISTORY_DAYS);
+    let since = Utc::now().date_naive() - Duration::days(i64::from(days) - 1);
+    let snapshots = db
+        .with_conn(|conn| load_snapshots::<NeonSnapshot>(conn, Service::Neon, &secret_id, since))?;
+    Ok(snapshots
+        .into_iter()
+        .map(|(date, snapshot)| NeonHistoryDay {
+            date: date.to_string(),
+            projects: snapshot.projects,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixture;
+    use super::*;
+
+    fn shop() -> NeonProject {
+        parse_project(
+            &fixture("neon", "project"),
+            parse_branches(&fixture("neon", "branches")),
+            parse_endpoints(&fixture("neon", "endpoints")),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_projects_with_branches_and_endpoints() {
+        let page = parse_project_ids(&fixture("neon", "projects"), 100).unwrap();
+        assert_eq!(page.items, ["shiny-wind-028834", "calm-river-441207"]);
+        assert_eq!(page.next_page, None);
+        // A full page is followed by the next one
+        let page = parse_project_ids(&fixture("neon", "projects"), 2).unwrap();
+        assert_eq!(page.next_page.as_deref(), Some("calm-river-441207"));
+
+        let project = shop();
+        assert_eq!(project.name, "shop");
+        assert_eq!(project.storage_bytes, 498_073_600);
+        assert_eq!(project.branch_size_limit_bytes, Some(536_870_912));
+        assert_eq!(project.compute_seconds, 300_000);
+        assert_eq!(project.compute_limit_seconds, Some(360_000));
+        assert_eq!(project.period_end.as_deref(), Some("2025-04-01T00:00:00Z"));
+
+        let branches: Vec<_> = project.branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(branches, ["main", "preview/checkout"]);
+        assert!(project.branches[0].default);
+
+        let suspended: Vec<_> = project.endpoints.iter().map(|e| e.suspended).collect();
+        assert_eq!(suspended, [false, true]);
+        assert!(parse_project_ids(&serde_json::json!({ "message": "x" }), 100).is_err());
+    }
+
+    #[test]
+    fn reports_consumption_against_plan_and_quota_limits() {
+        let plan = parse_plan(&fixture("neon", "me"));
+        assert_eq!(plan.name.as_deref(), Some("free"));
+        assert_eq!(plan.active_seconds_limit, Some(360_000));
+
+        let limits = limits(&plan, &[shop()]);
+        let percents: Vec<_> = limits
+            .iter()
+            .map(|l| (l.resource.as_str(), l.percent().round()))
+            .collect();
+        assert_eq!(
+            percents,
+            [
+                ("shop/main", 86.0),
+                ("shop/preview/checkout", 7.0),
+                ("shop", 83.0),
+                ("Konto", 83.0),
+            ]
+        );
+        assert_eq!(
+            limits[0].describe(),
+            "NeonDB · shop/main: Storage 0.43 GiB von 0.50 GiB (86%)"
+        );
+        assert!(super::limits(&NeonPlan::default(), &[]).is_empty());
+    }
+}
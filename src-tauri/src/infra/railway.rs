@@ -0,0 +1,337 @@
+//! Railway projects, services and deployments (GraphQL API v2).
+//!
+//! Works with account and team tokens; project tokens use a different header
+//! and are not supported.
+
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use tauri::State;
+
+use super::{audited, connect, logged, optional_str, Service};
+use crate::audit;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+const GRAPHQL_PATH: &str = "/graphql/v2";
+const RECENT_DEPLOYMENTS: u32 = 10;
+
+const PROJECTS_QUERY: &str = r#"
+query Projects {
+  projects {
+    edges {
+      node {
+        id
+        name
+        description
+        updatedAt
+        services { edges { node { id name icon } } }
+        environments {
+          edges {
+            node {
+              id
+              name
+              serviceInstances {
+                edges {
+                  node {
+                    serviceId
+                    latestDeployment { id status createdAt staticUrl meta }
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+const DEPLOYMENTS_QUERY: &str = r#"
+query Deployments($input: DeploymentListInput!, $first: Int) {
+  deployments(input: $input, first: $first) {
+    edges { node { id status createdAt staticUrl meta } }
+  }
+}
+"#;
+
+const REDEPLOY_MUTATION: &str = r#"
+mutation Redeploy($environmentId: String!, $serviceId: String!) {
+  serviceInstanceRedeploy(environmentId: $environmentId, serviceId: $serviceId)
+}
+"#;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RailwayDeployment {
+    pub id: String,
+    /// SUCCESS, FAILED, CRASHED, BUILDING, DEPLOYING, ...
+    pub status: String,
+    pub created_at: Option<String>,
+    pub url: Option<String>,
+    pub commit_message: Option<String>,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RailwayEnvironment {
+    pub id: String,
+    pub name: String,
+}
+
+/// A service in one environment, with its latest deployment.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RailwayServiceInstance {
+    pub service_id: String,
+    pub service_name: String,
+    pub icon: Option<String>,
+    pub environment_id: String,
+    pub environment_name: String,
+    pub latest_deployment: Option<RailwayDeployment>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RailwayProject {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub updated_at: Option<String>,
+    pub environments: Vec<RailwayEnvironment>,
+    pub instances: Vec<RailwayServiceInstance>,
+}
+
+/// The nodes of a Relay connection.
+fn nodes(connection: &JsonValue) -> impl Iterator<Item = &JsonValue> {
+    connection["edges"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|edge| &edge["node"])
+}
+
+fn parse_deployment(node: &JsonValue) -> Option<RailwayDeployment> {
+    Some(RailwayDeployment {
+        id: node["id"].as_str()?.to_owned(),
+        status: node["status"].as_str().unwrap_or("UNKNOWN").to_owned(),
+        created_at: optional_str(&node["createdAt"]),
+        url: optional_str(&node["staticUrl"]).map(|host| format!("https://{host}")),
+        commit_message: optional_str(&node["meta"]["commitMessage"]),
+        branch: optional_str(&node["meta"]["branch"]),
+    })
+}
+
+pub fn parse_projects(data: &JsonValue) -> Result<Vec<RailwayProject>> {
+    if !data["projects"].is_object() {
+        return Err(Error::InvalidInput(
+            "Unerwartete Antwort von Railway".into(),
+        ));
+    }
+    let projects = nodes(&data["projects"])
+        .filter_map(|project| {
+            let services: Vec<(&str, &str, Option<String>)> = nodes(&project["services"])
+                .filter_map(|service| {
+                    Some((
+                        service["id"].as_str()?,
+                        service["name"].as_str()?,
+                        optional_str(&service["icon"]),
+                    ))
+                })
+                .collect();
+            let mut environments = Vec::new();
+            let mut instances = Vec::new();
+            for environment in nodes(&project["environments"]) {
+                let (Some(env_id), Some(env_name)) =
+                    (environment["id"].as_str(), environment["name"].as_str())
+                else {
+                    continue;
+                };
+                environments.push(RailwayEnvironment {
+                    id: env_id.to_owned(),
+                    name: env_name.to_owned(),
+                });
+                for instance in nodes(&environment["serviceInstances"]) {
+                    let Some(service) = services
+                        .iter()
+                        .find(|(id, _, _)| Some(*id) == instance["serviceId"].as_str())
+                    else {
+                        continue;
+                    };
+                    instances.push(RailwayServiceInstance {
+                        service_id: service.0.to_owned(),
+                        service_name: service.1.to_owned(),
+                        icon: service.2.clone(),
+                        environment_id: env_id.to_owned(),
+                        environment_name: env_name.to_owned(),
+                        latest_deployment: parse_deployment(&instance["latestDeployment"]),
+                    });
+                }
+            }
+            Some(RailwayProject {
+                id: project["id"].as_str()?.to_owned(),
+                name: project["name"].as_str()?.to_owned(),
+                description: optional_str(&project["description"]),
+                updated_at: optional_str(&project["updatedAt"]),
+                environments,
+                instances,
+            })
+        })
+        .collect();
+    Ok(projects)
+}
+
+pub fn parse_deployments(data: &JsonValue) -> Vec<RailwayDeployment> {
+    nodes(&data["deployments"])
+        .filter_map(parse_deployment)
+        .collect()
+}
+
+/// Lists all projects of the token with their environments and the latest
+/// deployment of every service instance.
+#[tauri::command]
+pub async fn list_railway_projects(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+) -> Result<Vec<RailwayProject>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Railway, &secret_id)?;
+    logged(&db, Service::Railway, &secret_id, "projects", async {
+        let data = client
+            .graphql(GRAPHQL_PATH, PROJECTS_QUERY, json!({}), false)
+            .await?;
+        parse_projects(&data)
+    })
+    .await
+}
+
+/// The most recent deployments of a service in one environment.
+#[tauri::command]
+pub async fn list_railway_deployments(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    project_id: String,
+    environment_id: String,
+    service_id: String,
+) -> Result<Vec<RailwayDeployment>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Railway, &secret_id)?;
+    let variables = json!({
+        "input": {
+            "projectId": project_id,
+            "environmentId": environment_id,
+            "serviceId": service_id,
+        },
+        "first": RECENT_DEPLOYMENTS,
+    });
+    logged(&db, Service::Railway, &secret_id, "deployments", async {
+        let data = client
+            .graphql(GRAPHQL_PATH, DEPLOYMENTS_QUERY, variables, false)
+            .await?;
+        Ok(parse_deployments(&data))
+    })
+    .await
+}
+
+/// Redeploys the latest deployment of a service. The webview asks for
+/// confirmation first.
+#[tauri::command]
+pub async fn redeploy_railway_service(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    environment_id: String,
+    service_id: String,
+    service_name: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Railway, &secret_id)?;
+    let variables = json!({ "environmentId": environment_id, "serviceId": service_id });
+    let details = json!({
+        "provider": Service::Railway.id(),
+        "name": service_name,
+        "serviceId": service_id,
+        "environmentId": environment_id,
+    });
+    audited(
+        &db,
+        &secret_id,
+        audit::DEPLOYMENT_REDEPLOYED,
+        details,
+        async {
+            let data = client
+                .graphql(GRAPHQL_PATH, REDEPLOY_MUTATION, variables, true)
+                .await?;
+            if data["serviceInstanceRedeploy"].as_bool() == Some(true) {
+                Ok(())
+            } else {
+                Err(Error::Api {
+                    status: 200,
+                    message: "Railway hat den Redeploy nicht angenommen".into(),
+                })
+            }
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::client::graphql_data;
+    use super::super::fixture;
+    use super::*;
+
+    #[test]
+    fn parses_projects_with_service_instances() {
+        let data = graphql_data(fixture("railway", "projects")).unwrap();
+        let projects = parse_projects(&data).unwrap();
+        assert_eq!(projects.len(), 2);
+
+        let api = &projects[0];
+        assert_eq!(api.name, "panoptic-api");
+        let environments: Vec<_> = api.environments.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(environments, ["production", "staging"]);
+        assert_eq!(api.instances.len(), 3);
+
+        let web = &api.instances[0];
+        assert_eq!(
+            (web.service_name.as_str(), web.environment_name.as_str()),
+            ("web", "production")
+        );
+        let deployment = web.latest_deployment.as_ref().unwrap();
+        assert_eq!(deployment.status, "SUCCESS");
+        assert_eq!(
+            deployment.url.as_deref(),
+            Some("https://web-production.up.railway.app")
+        );
+        assert_eq!(
+            deployment.commit_message.as_deref(),
+            Some("Fix login redirect")
+        );
+        assert_eq!(deployment.branch.as_deref(), Some("main"));
+
+        // A service that was never deployed in staging
+        assert!(api.instances[2].latest_deployment.is_none());
+        assert!(projects[1].instances.is_empty());
+    }
+
+    #[test]
+    fn parses_recent_deployments() {
+        let data = graphql_data(fixture("railway", "deployments")).unwrap();
+        let deployments = parse_deployments(&data);
+        let statuses: Vec<_> = deployments.iter().map(|d| d.status.as_str()).collect();
+        assert_eq!(statuses, ["BUILDING", "CRASHED", "SUCCESS"]);
+        assert_eq!(deployments[0].url, None);
+    }
+
+    #[test]
+    fn maps_graphql_errors() {
+        let error = graphql_data(fixture("railway", "not_authorized")).unwrap_err();
+        assert!(matches!(error, Error::Unauthorized(_)), "{error:?}");
+        assert!(parse_projects(&json!({ "me": {} })).is_err());
+    }
+}
@@ -0,0 +1,283 @@
+//! Supabase projects, service health and database stats (Management API).
+//!
+//! Works with personal access tokens. Database size and auth users are read
+//! with the SQL endpoint of the Management API; paused projects have neither.
+
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use tauri::State;
+use tauri_plugin_http::reqwest::Method;
+
+use super::client::ServiceClient;
+use super::{audited, connect, logged, optional_str, Service};
+use crate::audit;
+use crate::db::Database;
+use crate::error::{ApiErrorKind, Result};
+use crate::vault::VaultState;
+
+const HEALTH_SERVICES: [&str; 5] = ["auth", "db", "rest", "storage", "realtime"];
+
+const STATS_QUERY: &str = "SELECT pg_database_size(current_database()) AS db_size_bytes, \
+     (SELECT count(*) FROM auth.users) AS auth_users";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupabaseProject {
+    /// The project ref, which all project endpoints take.
+    pub id: String,
+    pub name: String,
+    pub organization_id: Option<String>,
+    pub region: Option<String>,
+    /// ACTIVE_HEALTHY, ACTIVE_UNHEALTHY, COMING_UP, INACTIVE (paused),
+    /// PAUSING, RESTORING, ...
+    pub status: String,
+    pub created_at: Option<String>,
+    pub db_host: Option<String>,
+    pub db_version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupabaseServiceHealth {
+    /// `auth`, `db`, `rest`, `storage` or `realtime`.
+    pub name: String,
+    pub healthy: bool,
+    pub status: String,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupabaseProjectStats {
+    pub services: Vec<SupabaseServiceHealth>,
+    /// `None` if the token may not run queries.
+    pub db_size_bytes: Option<u64>,
+    pub auth_users: Option<u64>,
+}
+
+/// Postgres returns `bigint` columns as strings.
+fn count(value: &JsonValue) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+pub fn parse_projects(body: &JsonValue) -> Result<Vec<SupabaseProject>> {
+    let projects = body
+        .as_array()
+        .ok_or_else(|| Service::Supabase.unexpected())?;
+    Ok(projects
+        .iter()
+        .filter_map(|project| {
+            Some(SupabaseProject {
+                id: project["ref"]
+                    .as_str()
+                    .or_else(|| project["id"].as_str())?
+                    .to_owned(),
+                name: project["name"].as_str().unwrap_or_default().to_owned(),
+                organization_id: optional_str(&project["organization_id"]),
+                region: optional_str(&project["region"]),
+                status: project["status"].as_str().unwrap_or("UNKNOWN").to_owned(),
+                created_at: optional_str(&project["created_at"]),
+                db_host: optional_str(&project["database"]["host"]),
+                db_version: optional_str(&project["database"]["version"]),
+            })
+        })
+        .collect())
+}
+
+pub fn parse_health(body: &JsonValue) -> Vec<SupabaseServiceHealth> {
+    body.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|service| {
+            Some(SupabaseServiceHealth {
+                name: service["name"].as_str()?.to_owned(),
+                healthy: service["healthy"].as_bool().unwrap_or(false),
+                status: service["status"].as_str().unwrap_or("UNKNOWN").to_owned(),
+                error: optional_str(&service["error"]),
+            })
+        })
+        .collect()
+}
+
+/// Reads the single row of [`STATS_QUERY`].
+pub fn parse_stats(body: &JsonValue) -> (Option<u64>, Option<u64>) {
+    let row = &body[0];
+    (count(&row["db_size_bytes"]), count(&row["auth_users"]))
+}
+
+async fn fetch_stats(client: &ServiceClient, project_ref: &str) -> Result<SupabaseProjectStats> {
+    let query: Vec<_> = HEALTH_SERVICES
+        .iter()
+        .map(|service| ("services", (*service).to_owned()))
+        .collect();
+    let health = client
+        .get(&format!("/v1/projects/{project_ref}/health"), &query)
+        .await?;
+    // The SQL endpoint only takes POST; the query does not change anything
+    let body = json!({ "query": STATS_QUERY });
+    let stats = client
+        .send(
+            Method::POST,
+            &format!("/v1/projects/{project_ref}/database/query"),
+            &[],
+            Some(&body),
+        )
+        .await;
+    let (db_size_bytes, auth_users) = match stats {
+        Ok(rows) => parse_stats(&rows),
+        Err(e)
+            if matches!(
+                e.api_kind(),
+                Some(ApiErrorKind::Permission | ApiErrorKind::NotFound)
+            ) =>
+        {
+            (None, None)
+        }
+        Err(e) => return Err(e),
+    };
+    Ok(SupabaseProjectStats {
+        services: parse_health(&health),
+        db_size_bytes,
+        auth_users,
+    })
+}
+
+#[tauri::command]
+pub async fn list_supabase_projects(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+) -> Result<Vec<SupabaseProject>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Supabase, &secret_id)?;
+    logged(&db, Service::Supabase, &secret_id, "projects", async {
+        parse_projects(&client.get("/v1/projects", &[]).await?)
+    })
+    .await
+}
+
+/// Health of the project's services, database size and auth user count.
+#[tauri::command]
+pub async fn get_supabase_project_stats(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    project_ref: String,
+) -> Result<SupabaseProjectStats> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Supabase, &secret_id)?;
+    logged(
+        &db,
+        Service::Supabase,
+        &secret_id,
+        "project_stats",
+        fetch_stats(&client, &project_ref),
+    )
+    .await
+}
+
+fn action_details(project_ref: &str, project_name: &str) -> JsonValue {
+    json!({
+        "provider": Service::Supabase.id(),
+        "name": project_name,
+        "projectRef": project_ref,
+    })
+}
+
+/// Pauses a project; its database stops accepting connections. The webview
+/// asks for confirmation first.
+#[tauri::command]
+pub async fn pause_supabase_project(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    project_ref: String,
+    project_name: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Supabase, &secret_id)?;
+    let path = format!("/v1/projects/{project_ref}/pause");
+    audited(
+        &db,
+        &secret_id,
+        audit::PROJECT_PAUSED,
+        action_details(&project_ref, &project_name),
+        async {
+            client.send(Method::POST, &path, &[], None).await?;
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Restores a paused project.
+#[tauri::command]
+pub async fn restore_supabase_project(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    project_ref: String,
+    project_name: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Supabase, &secret_id)?;
+    let path = format!("/v1/projects/{project_ref}/restore");
+    audited(
+        &db,
+        &secret_id,
+        audit::PROJECT_RESTORED,
+        action_details(&project_ref, &project_name),
+        async {
+            client.send(Method::POST, &path, &[], None).await?;
+            Ok(())
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fixture;
+    use super::*;
+
+    #[test]
+    fn parses_projects_with_status() {
+        let projects = parse_projects(&fixture("supabase", "projects")).unwrap();
+        let statuses: Vec<_> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.status.as_str()))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("panoptic-auth", "ACTIVE_HEALTHY"),
+                ("old-prototype", "INACTIVE")
+            ]
+        );
+        assert_eq!(projects[0].id, "abcdefghijklmnopqrst");
+        assert_eq!(projects[0].db_version.as_deref(), Some("15.8.1.040"));
+        assert_eq!(projects[1].db_host, None);
+        assert!(parse_projects(&json!({ "message": "Unauthorized" })).is_err());
+    }
+
+    #[test]
+    fn parses_health_and_stats() {
+        let services = parse_health(&fixture("supabase", "health"));
+        let unhealthy: Vec<_> = services.iter().filter(|s| !s.healthy).collect();
+        assert_eq!(services.len(), 5);
+        assert_eq!(unhealthy.len(), 1);
+        assert_eq!(unhealthy[0].name, "rest");
+        assert_eq!(
+            unhealthy[0].error.as_deref(),
+            Some("upstream connect error")
+        );
+
+        assert_eq!(
+            parse_stats(&fixture("supabase", "stats")),
+            (Some(48_234_496), Some(1_284))
+        );
+        assert_eq!(parse_stats(&json!([])), (None, None));
+    }
+}
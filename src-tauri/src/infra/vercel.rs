@@ -0,0 +1,531 @@
+//! Vercel projects, deployments, domains and usage (REST API).
+//!
+//! Every command takes an optional `team_id`; without it the token's personal
+//! account is used.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use tauri::State;
+use tauri_plugin_http::reqwest::Method;
+
+use super::client::ServiceClient;
+use super::{audited, connect, logged, optional_str, Service};
+use crate::audit;
+use crate::db::Database;
+use crate::error::{ApiErrorKind, Result};
+use crate::vault::VaultState;
+
+const RECENT_DEPLOYMENTS: u32 = 20;
+/// Each domain needs its own config request.
+const MAX_DOMAIN_CHECKS: usize = 20;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VercelTeam {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VercelDeployment {
+    pub id: String,
+    pub url: Option<String>,
+    /// READY, ERROR, BUILDING, QUEUED, INITIALIZING, CANCELED
+    pub state: String,
+    /// `production`, or `None` for previews.
+    pub target: Option<String>,
+    /// Milliseconds since the epoch, like all Vercel timestamps.
+    pub created_at: Option<i64>,
+    pub commit_message: Option<String>,
+    pub branch: Option<String>,
+    pub creator: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VercelProject {
+    pub id: String,
+    pub name: String,
+    pub framework: Option<String>,
+    pub updated_at: Option<i64>,
+    /// Aliases of the current production deployment.
+    pub production_domains: Vec<String>,
+    pub latest_deployment: Option<VercelDeployment>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VercelDomain {
+    pub name: String,
+    pub verified: bool,
+    /// Set if the DNS records don't point to Vercel, so no certificate can be
+    /// issued. `None` if the config could not be checked.
+    pub misconfigured: Option<bool>,
+    pub redirect: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VercelServiceCost {
+    pub service: String,
+    pub cost_usd: f64,
+}
+
+/// Billed usage of the current month, most expensive services first.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VercelUsage {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub total_usd: f64,
+    pub services: Vec<VercelServiceCost>,
+}
+
+fn team_query(team_id: &Option<String>) -> Vec<(&'static str, String)> {
+    team_id.iter().map(|id| ("teamId", id.clone())).collect()
+}
+
+pub fn parse_teams(body: &JsonValue) -> Vec<VercelTeam> {
+    body["teams"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|team| {
+            Some(VercelTeam {
+                id: team["id"].as_str()?.to_owned(),
+                name: team["name"].as_str().unwrap_or_default().to_owned(),
+                slug: team["slug"].as_str().unwrap_or_default().to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Parses a deployment of `/v6/deployments` or of `latestDeployments`, which
+/// name some fields differently.
+fn parse_deployment(deployment: &JsonValue) -> Option<VercelDeployment> {
+    let meta = &deployment["meta"];
+    Some(VercelDeployment {
+        id: deployment["uid"]
+            .as_str()
+            .or_else(|| deployment["id"].as_str())?
+            .to_owned(),
+        url: optional_str(&deployment["url"]).map(|host| format!("https://{host}")),
+        state: deployment["readyState"]
+            .as_str()
+            .or_else(|| deployment["state"].as_str())
+            .unwrap_or("UNKNOWN")
+            .to_owned(),
+        target: optional_str(&deployment["target"]),
+        created_at: deployment["created"]
+            .as_i64()
+            .or_else(|| deployment["createdAt"].as_i64()),
+        commit_message: optional_str(&meta["githubCommitMessage"])
+            .or_else(|| optional_str(&meta["gitlabCommitMessage"])),
+        branch: optional_str(&meta["githubCommitRef"])
+            .or_else(|| optional_str(&meta["gitlabCommitRef"])),
+        creator: optional_str(&deployment["creator"]["username"]),
+    })
+}
+
+pub fn parse_projects(body: &JsonValue) -> Result<Vec<VercelProject>> {
+    let projects = body["projects"]
+        .as_array()
+        .ok_or_else(|| Service::Vercel.unexpected())?;
+    Ok(projects
+        .iter()
+        .filter_map(|project| {
+            let latest = project["latestDeployments"]
+                .as_array()
+                .and_then(|deployments| deployments.first())
+                .and_then(parse_deployment);
+            Some(VercelProject {
+                id: project["id"].as_str()?.to_owned(),
+                name: project["name"].as_str()?.to_owned(),
+                framework: optional_str(&project["framework"]),
+                updated_at: project["updatedAt"].as_i64(),
+                production_domains: project["targets"]["production"]["alias"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|alias| alias.as_str().map(str::to_owned))
+                    .collect(),
+                latest_deployment: latest,
+            })
+        })
+        .collect())
+}
+
+pub fn parse_deployments(body: &JsonValue) -> Result<Vec<VercelDeployment>> {
+    let deployments = body["deployments"]
+        .as_array()
+        .ok_or_else(|| Service::Vercel.unexpected())?;
+    Ok(deployments.iter().filter_map(parse_deployment).collect())
+}
+
+pub fn parse_domains(body: &JsonValue) -> Result<Vec<VercelDomain>> {
+    let domains = body["domains"]
+        .as_array()
+        .ok_or_else(|| Service::Vercel.unexpected())?;
+    Ok(domains
+        .iter()
+        .filter_map(|domain| {
+            Some(VercelDomain {
+                name: domain["name"].as_str()?.to_owned(),
+                verified: domain["verified"].as_bool().unwrap_or(false),
+                misconfigured: None,
+                redirect: optional_str(&domain["redirect"]),
+            })
+        })
+        .collect())
+}
+
+/// Sums the FOCUS charges (one JSON object per line) by service.
+pub fn parse_charges(body: &str, from: NaiveDate, to: NaiveDate) -> VercelUsage {
+    let mut services: BTreeMap<String, f64> = BTreeMap::new();
+    for charge in body
+        .lines()
+        .filter_map(|line| serde_json::from_str::<JsonValue>(line).ok())
+    {
+        let cost = &charge["BilledCost"];
+        let cost = cost
+            .as_f64()
+            .or_else(|| cost.as_str().and_then(|s| s.parse().ok()))
+            .unwrap_or(0.0);
+        let service = charge["ServiceName"].as_str().unwrap_or("Sonstiges");
+        *services.entry(service.to_owned()).or_default() += cost;
+    }
+    let mut services: Vec<VercelServiceCost> = services
+        .into_iter()
+        .map(|(service, cost_usd)| VercelServiceCost { service, cost_usd })
+        .collect();
+    services.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    VercelUsage {
+        from,
+        to,
+        total_usd: services.iter().map(|s| s.cost_usd).sum(),
+        services,
+    }
+}
+
+async fn fetch_domains(
+    client: &ServiceClient,
+    team: &[(&str, String)],
+    project_id: &str,
+) -> Result<Vec<VercelDomain>> {
+    let path = format!("/v9/projects/{project_id}/domains");
+    let mut domains = parse_domains(&client.get(&path, team).await?)?;
+    for domain in domains.iter_mut().take(MAX_DOMAIN_CHECKS) {
+        let path = format!("/v6/domains/{}/config", domain.name);
+        domain.misconfigured = client
+            .get(&path, team)
+            .await
+            .ok()
+            .and_then(|config| config["misconfigured"].as_bool());
+    }
+    Ok(domains)
+}
+
+/// The charges API is only available on some plans.
+async fn fetch_usage(
+    client: &ServiceClient,
+    team: &[(&str, String)],
+) -> Result<Option<VercelUsage>> {
+    let today = Utc::now().date_naive();
+    let from = today.with_day(1).expect("first of the month exists");
+    let mut query = team.to_vec();
+    query.push(("from", format!("{from}T00:00:00Z")));
+    query.push(("to", format!("{today}T23:59:59Z")));
+    match client.get("/v1/billing/charges", &query).await {
+        Ok(JsonValue::String(body)) => Ok(Some(parse_charges(&body, from, today))),
+        Ok(body) => Ok(Some(parse_charges(&body.to_string(), from, today))),
+        Err(e)
+            if matches!(
+                e.api_kind(),
+                Some(ApiErrorKind::NotFound | ApiErrorKind::Permission)
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Teams the token can access.
+#[tauri::command]
+pub async fn list_vercel_teams(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+) -> Result<Vec<VercelTeam>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    logged(&db, Service::Vercel, &secret_id, "teams", async {
+        Ok(parse_teams(&client.get("/v2/teams", &[]).await?))
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_vercel_projects(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    team_id: Option<String>,
+) -> Result<Vec<VercelProject>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    let mut query = team_query(&team_id);
+    query.push(("limit", "100".to_owned()));
+    logged(&db, Service::Vercel, &secret_id, "projects", async {
+        parse_projects(&client.get("/v9/projects", &query).await?)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_vercel_deployments(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    team_id: Option<String>,
+    project_id: String,
+) -> Result<Vec<VercelDeployment>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    let mut query = team_query(&team_id);
+    query.push(("projectId", project_id));
+    query.push(("limit", RECENT_DEPLOYMENTS.to_string()));
+    logged(&db, Service::Vercel, &secret_id, "deployments", async {
+        parse_deployments(&client.get("/v6/deployments", &query).await?)
+    })
+    .await
+}
+
+/// Domains of a project with their verification and DNS configuration.
+#[tauri::command]
+pub async fn list_vercel_domains(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    team_id: Option<String>,
+    project_id: String,
+) -> Result<Vec<VercelDomain>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    let team = team_query(&team_id);
+    logged(
+        &db,
+        Service::Vercel,
+        &secret_id,
+        "domains",
+        fetch_domains(&client, &team, &project_id),
+    )
+    .await
+}
+
+/// Billed usage of the current month, `None` if the plan has no billing API.
+#[tauri::command]
+pub async fn get_vercel_usage(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    team_id: Option<String>,
+) -> Result<Option<VercelUsage>> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    let team = team_query(&team_id);
+    logged(
+        &db,
+        Service::Vercel,
+        &secret_id,
+        "usage",
+        fetch_usage(&client, &team),
+    )
+    .await
+}
+
+fn action_details(project_name: &str, deployment_id: &str) -> JsonValue {
+    json!({
+        "provider": Service::Vercel.id(),
+        "name": project_name,
+        "deploymentId": deployment_id,
+    })
+}
+
+/// Builds `deployment_id` again as a new deployment with the same target.
+/// Returns the new deployment.
+#[tauri::command]
+pub async fn redeploy_vercel_deployment(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    team_id: Option<String>,
+    deployment_id: String,
+    project_name: String,
+    target: Option<String>,
+) -> Result<VercelDeployment> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    let mut query = team_query(&team_id);
+    query.push(("forceNew", "1".to_owned()));
+    let mut body = json!({ "name": project_name, "deploymentId": deployment_id });
+    if let Some(target) = &target {
+        body["target"] = json!(target);
+    }
+    let details = action_details(&project_name, &deployment_id);
+    audited(
+        &db,
+        &secret_id,
+        audit::DEPLOYMENT_REDEPLOYED,
+        details,
+        async {
+            let created = client
+                .send(Method::POST, "/v13/deployments", &query, Some(&body))
+                .await?;
+            parse_deployment(&created).ok_or_else(|| Service::Vercel.unexpected())
+        },
+    )
+    .await
+}
+
+/// Makes `deployment_id` the production deployment of the project.
+#[tauri::command]
+pub async fn promote_vercel_deployment(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    team_id: Option<String>,
+    project_id: String,
+    deployment_id: String,
+    project_name: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    let query = team_query(&team_id);
+    let path = format!("/v10/projects/{project_id}/promote/{deployment_id}");
+    let details = action_details(&project_name, &deployment_id);
+    audited(
+        &db,
+        &secret_id,
+        audit::DEPLOYMENT_PROMOTED,
+        details,
+        async {
+            client.send(Method::POST, &path, &query, None).await?;
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Cancels a deployment that is still queued or building.
+#[tauri::command]
+pub async fn cancel_vercel_deployment(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    team_id: Option<String>,
+    deployment_id: String,
+    project_name: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let client = connect(&db, Service::Vercel, &secret_id)?;
+    let query = team_query(&team_id);
+    let path = format!("/v12/deployments/{deployment_id}/cancel");
+    let details = action_details(&project_name, &deployment_id);
+    audited(
+        &db,
+        &secret_id,
+        audit::DEPLOYMENT_CANCELLED,
+        details,
+        async {
+            client.send(Method::PATCH, &path, &query, None).await?;
+            Ok(())
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{fixture, fixture_text};
+    use super::*;
+
+    #[test]
+    fn parses_projects_with_latest_deployment() {
+        let projects = parse_projects(&fixture("vercel", "projects")).unwrap();
+        assert_eq!(projects.len(), 2);
+
+        let web = &projects[0];
+        assert_eq!(web.name, "panoptic-web");
+        assert_eq!(web.framework.as_deref(), Some("nextjs"));
+        assert_eq!(web.production_domains, ["panoptic.app", "www.panoptic.app"]);
+        let latest = web.latest_deployment.as_ref().unwrap();
+        assert_eq!(
+            (latest.id.as_str(), latest.state.as_str()),
+            ("dpl_9xQ", "READY")
+        );
+        assert_eq!(latest.target.as_deref(), Some("production"));
+        assert_eq!(latest.created_at, Some(1_741_600_000_000));
+
+        assert!(projects[1].latest_deployment.is_none());
+        assert!(projects[1].production_domains.is_empty());
+    }
+
+    #[test]
+    fn parses_deployments() {
+        let deployments = parse_deployments(&fixture("vercel", "deployments")).unwrap();
+        let states: Vec<_> = deployments.iter().map(|d| d.state.as_str()).collect();
+        assert_eq!(states, ["BUILDING", "ERROR", "READY"]);
+
+        let building = &deployments[0];
+        assert_eq!(
+            building.url.as_deref(),
+            Some("https://panoptic-web-git-feature-alex.vercel.app")
+        );
+        assert_eq!(building.target, None);
+        assert_eq!(building.branch.as_deref(), Some("feature/alerts"));
+        assert_eq!(
+            building.commit_message.as_deref(),
+            Some("Add alert channels")
+        );
+        assert_eq!(building.creator.as_deref(), Some("alex"));
+        assert!(parse_deployments(&json!({ "error": {} })).is_err());
+    }
+
+    #[test]
+    fn parses_domains_and_teams() {
+        let domains = parse_domains(&fixture("vercel", "domains")).unwrap();
+        let summary: Vec<_> = domains
+            .iter()
+            .map(|d| (d.name.as_str(), d.verified, d.redirect.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("panoptic.app", true, None),
+                ("www.panoptic.app", true, Some("panoptic.app")),
+                ("beta.panoptic.app", false, None),
+            ]
+        );
+
+        let teams = parse_teams(&fixture("vercel", "teams"));
+        assert_eq!(teams.len(), 1);
+        assert_eq!(teams[0].slug, "panoptic");
+    }
+
+    #[test]
+    fn sums_charges_by_service() {
+        let from = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let usage = parse_charges(&fixture_text("vercel", "charges.jsonl"), from, to);
+        assert_eq!(usage.services.len(), 2);
+        assert_eq!(usage.services[0].service, "Fast Data Transfer");
+        assert!((usage.services[0].cost_usd - 3.5).abs() < 1e-9);
+        assert!((usage.total_usd - 4.75).abs() < 1e-9);
+    }
+}
@@ -1,5 +1,17 @@
 use tauri::Manager;
 
+mod alerts;
+mod audit;
+mod auth;
+mod db;
+mod error;
+mod http;
+mod infra;
+mod providers;
+mod recovery;
+mod secrets;
+mod vault;
+
 // Custom commands
 #[tauri::command]
 fn get_app_version() -> String {
@@ -11,15 +23,24 @@ pub fn run() {
     tauri::Builder::default()
         // Plugins
         .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_sql::Builder::new().build())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        // State
+        .manage(db::Database::default())
+        .manage(providers::backfill::BackfillJobs::default())
+        .manage(alerts::engine::AlertEngine::default())
         // Setup
         .setup(|app| {
+            app.manage(vault::VaultState::default());
+            vault::spawn_auto_lock(app.handle().clone());
+            alerts::engine::spawn_scheduler(app.handle().clone());
+            audit::syslog::spawn_forwarder(app.handle().clone());
+            secrets::policies::spawn_checker(app.handle().clone());
+
             #[cfg(debug_assertions)]
             {
                 let window = app.get_webview_window("main").unwrap();
@@ -28,7 +49,95 @@ pub fn run() {
             Ok(())
         })
         // Commands
-        .invoke_handler(tauri::generate_handler![get_app_version])
+        .invoke_handler(tauri::generate_handler![
+            get_app_version,
+            db::database_status,
+            db::unlock_database,
+            db::close_database,
+            db::open_database,
+            db::change_database_key,
+            db::is_database_encrypted,
+            db::database_info_at,
+            db::list_settings,
+            db::save_setting,
+            audit::log_audit,
+            audit::list_audit_log,
+            audit::count_audit_log,
+            audit::verify_audit_chain,
+            audit::prune_audit_log,
+            audit::export::export_audit_log,
+            audit::syslog::get_audit_syslog,
+            audit::syslog::set_audit_syslog,
+            audit::syslog::test_audit_syslog,
+            auth::set_master_password,
+            auth::verify_master_password,
+            recovery::recovery_status,
+            recovery::generate_recovery_phrase,
+            recovery::recover_with_phrase,
+            vault::vault_status,
+            vault::record_activity,
+            vault::lock_vault,
+            secrets::list_secrets,
+            secrets::get_secret,
+            secrets::create_secret,
+            secrets::update_secret,
+            secrets::delete_secret,
+            secrets::reveal_secret,
+            secrets::copy_secret_to_clipboard,
+            secrets::bundle::export_secrets,
+            secrets::bundle::import_secrets,
+            secrets::import::preview_secret_import,
+            secrets::import::import_external_secrets,
+            secrets::versions::list_secret_versions,
+            secrets::versions::rotate_secret,
+            secrets::versions::revert_secret,
+            secrets::policies::list_secret_ages,
+            secrets::policies::get_rotation_policies,
+            secrets::policies::set_rotation_policies,
+            secrets::policies::set_secret_policy,
+            providers::provider_request,
+            providers::list_provider_keys,
+            providers::usage::get_usage_summary,
+            providers::usage::diagnose_providers,
+            providers::usage::get_usage_history,
+            providers::backfill::start_usage_backfill,
+            providers::backfill::cancel_usage_backfill,
+            providers::backfill::list_usage_backfills,
+            providers::prices::get_price_table,
+            providers::prices::set_price_override,
+            providers::prices::remove_price_override,
+            infra::list_service_keys,
+            infra::railway::list_railway_projects,
+            infra::railway::list_railway_deployments,
+            infra::railway::redeploy_railway_service,
+            infra::vercel::list_vercel_teams,
+            infra::vercel::list_vercel_projects,
+            infra::vercel::list_vercel_deployments,
+            infra::vercel::list_vercel_domains,
+            infra::vercel::get_vercel_usage,
+            infra::vercel::redeploy_vercel_deployment,
+            infra::vercel::promote_vercel_deployment,
+            infra::vercel::cancel_vercel_deployment,
+            infra::neon::get_neon_overview,
+            infra::neon::get_neon_storage_history,
+            infra::supabase::list_supabase_projects,
+            infra::supabase::get_supabase_project_stats,
+            infra::supabase::pause_supabase_project,
+            infra::supabase::restore_supabase_project,
+            infra::cloudflare::list_cloudflare_accounts,
+            infra::cloudflare::list_cloudflare_zones,
+            infra::cloudflare::get_cloudflare_dns,
+            infra::cloudflare::save_dns_snapshot,
+            infra::cloudflare::delete_dns_snapshot,
+            infra::cloudflare::list_cloudflare_workers,
+            alerts::list_alert_rules,
+            alerts::create_alert_rule,
+            alerts::update_alert_rule,
+            alerts::delete_alert_rule,
+            alerts::list_alert_events,
+            alerts::engine::check_alerts_now,
+            alerts::engine::send_test_notification,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running Panoptic");
 }
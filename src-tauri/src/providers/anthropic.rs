@@ -0,0 +1,277 @@
+//! Anthropic Admin API: usage and cost reports (admin keys).
+
+use chrono::{DateTime, NaiveDate, SecondsFormat};
+use serde_json::Value as JsonValue;
+
+use super::usage::{
+    next_page_cursor, Account, CostProvider, CostRecord, DateRange, KeyDiagnosis, Page,
+    ProjectInfo, UsageRecord,
+};
+use super::Provider;
+use crate::error::{Error, Result};
+use crate::http;
+
+pub struct Anthropic {
+    account: Account,
+}
+
+impl Anthropic {
+    pub fn new(account: Account) -> Self {
+        Self { account }
+    }
+}
+
+impl CostProvider for Anthropic {
+    fn provider(&self) -> Provider {
+        Provider::Anthropic
+    }
+
+    fn account(&self) -> &Account {
+        &self.account
+    }
+
+    async fn diagnose(&self) -> KeyDiagnosis {
+        let mut diagnosis = self.account.diagnosis();
+        let organization = self.account.client.get("/v1/organizations/me", &[]).await;
+        match organization {
+            Ok(me) => {
+                diagnosis.valid = true;
+                diagnosis.organization = me["name"]
+                    .as_str()
+                    .or_else(|| me["id"].as_str())
+                    .map(str::to_owned);
+                match self.list_projects().await {
+                    Ok(workspaces) => diagnosis.projects = workspaces,
+                    Err(e) => diagnosis.set_error(&e),
+                }
+            }
+            // Standard API keys are rejected by the Admin API
+            Err(Error::Unauthorized(_) | Error::Forbidden(_))
+                if self.account.key_kind != "admin" =>
+            {
+                diagnosis.valid = true;
+                diagnosis.error = Some("Admin API nicht verfügbar (normaler API Key)".into());
+            }
+            Err(e) => diagnosis.set_error(&e),
+        }
+        diagnosis
+    }
+
+    async fn list_projects(&self) -> Result<Vec<ProjectInfo>> {
+        http::paginate(|after| {
+            let mut query = vec![("limit", "100".to_owned())];
+            if let Some(after) = after {
+                query.push(("after_id", after));
+            }
+            async move {
+                let page = self
+                    .account
+                    .client
+                    .get("/v1/organizations/workspaces", &query)
+                    .await?;
+                Ok(parse_workspaces(&page))
+            }
+        })
+        .await
+    }
+
+    async fn fetch_usage(&self, range: DateRange) -> Result<Vec<UsageRecord>> {
+        let mut query = report_query(range);
+        query.push(("group_by[]", "model".into()));
+        query.push(("group_by[]", "service_tier".into()));
+        self.account
+            .client
+            .get_pages(
+                "/v1/organizations/usage_report/messages",
+                query,
+                parse_usage_page,
+            )
+            .await
+    }
+
+    async fn fetch_costs(&self, range: DateRange) -> Result<Vec<CostRecord>> {
+        self.account
+            .client
+            .get_pages(
+                "/v1/organizations/cost_report",
+                report_query(range),
+                parse_costs_page,
+            )
+            .await
+    }
+}
+
+fn rfc3339(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn report_query(range: DateRange) -> Vec<(&'static str, String)> {
+    vec![
+        ("starting_at", rfc3339(range.start_timestamp())),
+        ("ending_at", rfc3339(range.end_timestamp())),
+        ("bucket_width", "1d".into()),
+        ("limit", "31".into()),
+        ("group_by[]", "workspace_id".into()),
+    ]
+}
+
+fn parse_workspaces(page: &JsonValue) -> Page<ProjectInfo> {
+    let items = page["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|ws| {
+            let id = ws["id"].as_str()?;
+            Some(ProjectInfo {
+                id: id.to_owned(),
+                name: ws["name"].as_str().unwrap_or(id).to_owned(),
+            })
+        })
+        .collect();
+    let next_page = if page["has_more"].as_bool().unwrap_or(false) {
+        page["last_id"].as_str().map(str::to_owned)
+    } else {
+        None
+    };
+    Page { items, next_page }
+}
+
+/// Iterates the results of every bucket together with the bucket's day.
+fn buckets(page: &JsonValue) -> Result<impl Iterator<Item = (NaiveDate, &JsonValue)> + '_> {
+    let data = page["data"]
+        .as_array()
+        .ok_or_else(|| Error::InvalidInput("Unerwartete Antwort von Anthropic".into()))?;
+    Ok(data.iter().flat_map(|bucket| {
+        let date = bucket["starting_at"]
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.date_naive());
+        bucket["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(move |result| Some((date?, result)))
+    }))
+}
+
+fn tokens(result: &JsonValue, pointer: &str) -> u64 {
+    result
+        .pointer(pointer)
+        .and_then(JsonValue::as_u64)
+        .unwrap_or(0)
+}
+
+/// Parses a page of the messages usage report. Input tokens are split into
+/// uncached, cache writes and cache reads; all of them count as input.
+pub fn parse_usage_page(page: &JsonValue) -> Result<Page<UsageRecord>> {
+    let items = buckets(page)?
+        .map(|(date, result)| {
+            let cached = tokens(result, "/cache_read_input_tokens");
+            UsageRecord {
+                date,
+                project_id: result["workspace_id"].as_str().map(str::to_owned),
+                model: result["model"].as_str().map(str::to_owned),
+                input_tokens: tokens(result, "/uncached_input_tokens")
+                    + tokens(result, "/cache_creation/ephemeral_5m_input_tokens")
+                    + tokens(result, "/cache_creation/ephemeral_1h_input_tokens")
+                    + cached,
+                cached_input_tokens: cached,
+                output_tokens: tokens(result, "/output_tokens"),
+                // The report has no request counts
+                requests: 0,
+                batch: result["service_tier"].as_str() == Some("batch"),
+            }
+        })
+        .collect();
+    Ok(Page {
+        items,
+        next_page: next_page_cursor(page),
+    })
+}
+
+/// Parses a page of the cost report. Amounts are decimal strings in cents.
+pub fn parse_costs_page(page: &JsonValue) -> Result<Page<CostRecord>> {
+    let items = buckets(page)?
+        .filter(|(_, result)| {
+            result["currency"]
+                .as_str()
+                .is_none_or(|c| c.eq_ignore_ascii_case("USD"))
+        })
+        .map(|(date, result)| CostRecord {
+            date,
+            project_id: result["workspace_id"].as_str().map(str::to_owned),
+            amount_usd: result["amount"]
+                .as_str()
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(0.0)
+                / 100.0,
+            estimated: false,
+        })
+        .collect();
+    Ok(Page {
+        items,
+        next_page: next_page_cursor(page),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> JsonValue {
+        let path = format!(
+            "{}/tests/fixtures/anthropic/{name}.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn report_query_uses_utc_day_boundaries() {
+        let range = DateRange::last_days("2025-03-10".parse().unwrap(), 2);
+        let query = report_query(range);
+        assert_eq!(query[0], ("starting_at", "2025-03-09T00:00:00Z".into()));
+        assert_eq!(query[1], ("ending_at", "2025-03-11T00:00:00Z".into()));
+    }
+
+    #[test]
+    fn parses_usage_report_with_cache_tokens() {
+        let page = parse_usage_page(&fixture("usage_report")).unwrap();
+        assert_eq!(
+            page.next_page.as_deref(),
+            Some("page_MjAyNS0wMy0xMVQwMDowMDowMFo=")
+        );
+        assert_eq!(page.items.len(), 3);
+
+        let first = &page.items[0];
+        assert_eq!(first.date.to_string(), "2025-03-09");
+        assert_eq!(first.project_id.as_deref(), Some("wrkspc_01"));
+        assert_eq!(first.model.as_deref(), Some("claude-sonnet-4-20250514"));
+        assert_eq!(first.input_tokens, 1_000 + 200 + 50 + 4_000);
+        assert_eq!(first.cached_input_tokens, 4_000);
+        assert_eq!(first.output_tokens, 900);
+        assert!(!first.batch);
+        assert!(page.items[2].batch);
+        // Default workspace
+        assert_eq!(page.items[1].project_id, None);
+    }
+
+    #[test]
+    fn parses_cost_report_in_cents() {
+        let page = parse_costs_page(&fixture("cost_report")).unwrap();
+        assert!(page.next_page.is_none());
+        let amounts: Vec<_> = page.items.iter().map(|c| c.amount_usd).collect();
+        assert_eq!(amounts, [1.25, 0.5, 2.0]);
+        assert_eq!(page.items[1].project_id, None);
+    }
+
+    #[test]
+    fn parses_workspaces() {
+        let page = parse_workspaces(&fixture("workspaces"));
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[1].name, "Panoptic");
+        assert_eq!(page.next_page.as_deref(), Some("wrkspc_02"));
+    }
+}
@@ -0,0 +1,390 @@
+//! Historical usage backfill for one key, one calendar month at a time.
+//!
+//! A job walks backwards from the current month and writes every month to
+//! `usage_cache`. Its progress is stored in `settings` after each month, so a
+//! job interrupted by closing or locking the app continues where it stopped
+//! once the vault is unlocked again.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use chrono::{Datelike, Days, Months, NaiveDate, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use super::anthropic::Anthropic;
+use super::cache;
+use super::openai::OpenAi;
+use super::usage::{self, Account, CostProvider, DateRange};
+use super::Provider;
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::secrets;
+use crate::vault::VaultState;
+
+pub const BACKFILL_EVENT: &str = "usage-backfill";
+
+const SETTING_PREFIX: &str = "usageBackfill:";
+const MAX_MONTHS: u32 = 36;
+/// Pause between two months to stay well below the providers' rate limits.
+const MONTH_PAUSE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Running,
+    Cancelled,
+    Failed,
+    Done,
+}
+
+/// Progress of a backfill, persisted as JSON in `settings`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackfillJob {
+    pub secret_id: String,
+    pub provider: Provider,
+    pub key_name: String,
+    /// First day of the oldest month to fetch.
+    pub oldest_month: NaiveDate,
+    /// First day of the next month to fetch.
+    pub next_month: NaiveDate,
+    pub total_months: u32,
+    pub completed_months: u32,
+    pub status: JobStatus,
+    pub error: Option<String>,
+    /// Whether the job runs right now; `running` jobs that are not active were
+    /// interrupted and can be resumed.
+    #[serde(skip_deserializing)]
+    pub active: bool,
+}
+
+impl BackfillJob {
+    fn is_finished(&self) -> bool {
+        self.next_month < self.oldest_month
+    }
+}
+
+/// Cancel flags of the running jobs, by secret id.
+#[derive(Default)]
+pub struct BackfillJobs {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl BackfillJobs {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Arc<AtomicBool>>> {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn is_active(&self, secret_id: &str) -> bool {
+        self.lock().contains_key(secret_id)
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("every month has a first day")
+}
+
+/// The days of the month starting at `first`, up to `today`.
+fn month_range(first: NaiveDate, today: NaiveDate) -> DateRange {
+    let end = first + Months::new(1) - Days::new(1);
+    DateRange {
+        start: first,
+        end: end.min(today),
+    }
+}
+
+fn setting_key(secret_id: &str) -> String {
+    format!("{SETTING_PREFIX}{secret_id}")
+}
+
+fn load_job(conn: &Connection, secret_id: &str) -> Result<Option<BackfillJob>> {
+    Ok(db::get_setting(conn, &setting_key(secret_id))?
+        .and_then(|value| serde_json::from_str(&value).ok()))
+}
+
+fn load_jobs(conn: &Connection) -> Result<Vec<BackfillJob>> {
+    let mut stmt =
+        conn.prepare("SELECT value FROM settings WHERE key LIKE ?1 ORDER BY updated_at DESC")?;
+    let rows = stmt.query_map([format!("{SETTING_PREFIX}%")], |row| {
+        row.get::<_, String>(0)
+    })?;
+    let values = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(values
+        .iter()
+        .filter_map(|value| serde_json::from_str(value).ok())
+        .collect())
+}
+
+fn save_job(conn: &Connection, job: &BackfillJob) -> Result<()> {
+    let value = serde_json::to_string(job).expect("serializable job");
+    db::set_setting(conn, &setting_key(&job.secret_id), &value)
+}
+
+/// Stores the job and tells the webview about it.
+fn report(app: &AppHandle, job: &BackfillJob) {
+    let saved = app
+        .state::<Database>()
+        .with_conn(|conn| save_job(conn, job));
+    if let Err(e) = saved {
+        eprintln!("Failed to save backfill progress: {e}");
+    }
+    if let Err(e) = app.emit(BACKFILL_EVENT, job) {
+        eprintln!("Failed to emit {BACKFILL_EVENT}: {e}");
+    }
+}
+
+async fn run<P: CostProvider>(
+    app: &AppHandle,
+    account: P,
+    job: &mut BackfillJob,
+    cancel: &AtomicBool,
+) -> Result<()> {
+    let db = app.state::<Database>();
+    let account_id = cache::account_id(job.provider, &job.secret_id);
+    while !job.is_finished() {
+        if cancel.load(Ordering::Relaxed) {
+            job.status = JobStatus::Cancelled;
+            return Ok(());
+        }
+        // Stop with the stored progress once the app locks
+        app.state::<VaultState>().ensure_unlocked()?;
+        let range = month_range(job.next_month, Utc::now().date_naive());
+        let data = usage::fetch_logged(&db, &account, range).await?;
+        db.with_conn(|conn| {
+            cache::store(
+                conn,
+                &account_id,
+                range,
+                &data.projects,
+                &data.usage,
+                &data.costs,
+                Utc::now().timestamp(),
+            )
+        })?;
+
+        job.next_month = job.next_month - Months::new(1);
+        job.completed_months += 1;
+        report(app, job);
+        tokio::time::sleep(MONTH_PAUSE).await;
+    }
+    job.status = JobStatus::Done;
+    Ok(())
+}
+
+fn spawn(app: AppHandle, account: Account, mut job: BackfillJob, cancel: Arc<AtomicBool>) {
+    tauri::async_runtime::spawn(async move {
+        let result = match job.provider {
+            Provider::OpenAi => run(&app, OpenAi::new(account), &mut job, &cancel).await,
+            Provider::Anthropic => run(&app, Anthropic::new(account), &mut job, &cancel).await,
+            Provider::Gemini => unreachable!("rejected in start_usage_backfill"),
+        };
+        match result {
+            // Stays `running`, so the next unlock resumes it
+            Ok(()) | Err(Error::Locked) => {}
+            Err(e) => {
+                job.status = JobStatus::Failed;
+                job.error = Some(e.to_string());
+            }
+        }
+        app.state::<BackfillJobs>().lock().remove(&job.secret_id);
+        job.active = false;
+        report(&app, &job);
+    });
+}
+
+/// The unfinished job of the key if it covers the same number of months, or
+/// a new one. A changed range starts over instead of keeping the old one.
+fn resume_or_new(
+    previous: Option<BackfillJob>,
+    secret_id: &str,
+    key_name: &str,
+    provider: Provider,
+    months: u32,
+    this_month: NaiveDate,
+) -> BackfillJob {
+    match previous {
+        Some(job) if !job.is_finished() && job.total_months == months => job,
+        _ => BackfillJob {
+            secret_id: secret_id.to_owned(),
+            provider,
+            key_name: key_name.to_owned(),
+            oldest_month: this_month - Months::new(months - 1),
+            next_month: this_month,
+            total_months: months,
+            completed_months: 0,
+            status: JobStatus::Running,
+            error: None,
+            active: false,
+        },
+    }
+}
+
+fn start(app: &AppHandle, secret_id: &str, months: u32) -> Result<BackfillJob> {
+    if !(1..=MAX_MONTHS).contains(&months) {
+        return Err(Error::InvalidInput(format!(
+            "Zeitraum muss zwischen 1 und {MAX_MONTHS} Monaten liegen"
+        )));
+    }
+    let db = app.state::<Database>();
+    let (secret, previous) = db.with_conn(|conn| {
+        Ok((
+            secrets::find_secret(conn, secret_id)?,
+            load_job(conn, secret_id)?,
+        ))
+    })?;
+    let secret = secret.ok_or_else(|| Error::InvalidInput("Secret nicht gefunden".into()))?;
+    let provider = match Provider::from_name(&secret.provider) {
+        Some(provider @ (Provider::OpenAi | Provider::Anthropic)) => provider,
+        _ => {
+            return Err(Error::InvalidInput(format!(
+                "Für '{}' gibt es keine Usage-API",
+                secret.provider
+            )))
+        }
+    };
+
+    let this_month = first_of_month(Utc::now().date_naive());
+    let mut job = resume_or_new(
+        previous,
+        &secret.id,
+        &secret.name,
+        provider,
+        months,
+        this_month,
+    );
+    job.status = JobStatus::Running;
+    job.error = None;
+    job.active = true;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let jobs = app.state::<BackfillJobs>();
+        let mut running = jobs.lock();
+        if running.contains_key(secret_id) {
+            return Err(Error::InvalidInput(
+                "Für diesen Key läuft bereits ein Backfill".into(),
+            ));
+        }
+        running.insert(secret_id.to_owned(), cancel.clone());
+    }
+    db.with_conn(|conn| save_job(conn, &job))?;
+    spawn(
+        app.clone(),
+        Account::new(provider, secret),
+        job.clone(),
+        cancel,
+    );
+    Ok(job)
+}
+
+/// Resumes the jobs that were still running when the app was closed or
+/// locked. Called after unlocking.
+pub fn resume_interrupted(app: &AppHandle) {
+    let jobs = match app.state::<Database>().with_conn(load_jobs) {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            eprintln!("Failed to load backfill jobs: {e}");
+            return;
+        }
+    };
+    for job in jobs {
+        if job.status != JobStatus::Running || job.is_finished() {
+            continue;
+        }
+        if let Err(e) = start(app, &job.secret_id, job.total_months) {
+            eprintln!("Failed to resume backfill for {}: {e}", job.key_name);
+        }
+    }
+}
+
+/// Starts a backfill of `months` months for the key `secret_id`, or resumes
+/// its unfinished job of the same range.
+#[tauri::command]
+pub async fn start_usage_backfill(
+    app: AppHandle,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    months: u32,
+) -> Result<BackfillJob> {
+    vault.ensure_unlocked()?;
+    start(&app, &secret_id, months)
+}
+
+/// Stops a running backfill after the current month. It can be resumed with
+/// [`start_usage_backfill`].
+#[tauri::command]
+pub fn cancel_usage_backfill(jobs: State<'_, BackfillJobs>, secret_id: String) {
+    if let Some(cancel) = jobs.lock().get(&secret_id) {
+        cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// All backfill jobs, including finished and interrupted ones.
+#[tauri::command]
+pub async fn list_usage_backfills(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    jobs: State<'_, BackfillJobs>,
+) -> Result<Vec<BackfillJob>> {
+    vault.ensure_unlocked()?;
+    Ok(db
+        .with_conn(load_jobs)?
+        .into_iter()
+        .map(|mut job| {
+            job.active = jobs.is_active(&job.secret_id);
+            job
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn month_ranges_end_at_month_end_or_today() {
+        let today = date("2025-03-10");
+        let march = month_range(first_of_month(today), today);
+        assert_eq!((march.start, march.end), (date("2025-03-01"), today));
+        let february = month_range(date("2025-02-01"), today);
+        assert_eq!(february.end, date("2025-02-28"));
+        let december = month_range(date("2024-12-01"), today);
+        assert_eq!(december.end, date("2024-12-31"));
+    }
+
+    #[test]
+    fn a_changed_range_starts_over() {
+        let this_month = date("2025-03-01");
+        let job = |previous, months| {
+            resume_or_new(
+                previous,
+                "s1",
+                "Admin",
+                Provider::OpenAi,
+                months,
+                this_month,
+            )
+        };
+        let mut interrupted = job(None, 6);
+        interrupted.next_month = date("2025-01-01");
+        interrupted.completed_months = 2;
+
+        let resumed = job(Some(interrupted.clone()), 6);
+        assert_eq!(resumed.completed_months, 2);
+        assert_eq!(resumed.next_month, date("2025-01-01"));
+
+        let restarted = job(Some(interrupted), 12);
+        assert_eq!(restarted.total_months, 12);
+        assert_eq!(restarted.completed_months, 0);
+        assert_eq!(restarted.next_month, this_month);
+        assert_eq!(restarted.oldest_month, date("2024-04-01"));
+    }
+}
@@ -0,0 +1,304 @@
+//! Day buckets of fetched usage in `usage_cache`.
+//!
+//! Every account (provider and secret) gets one row per UTC day. A day is
+//! refetched while it may still change: until it has been fetched once after
+//! [`SETTLE_SECS`], and then no more often than every [`REFRESH_SECS`].
+//!
+//! The error of a failed fetch is kept in `sync_errors` until a fetch of the
+//! account succeeds again, so it is still reported while no fetch is due.
+
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::usage::{AccountData, CostRecord, DateRange, ProjectInfo, UsageRecord};
+use super::Provider;
+use crate::error::{ApiErrorKind, Result};
+
+/// Providers may still correct usage and costs of a day this long after it
+/// ended.
+const SETTLE_SECS: i64 = 48 * 3600;
+/// Minimum age of an open day before it is fetched again.
+const REFRESH_SECS: i64 = 15 * 60;
+
+/// `usage_cache.provider_id` of an account.
+pub fn account_id(provider: Provider, secret_id: &str) -> String {
+    format!("{}:{secret_id}", provider.id())
+}
+
+/// Everything fetched for one account and day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DayBucket {
+    /// The projects of the account at the time of the fetch.
+    pub projects: Vec<ProjectInfo>,
+    pub usage: Vec<UsageRecord>,
+    pub costs: Vec<CostRecord>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CachedDay {
+    pub date: NaiveDate,
+    pub fetched_at: i64,
+    pub bucket: DayBucket,
+}
+
+/// Cached days of `account_id` within `range`, oldest first.
+pub fn load(conn: &Connection, account_id: &str, range: DateRange) -> Result<Vec<CachedDay>> {
+    let mut stmt = conn.prepare(
+        "SELECT date, fetched_at, data FROM usage_cache
+         WHERE provider_id = ?1 AND date BETWEEN ?2 AND ?3
+         ORDER BY date",
+    )?;
+    let rows = stmt.query_map(
+        params![account_id, range.start.to_string(), range.end.to_string()],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        },
+    )?;
+    let mut days = Vec::new();
+    for row in rows {
+        let (date, fetched_at, data) = row?;
+        // Unreadable rows are treated as missing and fetched again
+        let (Ok(date), Ok(bucket)) = (date.parse(), serde_json::from_str(&data)) else {
+            continue;
+        };
+        days.push(CachedDay {
+            date,
+            fetched_at,
+            bucket,
+        });
+    }
+    Ok(days)
+}
+
+/// Every cached day since `since`, with the provider of its account.
+pub fn load_since(conn: &Connection, since: NaiveDate) -> Result<Vec<(Provider, CachedDay)>> {
+    let mut stmt = conn.prepare(
+        "SELECT provider_id, date, fetched_at, data FROM usage_cache
+         WHERE date >= ?1 ORDER BY date",
+    )?;
+    let rows = stmt.query_map([since.to_string()], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+    let mut days = Vec::new();
+    for row in rows {
+        let (account_id, date, fetched_at, data) = row?;
+        let provider = account_id
+            .split_once(':')
+            .and_then(|(provider, _)| Provider::from_name(provider));
+        let (Some(provider), Ok(date), Ok(bucket)) =
+            (provider, date.parse(), serde_json::from_str(&data))
+        else {
+            continue;
+        };
+        days.push((
+            provider,
+            CachedDay {
+                date,
+                fetched_at,
+                bucket,
+            },
+        ));
+    }
+    Ok(days)
+}
+
+/// Replaces the buckets of every day in `range`, including days without
+/// records, so they are not fetched again.
+pub fn store(
+    conn: &Connection,
+    account_id: &str,
+    range: DateRange,
+    projects: &[ProjectInfo],
+    usage: &[UsageRecord],
+    costs: &[CostRecord],
+    fetched_at: i64,
+) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT OR REPLACE INTO usage_cache (id, provider_id, date, data, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for date in range.days() {
+            let bucket = DayBucket {
+                projects: projects.to_vec(),
+                usage: usage.iter().filter(|r| r.date == date).cloned().collect(),
+                costs: costs.iter().filter(|r| r.date == date).cloned().collect(),
+            };
+            let data = serde_json::to_string(&bucket).expect("serializable bucket");
+            stmt.execute(params![
+                format!("{account_id}:{date}"),
+                account_id,
+                date.to_string(),
+                data,
+                fetched_at
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// The days of `range` that need to be fetched at `now`, as one range from
+/// the oldest such day to the end, or `None` if the cache is current.
+pub fn stale_range(range: DateRange, cached: &[CachedDay], now: i64) -> Option<DateRange> {
+    let is_current = |date| {
+        cached.iter().any(|day| {
+            day.date == date
+                && (day.fetched_at >= DateRange::day(date).end_timestamp() + SETTLE_SECS
+                    || now - day.fetched_at < REFRESH_SECS)
+        })
+    };
+    let start = range.days().find(|&date| !is_current(date))?;
+    Some(DateRange {
+        start,
+        end: range.end,
+    })
+}
+
+/// Reassembles the records of an account from its cached days. Projects are
+/// taken from the most recent fetch.
+pub fn account_data(provider: Provider, account_name: &str, cached: Vec<CachedDay>) -> AccountData {
+    let projects = cached
+        .iter()
+        .max_by_key(|day| (day.fetched_at, day.date))
+        .map(|day| day.bucket.projects.clone())
+        .unwrap_or_default();
+    let mut data = AccountData {
+        provider,
+        account_name: account_name.to_owned(),
+        projects,
+        usage: Vec::new(),
+        costs: Vec::new(),
+    };
+    for day in cached {
+        data.usage.extend(day.bucket.usage);
+        data.costs.extend(day.bucket.costs);
+    }
+    data
+}
+
+/// Time of the last successful fetch of an account.
+pub fn last_synced(cached: &[CachedDay]) -> Option<i64> {
+    cached.iter().map(|day| day.fetched_at).max()
+}
+
+/// Error of the last failed fetch of an account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncError {
+    pub message: String,
+    pub kind: Option<ApiErrorKind>,
+}
+
+pub fn store_error(conn: &Connection, account_id: &str, error: &SyncError, now: i64) -> Result<()> {
+    let kind = error
+        .kind
+        .map(|kind| serde_json::to_string(&kind).expect("serializable kind"));
+    conn.execute(
+        "INSERT OR REPLACE INTO sync_errors (account_id, error, error_kind, failed_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![account_id, error.message, kind, now],
+    )?;
+    Ok(())
+}
+
+pub fn clear_error(conn: &Connection, account_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM sync_errors WHERE account_id = ?1",
+        [account_id],
+    )?;
+    Ok(())
+}
+
+/// The error of the last fetch of `account_id`, `None` if it succeeded.
+pub fn load_error(conn: &Connection, account_id: &str) -> Result<Option<SyncError>> {
+    let row = conn
+        .query_row(
+            "SELECT error, error_kind FROM sync_errors WHERE account_id = ?1",
+            [account_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?)),
+        )
+        .optional()?;
+    Ok(row.map(|(message, kind)| SyncError {
+        message,
+        kind: kind.and_then(|kind| serde_json::from_str(&kind).ok()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ChainKey;
+
+    fn cached(date: &str, fetched_at: i64) -> CachedDay {
+        CachedDay {
+            date: date.parse().unwrap(),
+            fetched_at,
+            bucket: DayBucket::default(),
+        }
+    }
+
+    #[test]
+    fn refetches_missing_and_open_days_only() {
+        let today = "2025-03-10".parse().unwrap();
+        let range = DateRange::last_days(today, 5);
+        // 2025-03-10 23:00 UTC: days up to 2025-03-07 have settled
+        let now = 1_741_647_600;
+
+        assert_eq!(stale_range(range, &[], now), Some(range));
+
+        let settled = [
+            cached("2025-03-06", now),
+            cached("2025-03-07", now),
+            // Still open and fetched long enough ago
+            cached("2025-03-08", now - REFRESH_SECS),
+            cached("2025-03-09", now),
+            cached("2025-03-10", now),
+        ];
+        let stale = stale_range(range, &settled, now).unwrap();
+        assert_eq!(stale.start.to_string(), "2025-03-08");
+        assert_eq!(stale.end, today);
+
+        let mut fresh = settled.clone();
+        fresh[2].fetched_at = now - 60;
+        assert_eq!(stale_range(range, &fresh, now), None);
+        // Settled days are not fetched again, even when old
+        assert_eq!(
+            stale_range(range, &fresh, now + REFRESH_SECS)
+                .unwrap()
+                .start,
+            "2025-03-08".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn keeps_the_last_error_until_a_fetch_succeeds() {
+        let conn = crate::db::open_in_memory(&ChainKey::derive("test"));
+        let account = account_id(Provider::OpenAi, "secret");
+        assert_eq!(load_error(&conn, &account).unwrap(), None);
+
+        let error = SyncError {
+            message: "Authentifizierung fehlgeschlagen: invalid key".into(),
+            kind: Some(ApiErrorKind::Auth),
+        };
+        store_error(&conn, &account, &error, 1000).unwrap();
+        assert_eq!(load_error(&conn, &account).unwrap(), Some(error));
+        assert_eq!(
+            load_error(&conn, &account_id(Provider::Anthropic, "secret")).unwrap(),
+            None
+        );
+
+        clear_error(&conn, &account).unwrap();
+        assert_eq!(load_error(&conn, &account).unwrap(), None);
+    }
+}
@@ -0,0 +1,135 @@
+//! HTTP client bound to one provider key.
+
+use serde_json::Value as JsonValue;
+use tauri_plugin_http::reqwest::{Method, Url};
+use zeroize::Zeroizing;
+
+use super::Provider;
+use crate::error::{Error, Result};
+use crate::http::{self, paginate, Page};
+
+/// Query parameters; keys may repeat (e.g. `group_by[]`).
+pub type Query<'a> = [(&'a str, String)];
+
+pub struct ApiClient {
+    provider: Provider,
+    key: Zeroizing<String>,
+}
+
+impl ApiClient {
+    pub fn new(provider: Provider, key: Zeroizing<String>) -> Self {
+        Self { provider, key }
+    }
+
+    /// Sends a request through the shared client and returns the status with
+    /// the parsed body, or the raw text if the body is not JSON.
+    pub async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &Query<'_>,
+    ) -> Result<(u16, JsonValue)> {
+        let (status, _, body) = self.request(method, path, query).await?;
+        Ok((status, body))
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        query: &Query<'_>,
+    ) -> Result<(u16, Option<u64>, JsonValue)> {
+        let url = self.provider.url(path)?;
+        self.request_url(method, url, query).await
+    }
+
+    /// Network errors are stripped of the URL before they reach messages,
+    /// audit rows or alerts.
+    async fn request_url(
+        &self,
+        method: Method,
+        url: Url,
+        query: &Query<'_>,
+    ) -> Result<(u16, Option<u64>, JsonValue)> {
+        let idempotent = method.is_idempotent();
+        let response = http::send(&url, idempotent, |client| {
+            let request = client.request(method.clone(), url.clone()).query(query);
+            self.provider.authorize(request, &self.key)
+        })
+        .await
+        .map_err(|e| match e {
+            Error::Network(e) => Error::Network(e.without_url()),
+            e => e,
+        })?;
+
+        let status = response.status().as_u16();
+        let retry_after = http::retry_after(&response).map(|wait| wait.as_secs());
+        let text = response.text().await.map_err(|e| e.without_url())?;
+        let body = serde_json::from_str(&text).unwrap_or(JsonValue::String(text));
+        Ok((status, retry_after, body))
+    }
+
+    /// GET that fails with the typed [`Error`] of non-2xx responses.
+    pub async fn get(&self, path: &str, query: &Query<'_>) -> Result<JsonValue> {
+        let (status, retry_after, body) = self.request(Method::GET, path, query).await?;
+        if (200..300).contains(&status) {
+            Ok(body)
+        } else {
+            Err(Error::from_status(
+                status,
+                http::error_message(&body),
+                retry_after,
+            ))
+        }
+    }
+
+    /// Follows the `page` cursor of a paginated report and parses every page.
+    pub async fn get_pages<T>(
+        &self,
+        path: &str,
+        query: Vec<(&str, String)>,
+        parse: fn(&JsonValue) -> Result<Page<T>>,
+    ) -> Result<Vec<T>> {
+        paginate(|cursor| {
+            let mut query = query.clone();
+            if let Some(page) = cursor {
+                query.push(("page", page));
+            }
+            async move { parse(&self.get(path, &query).await?) }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tauri_plugin_http::reqwest::Client;
+
+    use super::*;
+
+    const KEY: &str = "AIzaSyTestKey0123456789";
+
+    #[tokio::test]
+    async fn gemini_keys_stay_out_of_urls_and_errors() {
+        let client = ApiClient::new(Provider::Gemini, Zeroizing::new(KEY.into()));
+        let request = Provider::Gemini
+            .authorize(
+                Client::new().get(Provider::Gemini.url("/v1beta/models").unwrap()),
+                KEY,
+            )
+            .build()
+            .unwrap();
+        assert!(!request.url().as_str().contains(KEY));
+        assert_eq!(request.headers()["x-goog-api-key"], KEY);
+
+        // Nothing listens on port 1; POST is not retried
+        let url = Url::parse("http://127.0.0.1:1/v1beta/models").unwrap();
+        let error = client
+            .request_url(Method::POST, url, &[("pageSize", "10".into())])
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Network(_)), "{error:?}");
+        assert!(!error.to_string().contains(KEY), "{error}");
+        assert!(!format!("{error:?}").contains(KEY));
+    }
+}
@@ -0,0 +1,89 @@
+//! Google AI Studio (Gemini) keys.
+//!
+//! Google does not offer a public usage or billing API for AI Studio keys, so
+//! only the diagnosis talks to the API; usage and costs stay empty and cannot
+//! be estimated from the price table either.
+
+use serde_json::Value as JsonValue;
+
+use super::usage::{
+    Account, CostProvider, CostRecord, DateRange, KeyDiagnosis, ProjectInfo, UsageRecord,
+};
+use super::Provider;
+use crate::error::Result;
+
+pub struct Gemini {
+    account: Account,
+}
+
+impl Gemini {
+    pub fn new(account: Account) -> Self {
+        Self { account }
+    }
+}
+
+impl CostProvider for Gemini {
+    fn provider(&self) -> Provider {
+        Provider::Gemini
+    }
+
+    fn account(&self) -> &Account {
+        &self.account
+    }
+
+    /// Checks the key by listing the available models.
+    async fn diagnose(&self) -> KeyDiagnosis {
+        let mut diagnosis = self.account.diagnosis();
+        let query = [("pageSize", "1000".to_owned())];
+        match self.account.client.get("/v1beta/models", &query).await {
+            Ok(models) => {
+                diagnosis.valid = true;
+                diagnosis.organization = Some("Google AI Studio".into());
+                diagnosis.models = parse_models(&models);
+            }
+            Err(e) => diagnosis.set_error(&e),
+        }
+        diagnosis
+    }
+
+    async fn list_projects(&self) -> Result<Vec<ProjectInfo>> {
+        Ok(Vec::new())
+    }
+
+    async fn fetch_usage(&self, _range: DateRange) -> Result<Vec<UsageRecord>> {
+        Ok(Vec::new())
+    }
+
+    async fn fetch_costs(&self, _range: DateRange) -> Result<Vec<CostRecord>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Model names without the `models/` prefix.
+fn parse_models(page: &JsonValue) -> Vec<String> {
+    page["models"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|model| model["name"].as_str())
+        .map(|name| name.trim_start_matches("models/").to_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_model_names() {
+        let path = format!(
+            "{}/tests/fixtures/gemini/models.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let page = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(
+            parse_models(&page),
+            ["gemini-2.0-flash", "gemini-1.5-pro", "text-embedding-004"]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Authenticated requests to provider APIs.
+//!
+//! The webview only passes the id of a secret. The key is read from the
+//! database, attached to the request here and never sent back over IPC.
+
+mod anthropic;
+pub mod backfill;
+mod cache;
+mod client;
+mod gemini;
+mod openai;
+pub mod prices;
+pub mod usage;
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use tauri::State;
+use tauri_plugin_http::reqwest::{Method, RequestBuilder, Url};
+use zeroize::Zeroizing;
+
+use self::client::ApiClient;
+
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::http;
+use crate::secrets::{self, SecretRow};
+use crate::vault::VaultState;
+
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
+/// Providers whose keys can be used through [`provider_request`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Provider {
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    #[serde(rename = "google")]
+    Gemini,
+}
+
+impl Provider {
+    /// Maps the `provider` column of a secret.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "openai" => Some(Self::OpenAi),
+            "anthropic" => Some(Self::Anthropic),
+            "google" | "gemini" => Some(Self::Gemini),
+            _ => None,
+        }
+    }
+
+    /// The `provider` value stored with secrets of this provider.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai",
+            Self::Anthropic => "anthropic",
+            Self::Gemini => "google",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OpenAi => "OpenAI",
+            Self::Anthropic => "Anthropic",
+            Self::Gemini => "Gemini",
+        }
+    }
+
+    pub fn base_url(self) -> &'static str {
+        match self {
+            Self::OpenAi => "https://api.openai.com",
+            Self::Anthropic => "https://api.anthropic.com",
+            Self::Gemini => "https://generativelanguage.googleapis.com",
+        }
+    }
+
+    /// Attaches `key` the way the provider expects it. Keys always go into
+    /// headers: URLs end up in error messages.
+    pub fn authorize(self, request: RequestBuilder, key: &str) -> RequestBuilder {
+        match self {
+            Self::OpenAi => request.bearer_auth(key),
+            Self::Anthropic => request
+                .header("x-api-key", key)
+                .header("anthropic-version", ANTHROPIC_API_VERSION),
+            Self::Gemini => request.header("x-goog-api-key", key),
+        }
+    }
+
+    /// Describes a key by its prefix, without revealing it.
+    pub fn key_kind(self, key: &str) -> &'static str {
+        match self {
+            Self::OpenAi if key.starts_with("sk-admin-") => "admin",
+            Self::OpenAi if key.starts_with("sk-proj-") => "project",
+            Self::OpenAi if key.starts_with("sk-") => "user/legacy",
+            Self::Anthropic if key.starts_with("sk-ant-admin") => "admin",
+            Self::Anthropic if key.starts_with("sk-ant-") => "standard",
+            Self::Gemini if key.starts_with("AIza") => "api_key",
+            _ => "unknown",
+        }
+    }
+
+    fn url(self, path: &str) -> Result<Url> {
+        http::api_url(self.base_url(), path)
+    }
+}
+
+/// Loads a secret and the provider it belongs to.
+pub fn resolve_key(db: &Database, secret_id: &str) -> Result<(Provider, Zeroizing<String>)> {
+    let secret = db
+        .with_conn(|conn| secrets::use_secret(conn, secret_id))?
+        .ok_or_else(|| Error::InvalidInput("Secret nicht gefunden".into()))?;
+    let provider = Provider::from_name(&secret.provider).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Provider '{}' wird für API-Anfragen nicht unterstützt",
+            secret.provider
+        ))
+    })?;
+    Ok((provider, Zeroizing::new(secret.value)))
+}
+
+#[derive(Serialize)]
+pub struct ProviderResponse {
+    status: u16,
+    /// Parsed JSON, or the raw text if the body is not JSON.
+    body: JsonValue,
+}
+
+/// Sends `method path?query` to the provider of `secret_id` with its key
+/// attached. Only the status and body of the response are returned.
+#[tauri::command]
+pub async fn provider_request(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    method: String,
+    path: String,
+    query: Option<BTreeMap<String, String>>,
+) -> Result<ProviderResponse> {
+    vault.ensure_unlocked()?;
+    let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+        .map_err(|_| Error::InvalidInput(format!("Ungültige HTTP-Methode: {method}")))?;
+    let (provider, key) = resolve_key(&db, &secret_id)?;
+    let query: Vec<(&str, String)> = query
+        .iter()
+        .flatten()
+        .map(|(k, v)| (k.as_str(), v.clone()))
+        .collect();
+
+    let (status, body) = ApiClient::new(provider, key)
+        .send(method, &path, &query)
+        .await?;
+    Ok(ProviderResponse { status, body })
+}
+
+/// A secret that can be used with [`provider_request`], without its value.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderKey {
+    id: String,
+    name: String,
+    category: String,
+    provider: String,
+    key_kind: &'static str,
+}
+
+impl ProviderKey {
+    fn new(provider: Provider, secret: SecretRow) -> Self {
+        Self {
+            key_kind: provider.key_kind(&secret.value),
+            id: secret.id,
+            name: secret.name,
+            category: secret.category,
+            provider: secret.provider,
+        }
+    }
+}
+
+/// Lists the keys stored for `provider` so the webview can pick one by id.
+#[tauri::command]
+pub async fn list_provider_keys(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    provider: String,
+) -> Result<Vec<ProviderKey>> {
+    vault.ensure_unlocked()?;
+    let kind = Provider::from_name(&provider).ok_or_else(|| {
+        Error::InvalidInput(format!(
+            "Provider '{provider}' wird für API-Anfragen nicht unterstützt"
+        ))
+    })?;
+    let rows = db.with_conn(|conn| secrets::secrets_by_provider(conn, &provider))?;
+    Ok(rows
+        .into_iter()
+        .map(|secret| ProviderKey::new(kind, secret))
+        .collect())
+}
@@ -0,0 +1,316 @@
+//! OpenAI organization usage and costs API (admin keys).
+
+use chrono::DateTime;
+use serde_json::Value as JsonValue;
+
+use super::usage::{
+    next_page_cursor, Account, CostProvider, CostRecord, DateRange, KeyDiagnosis, Page,
+    ProjectInfo, UsageRecord,
+};
+use super::Provider;
+use crate::error::{Error, Result};
+use crate::http;
+
+/// Usage endpoints and whether they can be grouped by model.
+const USAGE_ENDPOINTS: &[(&str, bool)] = &[
+    ("completions", true),
+    ("embeddings", true),
+    ("images", true),
+    ("audio_speeches", true),
+    ("audio_transcriptions", true),
+    ("code_interpreter_sessions", false),
+    ("vector_stores", false),
+];
+
+pub struct OpenAi {
+    account: Account,
+}
+
+impl OpenAi {
+    pub fn new(account: Account) -> Self {
+        Self { account }
+    }
+}
+
+impl CostProvider for OpenAi {
+    fn provider(&self) -> Provider {
+        Provider::OpenAi
+    }
+
+    fn account(&self) -> &Account {
+        &self.account
+    }
+
+    async fn diagnose(&self) -> KeyDiagnosis {
+        let mut diagnosis = self.account.diagnosis();
+        diagnosis.organization = self
+            .account
+            .client
+            .get("/v1/me", &[])
+            .await
+            .ok()
+            .and_then(|me| parse_organization(&me));
+        match self.list_projects().await {
+            Ok(projects) => {
+                diagnosis.valid = true;
+                if projects.is_empty() {
+                    diagnosis.error = Some("Keine Projekte gefunden".into());
+                }
+                diagnosis.projects = projects;
+            }
+            Err(e) => diagnosis.set_error(&e),
+        }
+        diagnosis
+    }
+
+    async fn list_projects(&self) -> Result<Vec<ProjectInfo>> {
+        http::paginate(|after| {
+            let mut query = vec![("limit", "100".to_owned())];
+            if let Some(after) = after {
+                query.push(("after", after));
+            }
+            async move {
+                let page = self
+                    .account
+                    .client
+                    .get("/v1/organization/projects", &query)
+                    .await?;
+                Ok(parse_projects(&page))
+            }
+        })
+        .await
+    }
+
+    async fn fetch_usage(&self, range: DateRange) -> Result<Vec<UsageRecord>> {
+        let mut records = Vec::new();
+        for &(endpoint, by_model) in USAGE_ENDPOINTS {
+            let mut query = bucket_query(range);
+            if by_model {
+                query.push(("group_by", "model".into()));
+            }
+            if endpoint == "completions" {
+                query.push(("group_by", "batch".into()));
+            }
+            let path = format!("/v1/organization/usage/{endpoint}");
+            match self
+                .account
+                .client
+                .get_pages(&path, query, parse_usage_page)
+                .await
+            {
+                Ok(page) => records.extend(page),
+                // Endpoints the organization has no access to are skipped
+                Err(Error::NotFound(_) | Error::Api { status: 400, .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(records)
+    }
+
+    async fn fetch_costs(&self, range: DateRange) -> Result<Vec<CostRecord>> {
+        self.account
+            .client
+            .get_pages(
+                "/v1/organization/costs",
+                bucket_query(range),
+                parse_costs_page,
+            )
+            .await
+    }
+}
+
+fn bucket_query(range: DateRange) -> Vec<(&'static str, String)> {
+    vec![
+        ("start_time", range.start_timestamp().to_string()),
+        ("end_time", range.end_timestamp().to_string()),
+        ("bucket_width", "1d".into()),
+        ("limit", "31".into()),
+        ("group_by", "project_id".into()),
+    ]
+}
+
+fn parse_organization(me: &JsonValue) -> Option<String> {
+    ["name", "email", "id"]
+        .iter()
+        .find_map(|field| me.get(field).and_then(JsonValue::as_str))
+        .map(str::to_owned)
+}
+
+fn parse_projects(page: &JsonValue) -> Page<ProjectInfo> {
+    let items: Vec<ProjectInfo> = page["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|p| {
+            let id = p["id"].as_str()?;
+            Some(ProjectInfo {
+                id: id.to_owned(),
+                name: p["name"].as_str().unwrap_or(id).to_owned(),
+            })
+        })
+        .collect();
+    let next_page = if page["has_more"].as_bool().unwrap_or(false) {
+        page["last_id"].as_str().map(str::to_owned)
+    } else {
+        None
+    };
+    Page { items, next_page }
+}
+
+/// Iterates the results of every bucket together with the bucket's day.
+fn buckets(page: &JsonValue) -> Result<impl Iterator<Item = (chrono::NaiveDate, &JsonValue)> + '_> {
+    let data = page["data"]
+        .as_array()
+        .ok_or_else(|| Error::InvalidInput("Unerwartete Antwort von OpenAI".into()))?;
+    Ok(data.iter().flat_map(|bucket| {
+        let date = bucket["start_time"]
+            .as_i64()
+            .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            .map(|dt| dt.date_naive());
+        bucket["results"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(move |result| Some((date?, result)))
+    }))
+}
+
+/// First of `fields` present as a number.
+fn first_u64(result: &JsonValue, fields: &[&str]) -> u64 {
+    fields
+        .iter()
+        .find_map(|field| result[field].as_u64())
+        .unwrap_or(0)
+}
+
+fn optional_str(value: &JsonValue) -> Option<String> {
+    value.as_str().map(str::to_owned)
+}
+
+/// Parses a page of any `/organization/usage/*` endpoint. The endpoints use
+/// different field names for tokens, characters and request counts.
+pub fn parse_usage_page(page: &JsonValue) -> Result<Page<UsageRecord>> {
+    let items = buckets(page)?
+        .map(|(date, result)| UsageRecord {
+            date,
+            project_id: optional_str(&result["project_id"]),
+            model: optional_str(&result["model"]),
+            input_tokens: first_u64(result, &["input_tokens", "num_tokens", "input_characters"]),
+            cached_input_tokens: first_u64(result, &["input_cached_tokens"]),
+            output_tokens: first_u64(
+                result,
+                &["output_tokens", "generated_tokens", "output_characters"],
+            ),
+            requests: first_u64(
+                result,
+                &[
+                    "num_model_requests",
+                    "num_requests",
+                    "num_images",
+                    "num_sessions",
+                ],
+            ),
+            batch: result["batch"].as_bool().unwrap_or(false),
+        })
+        .collect();
+    Ok(Page {
+        items,
+        next_page: next_page_cursor(page),
+    })
+}
+
+/// Parses a page of `/organization/costs`. `amount.value` is a number, older
+/// responses sent it as a decimal string.
+pub fn parse_costs_page(page: &JsonValue) -> Result<Page<CostRecord>> {
+    let items = buckets(page)?
+        .map(|(date, result)| {
+            let amount = &result["amount"]["value"];
+            CostRecord {
+                date,
+                project_id: optional_str(&result["project_id"]),
+                amount_usd: amount
+                    .as_f64()
+                    .or_else(|| amount.as_str().and_then(|s| s.parse().ok()))
+                    .unwrap_or(0.0),
+                estimated: false,
+            }
+        })
+        .collect();
+    Ok(Page {
+        items,
+        next_page: next_page_cursor(page),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> JsonValue {
+        let path = format!(
+            "{}/tests/fixtures/openai/{name}.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_completions_usage_with_pagination() {
+        let page = parse_usage_page(&fixture("usage_completions")).unwrap();
+        assert_eq!(page.next_page.as_deref(), Some("page_AAAAAGfN"));
+        assert_eq!(page.items.len(), 3);
+
+        let first = &page.items[0];
+        assert_eq!(first.date.to_string(), "2025-03-09");
+        assert_eq!(first.project_id.as_deref(), Some("proj_abc"));
+        assert_eq!(first.model.as_deref(), Some("gpt-4o-mini-2024-07-18"));
+        assert_eq!(first.input_tokens, 12_000);
+        assert_eq!(first.cached_input_tokens, 2_000);
+        assert_eq!(first.output_tokens, 3_400);
+        assert_eq!(first.requests, 42);
+        assert!(!first.batch);
+        assert_eq!(page.items[2].date.to_string(), "2025-03-10");
+        assert!(page.items[2].batch);
+    }
+
+    #[test]
+    fn parses_endpoint_specific_usage_fields() {
+        let page = parse_usage_page(&fixture("usage_embeddings")).unwrap();
+        assert!(page.next_page.is_none());
+        assert_eq!(page.items[0].input_tokens, 5_000);
+        assert_eq!(page.items[0].output_tokens, 0);
+        assert_eq!(page.items[0].requests, 7);
+    }
+
+    #[test]
+    fn parses_costs_with_numeric_and_string_amounts() {
+        let page = parse_costs_page(&fixture("costs")).unwrap();
+        let amounts: Vec<_> = page.items.iter().map(|c| c.amount_usd).collect();
+        assert_eq!(amounts, [0.25, 1.5, 0.00158985]);
+        assert_eq!(page.items[1].project_id, None);
+    }
+
+    #[test]
+    fn parses_projects_and_cursor() {
+        let page = parse_projects(&fixture("projects"));
+        assert_eq!(
+            page.items,
+            [
+                ProjectInfo {
+                    id: "proj_abc".into(),
+                    name: "Default project".into()
+                },
+                ProjectInfo {
+                    id: "proj_def".into(),
+                    name: "Panoptic".into()
+                },
+            ]
+        );
+        assert!(page.next_page.is_none());
+    }
+
+    #[test]
+    fn rejects_unexpected_responses() {
+        assert!(parse_usage_page(&serde_json::json!({ "error": "nope" })).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+//! Model prices for estimating costs from token counts.
+//!
+//! The table in `data/prices.json` is compiled into the app and carries its
+//! own version. Local overrides in `settings` replace or add single models.
+//! Costs are only estimated for days on which a provider returned usage but
+//! no costs, e.g. while Anthropic's cost report lags behind. Gemini keys
+//! report no usage at all, so the table has no Gemini prices.
+
+use std::collections::BTreeSet;
+
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use super::usage::{AccountData, CostRecord, UsageRecord};
+use super::Provider;
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+const BUNDLED: &str = include_str!("../../data/prices.json");
+const OVERRIDES_SETTING: &str = "priceOverrides";
+const TOKENS_PER_UNIT: f64 = 1_000_000.0;
+
+/// USD per million tokens of one model. Missing cached and batch prices fall
+/// back to the regular ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPrice {
+    pub provider: Provider,
+    /// Prefix of the model names this price applies to.
+    pub model: String,
+    pub input: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input: Option<f64>,
+    pub output: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_input: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_output: Option<f64>,
+}
+
+impl ModelPrice {
+    fn cost(&self, record: &UsageRecord) -> f64 {
+        let cached = record.cached_input_tokens.min(record.input_tokens) as f64;
+        let uncached = record.input_tokens as f64 - cached;
+        let output = record.output_tokens as f64;
+        let (input_price, output_price) = if record.batch {
+            (
+                self.batch_input.unwrap_or(self.input),
+                self.batch_output.unwrap_or(self.output),
+            )
+        } else {
+            (self.input, self.output)
+        };
+        let cached_price = self.cached_input.unwrap_or(input_price);
+        (uncached * input_price + cached * cached_price + output * output_price) / TOKENS_PER_UNIT
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceTable {
+    pub version: String,
+    pub prices: Vec<ModelPrice>,
+}
+
+impl PriceTable {
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED).expect("valid bundled price table")
+    }
+
+    /// The bundled table with the local overrides applied.
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let mut table = Self::bundled();
+        for price in load_overrides(conn)? {
+            table.set(price);
+        }
+        Ok(table)
+    }
+
+    fn set(&mut self, price: ModelPrice) {
+        self.prices
+            .retain(|p| !(p.provider == price.provider && p.model == price.model));
+        self.prices.push(price);
+    }
+
+    /// The entry with the longest matching prefix, so `gpt-4o-mini-2024-07-18`
+    /// gets the `gpt-4o-mini` price and not the `gpt-4o` one.
+    pub fn find(&self, provider: Provider, model: &str) -> Option<&ModelPrice> {
+        let model = model.trim_start_matches("models/");
+        self.prices
+            .iter()
+            .filter(|p| p.provider == provider && model.starts_with(p.model.as_str()))
+            .max_by_key(|p| p.model.len())
+    }
+
+    pub fn estimate(&self, provider: Provider, record: &UsageRecord) -> Option<f64> {
+        let price = self.find(provider, record.model.as_deref()?)?;
+        Some(price.cost(record))
+    }
+
+    /// Adds estimated costs for the days on which `data` has usage but no
+    /// billed costs. Usage of unknown models stays without cost.
+    pub fn estimate_missing_costs(&self, data: &mut AccountData) {
+        let billed: BTreeSet<NaiveDate> = data.costs.iter().map(|c| c.date).collect();
+        let estimates: Vec<CostRecord> = data
+            .usage
+            .iter()
+            .filter(|record| !billed.contains(&record.date))
+            .filter_map(|record| {
+                Some(CostRecord {
+                    date: record.date,
+                    project_id: record.project_id.clone(),
+                    amount_usd: self.estimate(data.provider, record)?,
+                    estimated: true,
+                })
+            })
+            .collect();
+        data.costs.extend(estimates);
+    }
+}
+
+fn load_overrides(conn: &Connection) -> Result<Vec<ModelPrice>> {
+    Ok(db::get_setting(conn, OVERRIDES_SETTING)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+fn save_overrides(conn: &Connection, overrides: &[ModelPrice]) -> Result<()> {
+    let value = serde_json::to_string(overrides).expect("serializable prices");
+    db::set_setting(conn, OVERRIDES_SETTING, &value)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceEntry {
+    #[serde(flatten)]
+    price: ModelPrice,
+    overridden: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceTableView {
+    version: String,
+    prices: Vec<PriceEntry>,
+}
+
+/// The effective price table, marking the locally overridden models.
+#[tauri::command]
+pub async fn get_price_table(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<PriceTableView> {
+    vault.ensure_unlocked()?;
+    let (table, overrides) =
+        db.with_conn(|conn| Ok((PriceTable::load(conn)?, load_overrides(conn)?)))?;
+    let mut prices: Vec<PriceEntry> = table
+        .prices
+        .into_iter()
+        .map(|price| PriceEntry {
+            overridden: overrides.contains(&price),
+            price,
+        })
+        .collect();
+    prices.sort_by(|a, b| {
+        (a.price.provider.id(), &a.price.model).cmp(&(b.price.provider.id(), &b.price.model))
+    });
+    Ok(PriceTableView {
+        version: table.version,
+        prices,
+    })
+}
+
+/// Overrides the price of a model, or adds a model missing from the table.
+#[tauri::command]
+pub async fn set_price_override(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    price: ModelPrice,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let values = [
+        Some(price.input),
+        Some(price.output),
+        price.cached_input,
+        price.batch_input,
+        price.batch_output,
+    ];
+    if price.model.trim().is_empty()
+        || values
+            .into_iter()
+            .flatten()
+            .any(|v| !v.is_finite() || v < 0.0)
+    {
+        return Err(Error::InvalidInput("Ungültiger Preis".into()));
+    }
+    if price.provider == Provider::Gemini {
+        return Err(Error::InvalidInput(
+            "Für Gemini gibt es keine Verbrauchsdaten, aus denen Kosten geschätzt werden könnten"
+                .into(),
+        ));
+    }
+    db.with_conn(|conn| {
+        let mut overrides = load_overrides(conn)?;
+        overrides.retain(|p| !(p.provider == price.provider && p.model == price.model));
+        overrides.push(price);
+        save_overrides(conn, &overrides)
+    })
+}
+
+/// Goes back to the bundled price of a model.
+#[tauri::command]
+pub async fn remove_price_override(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    provider: Provider,
+    model: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| {
+        let mut overrides = load_overrides(conn)?;
+        overrides.retain(|p| !(p.provider == provider && p.model == model));
+        save_overrides(conn, &overrides)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(date: &str, model: &str, input: u64, cached: u64, output: u64) -> UsageRecord {
+        UsageRecord {
+            date: date.parse().unwrap(),
+            project_id: None,
+            model: Some(model.into()),
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            requests: 1,
+            batch: false,
+        }
+    }
+
+    #[test]
+    fn matches_the_longest_model_prefix() {
+        let table = PriceTable::bundled();
+        let find = |provider, model| table.find(provider, model).map(|p| p.model.as_str());
+        assert_eq!(
+            find(Provider::OpenAi, "gpt-4o-mini-2024-07-18"),
+            Some("gpt-4o-mini")
+        );
+        assert_eq!(find(Provider::OpenAi, "gpt-4o-2024-08-06"), Some("gpt-4o"));
+        assert_eq!(
+            find(Provider::Anthropic, "claude-3-5-haiku-20241022"),
+            Some("claude-3-5-haiku")
+        );
+        assert_eq!(find(Provider::Anthropic, "gpt-4o"), None);
+    }
+
+    #[test]
+    fn estimates_only_days_without_billed_costs() {
+        let mut table = PriceTable::bundled();
+        table.set(ModelPrice {
+            provider: Provider::Anthropic,
+            model: "claude-test".into(),
+            input: 2.0,
+            cached_input: Some(0.5),
+            output: 10.0,
+            batch_input: None,
+            batch_output: None,
+        });
+        let mut data = AccountData {
+            provider: Provider::Anthropic,
+            account_name: "Admin".into(),
+            projects: vec![],
+            usage: vec![
+                usage("2025-03-09", "claude-test-1", 1_000_000, 0, 0),
+                usage("2025-03-10", "claude-test-1", 1_000_000, 400_000, 100_000),
+                usage("2025-03-10", "unknown-model", 1_000_000, 0, 0),
+            ],
+            costs: vec![CostRecord {
+                date: "2025-03-09".parse().unwrap(),
+                project_id: None,
+                amount_usd: 1.0,
+                estimated: false,
+            }],
+        };
+
+        table.estimate_missing_costs(&mut data);
+
+        assert_eq!(data.costs.len(), 2);
+        let estimate = &data.costs[1];
+        assert!(estimate.estimated);
+        assert_eq!(estimate.date.to_string(), "2025-03-10");
+        // 0.6M uncached * $2 + 0.4M cached * $0.5 + 0.1M output * $10
+        assert!((estimate.amount_usd - 2.4).abs() < 1e-9);
+    }
+}
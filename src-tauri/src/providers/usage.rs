@@ -0,0 +1,863 @@
+//! Usage and cost ingestion across LLM providers.
+//!
+//! Each provider implements [`CostProvider`] and returns plain day records.
+//! [`summarize`] turns the records of all accounts into the [`UsageSummary`]
+//! shown on the Dashboard and the Costs page.
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use tauri::State;
+use zeroize::Zeroizing;
+
+use super::anthropic::Anthropic;
+use super::cache;
+use super::client::ApiClient;
+use super::gemini::Gemini;
+use super::openai::OpenAi;
+use super::prices::PriceTable;
+use super::Provider;
+use crate::audit;
+use crate::db::Database;
+use crate::error::{ApiErrorKind, Error, Result};
+use crate::secrets::{self, SecretRow};
+use crate::vault::VaultState;
+
+/// Days covered by the usage summary, including today.
+pub const SUMMARY_DAYS: u64 = 30;
+pub const WEEK_DAYS: u64 = 7;
+
+/// Inclusive range of UTC days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// The last `days` days up to and including `today`.
+    pub fn last_days(today: NaiveDate, days: u64) -> Self {
+        Self {
+            start: today - Days::new(days.saturating_sub(1)),
+            end: today,
+        }
+    }
+
+    pub fn day(date: NaiveDate) -> Self {
+        Self {
+            start: date,
+            end: date,
+        }
+    }
+
+    /// Unix timestamp of the start of the first day.
+    pub fn start_timestamp(&self) -> i64 {
+        self.start.and_time(NaiveTime::MIN).and_utc().timestamp()
+    }
+
+    /// Unix timestamp of the end of the last day (exclusive).
+    pub fn end_timestamp(&self) -> i64 {
+        (self.end + Days::new(1))
+            .and_time(NaiveTime::MIN)
+            .and_utc()
+            .timestamp()
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    pub fn days(self) -> impl Iterator<Item = NaiveDate> {
+        self.start
+            .iter_days()
+            .take_while(move |&date| date <= self.end)
+    }
+}
+
+/// Token usage of one day, optionally per project and model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub date: NaiveDate,
+    pub project_id: Option<String>,
+    pub model: Option<String>,
+    /// All input tokens, including cached ones.
+    pub input_tokens: u64,
+    /// The part of `input_tokens` read from the prompt cache.
+    pub cached_input_tokens: u64,
+    pub output_tokens: u64,
+    pub requests: u64,
+    /// Usage of the batch API, which is billed at a discount.
+    #[serde(default)]
+    pub batch: bool,
+}
+
+/// Cost of one day, optionally per project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostRecord {
+    pub date: NaiveDate,
+    pub project_id: Option<String>,
+    pub amount_usd: f64,
+    /// Estimated from the price table instead of billed by the provider.
+    #[serde(default)]
+    pub estimated: bool,
+}
+
+/// Project (OpenAI) or workspace (Anthropic).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub id: String,
+    pub name: String,
+}
+
+/// Result of checking one key against its provider.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyDiagnosis {
+    pub key_name: String,
+    pub valid: bool,
+    pub key_type: String,
+    pub organization: Option<String>,
+    pub projects: Vec<ProjectInfo>,
+    pub models: Vec<String>,
+    pub error: Option<String>,
+    pub error_kind: Option<ApiErrorKind>,
+}
+
+impl KeyDiagnosis {
+    pub fn set_error(&mut self, error: &Error) {
+        self.error = Some(error.to_string());
+        self.error_kind = error.api_kind();
+    }
+}
+
+pub use crate::http::Page;
+
+/// The `next_page` cursor of OpenAI and Anthropic reports, if `has_more`.
+pub fn next_page_cursor(page: &JsonValue) -> Option<String> {
+    if page["has_more"].as_bool().unwrap_or(false) {
+        page["next_page"].as_str().map(str::to_owned)
+    } else {
+        None
+    }
+}
+
+/// A stored key with an authenticated client for its provider.
+pub struct Account {
+    pub secret_id: String,
+    pub name: String,
+    pub key_kind: &'static str,
+    pub client: ApiClient,
+}
+
+impl Account {
+    pub fn new(provider: Provider, secret: SecretRow) -> Self {
+        Self {
+            key_kind: provider.key_kind(&secret.value),
+            client: ApiClient::new(provider, Zeroizing::new(secret.value)),
+            secret_id: secret.id,
+            name: secret.name,
+        }
+    }
+
+    pub fn diagnosis(&self) -> KeyDiagnosis {
+        KeyDiagnosis {
+            key_name: self.name.clone(),
+            key_type: self.key_kind.to_owned(),
+            ..KeyDiagnosis::default()
+        }
+    }
+}
+
+pub trait CostProvider {
+    fn provider(&self) -> Provider;
+    fn account(&self) -> &Account;
+    async fn diagnose(&self) -> KeyDiagnosis;
+    async fn list_projects(&self) -> Result<Vec<ProjectInfo>>;
+    async fn fetch_usage(&self, range: DateRange) -> Result<Vec<UsageRecord>>;
+    async fn fetch_costs(&self, range: DateRange) -> Result<Vec<CostRecord>>;
+}
+
+/// Everything fetched for one account.
+pub struct AccountData {
+    pub provider: Provider,
+    pub account_name: String,
+    pub projects: Vec<ProjectInfo>,
+    pub usage: Vec<UsageRecord>,
+    pub costs: Vec<CostRecord>,
+}
+
+pub async fn collect<P: CostProvider>(provider: &P, range: DateRange) -> Result<AccountData> {
+    Ok(AccountData {
+        provider: provider.provider(),
+        account_name: provider.account().name.clone(),
+        projects: provider.list_projects().await?,
+        usage: provider.fetch_usage(range).await?,
+        costs: provider.fetch_costs(range).await?,
+    })
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    pub date: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub requests: u64,
+    pub cost_usd: f64,
+    /// Whether part of `cost_usd` is estimated from the price table.
+    pub estimated: bool,
+}
+
+impl DailyUsage {
+    fn empty(date: NaiveDate) -> Self {
+        Self {
+            date: date.to_string(),
+            ..Self::default()
+        }
+    }
+
+    fn add_usage(&mut self, record: &UsageRecord) {
+        self.input_tokens += record.input_tokens;
+        self.output_tokens += record.output_tokens;
+        self.total_tokens += record.input_tokens + record.output_tokens;
+        self.requests += record.requests;
+    }
+
+    fn add_cost(&mut self, record: &CostRecord) {
+        self.cost_usd += record.amount_usd;
+        self.estimated |= record.estimated;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectUsage {
+    pub project: ProjectInfo,
+    pub provider: Provider,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+    pub requests: u64,
+    pub cost_usd: f64,
+    pub cost_today: f64,
+    pub cost_week: f64,
+    pub cost_month: f64,
+    /// Whether any of the costs are estimated.
+    pub estimated: bool,
+    /// Newest first.
+    pub daily_usage: Vec<DailyUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStatus {
+    pub provider: Provider,
+    pub keys: usize,
+    pub error: Option<String>,
+    /// Class of the first error, e.g. an invalid key or a rate limit.
+    pub error_kind: Option<ApiErrorKind>,
+    /// Unix time of the last successful fetch, `None` if never synced.
+    pub last_synced_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageSummary {
+    pub today: DailyUsage,
+    /// Days of the last week with data, newest first.
+    pub this_week: Vec<DailyUsage>,
+    /// Days of the last 30 days with data, newest first.
+    pub this_month: Vec<DailyUsage>,
+    pub total_cost_today: f64,
+    pub total_cost_week: f64,
+    pub total_cost_month: f64,
+    /// Sorted by cost, highest first.
+    pub projects: Vec<ProjectUsage>,
+    pub providers: Vec<ProviderStatus>,
+    /// Version of the price table used for estimated costs.
+    pub price_version: Option<String>,
+}
+
+/// Days of each project of one account, keyed by project id.
+type ProjectDays = BTreeMap<String, (ProjectInfo, BTreeMap<NaiveDate, DailyUsage>)>;
+
+/// The days of `project_id`; records without a project go to `default`.
+fn project_days<'a>(
+    per_project: &'a mut ProjectDays,
+    default: &ProjectInfo,
+    project_id: &Option<String>,
+) -> &'a mut BTreeMap<NaiveDate, DailyUsage> {
+    let project = match project_id {
+        Some(id) => ProjectInfo {
+            id: id.clone(),
+            name: id.clone(),
+        },
+        None => default.clone(),
+    };
+    &mut per_project
+        .entry(project.id.clone())
+        .or_insert_with(|| (project, BTreeMap::new()))
+        .1
+}
+
+/// Aggregates the records of all accounts into day and project totals.
+pub fn summarize(
+    today: NaiveDate,
+    accounts: &[AccountData],
+    providers: Vec<ProviderStatus>,
+) -> UsageSummary {
+    let month = DateRange::last_days(today, SUMMARY_DAYS);
+    let week = DateRange::last_days(today, WEEK_DAYS);
+
+    let mut days: BTreeMap<NaiveDate, DailyUsage> = BTreeMap::new();
+    let mut projects = Vec::new();
+
+    for account in accounts {
+        // Usage without a project, or all usage of providers without projects
+        let default_project = ProjectInfo {
+            id: format!("{}:{}", account.provider.id(), account.account_name),
+            name: format!("{} ({})", account.provider.label(), account.account_name),
+        };
+        let mut per_project: ProjectDays = account
+            .projects
+            .iter()
+            .map(|p| (p.id.clone(), (p.clone(), BTreeMap::new())))
+            .collect();
+        if per_project.is_empty() {
+            per_project.insert(
+                default_project.id.clone(),
+                (default_project.clone(), BTreeMap::new()),
+            );
+        }
+
+        for record in account.usage.iter().filter(|r| month.contains(r.date)) {
+            days.entry(record.date)
+                .or_insert_with(|| DailyUsage::empty(record.date))
+                .add_usage(record);
+            project_days(&mut per_project, &default_project, &record.project_id)
+                .entry(record.date)
+                .or_insert_with(|| DailyUsage::empty(record.date))
+                .add_usage(record);
+        }
+        for record in account.costs.iter().filter(|r| month.contains(r.date)) {
+            days.entry(record.date)
+                .or_insert_with(|| DailyUsage::empty(record.date))
+                .add_cost(record);
+            project_days(&mut per_project, &default_project, &record.project_id)
+                .entry(record.date)
+                .or_insert_with(|| DailyUsage::empty(record.date))
+                .add_cost(record);
+        }
+
+        for (project, project_days) in per_project.into_values() {
+            let mut usage = ProjectUsage {
+                project,
+                provider: account.provider,
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+                requests: 0,
+                cost_usd: 0.0,
+                cost_today: 0.0,
+                cost_week: 0.0,
+                cost_month: 0.0,
+                estimated: false,
+                daily_usage: Vec::with_capacity(project_days.len()),
+            };
+            for (date, day) in project_days.into_iter().rev() {
+                usage.input_tokens += day.input_tokens;
+                usage.output_tokens += day.output_tokens;
+                usage.total_tokens += day.total_tokens;
+                usage.requests += day.requests;
+                usage.cost_usd += day.cost_usd;
+                usage.cost_month += day.cost_usd;
+                usage.estimated |= day.estimated;
+                if week.contains(date) {
+                    usage.cost_week += day.cost_usd;
+                }
+                if date == today {
+                    usage.cost_today += day.cost_usd;
+                }
+                usage.daily_usage.push(day);
+            }
+            projects.push(usage);
+        }
+    }
+
+    projects.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+
+    let this_month: Vec<DailyUsage> = days.values().rev().cloned().collect();
+    let this_week: Vec<DailyUsage> = days
+        .range(week.start..)
+        .rev()
+        .map(|(_, day)| day.clone())
+        .collect();
+    let today_usage = days
+        .get(&today)
+        .cloned()
+        .unwrap_or_else(|| DailyUsage::empty(today));
+
+    UsageSummary {
+        total_cost_today: today_usage.cost_usd,
+        total_cost_week: this_week.iter().map(|d| d.cost_usd).sum(),
+        total_cost_month: this_month.iter().map(|d| d.cost_usd).sum(),
+        today: today_usage,
+        this_week,
+        this_month,
+        projects,
+        providers,
+        price_version: None,
+    }
+}
+
+/// Keys used for usage reports: admin keys (by prefix or name) and keys in
+/// the `llm` category.
+fn is_usage_key(provider: Provider, secret: &SecretRow) -> bool {
+    let kind = provider.key_kind(&secret.value);
+    let is_llm = secret.category.eq_ignore_ascii_case("llm");
+    match provider {
+        Provider::OpenAi | Provider::Anthropic => {
+            kind == "admin" || secret.name.to_lowercase().contains("admin") || is_llm
+        }
+        Provider::Gemini => kind == "api_key" || is_llm,
+    }
+}
+
+/// The usage keys of all LLM providers.
+pub struct Accounts {
+    pub openai: Vec<OpenAi>,
+    pub anthropic: Vec<Anthropic>,
+    pub gemini: Vec<Gemini>,
+}
+
+fn load_accounts(db: &Database) -> Result<Accounts> {
+    db.with_conn(|conn| {
+        let accounts = |provider: Provider| -> Result<Vec<Account>> {
+            Ok(secrets::secrets_by_provider(conn, provider.id())?
+                .into_iter()
+                .filter(|secret| is_usage_key(provider, secret))
+                .map(|secret| Account::new(provider, secret))
+                .collect())
+        };
+        Ok(Accounts {
+            openai: accounts(Provider::OpenAi)?
+                .into_iter()
+                .map(OpenAi::new)
+                .collect(),
+            anthropic: accounts(Provider::Anthropic)?
+                .into_iter()
+                .map(Anthropic::new)
+                .collect(),
+            gemini: accounts(Provider::Gemini)?
+                .into_iter()
+                .map(Gemini::new)
+                .collect(),
+        })
+    })
+}
+
+/// Fetches `range` for one account and writes the audit entry.
+pub async fn fetch_logged<P: CostProvider>(
+    db: &Database,
+    account: &P,
+    range: DateRange,
+) -> Result<AccountData> {
+    let details = json!({
+        "provider": account.provider().id(),
+        "key": account.account().name,
+        "startDate": range.start.to_string(),
+        "endDate": range.end.to_string(),
+    });
+    let result = collect(account, range).await;
+    let (action, details) = match &result {
+        Ok(_) => (audit::API_CALL, details),
+        Err(e) => (
+            audit::API_ERROR,
+            json!({ "request": details, "error": e.to_string() }),
+        ),
+    };
+    let secret_id = account.account().secret_id.as_str();
+    if let Err(e) = db.with_audit(|conn, key| {
+        audit::log(
+            conn,
+            key,
+            action,
+            Some("usage"),
+            Some(secret_id),
+            Some(&details),
+        )
+    }) {
+        eprintln!("Failed to write audit log: {e}");
+    }
+    result
+}
+
+/// Refreshes the stale days of every account of one provider and reads all
+/// accounts from the cache. Accounts that cannot be fetched (e.g. offline)
+/// are served from the cache and reported in the status until a fetch
+/// succeeds again.
+async fn collect_all<P: CostProvider>(
+    db: &Database,
+    accounts: &[P],
+    provider: Provider,
+    range: DateRange,
+    now: i64,
+    data: &mut Vec<AccountData>,
+) -> Result<ProviderStatus> {
+    let mut errors = Vec::new();
+    let mut error_kind = None;
+    let mut synced = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        let account_id = cache::account_id(provider, &account.account().secret_id);
+        let (mut cached, mut failure) = db.with_conn(|conn| {
+            Ok((
+                cache::load(conn, &account_id, range)?,
+                cache::load_error(conn, &account_id)?,
+            ))
+        })?;
+        if let Some(stale) = cache::stale_range(range, &cached, now) {
+            match fetch_logged(db, account, stale).await {
+                Ok(fetched) => {
+                    cached = db.with_conn(|conn| {
+                        cache::store(
+                            conn,
+                            &account_id,
+                            stale,
+                            &fetched.projects,
+                            &fetched.usage,
+                            &fetched.costs,
+                            now,
+                        )?;
+                        cache::clear_error(conn, &account_id)?;
+                        cache::load(conn, &account_id, range)
+                    })?;
+                    failure = None;
+                }
+                Err(e) => {
+                    let error = cache::SyncError {
+                        message: e.to_string(),
+                        kind: e.api_kind(),
+                    };
+                    db.with_conn(|conn| cache::store_error(conn, &account_id, &error, now))?;
+                    failure = Some(error);
+                }
+            }
+        }
+        // Also while the cache is fresh and nothing was fetched
+        if let Some(failure) = failure {
+            error_kind = error_kind.or(failure.kind);
+            errors.push(format!("{}: {}", account.account().name, failure.message));
+        }
+        synced.push(cache::last_synced(&cached));
+        data.push(cache::account_data(
+            provider,
+            &account.account().name,
+            cached,
+        ));
+    }
+    Ok(ProviderStatus {
+        provider,
+        keys: accounts.len(),
+        error: (!errors.is_empty()).then(|| errors.join("; ")),
+        error_kind,
+        // The oldest sync of all accounts
+        last_synced_at: synced
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .and_then(|synced| synced.into_iter().min()),
+    })
+}
+
+/// Usage and costs of the last 30 days across OpenAI, Anthropic and Gemini.
+/// Only days missing from `usage_cache` or still open are fetched.
+#[tauri::command]
+pub async fn get_usage_summary(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<UsageSummary> {
+    vault.ensure_unlocked()?;
+    load_summary(&db).await
+}
+
+/// [`get_usage_summary`] without the vault check, for background tasks that
+/// check it themselves.
+pub async fn load_summary(db: &Database) -> Result<UsageSummary> {
+    let accounts = load_accounts(db)?;
+    let now = Utc::now();
+    let today = now.date_naive();
+    let now = now.timestamp();
+    let range = DateRange::last_days(today, SUMMARY_DAYS);
+
+    let mut data = Vec::new();
+    let statuses = vec![
+        collect_all(
+            db,
+            &accounts.openai,
+            Provider::OpenAi,
+            range,
+            now,
+            &mut data,
+        )
+        .await?,
+        collect_all(
+            db,
+            &accounts.anthropic,
+            Provider::Anthropic,
+            range,
+            now,
+            &mut data,
+        )
+        .await?,
+        collect_all(
+            db,
+            &accounts.gemini,
+            Provider::Gemini,
+            range,
+            now,
+            &mut data,
+        )
+        .await?,
+    ];
+    let prices = db.with_conn(PriceTable::load)?;
+    let mut estimated = false;
+    for account in &mut data {
+        let billed = account.costs.len();
+        prices.estimate_missing_costs(account);
+        estimated |= account.costs.len() > billed;
+    }
+    let mut summary = summarize(today, &data, statuses);
+    summary.price_version = estimated.then_some(prices.version);
+    Ok(summary)
+}
+
+/// Totals of one provider in one calendar month.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthlyUsage {
+    /// `YYYY-MM`
+    pub month: String,
+    pub provider: Provider,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub requests: u64,
+    pub cost_usd: f64,
+    /// Whether part of `cost_usd` is estimated from the price table.
+    pub estimated: bool,
+}
+
+/// Sums cached days per month and provider, newest month first.
+pub fn monthly_totals(
+    days: &[(Provider, cache::CachedDay)],
+    prices: &PriceTable,
+) -> Vec<MonthlyUsage> {
+    let mut months: BTreeMap<(String, &str), MonthlyUsage> = BTreeMap::new();
+    for (provider, day) in days {
+        let month = day.date.format("%Y-%m").to_string();
+        let totals = months
+            .entry((month.clone(), provider.id()))
+            .or_insert_with(|| MonthlyUsage {
+                month,
+                provider: *provider,
+                input_tokens: 0,
+                output_tokens: 0,
+                requests: 0,
+                cost_usd: 0.0,
+                estimated: false,
+            });
+        for record in &day.bucket.usage {
+            totals.input_tokens += record.input_tokens;
+            totals.output_tokens += record.output_tokens;
+            totals.requests += record.requests;
+        }
+        if day.bucket.costs.is_empty() {
+            let estimates: Vec<f64> = day
+                .bucket
+                .usage
+                .iter()
+                .filter_map(|record| prices.estimate(*provider, record))
+                .collect();
+            totals.estimated |= !estimates.is_empty();
+            totals.cost_usd += estimates.iter().sum::<f64>();
+        } else {
+            totals.cost_usd += day.bucket.costs.iter().map(|c| c.amount_usd).sum::<f64>();
+        }
+    }
+    months.into_values().rev().collect()
+}
+
+/// Monthly totals of the last `months` months from `usage_cache`, including
+/// months loaded with a backfill. Nothing is fetched.
+#[tauri::command]
+pub async fn get_usage_history(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    months: u32,
+) -> Result<Vec<MonthlyUsage>> {
+    vault.ensure_unlocked()?;
+    let today = Utc::now().date_naive();
+    let since = today
+        .with_day(1)
+        .and_then(|first| first.checked_sub_months(Months::new(months.saturating_sub(1))))
+        .unwrap_or(NaiveDate::MIN);
+    let (days, prices) =
+        db.with_conn(|conn| Ok((cache::load_since(conn, since)?, PriceTable::load(conn)?)))?;
+    Ok(monthly_totals(&days, &prices))
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDiagnosis {
+    pub keys: Vec<KeyDiagnosis>,
+    pub total_keys: usize,
+    pub valid_keys: usize,
+    pub total_projects: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnoses {
+    pub openai: ProviderDiagnosis,
+    pub anthropic: ProviderDiagnosis,
+    pub gemini: ProviderDiagnosis,
+}
+
+async fn diagnose_all<P: CostProvider>(accounts: &[P]) -> ProviderDiagnosis {
+    let mut keys = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        keys.push(account.diagnose().await);
+    }
+    ProviderDiagnosis {
+        total_keys: keys.len(),
+        valid_keys: keys.iter().filter(|k| k.valid).count(),
+        total_projects: keys.iter().map(|k| k.projects.len()).sum(),
+        keys,
+    }
+}
+
+/// Diagnoses a single key, e.g. a new value before it replaces the old one.
+/// `None` if the provider has no usage API or the key is not a usage key.
+pub async fn diagnose_secret(secret: SecretRow) -> Option<KeyDiagnosis> {
+    let provider = Provider::from_name(&secret.provider)?;
+    if !is_usage_key(provider, &secret) {
+        return None;
+    }
+    let account = Account::new(provider, secret);
+    Some(match provider {
+        Provider::OpenAi => OpenAi::new(account).diagnose().await,
+        Provider::Anthropic => Anthropic::new(account).diagnose().await,
+        Provider::Gemini => Gemini::new(account).diagnose().await,
+    })
+}
+
+/// Checks every usage key of the LLM providers.
+#[tauri::command]
+pub async fn diagnose_providers(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<Diagnoses> {
+    vault.ensure_unlocked()?;
+    let accounts = load_accounts(&db)?;
+    Ok(Diagnoses {
+        openai: diagnose_all(&accounts.openai).await,
+        anthropic: diagnose_all(&accounts.anthropic).await,
+        gemini: diagnose_all(&accounts.gemini).await,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn usage(day: &str, project: Option<&str>, input: u64, output: u64) -> UsageRecord {
+        UsageRecord {
+            date: date(day),
+            project_id: project.map(str::to_owned),
+            model: None,
+            input_tokens: input,
+            cached_input_tokens: 0,
+            output_tokens: output,
+            requests: 1,
+            batch: false,
+        }
+    }
+
+    fn cost(day: &str, project: Option<&str>, amount_usd: f64) -> CostRecord {
+        CostRecord {
+            date: date(day),
+            project_id: project.map(str::to_owned),
+            amount_usd,
+            estimated: false,
+        }
+    }
+
+    #[test]
+    fn date_range_timestamps_cover_whole_days() {
+        let range = DateRange::last_days(date("2025-03-10"), 2);
+        assert_eq!(range.start, date("2025-03-09"));
+        assert_eq!(range.start_timestamp(), 1_741_478_400);
+        assert_eq!(range.end_timestamp(), 1_741_651_200);
+    }
+
+    #[test]
+    fn summarize_splits_days_projects_and_periods() {
+        let today = date("2025-03-10");
+        let accounts = [
+            AccountData {
+                provider: Provider::OpenAi,
+                account_name: "Admin".into(),
+                projects: vec![ProjectInfo {
+                    id: "proj_a".into(),
+                    name: "App".into(),
+                }],
+                usage: vec![
+                    usage("2025-03-10", Some("proj_a"), 100, 50),
+                    usage("2025-03-01", Some("proj_a"), 10, 5),
+                    // Outside of the 30-day window
+                    usage("2025-01-01", Some("proj_a"), 1_000, 1_000),
+                ],
+                costs: vec![
+                    cost("2025-03-10", Some("proj_a"), 1.5),
+                    cost("2025-03-01", Some("proj_a"), 0.25),
+                    cost("2025-03-09", None, 2.0),
+                ],
+            },
+            AccountData {
+                provider: Provider::Anthropic,
+                account_name: "Claude".into(),
+                projects: vec![],
+                usage: vec![usage("2025-03-09", None, 7, 3)],
+                costs: vec![cost("2025-03-09", None, 4.0)],
+            },
+        ];
+
+        let summary = summarize(today, &accounts, vec![]);
+
+        assert_eq!(summary.today.date, "2025-03-10");
+        assert_eq!(summary.today.total_tokens, 150);
+        assert_eq!(summary.total_cost_today, 1.5);
+        assert_eq!(summary.total_cost_week, 7.5);
+        assert_eq!(summary.total_cost_month, 7.75);
+        let dates: Vec<_> = summary.this_month.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, ["2025-03-10", "2025-03-09", "2025-03-01"]);
+        assert_eq!(summary.this_week.len(), 2);
+
+        let names: Vec<_> = summary
+            .projects
+            .iter()
+            .map(|p| p.project.name.as_str())
+            .collect();
+        assert_eq!(names, ["Anthropic (Claude)", "OpenAI (Admin)", "App"]);
+        let app = &summary.projects[2];
+        assert_eq!(app.cost_month, 1.75);
+        assert_eq!(app.cost_week, 1.5);
+        assert_eq!(app.cost_today, 1.5);
+        assert_eq!(app.input_tokens, 110);
+        assert_eq!(app.daily_usage[0].date, "2025-03-10");
+    }
+}
@@ -0,0 +1,390 @@
+//! Recovery phrase for a forgotten master password.
+//!
+//! The phrase is a 24-word BIP39 mnemonic. Its seed yields an X25519 key pair;
+//! only the public key is kept. The vault key is sealed to that public key
+//! (ephemeral X25519, XChaCha20-Poly1305) in `panoptic.recovery` next to the
+//! database, so a password change can re-seal the new key without the phrase.
+//! Regenerating the phrase replaces the key pair, which makes the old phrase
+//! useless.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use bip39::Mnemonic;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use tauri::{AppHandle, State};
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroizing;
+
+use crate::audit;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::vault::{self, VaultState};
+
+pub const RECOVERY_FILE_NAME: &str = "panoptic.recovery";
+
+pub const RECOVERY_CREATED: &str = "recovery_phrase_created";
+pub const VAULT_RECOVERED: &str = "vault_recovered";
+
+const WORD_COUNT: usize = 24;
+const ENTROPY_BYTES: usize = 32;
+const FORMAT_VERSION: u32 = 1;
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub fn recovery_file(data_path: &Path) -> PathBuf {
+    data_path.join(RECOVERY_FILE_NAME)
+}
+
+/// Contents of `panoptic.recovery`. Binary fields are hex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope {
+    version: u32,
+    public_key: String,
+    ephemeral_key: String,
+    nonce: String,
+    ciphertext: String,
+    created_at: i64,
+}
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> Zeroizing<[u8; 32]> {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    Zeroizing::new(mac.finalize().into_bytes().into())
+}
+
+fn random<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn invalid_phrase() -> Error {
+    Error::InvalidInput("Die Wiederherstellungsphrase ist ungültig".into())
+}
+
+fn damaged() -> Error {
+    Error::InvalidInput("Die Wiederherstellungsdatei ist beschädigt".into())
+}
+
+fn decode<const N: usize>(value: &str) -> Result<[u8; N]> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(damaged)
+}
+
+fn generate_phrase() -> Mnemonic {
+    let entropy = Zeroizing::new(random::<ENTROPY_BYTES>());
+    Mnemonic::from_entropy(entropy.as_slice()).expect("32 bytes are valid BIP39 entropy")
+}
+
+/// Accepts the words in any case and with any whitespace between them.
+fn parse_phrase(phrase: &str) -> Result<Mnemonic> {
+    let normalized = Zeroizing::new(
+        phrase
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    let mnemonic = Mnemonic::parse_normalized(&normalized).map_err(|_| invalid_phrase())?;
+    if mnemonic.word_count() != WORD_COUNT {
+        return Err(invalid_phrase());
+    }
+    Ok(mnemonic)
+}
+
+fn secret_key(mnemonic: &Mnemonic) -> StaticSecret {
+    let seed = Zeroizing::new(mnemonic.to_seed(""));
+    StaticSecret::from(*hmac(seed.as_slice(), &[b"panoptic recovery x25519"]))
+}
+
+fn wrapping_key(shared: &[u8; 32], ephemeral: &PublicKey, public: &PublicKey) -> XChaCha20Poly1305 {
+    let key = hmac(
+        shared,
+        &[
+            b"panoptic recovery wrap",
+            ephemeral.as_bytes(),
+            public.as_bytes(),
+        ],
+    );
+    XChaCha20Poly1305::new(key.as_slice().into())
+}
+
+/// Seals `vault_key` to `public`.
+fn seal(public: &PublicKey, vault_key: &str) -> Envelope {
+    let ephemeral = StaticSecret::from(random::<32>());
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(public);
+    let nonce = random::<24>();
+    let ciphertext = wrapping_key(shared.as_bytes(), &ephemeral_public, public)
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: vault_key.as_bytes(),
+                aad: public.as_bytes(),
+            },
+        )
+        .expect("encryption with a valid key cannot fail");
+    Envelope {
+        version: FORMAT_VERSION,
+        public_key: hex::encode(public.as_bytes()),
+        ephemeral_key: hex::encode(ephemeral_public.as_bytes()),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+        created_at: Utc::now().timestamp(),
+    }
+}
+
+/// Opens the envelope with the phrase. Fails if the phrase belongs to another
+/// (e.g. regenerated) key pair.
+fn open(envelope: &Envelope, mnemonic: &Mnemonic) -> Result<Zeroizing<String>> {
+    if envelope.version != FORMAT_VERSION {
+        return Err(damaged());
+    }
+    let secret = secret_key(mnemonic);
+    let public = PublicKey::from(&secret);
+    if hex::encode(public.as_bytes()) != envelope.public_key {
+        return Err(Error::InvalidInput(
+            "Diese Wiederherstellungsphrase gehört nicht zu diesem Vault \
+             oder wurde inzwischen neu erzeugt"
+                .into(),
+        ));
+    }
+    let ephemeral = PublicKey::from(decode::<32>(&envelope.ephemeral_key)?);
+    let nonce = decode::<24>(&envelope.nonce)?;
+    let ciphertext = hex::decode(&envelope.ciphertext).map_err(|_| damaged())?;
+    let shared = secret.diffie_hellman(&ephemeral);
+    let plaintext = wrapping_key(shared.as_bytes(), &ephemeral, &public)
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: public.as_bytes(),
+            },
+        )
+        .map_err(|_| damaged())?;
+    String::from_utf8(plaintext)
+        .map(Zeroizing::new)
+        .map_err(|_| damaged())
+}
+
+fn read(data_path: &Path) -> Result<Option<Envelope>> {
+    match fs::read_to_string(recovery_file(data_path)) {
+        Ok(json) => serde_json::from_str(&json).map(Some).map_err(|_| damaged()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Written next to the file and renamed over it, like the database itself.
+fn write(data_path: &Path, envelope: &Envelope) -> Result<()> {
+    let file = recovery_file(data_path);
+    let tmp = file.with_extension("recovery.tmp");
+    fs::write(
+        &tmp,
+        serde_json::to_string_pretty(envelope).expect("serializable envelope"),
+    )?;
+    fs::rename(&tmp, &file)?;
+    Ok(())
+}
+
+/// Creates a new phrase for `vault_key`, replacing any previous one.
+fn create(data_path: &Path, vault_key: &str) -> Result<(Mnemonic, Envelope)> {
+    let mnemonic = generate_phrase();
+    let public = PublicKey::from(&secret_key(&mnemonic));
+    let envelope = seal(&public, vault_key);
+    write(data_path, &envelope)?;
+    Ok((mnemonic, envelope))
+}
+
+/// Re-seals the recovery file, if there is one, after the vault key changed.
+pub fn reseal(data_path: &Path, vault_key: &str) -> Result<()> {
+    let Some(envelope) = read(data_path)? else {
+        return Ok(());
+    };
+    let public = PublicKey::from(decode::<32>(&envelope.public_key)?);
+    let mut sealed = seal(&public, vault_key);
+    sealed.created_at = envelope.created_at;
+    write(data_path, &sealed)
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryStatus {
+    configured: bool,
+    created_at: Option<i64>,
+}
+
+#[tauri::command]
+pub fn recovery_status(data_path: String) -> Result<RecoveryStatus> {
+    let envelope = read(Path::new(&data_path))?;
+    Ok(RecoveryStatus {
+        configured: envelope.is_some(),
+        created_at: envelope.map(|e| e.created_at),
+    })
+}
+
+/// Generates a new phrase for the open vault and returns its words. This is
+/// the only time the phrase leaves the backend; any previous phrase stops
+/// working.
+#[tauri::command]
+pub async fn generate_recovery_phrase(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<Vec<String>> {
+    let key = vault.key()?;
+    let data_path = db.data_path()?;
+    let replaced = read(&data_path)?.is_some();
+    let (mnemonic, _) = create(&data_path, &key)?;
+    db.with_audit(|conn, chain_key| {
+        audit::log(
+            conn,
+            chain_key,
+            RECOVERY_CREATED,
+            Some("vault"),
+            None,
+            Some(&json!({ "replaced": replaced })),
+        )
+    })?;
+    Ok(mnemonic.words().map(str::to_owned).collect())
+}
+
+/// Opens the vault in `data_path` with the recovery phrase and sets a new
+/// master password. The phrase stays valid.
+#[tauri::command]
+pub async fn recover_with_phrase(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    data_path: String,
+    phrase: String,
+    new_password: String,
+) -> Result<()> {
+    let phrase = Zeroizing::new(phrase);
+    let new_password = Zeroizing::new(new_password);
+    if new_password.is_empty() {
+        return Err(Error::InvalidInput(
+            "Das neue Passwort darf nicht leer sein".into(),
+        ));
+    }
+    let data_path = PathBuf::from(data_path);
+    let envelope = read(&data_path)?.ok_or_else(|| {
+        Error::NotFound("Für diesen Vault wurde keine Wiederherstellungsphrase eingerichtet".into())
+    })?;
+    let old_key = open(&envelope, &parse_phrase(&phrase)?)?;
+
+    db.recover(&vault, &data_path, &old_key, &new_password)?;
+    db.with_audit(|conn, chain_key| {
+        audit::log(conn, chain_key, VAULT_RECOVERED, Some("vault"), None, None)
+    })?;
+    vault::unlocked(&app);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("panoptic-recovery-{name}-{}", audit::new_id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn phrase_opens_the_sealed_key_even_after_a_password_change() {
+        let dir = temp_dir("reseal");
+        let (mnemonic, _) = create(&dir, "altes passwort").unwrap();
+        let phrase = mnemonic.to_string();
+        assert_eq!(phrase.split(' ').count(), WORD_COUNT);
+
+        reseal(&dir, "neues passwort").unwrap();
+        let envelope = read(&dir).unwrap().unwrap();
+        // Case and extra whitespace do not matter
+        let typed = format!("  {}\n", phrase.to_uppercase().replace(' ', "   "));
+        let key = open(&envelope, &parse_phrase(&typed).unwrap()).unwrap();
+        assert_eq!(key.as_str(), "neues passwort");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_recovery_keeps_the_old_key() {
+        let dir = temp_dir("rollback");
+        let (mnemonic, _) = create(&dir, "altes passwort").unwrap();
+        // A directory in place of the temporary file makes the re-seal fail
+        let tmp = recovery_file(&dir).with_extension("recovery.tmp");
+        fs::create_dir(&tmp).unwrap();
+
+        let db = Database::default();
+        let vault = VaultState::default();
+        assert!(db
+            .recover(&vault, &dir, "altes passwort", "neues passwort")
+            .is_err());
+        assert!(!vault.is_unlocked());
+        let envelope = read(&dir).unwrap().unwrap();
+        assert_eq!(
+            open(&envelope, &mnemonic).unwrap().as_str(),
+            "altes passwort"
+        );
+
+        // The database still opens with the old key, and a retry goes through
+        fs::remove_dir(&tmp).unwrap();
+        db.recover(&vault, &dir, "altes passwort", "neues passwort")
+            .unwrap();
+        db.with_audit(|conn, key| {
+            assert!(audit::verify(conn, key)?.intact);
+            assert!(crate::auth::verify_and_upgrade(conn, "neues passwort")?);
+            Ok(())
+        })
+        .unwrap();
+        // Sealed is the key derived from the new password, not the password
+        let envelope = read(&dir).unwrap().unwrap();
+        assert_eq!(
+            open(&envelope, &mnemonic).unwrap(),
+            crate::auth::derive_vault_key("neues passwort").unwrap()
+        );
+        db.close();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn regenerating_invalidates_the_old_phrase() {
+        let dir = temp_dir("regenerate");
+        let (old, _) = create(&dir, "passwort").unwrap();
+        let (new, envelope) = create(&dir, "passwort").unwrap();
+        assert!(matches!(open(&envelope, &old), Err(Error::InvalidInput(_))));
+        assert_eq!(open(&envelope, &new).unwrap().as_str(), "passwort");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_malformed_phrases_and_tampered_files() {
+        let mnemonic = Mnemonic::from_entropy(&[0; ENTROPY_BYTES]).unwrap();
+        assert!(parse_phrase(&mnemonic.to_string()).is_ok());
+        // Wrong checksum
+        assert!(parse_phrase(&["abandon"; 24].join(" ")).is_err());
+        // Valid, but only 12 words
+        assert!(parse_phrase(&format!("{} about", ["abandon"; 11].join(" "))).is_err());
+        assert!(parse_phrase("kein gültiges wort").is_err());
+
+        let public = PublicKey::from(&secret_key(&mnemonic));
+        let mut envelope = seal(&public, "passwort");
+        let mut ciphertext = hex::decode(&envelope.ciphertext).unwrap();
+        ciphertext[0] ^= 1;
+        envelope.ciphertext = hex::encode(ciphertext);
+        assert!(open(&envelope, &mnemonic).is_err());
+    }
+}
@@ -0,0 +1,543 @@
+//! Encrypted export bundles for moving secrets between machines.
+//!
+//! A bundle is a JSON file with a plaintext header and the secrets encrypted
+//! with XChaCha20-Poly1305. The key is derived from a passphrase with
+//! Argon2id; salt, cost parameters and nonce are part of the header, which is
+//! authenticated as associated data.
+
+use std::path::{Path, PathBuf};
+
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+use zeroize::Zeroizing;
+
+use super::{find_secret, versions, SecretRow};
+use crate::audit;
+use crate::auth::HashParams;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+const FORMAT: &str = "panoptic-secrets";
+const FORMAT_VERSION: u32 = 1;
+const EXTENSION: &str = "panoptic";
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Upper bounds for the Argon2id parameters of a bundle. The header is read
+/// before the passphrase can be checked, so a crafted file must not be able
+/// to demand gigabytes of memory or minutes of hashing. Exports are capped
+/// the same way.
+const MAX_KDF: HashParams = HashParams {
+    memory_kib: 256 * 1024,
+    iterations: 10,
+    parallelism: 4,
+};
+
+/// Argon2id parameters and salt of a bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Kdf {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+}
+
+/// Everything but the ciphertext. Serialized as associated data, so changing
+/// any field makes decryption fail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+    format: String,
+    version: u32,
+    created_at: i64,
+    count: usize,
+    kdf: Kdf,
+    nonce: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Bundle {
+    #[serde(flatten)]
+    header: Header,
+    ciphertext: String,
+}
+
+/// A secret as stored in a bundle. IDs are not exported; references between
+/// machines do not carry over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundledSecret {
+    name: String,
+    category: String,
+    provider: String,
+    value: String,
+    created_at: i64,
+    rotated_at: Option<i64>,
+}
+
+impl From<SecretRow> for BundledSecret {
+    fn from(row: SecretRow) -> Self {
+        Self {
+            name: row.name,
+            category: row.category,
+            provider: row.provider,
+            value: row.value,
+            created_at: row.created_at,
+            rotated_at: row.rotated_at,
+        }
+    }
+}
+
+/// What to do with a bundled secret whose provider and name already exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictStrategy {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+fn invalid_bundle() -> Error {
+    Error::InvalidInput("Die Datei ist kein gültiges Panoptic-Export-Bundle".into())
+}
+
+fn check_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(Error::InvalidInput(format!(
+            "Die Passphrase muss mindestens {MIN_PASSPHRASE_LEN} Zeichen haben"
+        )));
+    }
+    Ok(())
+}
+
+fn cipher(passphrase: &str, kdf: &Kdf) -> Result<XChaCha20Poly1305> {
+    if kdf.memory_kib > MAX_KDF.memory_kib
+        || kdf.iterations > MAX_KDF.iterations
+        || kdf.parallelism > MAX_KDF.parallelism
+    {
+        return Err(invalid_bundle());
+    }
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|_| invalid_bundle())?;
+    let salt = hex::decode(&kdf.salt).map_err(|_| invalid_bundle())?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, key.as_mut_slice())
+        .map_err(|_| invalid_bundle())?;
+    Ok(XChaCha20Poly1305::new(key.as_slice().into()))
+}
+
+fn aad(header: &Header) -> Vec<u8> {
+    serde_json::to_vec(header).expect("serializable header")
+}
+
+fn seal(secrets: &[BundledSecret], passphrase: &str, params: &HashParams) -> Result<Bundle> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 24];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+    let header = Header {
+        format: FORMAT.into(),
+        version: FORMAT_VERSION,
+        created_at: Utc::now().timestamp(),
+        count: secrets.len(),
+        kdf: Kdf {
+            memory_kib: params.memory_kib.min(MAX_KDF.memory_kib),
+            iterations: params.iterations.min(MAX_KDF.iterations),
+            parallelism: params.parallelism.min(MAX_KDF.parallelism),
+            salt: hex::encode(salt),
+        },
+        nonce: hex::encode(nonce),
+    };
+    let plaintext = Zeroizing::new(serde_json::to_vec(secrets).expect("serializable secrets"));
+    let ciphertext = cipher(passphrase, &header.kdf)?
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &plaintext,
+                aad: &aad(&header),
+            },
+        )
+        .expect("encryption with a valid key cannot fail");
+    Ok(Bundle {
+        header,
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+fn open(bundle: &Bundle, passphrase: &str) -> Result<Vec<BundledSecret>> {
+    let header = &bundle.header;
+    if header.format != FORMAT || header.version != FORMAT_VERSION {
+        return Err(invalid_bundle());
+    }
+    let nonce: [u8; 24] = hex::decode(&header.nonce)
+        .ok()
+        .and_then(|n| n.try_into().ok())
+        .ok_or_else(invalid_bundle)?;
+    let ciphertext = hex::decode(&bundle.ciphertext).map_err(|_| invalid_bundle())?;
+    let plaintext = Zeroizing::new(
+        cipher(passphrase, &header.kdf)?
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &aad(header),
+                },
+            )
+            .map_err(|_| {
+                Error::InvalidInput("Falsche Passphrase oder die Datei wurde verändert".into())
+            })?,
+    );
+    serde_json::from_slice(&plaintext).map_err(|_| invalid_bundle())
+}
+
+fn read_bundle(path: &Path) -> Result<Bundle> {
+    let json = std::fs::read_to_string(path)?;
+    serde_json::from_str(&json).map_err(|_| invalid_bundle())
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    imported: usize,
+    overwritten: usize,
+    skipped: usize,
+}
+
+pub(super) fn existing_id(conn: &Connection, provider: &str, name: &str) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM secrets WHERE LOWER(provider) = LOWER(?1) AND name = ?2",
+            [provider, name],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// First free "name (2)", "name (3)", … for the provider.
+fn free_name(conn: &Connection, provider: &str, name: &str) -> Result<String> {
+    for n in 2.. {
+        let candidate = format!("{name} ({n})");
+        if existing_id(conn, provider, &candidate)?.is_none() {
+            return Ok(candidate);
+        }
+    }
+    unreachable!("some suffix is always free")
+}
+
+fn insert(conn: &Connection, secret: &BundledSecret, name: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO secrets (id, name, category, provider, value, created_at, rotated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            audit::new_id(),
+            name,
+            secret.category,
+            secret.provider.to_lowercase(),
+            secret.value,
+            secret.created_at,
+            secret.rotated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Writes the bundled secrets in one transaction. Conflicts are matched by
+/// provider (case-insensitive) and name.
+fn import(
+    conn: &Connection,
+    secrets: &[BundledSecret],
+    strategy: ConflictStrategy,
+) -> Result<ImportSummary> {
+    let tx = conn.unchecked_transaction()?;
+    let mut summary = ImportSummary::default();
+    for secret in secrets {
+        match (existing_id(&tx, &secret.provider, &secret.name)?, strategy) {
+            (None, _) => {
+                insert(&tx, secret, &secret.name)?;
+                summary.imported += 1;
+            }
+            (Some(_), ConflictStrategy::Skip) => summary.skipped += 1,
+            (Some(id), ConflictStrategy::Overwrite) => {
+                // Like a rotation, the replaced value stays as a version
+                let current = find_secret(&tx, &id)?
+                    .ok_or_else(|| Error::NotFound(format!("Secret {id}")))?;
+                if current.value != secret.value {
+                    versions::archive(&tx, &current)?;
+                }
+                tx.execute(
+                    "UPDATE secrets SET category = ?2, value = ?3,
+                       rotated_at = COALESCE(?4, unixepoch())
+                     WHERE id = ?1",
+                    params![id, secret.category, secret.value, secret.rotated_at],
+                )?;
+                summary.overwritten += 1;
+            }
+            (Some(_), ConflictStrategy::KeepBoth) => {
+                insert(
+                    &tx,
+                    secret,
+                    &free_name(&tx, &secret.provider, &secret.name)?,
+                )?;
+                summary.imported += 1;
+            }
+        }
+    }
+    tx.commit()?;
+    Ok(summary)
+}
+
+async fn pick_save_path(app: &AppHandle) -> Result<Option<PathBuf>> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Secrets exportieren")
+        .set_file_name(format!(
+            "panoptic-secrets-{}.{EXTENSION}",
+            Utc::now().format("%Y-%m-%d")
+        ))
+        .add_filter("Panoptic-Export", &[EXTENSION])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    path.into_path()
+        .map(Some)
+        .map_err(|_| Error::InvalidInput("Ungültiger Speicherort".into()))
+}
+
+async fn pick_open_path(app: &AppHandle) -> Result<Option<PathBuf>> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Secrets importieren")
+        .add_filter("Panoptic-Export", &[EXTENSION])
+        .pick_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    path.into_path()
+        .map(Some)
+        .map_err(|_| Error::InvalidInput("Ungültige Datei".into()))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleExport {
+    path: String,
+    count: usize,
+}
+
+/// Encrypts the chosen secrets into a file the user picks in a save dialog.
+/// Returns `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_secrets(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    ids: Vec<String>,
+    passphrase: String,
+) -> Result<Option<BundleExport>> {
+    vault.ensure_unlocked()?;
+    let passphrase = Zeroizing::new(passphrase);
+    check_passphrase(&passphrase)?;
+    if ids.is_empty() {
+        return Err(Error::InvalidInput("Keine Secrets ausgewählt".into()));
+    }
+
+    let (secrets, params) = db.with_conn(|conn| {
+        let secrets = ids
+            .iter()
+            .map(|id| {
+                find_secret(conn, id)?
+                    .map(BundledSecret::from)
+                    .ok_or_else(|| Error::NotFound(format!("Secret {id}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((secrets, HashParams::load(conn)?))
+    })?;
+    let Some(path) = pick_save_path(&app).await? else {
+        return Ok(None);
+    };
+
+    let bundle = seal(&secrets, &passphrase, &params)?;
+    std::fs::write(
+        &path,
+        serde_json::to_string_pretty(&bundle).expect("serializable bundle"),
+    )?;
+    db.with_audit(|conn, key| {
+        audit::log(
+            conn,
+            key,
+            audit::SECRETS_EXPORTED,
+            Some("secret"),
+            None,
+            Some(&json!({
+                "count": secrets.len(),
+                "names": secrets.iter().map(|s| &s.name).collect::<Vec<_>>(),
+                "path": path.to_string_lossy(),
+            })),
+        )
+    })?;
+    Ok(Some(BundleExport {
+        path: path.to_string_lossy().into_owned(),
+        count: secrets.len(),
+    }))
+}
+
+/// Decrypts a bundle the user picks and imports its secrets. Returns `None`
+/// if the dialog was cancelled.
+#[tauri::command]
+pub async fn import_secrets(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    passphrase: String,
+    conflict: ConflictStrategy,
+) -> Result<Option<ImportSummary>> {
+    vault.ensure_unlocked()?;
+    let passphrase = Zeroizing::new(passphrase);
+    let Some(path) = pick_open_path(&app).await? else {
+        return Ok(None);
+    };
+
+    let secrets = open(&read_bundle(&path)?, &passphrase)?;
+    db.with_audit(|conn, key| {
+        let summary = import(conn, &secrets, conflict)?;
+        audit::log(
+            conn,
+            key,
+            audit::SECRETS_IMPORTED,
+            Some("secret"),
+            None,
+            Some(&json!({
+                "path": path.to_string_lossy(),
+                "conflict": conflict,
+                "imported": summary.imported,
+                "overwritten": summary.overwritten,
+                "skipped": summary.skipped,
+            })),
+        )?;
+        Ok(Some(summary))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ChainKey;
+    use crate::db;
+
+    /// Cheap parameters, the defaults take a while in debug builds.
+    const PARAMS: HashParams = HashParams {
+        memory_kib: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn secret(provider: &str, name: &str, value: &str) -> BundledSecret {
+        BundledSecret {
+            name: name.into(),
+            category: "llm".into(),
+            provider: provider.into(),
+            value: value.into(),
+            created_at: 1_700_000_000,
+            rotated_at: Some(1_710_000_000),
+        }
+    }
+
+    fn rows(conn: &Connection) -> Vec<(String, String, String)> {
+        conn.prepare("SELECT provider, name, value FROM secrets ORDER BY name")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trips_and_rejects_wrong_passphrase_or_tampering() {
+        let secrets = vec![secret("openai", "Prod", "sk-1")];
+        let bundle = seal(&secrets, "richtig lang", &PARAMS).unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("sk-1"));
+
+        let opened = open(&serde_json::from_str(&json).unwrap(), "richtig lang").unwrap();
+        assert_eq!(opened[0].value, "sk-1");
+        assert!(open(&bundle, "falsch lang").is_err());
+
+        // The header is authenticated
+        let mut tampered: Bundle = serde_json::from_str(&json).unwrap();
+        tampered.header.count = 2;
+        assert!(open(&tampered, "richtig lang").is_err());
+    }
+
+    #[test]
+    fn rejects_excessive_kdf_parameters_before_hashing() {
+        let bundle = seal(&[secret("openai", "Prod", "sk-1")], "richtig lang", &PARAMS).unwrap();
+        let json = serde_json::to_string(&bundle).unwrap();
+        for tamper in [
+            |kdf: &mut Kdf| kdf.memory_kib = 4 * 1024 * 1024,
+            |kdf: &mut Kdf| kdf.iterations = 10_000,
+            |kdf: &mut Kdf| kdf.parallelism = 64,
+        ] {
+            let mut crafted: Bundle = serde_json::from_str(&json).unwrap();
+            tamper(&mut crafted.header.kdf);
+            let error = open(&crafted, "richtig lang").unwrap_err();
+            assert_eq!(error.to_string(), invalid_bundle().to_string());
+        }
+
+        // Exports stay importable with expensive local settings
+        let expensive = HashParams {
+            iterations: 99,
+            ..PARAMS
+        };
+        let header = seal(&[], "richtig lang", &expensive).unwrap().header;
+        assert_eq!(header.kdf.iterations, MAX_KDF.iterations);
+    }
+
+    #[test]
+    fn resolves_conflicts_by_provider_and_name() {
+        let conn = db::open_in_memory(&ChainKey::derive("test"));
+        let existing = [secret("openai", "Prod", "alt")];
+        let bundled = [
+            secret("OpenAI", "Prod", "neu"),
+            secret("anthropic", "Dev", "sk-ant"),
+        ];
+
+        import(&conn, &existing, ConflictStrategy::Skip).unwrap();
+        let summary = import(&conn, &bundled, ConflictStrategy::Skip).unwrap();
+        assert_eq!((summary.imported, summary.skipped), (1, 1));
+        assert!(rows(&conn).contains(&("openai".into(), "Prod".into(), "alt".into())));
+
+        let summary = import(&conn, &bundled[..1], ConflictStrategy::Overwrite).unwrap();
+        assert_eq!(summary.overwritten, 1);
+        assert!(rows(&conn).contains(&("openai".into(), "Prod".into(), "neu".into())));
+        let archived: Vec<(i64, String)> = conn
+            .prepare("SELECT version, value FROM secret_versions")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(archived, [(1, "alt".to_owned())]);
+
+        import(&conn, &bundled[..1], ConflictStrategy::KeepBoth).unwrap();
+        import(&conn, &bundled[..1], ConflictStrategy::KeepBoth).unwrap();
+        let names: Vec<_> = rows(&conn).into_iter().map(|(_, name, _)| name).collect();
+        assert_eq!(names, ["Dev", "Prod", "Prod (2)", "Prod (3)"]);
+    }
+}
@@ -0,0 +1,534 @@
+//! Import of secrets from `.env` files, 1Password CSV exports and unencrypted
+//! Bitwarden JSON exports.
+//!
+//! The file is parsed twice: once for the preview, which only carries a masked
+//! hint of each value, and again when the user confirms the entries to
+//! import. Values never reach the webview, the audit log or error messages.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+use zeroize::Zeroizing;
+
+use super::bundle::existing_id;
+use super::{mask, CATEGORIES};
+use crate::audit;
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportSource {
+    Env,
+    OnePassword,
+    Bitwarden,
+}
+
+impl ImportSource {
+    fn filter(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Self::Env => (".env-Datei", &["env", "local", "development", "production"]),
+            Self::OnePassword => ("1Password-CSV", &["csv"]),
+            Self::Bitwarden => ("Bitwarden-JSON", &["json"]),
+        }
+    }
+}
+
+/// A parsed entry before the provider is guessed.
+struct Candidate {
+    name: String,
+    value: Zeroizing<String>,
+}
+
+fn candidate(name: &str, value: &str) -> Option<Candidate> {
+    let (name, value) = (name.trim(), value.trim());
+    (!name.is_empty() && !value.is_empty()).then(|| Candidate {
+        name: name.to_owned(),
+        value: Zeroizing::new(value.to_owned()),
+    })
+}
+
+/// `KEY=value` lines with optional `export`, quotes and trailing comments.
+fn parse_env(text: &str) -> Vec<Candidate> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            if line.starts_with('#') {
+                return None;
+            }
+            let (key, raw) = line.split_once('=')?;
+            let raw = raw.trim();
+            let value = match raw.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &raw[1..];
+                    &inner[..inner.find(quote).unwrap_or(inner.len())]
+                }
+                _ => raw.split(" #").next().unwrap_or(raw),
+            };
+            candidate(key, value)
+        })
+        .collect()
+}
+
+/// Minimal RFC 4180 reader: quoted fields may contain commas, doubled quotes
+/// and line breaks.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => quoted = false,
+            ('"', false) if field.is_empty() => quoted = true,
+            (',', false) => row.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|f| !f.is_empty()));
+    rows
+}
+
+/// 1Password CSV: the title and the password (or credential) column, found
+/// by their header names.
+fn parse_one_password(text: &str) -> Result<Vec<Candidate>> {
+    let mut rows = parse_csv(text).into_iter();
+    let header: Vec<String> = rows
+        .next()
+        .unwrap_or_default()
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|h| names.contains(&h.as_str()));
+    let (Some(title), Some(value)) = (
+        column(&["title", "name"]),
+        column(&["password", "credential"]),
+    ) else {
+        return Err(Error::InvalidInput(
+            "Keine 1Password-CSV: Spalten \"Title\" und \"Password\" fehlen".into(),
+        ));
+    };
+    Ok(rows
+        .filter_map(|row| {
+            let row = Zeroizing::new(row);
+            candidate(row.get(title)?, row.get(value)?)
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct BitwardenExport {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    items: Vec<BitwardenItem>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenItem {
+    name: String,
+    login: Option<BitwardenLogin>,
+    #[serde(default)]
+    fields: Vec<BitwardenField>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenLogin {
+    password: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenField {
+    name: Option<String>,
+    value: Option<String>,
+    /// 0 text, 1 hidden, 2 boolean, 3 linked.
+    #[serde(rename = "type")]
+    kind: u8,
+}
+
+/// Bitwarden JSON: login passwords and hidden custom fields.
+fn parse_bitwarden(text: &str) -> Result<Vec<Candidate>> {
+    let export: BitwardenExport = serde_json::from_str(text)
+        .map_err(|_| Error::InvalidInput("Kein gültiger Bitwarden-JSON-Export".into()))?;
+    if export.encrypted {
+        return Err(Error::InvalidInput(
+            "Verschlüsselte Bitwarden-Exporte werden nicht unterstützt".into(),
+        ));
+    }
+    let mut candidates = Vec::new();
+    for item in export.items {
+        if let Some(password) = item.login.and_then(|l| l.password) {
+            candidates.extend(candidate(&item.name, &Zeroizing::new(password)));
+        }
+        for field in item.fields.into_iter().filter(|f| f.kind == 1) {
+            let value = Zeroizing::new(field.value.unwrap_or_default());
+            let name = match field.name {
+                Some(name) => format!("{} – {name}", item.name),
+                None => item.name.clone(),
+            };
+            candidates.extend(candidate(&name, &value));
+        }
+    }
+    Ok(candidates)
+}
+
+fn parse(source: ImportSource, path: &Path) -> Result<Vec<Candidate>> {
+    let text = Zeroizing::new(std::fs::read_to_string(path)?);
+    match source {
+        ImportSource::Env => Ok(parse_env(&text)),
+        ImportSource::OnePassword => parse_one_password(&text),
+        ImportSource::Bitwarden => parse_bitwarden(&text),
+    }
+}
+
+/// Value prefixes, most specific first.
+const VALUE_PREFIXES: &[(&str, &str, &str)] = &[
+    ("sk-ant-", "anthropic", "llm"),
+    ("sk_live_", "stripe", "app"),
+    ("sk_test_", "stripe", "app"),
+    ("rk_live_", "stripe", "app"),
+    ("rk_test_", "stripe", "app"),
+    ("sk-", "openai", "llm"),
+    ("AIza", "google", "llm"),
+    ("ghp_", "github", "app"),
+    ("github_pat_", "github", "app"),
+    ("re_", "resend", "app"),
+    ("AKIA", "aws", "infrastructure"),
+];
+
+/// Keywords in the (upper-cased) name.
+const NAME_HINTS: &[(&str, &str, &str)] = &[
+    ("ANTHROPIC", "anthropic", "llm"),
+    ("CLAUDE", "anthropic", "llm"),
+    ("OPENAI", "openai", "llm"),
+    ("GEMINI", "google", "llm"),
+    ("GOOGLE", "google", "llm"),
+    ("MISTRAL", "mistral", "llm"),
+    ("COHERE", "cohere", "llm"),
+    ("RAILWAY", "railway", "infrastructure"),
+    ("VERCEL", "vercel", "infrastructure"),
+    ("SUPABASE", "supabase", "infrastructure"),
+    ("NEON", "neondb", "infrastructure"),
+    ("CLOUDFLARE", "cloudflare", "infrastructure"),
+    ("AWS", "aws", "infrastructure"),
+    ("GITHUB", "github", "app"),
+    ("STRIPE", "stripe", "app"),
+    ("RESEND", "resend", "app"),
+];
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// Guesses provider and category from the value prefix, then the name, then
+/// the token format: Railway tokens are UUIDs, Vercel tokens 24 alphanumeric
+/// characters.
+fn guess(name: &str, value: &str) -> (&'static str, &'static str) {
+    if let Some(&(_, provider, category)) = VALUE_PREFIXES
+        .iter()
+        .find(|(prefix, ..)| value.starts_with(prefix))
+    {
+        return (provider, category);
+    }
+    let upper = name.to_uppercase();
+    if let Some(&(_, provider, category)) =
+        NAME_HINTS.iter().find(|(hint, ..)| upper.contains(hint))
+    {
+        return (provider, category);
+    }
+    if is_uuid(value) {
+        ("railway", "infrastructure")
+    } else if value.len() == 24 && value.chars().all(|c| c.is_ascii_alphanumeric()) {
+        ("vercel", "infrastructure")
+    } else {
+        ("other", "app")
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewEntry {
+    index: usize,
+    name: String,
+    provider: &'static str,
+    category: &'static str,
+    hint: String,
+    /// A secret with this provider and name exists already.
+    exists: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    path: String,
+    entries: Vec<PreviewEntry>,
+}
+
+fn preview(conn: &Connection, candidates: &[Candidate]) -> Result<Vec<PreviewEntry>> {
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(index, c)| {
+            let (provider, category) = guess(&c.name, &c.value);
+            Ok(PreviewEntry {
+                index,
+                name: c.name.clone(),
+                provider,
+                category,
+                hint: mask(&c.value),
+                exists: existing_id(conn, provider, &c.name)?.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// An entry the user confirmed, possibly with edited name, provider or
+/// category.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSelection {
+    index: usize,
+    name: String,
+    provider: String,
+    category: String,
+}
+
+fn insert_selected(
+    conn: &Connection,
+    candidates: &[Candidate],
+    selections: &[ImportSelection],
+) -> Result<Vec<String>> {
+    let tx = conn.unchecked_transaction()?;
+    let mut names = Vec::new();
+    for selection in selections {
+        let candidate = candidates.get(selection.index).ok_or_else(|| {
+            Error::InvalidInput("Die Datei hat sich seit der Vorschau geändert".into())
+        })?;
+        let name = selection.name.trim();
+        let provider = selection.provider.trim().to_lowercase();
+        if name.is_empty() || provider.is_empty() {
+            return Err(Error::InvalidInput(
+                "Name und Provider dürfen nicht leer sein".into(),
+            ));
+        }
+        if !CATEGORIES.contains(&selection.category.as_str()) {
+            return Err(Error::InvalidInput(format!(
+                "Unbekannte Kategorie {}",
+                selection.category
+            )));
+        }
+        tx.execute(
+            "INSERT INTO secrets (id, name, category, provider, value)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                audit::new_id(),
+                name,
+                selection.category,
+                provider,
+                candidate.value.as_str()
+            ],
+        )?;
+        names.push(name.to_owned());
+    }
+    tx.commit()?;
+    Ok(names)
+}
+
+async fn pick_file(app: &AppHandle, source: ImportSource) -> Result<Option<PathBuf>> {
+    let (label, extensions) = source.filter();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Secrets importieren")
+        .add_filter(label, extensions)
+        .add_filter("Alle Dateien", &["*"])
+        .pick_file(move |path| {
+            let _ = tx.send(path);
+        });
+    let Some(path) = rx.await.ok().flatten() else {
+        return Ok(None);
+    };
+    path.into_path()
+        .map(Some)
+        .map_err(|_| Error::InvalidInput("Ungültige Datei".into()))
+}
+
+/// Lets the user pick a file and returns what would be imported, with masked
+/// values. Returns `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn preview_secret_import(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    source: ImportSource,
+) -> Result<Option<ImportPreview>> {
+    vault.ensure_unlocked()?;
+    let Some(path) = pick_file(&app, source).await? else {
+        return Ok(None);
+    };
+    let candidates = parse(source, &path)?;
+    let entries = db.with_conn(|conn| preview(conn, &candidates))?;
+    Ok(Some(ImportPreview {
+        path: path.to_string_lossy().into_owned(),
+        entries,
+    }))
+}
+
+/// Parses the previewed file again and inserts the selected entries.
+/// Returns the number of imported secrets.
+#[tauri::command]
+pub async fn import_external_secrets(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    path: String,
+    source: ImportSource,
+    selections: Vec<ImportSelection>,
+) -> Result<usize> {
+    vault.ensure_unlocked()?;
+    let candidates = parse(source, Path::new(&path))?;
+    db.with_audit(|conn, key| {
+        let names = insert_selected(conn, &candidates, &selections)?;
+        audit::log(
+            conn,
+            key,
+            audit::SECRETS_IMPORTED,
+            Some("secret"),
+            None,
+            Some(&json!({
+                "source": source,
+                "path": path,
+                "imported": names.len(),
+                "names": names,
+            })),
+        )?;
+        Ok(names.len())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ChainKey;
+    use crate::db;
+
+    fn pairs(candidates: &[Candidate]) -> Vec<(&str, &str)> {
+        candidates
+            .iter()
+            .map(|c| (c.name.as_str(), c.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_env_files() {
+        let text = "# Kommentar\n\
+                    export OPENAI_API_KEY=sk-abc # inline\n\
+                    ANTHROPIC_API_KEY=\"sk-ant-x # kein Kommentar\"\n\
+                    EMPTY=\n\
+                    SINGLE='a=b'\n\
+                    invalid line\n";
+        assert_eq!(
+            pairs(&parse_env(text)),
+            [
+                ("OPENAI_API_KEY", "sk-abc"),
+                ("ANTHROPIC_API_KEY", "sk-ant-x # kein Kommentar"),
+                ("SINGLE", "a=b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_one_password_csv_with_quoted_fields() {
+        let text = "\u{feff}Title,Url,Username,Password,Notes\r\n\
+                    \"Vercel, Prod\",https://vercel.com,me,abc123,\"zwei\nZeilen\"\r\n\
+                    \"Quote \"\"x\"\"\",,,\"p,w\",\r\n\
+                    Leer,,,,\r\n";
+        assert_eq!(
+            pairs(&parse_one_password(text).unwrap()),
+            [("Vercel, Prod", "abc123"), ("Quote \"x\"", "p,w")]
+        );
+        assert!(parse_one_password("a,b\n1,2").is_err());
+    }
+
+    #[test]
+    fn parses_bitwarden_logins_and_hidden_fields() {
+        let text = r#"{"encrypted":false,"items":[
+            {"type":1,"name":"GitHub","login":{"username":"me","password":"ghp_1"},"fields":[
+                {"name":"Token","value":"secret","type":1},
+                {"name":"Hinweis","value":"sichtbar","type":0}]},
+            {"type":2,"name":"Notiz","notes":"x"}]}"#;
+        assert_eq!(
+            pairs(&parse_bitwarden(text).unwrap()),
+            [("GitHub", "ghp_1"), ("GitHub – Token", "secret")]
+        );
+        assert!(parse_bitwarden(r#"{"encrypted":true,"items":[]}"#).is_err());
+    }
+
+    #[test]
+    fn guesses_providers_from_prefixes_names_and_formats() {
+        assert_eq!(guess("KEY", "sk-ant-api03-x"), ("anthropic", "llm"));
+        assert_eq!(guess("KEY", "sk-proj-x"), ("openai", "llm"));
+        assert_eq!(guess("KEY", "sk_live_x"), ("stripe", "app"));
+        assert_eq!(guess("KEY", "AIzaSy"), ("google", "llm"));
+        assert_eq!(
+            guess("SUPABASE_KEY", "eyJhbGci"),
+            ("supabase", "infrastructure")
+        );
+        assert_eq!(
+            guess("TOKEN", "6f1c2d3e-4a5b-4c6d-8e7f-0123456789ab"),
+            ("railway", "infrastructure")
+        );
+        assert_eq!(
+            guess("TOKEN", "AbCdEfGhIjKlMnOpQrStUvWx"),
+            ("vercel", "infrastructure")
+        );
+        assert_eq!(guess("TOKEN", "x"), ("other", "app"));
+    }
+
+    #[test]
+    fn inserts_only_selected_entries_with_edits() {
+        let conn = db::open_in_memory(&ChainKey::derive("test"));
+        let candidates = parse_env("A=sk-1\nB=sk-2\n");
+        let entries = preview(&conn, &candidates).unwrap();
+        assert_eq!(entries[0].hint, "••••••••");
+        assert!(!entries[0].exists);
+
+        let selections = [ImportSelection {
+            index: 1,
+            name: "Mein Key".into(),
+            provider: "OpenAI".into(),
+            category: "llm".into(),
+        }];
+        insert_selected(&conn, &candidates, &selections).unwrap();
+        let row: (String, String, String) = conn
+            .query_row("SELECT name, provider, value FROM secrets", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(row, ("Mein Key".into(), "openai".into(), "sk-2".into()));
+        assert!(preview(&conn, &parse_env("Mein Key=sk-x")).unwrap()[0].exists);
+    }
+}
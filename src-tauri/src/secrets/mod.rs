@@ -0,0 +1,473 @@
+//! Access to the `secrets` table. Every command refuses to run while the
+//! vault is locked; changes are audited here, not in the webview.
+
+pub mod bundle;
+pub mod import;
+pub mod policies;
+pub mod versions;
+
+use std::time::Duration;
+
+use rusqlite::{params, Connection, Row};
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use zeroize::Zeroizing;
+
+use crate::audit::{self, ChainKey};
+use crate::db::Database;
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+pub(crate) const CATEGORIES: &[&str] = &["llm", "infrastructure", "app"];
+
+const DEFAULT_CLIPBOARD_TTL_SECS: u64 = 30;
+const MAX_CLIPBOARD_TTL_SECS: u64 = 300;
+
+/// A row of the `secrets` table, in the shape the webview already uses. The
+/// value stays in Rust; the webview gets a masked `hint` and has to go
+/// through `reveal_secret` for the plaintext.
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretRow {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub provider: String,
+    #[serde(skip)]
+    pub value: String,
+    pub hint: String,
+    pub created_at: i64,
+    pub rotated_at: Option<i64>,
+    pub last_used_at: Option<i64>,
+}
+
+impl SecretRow {
+    const COLUMNS: &'static str =
+        "id, name, category, provider, value, created_at, rotated_at, last_used_at";
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        let value: String = row.get(4)?;
+        Ok(Self {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            category: row.get(2)?,
+            provider: row.get(3)?,
+            hint: mask(&value),
+            value,
+            created_at: row.get(5)?,
+            rotated_at: row.get(6)?,
+            last_used_at: row.get(7)?,
+        })
+    }
+}
+
+pub fn find_secret(conn: &Connection, id: &str) -> Result<Option<SecretRow>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM secrets WHERE id = ?1",
+        SecretRow::COLUMNS
+    ))?;
+    let mut rows = stmt.query_map([id], SecretRow::from_row)?;
+    Ok(rows.next().transpose()?)
+}
+
+/// Like [`find_secret`], and records the use in `last_used_at`.
+pub fn use_secret(conn: &Connection, id: &str) -> Result<Option<SecretRow>> {
+    let secret = find_secret(conn, id)?;
+    if secret.is_some() {
+        conn.execute(
+            "UPDATE secrets SET last_used_at = unixepoch() WHERE id = ?1",
+            [id],
+        )?;
+    }
+    Ok(secret)
+}
+
+fn query_secrets(
+    conn: &Connection,
+    category: Option<&str>,
+    provider: Option<&str>,
+    search: Option<&str>,
+) -> Result<Vec<SecretRow>> {
+    let search = search.map(|q| format!("%{}%", q.to_lowercase()));
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM secrets
+         WHERE (?1 IS NULL OR category = ?1)
+           AND (?2 IS NULL OR LOWER(provider) = LOWER(?2))
+           AND (?3 IS NULL OR LOWER(name) LIKE ?3 OR LOWER(provider) LIKE ?3)
+         ORDER BY created_at DESC",
+        SecretRow::COLUMNS
+    ))?;
+    let rows = stmt
+        .query_map((category, provider, search), SecretRow::from_row)?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(rows)
+}
+
+pub fn secrets_by_provider(conn: &Connection, provider: &str) -> Result<Vec<SecretRow>> {
+    query_secrets(conn, None, Some(provider), None)
+}
+
+/// Lists secrets, newest first. All filters are optional and case-insensitive
+/// for `provider` and `query` (matched against name and provider).
+#[tauri::command]
+pub async fn list_secrets(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    category: Option<String>,
+    provider: Option<String>,
+    query: Option<String>,
+) -> Result<Vec<SecretRow>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| {
+        query_secrets(
+            conn,
+            category.as_deref(),
+            provider.as_deref(),
+            query.as_deref(),
+        )
+    })
+}
+
+/// Returns a single secret and records its use in `last_used_at`.
+#[tauri::command]
+pub async fn get_secret(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    id: String,
+) -> Result<Option<SecretRow>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| use_secret(conn, &id))
+}
+
+fn clean_name(name: &str) -> Result<&str> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Error::InvalidInput("Der Name darf nicht leer sein".into()));
+    }
+    Ok(name)
+}
+
+/// Providers are stored in lowercase.
+fn clean_provider(provider: &str) -> Result<String> {
+    let provider = provider.trim().to_lowercase();
+    if provider.is_empty() {
+        return Err(Error::InvalidInput(
+            "Der Provider darf nicht leer sein".into(),
+        ));
+    }
+    Ok(provider)
+}
+
+fn check_category(category: &str) -> Result<()> {
+    if !CATEGORIES.contains(&category) {
+        return Err(Error::InvalidInput(format!(
+            "Unbekannte Kategorie {category}"
+        )));
+    }
+    Ok(())
+}
+
+/// Inserts a secret and returns its id.
+pub fn insert(
+    conn: &Connection,
+    key: &ChainKey,
+    name: &str,
+    category: &str,
+    provider: &str,
+    value: &str,
+) -> Result<String> {
+    let name = clean_name(name)?;
+    let provider = clean_provider(provider)?;
+    check_category(category)?;
+    if value.is_empty() {
+        return Err(Error::InvalidInput("Der Wert darf nicht leer sein".into()));
+    }
+    let id = audit::new_id();
+    conn.execute(
+        "INSERT INTO secrets (id, name, category, provider, value) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id, name, category, provider, value],
+    )?;
+    audit::log(
+        conn,
+        key,
+        audit::SECRET_CREATED,
+        Some("secret"),
+        Some(&id),
+        Some(&json!({ "name": name, "category": category, "provider": provider })),
+    )?;
+    Ok(id)
+}
+
+/// Changes name, category or provider; `None` keeps the current value.
+pub fn update(
+    conn: &Connection,
+    key: &ChainKey,
+    id: &str,
+    name: Option<&str>,
+    category: Option<&str>,
+    provider: Option<&str>,
+) -> Result<()> {
+    let secret = find_secret(conn, id)?.ok_or_else(|| Error::NotFound(format!("Secret {id}")))?;
+    let mut fields = Vec::new();
+    if name.is_some() {
+        fields.push("name");
+    }
+    if category.is_some() {
+        fields.push("category");
+    }
+    if provider.is_some() {
+        fields.push("provider");
+    }
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    let name = name.map(clean_name).transpose()?.unwrap_or(&secret.name);
+    let provider = provider
+        .map(clean_provider)
+        .transpose()?
+        .unwrap_or(secret.provider);
+    let category = category.unwrap_or(&secret.category);
+    check_category(category)?;
+    conn.execute(
+        "UPDATE secrets SET name = ?2, category = ?3, provider = ?4 WHERE id = ?1",
+        params![id, name, category, provider],
+    )?;
+    audit::log(
+        conn,
+        key,
+        audit::SECRET_UPDATED,
+        Some("secret"),
+        Some(id),
+        Some(&json!({ "fields": fields })),
+    )
+}
+
+/// Deletes a secret with its versions. Returns `false` if it did not exist.
+pub fn delete(conn: &Connection, key: &ChainKey, id: &str) -> Result<bool> {
+    let Some(secret) = find_secret(conn, id)? else {
+        return Ok(false);
+    };
+    conn.execute("DELETE FROM secrets WHERE id = ?1", [id])?;
+    audit::log(
+        conn,
+        key,
+        audit::SECRET_DELETED,
+        Some("secret"),
+        Some(id),
+        Some(&json!({ "name": secret.name, "provider": secret.provider })),
+    )?;
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn create_secret(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    name: String,
+    category: String,
+    provider: String,
+    value: String,
+) -> Result<SecretRow> {
+    vault.ensure_unlocked()?;
+    let value = Zeroizing::new(value);
+    db.with_audit(|conn, key| {
+        let tx = conn.unchecked_transaction()?;
+        let id = insert(&tx, key, &name, &category, &provider, &value)?;
+        tx.commit()?;
+        find_secret(conn, &id)?.ok_or_else(|| Error::NotFound(format!("Secret {id}")))
+    })
+}
+
+/// Changes the metadata of a secret. A `value` that differs from the current
+/// one is a rotation: it is checked with the provider first and the old value
+/// is kept as a version.
+#[tauri::command]
+pub async fn update_secret(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    id: String,
+    name: Option<String>,
+    category: Option<String>,
+    provider: Option<String>,
+    value: Option<String>,
+) -> Result<SecretRow> {
+    vault.ensure_unlocked()?;
+    let value = value.map(|v| Zeroizing::new(v.trim().to_owned()));
+    if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+        let secret = db
+            .with_conn(|conn| find_secret(conn, &id))?
+            .ok_or_else(|| Error::NotFound(format!("Secret {id}")))?;
+        if secret.value != *value {
+            versions::rotate_checked(&db, &secret, value, false).await?;
+        }
+    }
+    db.with_audit(|conn, key| {
+        let tx = conn.unchecked_transaction()?;
+        update(
+            &tx,
+            key,
+            &id,
+            name.as_deref(),
+            category.as_deref(),
+            provider.as_deref(),
+        )?;
+        tx.commit()?;
+        find_secret(conn, &id)?.ok_or_else(|| Error::NotFound(format!("Secret {id}")))
+    })
+}
+
+#[tauri::command]
+pub async fn delete_secret(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    id: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    db.with_audit(|conn, key| {
+        let tx = conn.unchecked_transaction()?;
+        delete(&tx, key, &id)?;
+        tx.commit()?;
+        Ok(())
+    })
+}
+
+/// Hands the plaintext of a secret to the webview, e.g. to show it for a
+/// moment. Every call is audited.
+#[tauri::command]
+pub async fn reveal_secret(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    id: String,
+) -> Result<String> {
+    vault.ensure_unlocked()?;
+    db.with_audit(|conn, key| {
+        let secret =
+            use_secret(conn, &id)?.ok_or_else(|| Error::NotFound(format!("Secret {id}")))?;
+        audit::log(
+            conn,
+            key,
+            audit::SECRET_REVEALED,
+            Some("secret"),
+            Some(&id),
+            Some(&json!({ "name": secret.name })),
+        )?;
+        Ok(secret.value)
+    })
+}
+
+/// Like `maskSecret` in the webview: the first and last four characters.
+pub(crate) fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 8 {
+        return "••••••••".into();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{head}••••••••{tail}")
+}
+
+fn digest(value: &str) -> [u8; 32] {
+    Sha256::digest(value.as_bytes()).into()
+}
+
+/// Copies a secret to the clipboard without handing it to the webview and
+/// clears the clipboard after `ttl` seconds (default 30), unless something
+/// else was copied in the meantime. Returns the effective TTL.
+#[tauri::command]
+pub async fn copy_secret_to_clipboard(
+    app: AppHandle,
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    ttl: Option<u64>,
+) -> Result<u64> {
+    vault.ensure_unlocked()?;
+    let ttl = ttl
+        .unwrap_or(DEFAULT_CLIPBOARD_TTL_SECS)
+        .clamp(1, MAX_CLIPBOARD_TTL_SECS);
+
+    let value = db.with_audit(|conn, key| {
+        let secret = use_secret(conn, &secret_id)?
+            .ok_or_else(|| Error::NotFound(format!("Secret {secret_id}")))?;
+        audit::log(
+            conn,
+            key,
+            audit::SECRET_COPIED,
+            Some("secret"),
+            Some(&secret_id),
+            Some(&json!({ "name": secret.name, "ttlSeconds": ttl })),
+        )?;
+        Ok(Zeroizing::new(secret.value))
+    })?;
+
+    app.clipboard()
+        .write_text(value.as_str())
+        .map_err(|e| Error::Clipboard(e.to_string()))?;
+
+    // Only a digest is kept to recognise our value later
+    let copied = digest(&value);
+    drop(value);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(ttl)).await;
+        let clipboard = app.clipboard();
+        let unchanged = clipboard
+            .read_text()
+            .map(Zeroizing::new)
+            .is_ok_and(|current| digest(&current) == copied);
+        if unchanged {
+            if let Err(e) = clipboard.clear() {
+                eprintln!("Failed to clear clipboard: {e}");
+            }
+        }
+    });
+    Ok(ttl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    fn actions(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT action FROM audit_log WHERE resource_type = 'secret' ORDER BY seq")
+            .unwrap();
+        let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn writes_are_validated_and_audited() {
+        let key = ChainKey::derive("test");
+        let conn = db::open_in_memory(&key);
+        let id = insert(&conn, &key, " Prod ", "llm", "OpenAI", "sk-proj-12345678").unwrap();
+        let secret = find_secret(&conn, &id).unwrap().unwrap();
+        assert_eq!(
+            (secret.name.as_str(), secret.provider.as_str()),
+            ("Prod", "openai")
+        );
+
+        assert!(insert(&conn, &key, "X", "other", "openai", "sk-2").is_err());
+        assert!(insert(&conn, &key, "  ", "llm", "openai", "sk-2").is_err());
+        assert!(update(&conn, &key, &id, None, Some("other"), None).is_err());
+
+        update(&conn, &key, &id, Some("Staging"), None, None).unwrap();
+        update(&conn, &key, &id, None, None, None).unwrap();
+        let secret = find_secret(&conn, &id).unwrap().unwrap();
+        assert_eq!(
+            (secret.name.as_str(), secret.category.as_str()),
+            ("Staging", "llm")
+        );
+
+        assert!(delete(&conn, &key, &id).unwrap());
+        assert!(!delete(&conn, &key, &id).unwrap());
+        assert_eq!(
+            actions(&conn),
+            ["secret_created", "secret_updated", "secret_deleted"]
+        );
+        assert!(audit::verify(&conn, &key).unwrap().intact);
+    }
+}
@@ -0,0 +1,477 @@
+//! Rotation reminders and expiry.
+//!
+//! Every category has a maximum age (setting `rotationPolicies`), which a
+//! secret can override with its own `max_age_days`; `expires_at` is an
+//! optional hard expiry. A background task evaluates them hourly and sends a
+//! desktop notification when a secret becomes due, overdue or expired. The
+//! last state is kept in `rotation_state`, so each change notifies once.
+
+use std::time::Duration;
+
+use chrono::Utc;
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::audit;
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::vault::VaultState;
+
+pub const POLICIES_SETTING: &str = "rotationPolicies";
+pub const ROTATION_EVENT: &str = "secret-rotation";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DAY_SECS: i64 = 24 * 60 * 60;
+/// Days before the due date from which a secret counts as due soon.
+const DUE_SOON_DAYS: i64 = 14;
+/// Unused for this long, a secret is flagged as stale.
+const STALE_DAYS: i64 = 90;
+
+/// Maximum age per category; `None` disables the reminder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryPolicies {
+    pub llm: Option<u32>,
+    pub infrastructure: Option<u32>,
+    pub app: Option<u32>,
+}
+
+impl Default for CategoryPolicies {
+    fn default() -> Self {
+        Self {
+            llm: Some(90),
+            infrastructure: Some(90),
+            app: Some(90),
+        }
+    }
+}
+
+impl CategoryPolicies {
+    fn load(conn: &Connection) -> Result<Self> {
+        Ok(db::get_setting(conn, POLICIES_SETTING)?
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default())
+    }
+
+    fn max_age(&self, category: &str) -> Option<u32> {
+        match category {
+            "llm" => self.llm,
+            "infrastructure" => self.infrastructure,
+            "app" => self.app,
+            _ => None,
+        }
+    }
+}
+
+/// Ordered from harmless to urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RotationStatus {
+    Ok,
+    DueSoon,
+    Overdue,
+    Expired,
+}
+
+impl RotationStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::DueSoon => "dueSoon",
+            Self::Overdue => "overdue",
+            Self::Expired => "expired",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "dueSoon" => Self::DueSoon,
+            "overdue" => Self::Overdue,
+            "expired" => Self::Expired,
+            _ => Self::Ok,
+        }
+    }
+}
+
+struct PolicyRow {
+    id: String,
+    name: String,
+    category: String,
+    provider: String,
+    created_at: i64,
+    rotated_at: Option<i64>,
+    last_used_at: Option<i64>,
+    max_age_days: Option<u32>,
+    expires_at: Option<i64>,
+    state: RotationStatus,
+}
+
+impl PolicyRow {
+    const COLUMNS: &'static str = "id, name, category, provider, created_at, rotated_at, \
+         last_used_at, max_age_days, expires_at, rotation_state";
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            category: row.get(2)?,
+            provider: row.get(3)?,
+            created_at: row.get(4)?,
+            rotated_at: row.get(5)?,
+            last_used_at: row.get(6)?,
+            max_age_days: row.get(7)?,
+            expires_at: row.get(8)?,
+            state: RotationStatus::parse(&row.get::<_, String>(9)?),
+        })
+    }
+}
+
+fn load_rows(conn: &Connection) -> Result<Vec<PolicyRow>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM secrets", PolicyRow::COLUMNS))?;
+    let rows = stmt
+        .query_map([], PolicyRow::from_row)?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(rows)
+}
+
+/// Age and rotation status of a secret. No values.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretAge {
+    pub id: String,
+    pub name: String,
+    pub category: String,
+    pub provider: String,
+    /// Days since the last rotation, or since creation.
+    pub age_days: i64,
+    /// Days since the last use, `None` if never used.
+    pub unused_days: Option<i64>,
+    /// Unused for [`STALE_DAYS`] or longer (never used counts from creation).
+    pub stale: bool,
+    /// Effective maximum age: the secret's own, else its category's.
+    pub max_age_days: Option<u32>,
+    /// Whether `max_age_days` is set on the secret itself.
+    pub custom_policy: bool,
+    pub expires_at: Option<i64>,
+    /// When rotation is due: the earlier of maximum age and expiry.
+    pub due_at: Option<i64>,
+    pub status: RotationStatus,
+}
+
+fn evaluate(row: &PolicyRow, policies: &CategoryPolicies, now: i64) -> SecretAge {
+    let since = row.rotated_at.unwrap_or(row.created_at);
+    let max_age_days = row.max_age_days.or(policies.max_age(&row.category));
+    let age_due = max_age_days.map(|days| since + i64::from(days) * DAY_SECS);
+    let due_at = match (age_due, row.expires_at) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+
+    let status = if row.expires_at.is_some_and(|at| at <= now) {
+        RotationStatus::Expired
+    } else if age_due.is_some_and(|at| at <= now) {
+        RotationStatus::Overdue
+    } else if due_at.is_some_and(|at| at - now <= DUE_SOON_DAYS * DAY_SECS) {
+        RotationStatus::DueSoon
+    } else {
+        RotationStatus::Ok
+    };
+
+    let unused_days = row.last_used_at.map(|at| (now - at) / DAY_SECS);
+    SecretAge {
+        id: row.id.clone(),
+        name: row.name.clone(),
+        category: row.category.clone(),
+        provider: row.provider.clone(),
+        age_days: (now - since) / DAY_SECS,
+        unused_days,
+        stale: unused_days.unwrap_or((now - row.created_at) / DAY_SECS) >= STALE_DAYS,
+        max_age_days,
+        custom_policy: row.max_age_days.is_some(),
+        expires_at: row.expires_at,
+        due_at,
+        status,
+    }
+}
+
+/// All secrets, most urgent first, then oldest first.
+fn ages(conn: &Connection, now: i64) -> Result<Vec<SecretAge>> {
+    let policies = CategoryPolicies::load(conn)?;
+    let mut ages: Vec<SecretAge> = load_rows(conn)?
+        .iter()
+        .map(|row| evaluate(row, &policies, now))
+        .collect();
+    ages.sort_by(|a, b| b.status.cmp(&a.status).then(b.age_days.cmp(&a.age_days)));
+    Ok(ages)
+}
+
+/// Whether a state change is worth a notification: only when it gets worse.
+fn should_notify(previous: RotationStatus, current: RotationStatus) -> bool {
+    current > previous
+}
+
+fn notification_text(age: &SecretAge) -> (String, String) {
+    match age.status {
+        RotationStatus::Expired => (
+            format!("Secret abgelaufen: {}", age.name),
+            format!(
+                "{} ({}) hat sein Ablaufdatum erreicht.",
+                age.name, age.provider
+            ),
+        ),
+        RotationStatus::Overdue => (
+            format!("Rotation überfällig: {}", age.name),
+            format!(
+                "Key ist {} Tage alt (Maximum {} Tage).",
+                age.age_days,
+                age.max_age_days.unwrap_or_default()
+            ),
+        ),
+        _ => (
+            format!("Rotation bald fällig: {}", age.name),
+            format!("Key ist {} Tage alt.", age.age_days),
+        ),
+    }
+}
+
+/// Evaluates all secrets, stores their state and returns those whose state
+/// got worse since the last run.
+fn refresh(conn: &Connection, now: i64) -> Result<Vec<SecretAge>> {
+    let policies = CategoryPolicies::load(conn)?;
+    let tx = conn.unchecked_transaction()?;
+    let mut changed = Vec::new();
+    for row in load_rows(&tx)? {
+        let age = evaluate(&row, &policies, now);
+        if age.status != row.state {
+            tx.execute(
+                "UPDATE secrets SET rotation_state = ?2 WHERE id = ?1",
+                params![row.id, age.status.as_str()],
+            )?;
+        }
+        if should_notify(row.state, age.status) {
+            changed.push(age);
+        }
+    }
+    tx.commit()?;
+    Ok(changed)
+}
+
+async fn check(app: &AppHandle) -> Result<()> {
+    app.state::<VaultState>().ensure_unlocked()?;
+    let changed = app
+        .state::<Database>()
+        .with_conn(|conn| refresh(conn, Utc::now().timestamp()))?;
+    for age in &changed {
+        let (title, body) = notification_text(age);
+        if let Err(e) = app.notification().builder().title(title).body(body).show() {
+            eprintln!("Failed to show rotation notification: {e}");
+        }
+    }
+    if !changed.is_empty() {
+        if let Err(e) = app.emit(ROTATION_EVENT, &changed) {
+            eprintln!("Failed to emit {ROTATION_EVENT}: {e}");
+        }
+    }
+    Ok(())
+}
+
+async fn run_check(app: &AppHandle) {
+    if let Err(e) = check(app).await {
+        eprintln!("Rotation check failed: {e}");
+    }
+}
+
+/// Starts the background task that evaluates the rotation policies.
+pub fn spawn_checker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if app.state::<VaultState>().is_unlocked() {
+                run_check(&app).await;
+            }
+        }
+    });
+}
+
+/// Runs one check right away. Ticks of [`spawn_checker`] are skipped while
+/// the vault is locked, so unlocking would otherwise wait for the next one.
+pub fn spawn_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move { run_check(&app).await });
+}
+
+/// Lists all secrets by age and staleness, most urgent first.
+#[tauri::command]
+pub async fn list_secret_ages(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<Vec<SecretAge>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| ages(conn, Utc::now().timestamp()))
+}
+
+#[tauri::command]
+pub async fn get_rotation_policies(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+) -> Result<CategoryPolicies> {
+    vault.ensure_unlocked()?;
+    db.with_conn(CategoryPolicies::load)
+}
+
+#[tauri::command]
+pub async fn set_rotation_policies(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    policies: CategoryPolicies,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let value = serde_json::to_string(&policies).expect("serializable policies");
+    db.with_audit(|conn, key| {
+        db::set_setting(conn, POLICIES_SETTING, &value)?;
+        audit::log(
+            conn,
+            key,
+            audit::SETTINGS_UPDATED,
+            Some("settings"),
+            Some(POLICIES_SETTING),
+            Some(&json!(policies)),
+        )
+    })
+}
+
+/// Sets or clears the own maximum age and the hard expiry of a secret.
+#[tauri::command]
+pub async fn set_secret_policy(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    max_age_days: Option<u32>,
+    expires_at: Option<i64>,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    if max_age_days == Some(0) {
+        return Err(Error::InvalidInput(
+            "Das Höchstalter muss mindestens einen Tag betragen".into(),
+        ));
+    }
+    db.with_audit(|conn, key| {
+        let updated = conn.execute(
+            "UPDATE secrets SET max_age_days = ?2, expires_at = ?3 WHERE id = ?1",
+            params![secret_id, max_age_days, expires_at],
+        )?;
+        if updated == 0 {
+            return Err(Error::NotFound(format!("Secret {secret_id}")));
+        }
+        audit::log(
+            conn,
+            key,
+            audit::SECRET_UPDATED,
+            Some("secret"),
+            Some(&secret_id),
+            Some(&json!({
+                "fields": ["maxAgeDays", "expiresAt"],
+                "maxAgeDays": max_age_days,
+                "expiresAt": expires_at,
+            })),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ChainKey;
+
+    const NOW: i64 = 1_750_000_000;
+
+    fn row(category: &str, age_days: i64) -> PolicyRow {
+        PolicyRow {
+            id: "s1".into(),
+            name: "Prod".into(),
+            category: category.into(),
+            provider: "openai".into(),
+            created_at: NOW - 400 * DAY_SECS,
+            rotated_at: Some(NOW - age_days * DAY_SECS),
+            last_used_at: None,
+            max_age_days: None,
+            expires_at: None,
+            state: RotationStatus::Ok,
+        }
+    }
+
+    #[test]
+    fn evaluates_category_and_secret_policies() {
+        let policies = CategoryPolicies {
+            app: None,
+            ..CategoryPolicies::default()
+        };
+        let status = |row: &PolicyRow| evaluate(row, &policies, NOW).status;
+
+        assert_eq!(status(&row("llm", 10)), RotationStatus::Ok);
+        assert_eq!(status(&row("llm", 80)), RotationStatus::DueSoon);
+        assert_eq!(status(&row("llm", 90)), RotationStatus::Overdue);
+        assert_eq!(status(&row("app", 900)), RotationStatus::Ok);
+
+        // The secret's own maximum age wins over its category
+        let own = PolicyRow {
+            max_age_days: Some(30),
+            ..row("llm", 40)
+        };
+        let age = evaluate(&own, &policies, NOW);
+        assert_eq!(age.status, RotationStatus::Overdue);
+        assert_eq!((age.age_days, age.max_age_days), (40, Some(30)));
+        assert!(age.custom_policy);
+
+        // Expiry applies without any maximum age
+        let expiring = PolicyRow {
+            expires_at: Some(NOW + 3 * DAY_SECS),
+            ..row("app", 1)
+        };
+        assert_eq!(status(&expiring), RotationStatus::DueSoon);
+        let expired = PolicyRow {
+            expires_at: Some(NOW),
+            ..row("llm", 1)
+        };
+        assert_eq!(status(&expired), RotationStatus::Expired);
+    }
+
+    #[test]
+    fn flags_stale_secrets() {
+        let policies = CategoryPolicies::default();
+        // Never used, created 400 days ago
+        assert!(evaluate(&row("llm", 1), &policies, NOW).stale);
+        let used = PolicyRow {
+            last_used_at: Some(NOW - 2 * DAY_SECS),
+            ..row("llm", 1)
+        };
+        let age = evaluate(&used, &policies, NOW);
+        assert_eq!(age.unused_days, Some(2));
+        assert!(!age.stale);
+    }
+
+    #[test]
+    fn notifies_once_per_escalation() {
+        let conn = db::open_in_memory(&ChainKey::derive("test"));
+        conn.execute(
+            "INSERT INTO secrets (id, name, category, provider, value, created_at)
+             VALUES ('s1', 'Prod', 'llm', 'openai', 'sk-1', ?1)",
+            [NOW - 100 * DAY_SECS],
+        )
+        .unwrap();
+
+        let changed = refresh(&conn, NOW).unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].status, RotationStatus::Overdue);
+        assert!(refresh(&conn, NOW).unwrap().is_empty());
+
+        // Rotating resets the state without a notification
+        conn.execute("UPDATE secrets SET rotated_at = ?1", [NOW])
+            .unwrap();
+        assert!(refresh(&conn, NOW).unwrap().is_empty());
+        assert_eq!(ages(&conn, NOW).unwrap()[0].status, RotationStatus::Ok);
+    }
+}
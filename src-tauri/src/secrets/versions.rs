@@ -0,0 +1,310 @@
+//! Rotation with version history.
+//!
+//! Rotating a secret moves its current value into `secret_versions` and keeps
+//! the newest `secretVersionsKept` of them (default 5) for a revert. Keys of
+//! providers with a usage API are diagnosed first; the old value is only
+//! retired once the new one works.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::json;
+use tauri::State;
+use zeroize::Zeroizing;
+
+use super::{find_secret, mask, SecretRow};
+use crate::audit;
+use crate::db::{self, Database};
+use crate::error::{ApiErrorKind, Error, Result};
+use crate::providers::usage::{self, KeyDiagnosis};
+use crate::vault::VaultState;
+
+pub const VERSIONS_KEPT_SETTING: &str = "secretVersionsKept";
+const DEFAULT_VERSIONS_KEPT: u32 = 5;
+const MAX_VERSIONS_KEPT: u32 = 50;
+
+fn versions_kept(conn: &Connection) -> Result<u32> {
+    Ok(db::get_setting(conn, VERSIONS_KEPT_SETTING)?
+        .and_then(|value| value.trim_matches('"').parse().ok())
+        .unwrap_or(DEFAULT_VERSIONS_KEPT)
+        .clamp(1, MAX_VERSIONS_KEPT))
+}
+
+/// A retired value, masked.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVersion {
+    id: String,
+    version: i64,
+    hint: String,
+    created_at: Option<i64>,
+    retired_at: i64,
+}
+
+fn list(conn: &Connection, secret_id: &str) -> Result<Vec<SecretVersion>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, version, value, created_at, retired_at FROM secret_versions
+         WHERE secret_id = ?1 ORDER BY version DESC",
+    )?;
+    let versions = stmt
+        .query_map([secret_id], |row| {
+            let value = Zeroizing::new(row.get::<_, String>(2)?);
+            Ok(SecretVersion {
+                id: row.get(0)?,
+                version: row.get(1)?,
+                hint: mask(&value),
+                created_at: row.get(3)?,
+                retired_at: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(versions)
+}
+
+/// Moves the current value of `secret` into the history and returns its
+/// version number.
+pub(super) fn archive(conn: &Connection, secret: &SecretRow) -> Result<i64> {
+    let version: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) + 1 FROM secret_versions WHERE secret_id = ?1",
+        [&secret.id],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "INSERT INTO secret_versions (id, secret_id, version, value, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            audit::new_id(),
+            secret.id,
+            version,
+            secret.value,
+            secret.rotated_at.unwrap_or(secret.created_at),
+        ],
+    )?;
+    conn.execute(
+        "DELETE FROM secret_versions WHERE secret_id = ?1 AND id NOT IN (
+           SELECT id FROM secret_versions WHERE secret_id = ?1
+           ORDER BY version DESC LIMIT ?2
+         )",
+        params![secret.id, versions_kept(conn)?],
+    )?;
+    Ok(version)
+}
+
+fn set_value(conn: &Connection, secret_id: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE secrets SET value = ?2, rotated_at = unixepoch() WHERE id = ?1",
+        params![secret_id, value],
+    )?;
+    Ok(())
+}
+
+fn rotate(conn: &Connection, secret: &SecretRow, value: &str) -> Result<i64> {
+    let tx = conn.unchecked_transaction()?;
+    let version = archive(&tx, secret)?;
+    set_value(&tx, &secret.id, value)?;
+    tx.commit()?;
+    Ok(version)
+}
+
+/// Makes version `version_id` current again; the value it replaces becomes
+/// the newest version. Returns the restored version number.
+fn revert(conn: &Connection, secret: &SecretRow, version_id: &str) -> Result<i64> {
+    let tx = conn.unchecked_transaction()?;
+    let (version, value): (i64, Zeroizing<String>) = tx
+        .query_row(
+            "SELECT version, value FROM secret_versions WHERE id = ?1 AND secret_id = ?2",
+            [version_id, &secret.id],
+            |row| Ok((row.get(0)?, Zeroizing::new(row.get(1)?))),
+        )
+        .optional()?
+        .ok_or_else(|| Error::NotFound(format!("Version {version_id}")))?;
+    archive(&tx, secret)?;
+    tx.execute("DELETE FROM secret_versions WHERE id = ?1", [version_id])?;
+    set_value(&tx, &secret.id, &value)?;
+    tx.commit()?;
+    Ok(version)
+}
+
+/// A key counts as working if the provider accepted it, even without access
+/// to the usage API.
+fn accepted(diagnosis: &KeyDiagnosis) -> bool {
+    diagnosis.valid || diagnosis.error_kind == Some(ApiErrorKind::Permission)
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationResult {
+    /// Version number the previous value was stored under.
+    version: i64,
+    /// `None` if the provider cannot be checked or the check was skipped.
+    validation: Option<KeyDiagnosis>,
+}
+
+fn load(db: &Database, secret_id: &str) -> Result<SecretRow> {
+    db.with_conn(|conn| find_secret(conn, secret_id))?
+        .ok_or_else(|| Error::NotFound(format!("Secret {secret_id}")))
+}
+
+#[tauri::command]
+pub async fn list_secret_versions(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+) -> Result<Vec<SecretVersion>> {
+    vault.ensure_unlocked()?;
+    db.with_conn(|conn| list(conn, &secret_id))
+}
+
+/// Replaces the value of `secret` and keeps the old one as a version. Unless
+/// `skip_validation` is set, the new key is diagnosed first and the rotation
+/// is refused if the provider rejects it.
+pub(super) async fn rotate_checked(
+    db: &Database,
+    secret: &SecretRow,
+    value: &str,
+    skip_validation: bool,
+) -> Result<RotationResult> {
+    let validation = if skip_validation {
+        None
+    } else {
+        let candidate = SecretRow {
+            value: value.to_owned(),
+            ..secret.clone()
+        };
+        usage::diagnose_secret(candidate).await
+    };
+    if let Some(diagnosis) = validation.as_ref().filter(|d| !accepted(d)) {
+        return Err(Error::InvalidInput(format!(
+            "Der neue Key wurde nicht akzeptiert, der alte bleibt aktiv: {}",
+            diagnosis.error.as_deref().unwrap_or("unbekannter Fehler")
+        )));
+    }
+
+    let version = db.with_audit(|conn, key| {
+        let version = rotate(conn, secret, value)?;
+        audit::log(
+            conn,
+            key,
+            audit::SECRET_ROTATED,
+            Some("secret"),
+            Some(&secret.id),
+            Some(&json!({
+                "name": secret.name,
+                "previousVersion": version,
+                "validated": validation.is_some(),
+            })),
+        )?;
+        Ok(version)
+    })?;
+    Ok(RotationResult {
+        version,
+        validation,
+    })
+}
+
+#[tauri::command]
+pub async fn rotate_secret(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    value: String,
+    skip_validation: Option<bool>,
+) -> Result<RotationResult> {
+    vault.ensure_unlocked()?;
+    let value = Zeroizing::new(value.trim().to_owned());
+    if value.is_empty() {
+        return Err(Error::InvalidInput(
+            "Der neue Wert darf nicht leer sein".into(),
+        ));
+    }
+    let secret = load(&db, &secret_id)?;
+    if secret.value == *value {
+        return Err(Error::InvalidInput(
+            "Der neue Wert entspricht dem aktuellen".into(),
+        ));
+    }
+    rotate_checked(&db, &secret, &value, skip_validation.unwrap_or(false)).await
+}
+
+/// Restores a previous value of a secret.
+#[tauri::command]
+pub async fn revert_secret(
+    db: State<'_, Database>,
+    vault: State<'_, VaultState>,
+    secret_id: String,
+    version_id: String,
+) -> Result<()> {
+    vault.ensure_unlocked()?;
+    let secret = load(&db, &secret_id)?;
+    db.with_audit(|conn, key| {
+        let version = revert(conn, &secret, &version_id)?;
+        audit::log(
+            conn,
+            key,
+            audit::SECRET_REVERTED,
+            Some("secret"),
+            Some(&secret.id),
+            Some(&json!({ "name": secret.name, "version": version })),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::ChainKey;
+
+    fn setup() -> (Connection, SecretRow) {
+        let conn = db::open_in_memory(&ChainKey::derive("test"));
+        conn.execute(
+            "INSERT INTO secrets (id, name, category, provider, value, created_at)
+             VALUES ('s1', 'Prod', 'llm', 'openai', 'sk-v1', 100)",
+            [],
+        )
+        .unwrap();
+        let secret = find_secret(&conn, "s1").unwrap().unwrap();
+        (conn, secret)
+    }
+
+    fn current(conn: &Connection) -> SecretRow {
+        find_secret(conn, "s1").unwrap().unwrap()
+    }
+
+    #[test]
+    fn rotation_keeps_the_newest_versions() {
+        let (conn, secret) = setup();
+        db::set_setting(&conn, VERSIONS_KEPT_SETTING, "2").unwrap();
+        assert_eq!(rotate(&conn, &secret, "sk-v2").unwrap(), 1);
+        assert_eq!(rotate(&conn, &current(&conn), "sk-v3").unwrap(), 2);
+        assert_eq!(rotate(&conn, &current(&conn), "sk-v4").unwrap(), 3);
+
+        let secret = current(&conn);
+        assert_eq!(secret.value, "sk-v4");
+        assert!(secret.rotated_at.is_some());
+        let versions: Vec<i64> = list(&conn, "s1")
+            .unwrap()
+            .iter()
+            .map(|v| v.version)
+            .collect();
+        assert_eq!(versions, [3, 2]);
+    }
+
+    #[test]
+    fn revert_swaps_the_current_value_into_the_history() {
+        let (conn, secret) = setup();
+        rotate(&conn, &secret, "sk-v2").unwrap();
+        let old = &list(&conn, "s1").unwrap()[0];
+        assert_eq!(old.created_at, Some(100));
+
+        assert_eq!(revert(&conn, &current(&conn), &old.id).unwrap(), 1);
+        assert_eq!(current(&conn).value, "sk-v1");
+        let versions = list(&conn, "s1").unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version, 2);
+        assert!(revert(&conn, &current(&conn), &old.id).is_err());
+
+        // Deleting the secret removes its history
+        conn.execute("DELETE FROM secrets WHERE id = 's1'", [])
+            .unwrap();
+        assert!(list(&conn, "s1").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+//! Unlock state of the app, owned by Rust instead of React.
+//!
+//! The vault holds the database key derived from the master password (never
+//! the password itself) while unlocked and the time of the last user
+//! activity. A background task locks it after `autoLockMinutes` of
+//! inactivity, closes the database and emits `app-locked` to the webview.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use zeroize::Zeroizing;
+
+use crate::db::{self, Database};
+use crate::error::{Error, Result};
+use crate::providers;
+use crate::secrets;
+
+pub const APP_LOCKED_EVENT: &str = "app-locked";
+
+const AUTO_LOCK_SETTING: &str = "autoLockMinutes";
+const DEFAULT_AUTO_LOCK_MINUTES: u64 = 5;
+const AUTO_LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct VaultState {
+    inner: Mutex<VaultInner>,
+}
+
+struct VaultInner {
+    key: Option<Zeroizing<String>>,
+    last_activity: Instant,
+}
+
+impl Default for VaultState {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(VaultInner {
+                key: None,
+                last_activity: Instant::now(),
+            }),
+        }
+    }
+}
+
+impl VaultState {
+    pub fn is_unlocked(&self) -> bool {
+        self.lock().key.is_some()
+    }
+
+    /// Guard for every command that reads secrets.
+    pub fn ensure_unlocked(&self) -> Result<()> {
+        if self.is_unlocked() {
+            Ok(())
+        } else {
+            Err(Error::Locked)
+        }
+    }
+
+    /// The database key of the current session, see
+    /// [`crate::auth::derive_vault_key`].
+    pub fn key(&self) -> Result<Zeroizing<String>> {
+        self.lock().key.clone().ok_or(Error::Locked)
+    }
+
+    pub fn unlock(&self, key: Zeroizing<String>) {
+        let mut inner = self.lock();
+        inner.key = Some(key);
+        inner.last_activity = Instant::now();
+    }
+
+    pub fn touch(&self) {
+        self.lock().last_activity = Instant::now();
+    }
+
+    /// Time since the last activity, or `None` while locked.
+    fn idle_for(&self) -> Option<Duration> {
+        let inner = self.lock();
+        inner.key.as_ref().map(|_| inner.last_activity.elapsed())
+    }
+
+    fn clear(&self) {
+        self.lock().key = None;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VaultInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LockReason {
+    Manual,
+    Inactivity,
+}
+
+#[derive(Clone, Serialize)]
+struct AppLockedPayload {
+    reason: LockReason,
+}
+
+/// Forgets the key, closes the database and notifies the webview.
+pub fn lock(app: &AppHandle, reason: LockReason) {
+    app.state::<VaultState>().clear();
+    app.state::<Database>().close();
+    if let Err(e) = app.emit(APP_LOCKED_EVENT, AppLockedPayload { reason }) {
+        eprintln!("Failed to emit {APP_LOCKED_EVENT}: {e}");
+    }
+}
+
+/// Starts the work that was put off while the vault was locked.
+pub fn unlocked(app: &AppHandle) {
+    secrets::policies::spawn_check(app.clone());
+    providers::backfill::resume_interrupted(app);
+}
+
+fn auto_lock_minutes(db: &Database) -> u64 {
+    db.with_conn(|conn| db::get_setting(conn, AUTO_LOCK_SETTING))
+        .ok()
+        .flatten()
+        .and_then(|value| value.trim_matches('"').parse().ok())
+        .unwrap_or(DEFAULT_AUTO_LOCK_MINUTES)
+}
+
+/// Starts the background task that enforces `autoLockMinutes`. A value of 0
+/// disables auto-lock.
+pub fn spawn_auto_lock(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(AUTO_LOCK_CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let Some(idle) = app.state::<VaultState>().idle_for() else {
+                continue;
+            };
+            let minutes = auto_lock_minutes(&app.state::<Database>());
+            if minutes > 0 && idle >= Duration::from_secs(minutes * 60) {
+                lock(&app, LockReason::Inactivity);
+            }
+        }
+    });
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    unlocked: bool,
+    auto_lock_minutes: u64,
+    idle_seconds: u64,
+}
+
+#[tauri::command]
+pub fn vault_status(vault: State<'_, VaultState>, db: State<'_, Database>) -> VaultStatus {
+    let idle = vault.idle_for();
+    VaultStatus {
+        unlocked: idle.is_some(),
+        auto_lock_minutes: auto_lock_minutes(&db),
+        idle_seconds: idle.map_or(0, |d| d.as_secs()),
+    }
+}
+
+/// Called by the webview (throttled) on user input to postpone auto-lock.
+#[tauri::command]
+pub fn record_activity(vault: State<'_, VaultState>) {
+    vault.touch();
+}
+
+#[tauri::command]
+pub fn lock_vault(app: AppHandle) {
+    lock(&app, LockReason::Manual);
+}
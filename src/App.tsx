@@ -1,6 +1,8 @@
-import { useState, useEffect, useRef, useCallback } from "react";
+import { useState, useEffect, useRef } from "react";
 import { BrowserRouter, Routes, Route } from "react-router-dom";
 import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { Loader2, AlertTriangle, RefreshCw, Database } from "lucide-react";
 
 import { Layout } from "@/components/layout/Layout";
@@ -10,7 +12,9 @@ import { Costs } from "@/pages/Costs";
 import { Secrets } from "@/pages/Secrets";
 import { Settings } from "@/pages/Settings";
 import { AuditLog } from "@/pages/AuditLog";
+import { Alerts } from "@/pages/Alerts";
 import { Placeholder } from "@/pages/Placeholder";
+import { Infrastructure } from "@/pages/Infrastructure";
 import { initializeDatabase } from "@/lib/database";
 import { Button } from "@/components/ui/button";
 
@@ -25,8 +29,9 @@ const queryClient = new QueryClient({
   },
 });
 
-// Auto-lock timeout in milliseconds (5 minutes)
-const AUTO_LOCK_TIMEOUT = 5 * 60 * 1000;
+// Minimum interval between activity reports to the backend, which enforces
+// the auto-lock timeout (autoLockMinutes)
+const ACTIVITY_REPORT_INTERVAL = 15 * 1000;
 
 // Database initialization states
 type DbState = "loading" | "ready" | "error";
@@ -35,7 +40,7 @@ function App() {
   const [dbState, setDbState] = useState<DbState>("loading");
   const [dbError, setDbError] = useState<string | null>(null);
   const [isUnlocked, setIsUnlocked] = useState(false);
-  const timeoutRef = useRef<number | null>(null);
+  const lastActivityReportRef = useRef(0);
 
   // Initialize database on app start
   useEffect(() => {
@@ -56,42 +61,30 @@ function App() {
     initDb();
   }, []);
 
-  const lock = useCallback(() => {
-    console.log("Auto-locking app due to inactivity");
-    setIsUnlocked(false);
+  // The backend locks the vault (auto-lock or manual) and tells us about it
+  useEffect(() => {
+    const unlisten = listen<{ reason: string }>("app-locked", (event) => {
+      console.log("App locked:", event.payload.reason);
+      setIsUnlocked(false);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
   }, []);
 
-  const resetTimer = useCallback(() => {
-    // Clear existing timer
-    if (timeoutRef.current) {
-      window.clearTimeout(timeoutRef.current);
-    }
-    
-    // Only set new timer if unlocked
-    if (isUnlocked) {
-      timeoutRef.current = window.setTimeout(lock, AUTO_LOCK_TIMEOUT);
-    }
-  }, [isUnlocked, lock]);
-
-  // Setup auto-lock on activity
+  // Report user activity so the backend can postpone auto-lock
   useEffect(() => {
-    if (!isUnlocked) {
-      // Clear timer when locked
-      if (timeoutRef.current) {
-        window.clearTimeout(timeoutRef.current);
-        timeoutRef.current = null;
-      }
-      return;
-    }
+    if (!isUnlocked) return;
 
-    // Start initial timer
-    resetTimer();
-
-    // Reset timer on user activity
     const events = ["mousedown", "keydown", "touchstart", "scroll", "mousemove"];
-    
+
     const handleActivity = () => {
-      resetTimer();
+      const now = Date.now();
+      if (now - lastActivityReportRef.current < ACTIVITY_REPORT_INTERVAL) return;
+      lastActivityReportRef.current = now;
+      invoke("record_activity").catch((error) =>
+        console.error("Failed to record activity:", error)
+      );
     };
 
     events.forEach((event) => {
@@ -102,11 +95,8 @@ function App() {
       events.forEach((event) => {
         window.removeEventListener(event, handleActivity);
       });
-      if (timeoutRef.current) {
-        window.clearTimeout(timeoutRef.current);
-      }
     };
-  }, [isUnlocked, resetTimer]);
+  }, [isUnlocked]);
 
   // Show loading screen while initializing database
   if (dbState === "loading") {
@@ -195,15 +185,7 @@ function App() {
           <Route path="/" element={<Layout />}>
             <Route index element={<Dashboard />} />
             <Route path="costs" element={<Costs />} />
-            <Route
-              path="infrastructure"
-              element={
-                <Placeholder
-                  title="Infrastruktur"
-                  description="Status und Verwaltung deiner Cloud-Services."
-                />
-              }
-            />
+            <Route path="infrastructure" element={<Infrastructure />} />
             <Route
               path="users"
               element={
@@ -214,15 +196,7 @@ function App() {
               }
             />
             <Route path="secrets" element={<Secrets />} />
-            <Route
-              path="alerts"
-              element={
-                <Placeholder
-                  title="Alerts"
-                  description="Konfiguriere Benachrichtigungen für wichtige Events."
-                />
-              }
-            />
+            <Route path="alerts" element={<Alerts />} />
             <Route path="audit" element={<AuditLog />} />
             <Route path="settings" element={<Settings />} />
           </Route>
@@ -0,0 +1,179 @@
+import { Input } from "@/components/ui/input";
+import { useSecrets } from "@/hooks/useSecrets";
+import { CHANNEL_SECRET_PROVIDERS, type AlertChannel } from "@/lib/alerts";
+
+const selectClassName =
+  "flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+type ChannelConfig = Record<string, unknown>;
+
+interface ChannelFieldsProps {
+  channel: AlertChannel;
+  config: ChannelConfig | null;
+  onChange: (config: ChannelConfig | null) => void;
+}
+
+// Settings of the outbound channels. Tokens, webhook URLs and passwords are
+// never entered here, only picked from the vault by secret id.
+export function ChannelFields({ channel, config, onChange }: ChannelFieldsProps) {
+  const { data: secrets = [] } = useSecrets();
+  const secretProvider = CHANNEL_SECRET_PROVIDERS[channel];
+  const channelSecrets = secrets.filter(
+    (s) => s.provider.toLowerCase() === secretProvider
+  );
+
+  const value = (key: string) => {
+    const v = config?.[key];
+    if (Array.isArray(v)) return v.join(", ");
+    return v === undefined || v === null ? "" : String(v);
+  };
+
+  // Empty inputs are dropped so optional fields stay unset in the backend
+  const set = (key: string, raw: unknown) => {
+    const next: ChannelConfig = { ...(config ?? {}) };
+    if (raw === "" || raw === undefined) {
+      delete next[key];
+    } else {
+      next[key] = raw;
+    }
+    onChange(Object.keys(next).length > 0 ? next : null);
+  };
+
+  const secretSelect = (key: string, label: string, optional = false) => (
+    <div className="space-y-2">
+      <label className="text-sm font-medium">{label}</label>
+      <select
+        className={selectClassName}
+        value={value(key)}
+        onChange={(e) => set(key, e.target.value)}
+        required={!optional}
+      >
+        <option value="">{optional ? "Keins" : "Secret wählen…"}</option>
+        {channelSecrets.map((s) => (
+          <option key={s.id} value={s.id}>
+            {s.name}
+          </option>
+        ))}
+      </select>
+      {channelSecrets.length === 0 && (
+        <p className="text-xs text-muted-foreground">
+          Lege zuerst im Vault ein Secret mit Provider „{secretProvider}“ an.
+        </p>
+      )}
+    </div>
+  );
+
+  switch (channel) {
+    case "system":
+      return null;
+    case "email":
+      return (
+        <div className="grid gap-4 md:grid-cols-3">
+          <div className="space-y-2">
+            <label className="text-sm font-medium">SMTP-Server</label>
+            <Input
+              placeholder="smtp.example.com"
+              value={value("host")}
+              onChange={(e) => set("host", e.target.value.trim())}
+              required
+            />
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Verschlüsselung</label>
+            <select
+              className={selectClassName}
+              value={value("security") || "starttls"}
+              onChange={(e) => set("security", e.target.value)}
+            >
+              <option value="starttls">STARTTLS</option>
+              <option value="tls">TLS</option>
+              <option value="none">Keine (nur lokal)</option>
+            </select>
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Port</label>
+            <Input
+              type="number"
+              min={1}
+              max={65535}
+              placeholder="Standard"
+              value={value("port")}
+              onChange={(e) => set("port", e.target.value === "" ? "" : Number(e.target.value))}
+            />
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Benutzername</label>
+            <Input
+              placeholder="optional"
+              value={value("username")}
+              onChange={(e) => set("username", e.target.value.trim())}
+            />
+          </div>
+          {secretSelect("passwordSecretId", "Passwort", true)}
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Absender</label>
+            <Input
+              placeholder="Panoptic <alerts@example.com>"
+              value={value("from")}
+              onChange={(e) => set("from", e.target.value)}
+              required
+            />
+          </div>
+          <div className="space-y-2 md:col-span-3">
+            <label className="text-sm font-medium">Empfänger (kommagetrennt)</label>
+            <Input
+              placeholder="me@example.com, team@example.com"
+              value={value("to")}
+              onChange={(e) =>
+                set(
+                  "to",
+                  e.target.value === ""
+                    ? ""
+                    : e.target.value.split(",").map((addr) => addr.trim())
+                )
+              }
+              required
+            />
+          </div>
+        </div>
+      );
+    case "telegram":
+      return (
+        <div className="grid gap-4 md:grid-cols-2">
+          {secretSelect("botTokenSecretId", "Bot-Token")}
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Chat-ID</label>
+            <Input
+              placeholder="z.B. 123456789 oder @kanal"
+              value={value("chatId")}
+              onChange={(e) => set("chatId", e.target.value.trim())}
+              required
+            />
+          </div>
+        </div>
+      );
+    case "discord":
+    case "slack":
+      return (
+        <div className="grid gap-4 md:grid-cols-2">
+          {secretSelect("webhookSecretId", "Webhook-URL")}
+        </div>
+      );
+    case "webhook":
+      return (
+        <div className="grid gap-4 md:grid-cols-2">
+          <div className="space-y-2">
+            <label className="text-sm font-medium">URL</label>
+            <Input
+              type="url"
+              placeholder="https://example.com/hooks/panoptic"
+              value={value("url")}
+              onChange={(e) => set("url", e.target.value.trim())}
+              required
+            />
+          </div>
+          {secretSelect("signingSecretId", "Signatur-Schlüssel (HMAC)", true)}
+        </div>
+      );
+  }
+}
@@ -0,0 +1,114 @@
+import { useState } from "react";
+import { Download, Loader2 } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { useExportAuditLog } from "@/hooks/useAuditLog";
+import { AUDIT_EXPORT_FORMAT_LABELS, type AuditExportFormat } from "@/lib/audit";
+
+const selectClassName =
+  "flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+interface AuditExportProps {
+  actionLabels: Record<string, string>;
+}
+
+export function AuditExport({ actionLabels }: AuditExportProps) {
+  const exportMutation = useExportAuditLog();
+  const [format, setFormat] = useState<AuditExportFormat>("jsonl");
+  const [from, setFrom] = useState("");
+  const [to, setTo] = useState("");
+  const [action, setAction] = useState("");
+  const [resourceType, setResourceType] = useState("");
+
+  const handleExport = () => {
+    exportMutation.mutate({
+      format,
+      filter: {
+        startDate: from ? new Date(`${from}T00:00:00`) : undefined,
+        endDate: to ? new Date(`${to}T23:59:59`) : undefined,
+        action: action || undefined,
+        resourceType: resourceType.trim() || undefined,
+      },
+    });
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Download className="h-5 w-5" />
+          Export für Review
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="grid gap-4 md:grid-cols-5">
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Format</label>
+            <select
+              className={selectClassName}
+              value={format}
+              onChange={(e) => setFormat(e.target.value as AuditExportFormat)}
+            >
+              {Object.entries(AUDIT_EXPORT_FORMAT_LABELS).map(([value, label]) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Von</label>
+            <Input type="date" value={from} onChange={(e) => setFrom(e.target.value)} />
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Bis</label>
+            <Input type="date" value={to} onChange={(e) => setTo(e.target.value)} />
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Aktion</label>
+            <select
+              className={selectClassName}
+              value={action}
+              onChange={(e) => setAction(e.target.value)}
+            >
+              <option value="">Alle</option>
+              {Object.entries(actionLabels).map(([value, label]) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Ressource</label>
+            <Input
+              placeholder="z.B. secret"
+              value={resourceType}
+              onChange={(e) => setResourceType(e.target.value)}
+            />
+          </div>
+        </div>
+
+        <div className="flex items-center gap-4">
+          <Button onClick={handleExport} disabled={exportMutation.isPending}>
+            {exportMutation.isPending ? (
+              <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+            ) : (
+              <Download className="mr-2 h-4 w-4" />
+            )}
+            Exportieren
+          </Button>
+          {exportMutation.data && (
+            <p className="text-sm text-muted-foreground">
+              {exportMutation.data.count} Einträge gespeichert in {exportMutation.data.path}
+            </p>
+          )}
+          {exportMutation.error && (
+            <p className="text-sm text-destructive">{String(exportMutation.error)}</p>
+          )}
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
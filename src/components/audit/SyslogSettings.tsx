@@ -0,0 +1,130 @@
+import { useEffect, useState } from "react";
+import { Loader2, Radio, Send } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { useAuditSyslog, useSaveAuditSyslog, useTestAuditSyslog } from "@/hooks/useAuditLog";
+import type { SyslogConfig } from "@/lib/audit";
+
+const selectClassName =
+  "flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+const defaultConfig: SyslogConfig = {
+  enabled: false,
+  host: "",
+  port: 514,
+  protocol: "udp",
+};
+
+export function SyslogSettings() {
+  const { data: saved } = useAuditSyslog();
+  const saveMutation = useSaveAuditSyslog();
+  const testMutation = useTestAuditSyslog();
+  const [config, setConfig] = useState<SyslogConfig>(defaultConfig);
+
+  useEffect(() => {
+    if (saved) setConfig(saved);
+  }, [saved]);
+
+  const update = (changes: Partial<SyslogConfig>) => {
+    setConfig({ ...config, ...changes });
+    saveMutation.reset();
+    testMutation.reset();
+  };
+
+  const handleSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    saveMutation.mutate(config);
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Radio className="h-5 w-5" />
+          Syslog-Weiterleitung
+        </CardTitle>
+      </CardHeader>
+      <CardContent>
+        <form onSubmit={handleSubmit} className="space-y-4">
+          <p className="text-sm text-muted-foreground">
+            Neue Einträge werden alle 30 Sekunden als RFC-5424-Nachricht mit CEF-Inhalt an
+            deinen Syslog-Server gesendet.
+          </p>
+          <div className="grid gap-4 md:grid-cols-4">
+            <div className="space-y-2 md:col-span-2">
+              <label className="text-sm font-medium">Host</label>
+              <Input
+                placeholder="siem.example.com"
+                value={config.host}
+                onChange={(e) => update({ host: e.target.value })}
+                required
+              />
+            </div>
+            <div className="space-y-2">
+              <label className="text-sm font-medium">Port</label>
+              <Input
+                type="number"
+                min={1}
+                max={65535}
+                value={config.port}
+                onChange={(e) => update({ port: Number(e.target.value) })}
+                required
+              />
+            </div>
+            <div className="space-y-2">
+              <label className="text-sm font-medium">Protokoll</label>
+              <select
+                className={selectClassName}
+                value={config.protocol}
+                onChange={(e) => update({ protocol: e.target.value as SyslogConfig["protocol"] })}
+              >
+                <option value="udp">UDP</option>
+                <option value="tcp">TCP</option>
+              </select>
+            </div>
+          </div>
+
+          <label className="flex items-center gap-2 text-sm">
+            <input
+              type="checkbox"
+              checked={config.enabled}
+              onChange={(e) => update({ enabled: e.target.checked })}
+            />
+            Weiterleitung aktiv
+          </label>
+
+          {saveMutation.isSuccess && <p className="text-sm text-success">Gespeichert.</p>}
+          {testMutation.isSuccess && (
+            <p className="text-sm text-success">Testnachricht versendet.</p>
+          )}
+          {(saveMutation.error || testMutation.error) && (
+            <p className="text-sm text-destructive">
+              {String(saveMutation.error ?? testMutation.error)}
+            </p>
+          )}
+
+          <div className="flex gap-2">
+            <Button type="submit" disabled={saveMutation.isPending}>
+              {saveMutation.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+              Speichern
+            </Button>
+            <Button
+              type="button"
+              variant="outline"
+              onClick={() => testMutation.mutate(config)}
+              disabled={testMutation.isPending || !config.host}
+            >
+              {testMutation.isPending ? (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              ) : (
+                <Send className="mr-2 h-4 w-4" />
+              )}
+              Test senden
+            </Button>
+          </div>
+        </form>
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,11 @@
+// Marks a cost that was estimated from token counts and the price table
+export function EstimatedBadge({ version }: { version?: string | null }) {
+  return (
+    <span
+      className="ml-2 rounded bg-muted px-1.5 py-0.5 align-middle text-xs font-normal text-muted-foreground"
+      title={version ? `Geschätzt nach Preisliste ${version}` : "Aus Token-Zahlen geschätzt"}
+    >
+      geschätzt
+    </span>
+  );
+}
@@ -0,0 +1,179 @@
+import { useEffect, useState } from "react";
+import { Loader2, RotateCcw, Save, Tags } from "lucide-react";
+import { useQueryClient } from "@tanstack/react-query";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  getPriceTable,
+  removePriceOverride,
+  setPriceOverride,
+  PROVIDER_LABELS,
+  type ModelPrice,
+  type PriceTable as PriceTableData,
+} from "@/lib/usage";
+
+type PriceField = "input" | "cachedInput" | "output" | "batchInput" | "batchOutput";
+
+const PRICE_FIELDS: { field: PriceField; label: string }[] = [
+  { field: "input", label: "Input" },
+  { field: "cachedInput", label: "Cached" },
+  { field: "output", label: "Output" },
+  { field: "batchInput", label: "Batch In" },
+  { field: "batchOutput", label: "Batch Out" },
+];
+
+const priceKey = (price: ModelPrice) => `${price.provider}:${price.model}`;
+
+// Prices used for estimated costs, with local overrides per model
+export function PriceTable() {
+  const queryClient = useQueryClient();
+  const [table, setTable] = useState<PriceTableData | null>(null);
+  const [editing, setEditing] = useState<ModelPrice | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = () =>
+    getPriceTable()
+      .then(setTable)
+      .catch((e) => setError(String(e)));
+
+  useEffect(() => {
+    load();
+  }, []);
+
+  const afterChange = async () => {
+    setEditing(null);
+    setError(null);
+    await load();
+    queryClient.invalidateQueries({ queryKey: ["usage"] });
+  };
+
+  const save = async () => {
+    if (!editing) return;
+    try {
+      await setPriceOverride(editing);
+      await afterChange();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const reset = async (price: ModelPrice) => {
+    try {
+      await removePriceOverride(price.provider, price.model);
+      await afterChange();
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const updateField = (field: PriceField, value: string) => {
+    if (!editing) return;
+    const parsed = value === "" ? undefined : Number(value);
+    setEditing({ ...editing, [field]: field === "input" || field === "output" ? parsed ?? 0 : parsed });
+  };
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <Tags className="h-5 w-5" />
+          Preisliste
+          {table && (
+            <span className="text-sm font-normal text-muted-foreground">
+              Stand {table.version} · USD pro 1M Tokens
+            </span>
+          )}
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-2">
+        <p className="text-sm text-muted-foreground">
+          Liefert ein Provider nur Tokens und keine Kosten, werden die Kosten mit diesen Preisen
+          geschätzt. Eigene Preise gelten auch rückwirkend. Gemini liefert weder Tokens noch
+          Kosten, daher können Gemini-Kosten nicht geschätzt werden.
+        </p>
+        {error && <p className="text-sm text-destructive">{error}</p>}
+        {!table ? (
+          <div className="flex justify-center py-6">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="border-b border-border text-left text-muted-foreground">
+                <th className="py-2 font-medium">Provider</th>
+                <th className="py-2 font-medium">Modell</th>
+                {PRICE_FIELDS.map(({ field, label }) => (
+                  <th key={field} className="py-2 text-right font-medium">
+                    {label}
+                  </th>
+                ))}
+                <th className="py-2" />
+              </tr>
+            </thead>
+            <tbody>
+              {table.prices.map((price) => {
+                const isEditing = editing !== null && priceKey(editing) === priceKey(price);
+                return (
+                  <tr key={priceKey(price)} className="border-b border-border/50">
+                    <td className="py-2">{PROVIDER_LABELS[price.provider]}</td>
+                    <td className="py-2 font-mono text-xs">
+                      {price.model}
+                      {price.overridden && (
+                        <span className="ml-2 font-sans text-primary">eigener Preis</span>
+                      )}
+                    </td>
+                    {PRICE_FIELDS.map(({ field }) => (
+                      <td key={field} className="py-1 text-right">
+                        {isEditing ? (
+                          <Input
+                            type="number"
+                            min={0}
+                            step="0.01"
+                            className="ml-auto h-8 w-20 text-right"
+                            value={editing[field] ?? ""}
+                            onChange={(e) => updateField(field, e.target.value)}
+                          />
+                        ) : (
+                          price[field] ?? "–"
+                        )}
+                      </td>
+                    ))}
+                    <td className="py-1 text-right">
+                      {isEditing ? (
+                        <Button size="sm" variant="ghost" onClick={save}>
+                          <Save className="h-4 w-4" />
+                        </Button>
+                      ) : (
+                        <Button
+                          size="sm"
+                          variant="ghost"
+                          onClick={() => {
+                            const { overridden: _, ...rest } = price;
+                            setEditing(rest);
+                          }}
+                        >
+                          Bearbeiten
+                        </Button>
+                      )}
+                      {price.overridden && !isEditing && (
+                        <Button
+                          size="sm"
+                          variant="ghost"
+                          title="Auf Standardpreis zurücksetzen"
+                          onClick={() => reset(price)}
+                        >
+                          <RotateCcw className="h-4 w-4" />
+                        </Button>
+                      )}
+                    </td>
+                  </tr>
+                );
+              })}
+            </tbody>
+          </table>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,174 @@
+import { useEffect, useState } from "react";
+import { History, Loader2, Play, Square } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { EstimatedBadge } from "@/components/costs/EstimatedBadge";
+import { useUsageBackfills, useUsageHistory } from "@/hooks/useOpenAI";
+import { listProviderKeys, type ProviderKey } from "@/lib/providers";
+import {
+  cancelUsageBackfill,
+  startUsageBackfill,
+  PROVIDER_LABELS,
+  type BackfillJob,
+  type MonthlyUsage,
+} from "@/lib/usage";
+import { formatCurrency, formatNumber } from "@/lib/utils";
+
+const MONTH_OPTIONS = [3, 6, 12, 24];
+
+const selectClassName =
+  "flex h-10 rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+function jobLabel(job: BackfillJob): string {
+  if (job.active) return `${job.completedMonths}/${job.totalMonths} Monate`;
+  switch (job.status) {
+    case "done":
+      return `${job.totalMonths} Monate geladen`;
+    case "cancelled":
+      return `Abgebrochen bei ${job.completedMonths}/${job.totalMonths}`;
+    case "failed":
+      return `Fehler bei ${job.completedMonths}/${job.totalMonths}`;
+    default:
+      return `Unterbrochen bei ${job.completedMonths}/${job.totalMonths}`;
+  }
+}
+
+// Spend of past months from usage_cache, with a backfill per admin key
+export function UsageHistory() {
+  const [months, setMonths] = useState(6);
+  const [keys, setKeys] = useState<ProviderKey[]>([]);
+  const [secretId, setSecretId] = useState("");
+  const [startError, setStartError] = useState<string | null>(null);
+  const { data: history = [], isLoading } = useUsageHistory(months);
+  const { data: jobs = [], refetch: refetchJobs } = useUsageBackfills();
+
+  useEffect(() => {
+    Promise.all([listProviderKeys("openai"), listProviderKeys("anthropic")])
+      .then(([openai, anthropic]) => {
+        const adminKeys = [...openai, ...anthropic].filter((k) => k.keyKind === "admin");
+        setKeys(adminKeys);
+        setSecretId((current) => current || adminKeys[0]?.id || "");
+      })
+      .catch((e) => console.error("Failed to load keys:", e));
+  }, []);
+
+  const selectedJob = jobs.find((job) => job.secretId === secretId);
+  // A different range starts a new backfill instead of resuming
+  const canResume =
+    selectedJob &&
+    !selectedJob.active &&
+    selectedJob.totalMonths === months &&
+    selectedJob.completedMonths < selectedJob.totalMonths;
+
+  const start = async () => {
+    setStartError(null);
+    try {
+      await startUsageBackfill(secretId, months);
+      await refetchJobs();
+    } catch (e) {
+      setStartError(String(e));
+    }
+  };
+
+  const byMonth = history.reduce<Record<string, MonthlyUsage[]>>((acc, entry) => {
+    (acc[entry.month] ||= []).push(entry);
+    return acc;
+  }, {});
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <History className="h-5 w-5" />
+          Verlauf
+          <select
+            className={`${selectClassName} ml-auto`}
+            value={months}
+            onChange={(e) => setMonths(Number(e.target.value))}
+          >
+            {MONTH_OPTIONS.map((m) => (
+              <option key={m} value={m}>
+                {m} Monate
+              </option>
+            ))}
+          </select>
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {keys.length > 0 && (
+          <div className="flex flex-wrap items-center gap-2">
+            <select
+              className={selectClassName}
+              value={secretId}
+              onChange={(e) => setSecretId(e.target.value)}
+            >
+              {keys.map((key) => (
+                <option key={key.id} value={key.id}>
+                  {key.name} ({key.provider})
+                </option>
+              ))}
+            </select>
+            {selectedJob?.active ? (
+              <Button variant="outline" onClick={() => cancelUsageBackfill(secretId)}>
+                <Square className="mr-2 h-4 w-4" />
+                Abbrechen
+              </Button>
+            ) : (
+              <Button variant="outline" onClick={start} disabled={!secretId}>
+                <Play className="mr-2 h-4 w-4" />
+                {canResume ? "Fortsetzen" : `${months} Monate nachladen`}
+              </Button>
+            )}
+            {selectedJob && (
+              <span className="flex items-center gap-2 text-sm text-muted-foreground">
+                {selectedJob.active && <Loader2 className="h-4 w-4 animate-spin" />}
+                {jobLabel(selectedJob)}
+              </span>
+            )}
+          </div>
+        )}
+        {(startError || selectedJob?.error) && (
+          <p className="text-sm text-destructive">{startError || selectedJob?.error}</p>
+        )}
+
+        {isLoading ? (
+          <div className="flex justify-center py-6">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : history.length === 0 ? (
+          <p className="text-sm text-muted-foreground">
+            Noch keine Daten im Cache. Lade vergangene Monate mit einem Admin Key nach.
+          </p>
+        ) : (
+          <table className="w-full text-sm">
+            <thead>
+              <tr className="border-b border-border text-left text-muted-foreground">
+                <th className="py-2 font-medium">Monat</th>
+                <th className="py-2 font-medium">Provider</th>
+                <th className="py-2 text-right font-medium">Tokens</th>
+                <th className="py-2 text-right font-medium">Kosten</th>
+              </tr>
+            </thead>
+            <tbody>
+              {Object.entries(byMonth).map(([month, entries]) =>
+                entries.map((entry, index) => (
+                  <tr key={`${month}-${entry.provider}`} className="border-b border-border/50">
+                    <td className="py-2">{index === 0 ? month : ""}</td>
+                    <td className="py-2">{PROVIDER_LABELS[entry.provider]}</td>
+                    <td className="py-2 text-right">
+                      {formatNumber(entry.inputTokens + entry.outputTokens)}
+                    </td>
+                    <td className="py-2 text-right">
+                      {formatCurrency(entry.costUsd)}
+                      {entry.estimated && <EstimatedBadge />}
+                    </td>
+                  </tr>
+                ))
+              )}
+            </tbody>
+          </table>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,299 @@
+import { useEffect, useState } from "react";
+import { Camera, Cloud, Loader2, Trash2 } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { ConfirmAction } from "@/components/infrastructure/ConfirmAction";
+import { ServiceKeySelect } from "@/components/infrastructure/ServiceKeySelect";
+import {
+  useCloudflareAccounts,
+  useCloudflareDns,
+  useCloudflareWorkers,
+  useCloudflareZones,
+  useDeleteDnsSnapshot,
+  useSaveDnsSnapshot,
+} from "@/hooks/useInfrastructure";
+import type { CloudflareZone, DnsChange, DnsRecord } from "@/lib/infrastructure";
+import { formatNumber, formatRelativeTime } from "@/lib/utils";
+
+const selectClassName =
+  "flex h-9 w-full rounded-md border border-input bg-background px-2 py-1 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+type SnapshotAction = "save" | "delete";
+
+function zoneStatusColor(zone: CloudflareZone): string {
+  if (zone.paused) return "bg-muted-foreground";
+  if (zone.status === "active") return "bg-success";
+  if (zone.status === "pending" || zone.status === "initializing") return "bg-warning";
+  return "bg-destructive";
+}
+
+function fromUnix(seconds: number): Date {
+  return new Date(seconds * 1000);
+}
+
+function recordValue(record: DnsRecord): string {
+  const priority = record.priority !== null ? `${record.priority} ` : "";
+  return `${priority}${record.content}`;
+}
+
+function recordMeta(record: DnsRecord): string {
+  const ttl = record.ttl === 1 ? "Auto" : `${record.ttl} s`;
+  return record.proxied ? `Proxied · ${ttl}` : ttl;
+}
+
+const changeStyles: Record<DnsChange["kind"], { label: string; className: string }> = {
+  added: { label: "Neu", className: "border-success/50 bg-success/10" },
+  removed: { label: "Entfernt", className: "border-destructive/50 bg-destructive/10" },
+  changed: { label: "Geändert", className: "border-warning/50 bg-warning/10" },
+};
+
+function RecordRow({ record, className = "" }: { record: DnsRecord; className?: string }) {
+  return (
+    <div className={`grid grid-cols-[4rem_1fr_auto] gap-3 text-sm ${className}`}>
+      <span className="font-mono text-xs text-muted-foreground">{record.recordType}</span>
+      <span className="min-w-0">
+        <span className="block truncate">{record.name}</span>
+        <span className="block truncate font-mono text-xs text-muted-foreground">
+          {recordValue(record)}
+        </span>
+      </span>
+      <span className="text-xs text-muted-foreground">{recordMeta(record)}</span>
+    </div>
+  );
+}
+
+function ChangeRow({ change }: { change: DnsChange }) {
+  const style = changeStyles[change.kind];
+  const record = (change.after ?? change.before)!;
+  return (
+    <div className={`space-y-1 rounded border p-2 ${style.className}`}>
+      <span className="text-xs font-medium">{style.label}</span>
+      {change.kind === "changed" && change.before && (
+        <RecordRow record={change.before} className="line-through opacity-60" />
+      )}
+      <RecordRow record={record} className={change.kind === "removed" ? "line-through" : ""} />
+    </div>
+  );
+}
+
+function ZoneDns({ secretId, zone }: { secretId: string; zone: CloudflareZone }) {
+  const { data: dns, isLoading, error } = useCloudflareDns(secretId, zone.id);
+
+  if (isLoading) return <Loader2 className="mt-3 h-4 w-4 animate-spin text-muted-foreground" />;
+  if (error) return <p className="mt-3 text-sm text-destructive">{String(error)}</p>;
+  if (!dns) return null;
+
+  // Changed and removed records are listed with the flagged changes instead
+  const flagged = new Set(dns.changes.flatMap((c) => [c.before?.id, c.after?.id]));
+  const unchanged = dns.records.filter((record) => !flagged.has(record.id));
+
+  return (
+    <div className="mt-3 space-y-3 border-t border-border pt-3">
+      {dns.snapshotAt === null ? (
+        <p className="text-sm text-muted-foreground">
+          Ohne Snapshot werden DNS-Änderungen nicht erkannt.
+        </p>
+      ) : dns.changes.length === 0 ? (
+        <p className="text-sm text-muted-foreground">
+          Keine Abweichungen vom Snapshot ({formatRelativeTime(fromUnix(dns.snapshotAt))}).
+        </p>
+      ) : (
+        <div className="space-y-2">
+          <p className="text-sm font-medium text-warning">
+            {dns.changes.length} Abweichungen vom Snapshot (
+            {formatRelativeTime(fromUnix(dns.snapshotAt))})
+          </p>
+          {dns.changes.map((change) => (
+            <ChangeRow key={(change.after ?? change.before)!.id} change={change} />
+          ))}
+        </div>
+      )}
+      {unchanged.length === 0 && dns.changes.length === 0 ? (
+        <p className="text-sm text-muted-foreground">Keine DNS-Records.</p>
+      ) : (
+        <div className="space-y-2">
+          {unchanged.map((record) => (
+            <RecordRow key={record.id} record={record} />
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
+
+function Workers({ secretId }: { secretId: string }) {
+  const [accountId, setAccountId] = useState<string | null>(null);
+  const { data: accounts = [] } = useCloudflareAccounts(secretId);
+  const { data: workers = [], isLoading, error } = useCloudflareWorkers(secretId, accountId);
+
+  useEffect(() => {
+    if (!accounts.some((a) => a.id === accountId)) setAccountId(accounts[0]?.id ?? null);
+  }, [accounts, accountId]);
+
+  if (accounts.length === 0) return null;
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center justify-between gap-4">
+        <p className="text-sm font-medium">Workers (24 h)</p>
+        {accounts.length > 1 && (
+          <select
+            className={`${selectClassName} max-w-xs`}
+            value={accountId ?? ""}
+            onChange={(e) => setAccountId(e.target.value)}
+          >
+            {accounts.map((account) => (
+              <option key={account.id} value={account.id}>
+                {account.name}
+              </option>
+            ))}
+          </select>
+        )}
+      </div>
+      {isLoading ? (
+        <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />
+      ) : error ? (
+        <p className="text-sm text-destructive">{String(error)}</p>
+      ) : workers.length === 0 ? (
+        <p className="text-sm text-muted-foreground">Keine Workers.</p>
+      ) : (
+        workers.map((worker) => (
+          <div key={worker.name} className="flex items-center justify-between gap-4 text-sm">
+            <span className="truncate font-mono text-xs">{worker.name}</span>
+            <span className="shrink-0 text-xs text-muted-foreground">
+              {worker.requests !== null ? formatNumber(worker.requests) : "–"} Requests ·{" "}
+              <span className={worker.errors ? "text-destructive" : ""}>
+                {worker.errors !== null ? formatNumber(worker.errors) : "–"} Fehler
+              </span>
+              {worker.modifiedOn && ` · ${formatRelativeTime(worker.modifiedOn)}`}
+            </span>
+          </div>
+        ))
+      )}
+    </div>
+  );
+}
+
+export function CloudflarePanel() {
+  const [secretId, setSecretId] = useState<string | null>(null);
+  const [openZone, setOpenZone] = useState<string | null>(null);
+  const [pending, setPending] = useState<{ action: SnapshotAction; zone: CloudflareZone } | null>(
+    null
+  );
+  const { data: zones = [], isLoading, error } = useCloudflareZones(secretId);
+  const saveMutation = useSaveDnsSnapshot();
+  const deleteMutation = useDeleteDnsSnapshot();
+  const mutation = pending?.action === "delete" ? deleteMutation : saveMutation;
+
+  const closeDialog = () => {
+    setPending(null);
+    saveMutation.reset();
+    deleteMutation.reset();
+  };
+
+  const confirm = () => {
+    if (!pending || !secretId) return;
+    if (pending.action === "save") {
+      saveMutation.mutate({ secretId, zone: pending.zone }, { onSuccess: closeDialog });
+    } else {
+      deleteMutation.mutate(pending.zone.id, { onSuccess: closeDialog });
+    }
+  };
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between gap-4">
+        <CardTitle className="flex items-center gap-2">
+          <Cloud className="h-5 w-5" />
+          Cloudflare
+        </CardTitle>
+        <ServiceKeySelect service="cloudflare" value={secretId} onChange={setSecretId} />
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {!secretId ? null : isLoading ? (
+          <div className="flex items-center justify-center py-8">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : error ? (
+          <p className="text-sm text-destructive">{String(error)}</p>
+        ) : (
+          <>
+            {zones.length === 0 ? (
+              <p className="py-8 text-center text-muted-foreground">Keine Zonen gefunden.</p>
+            ) : (
+              zones.map((zone) => (
+                <div key={zone.id} className="rounded-lg border border-border p-3">
+                  <div className="flex items-center justify-between gap-4">
+                    <button
+                      className="flex min-w-0 items-center gap-3 text-left"
+                      onClick={() => setOpenZone(openZone === zone.id ? null : zone.id)}
+                    >
+                      <div className={`h-2 w-2 shrink-0 rounded-full ${zoneStatusColor(zone)}`} />
+                      <span className="font-medium">{zone.name}</span>
+                      {zone.plan && (
+                        <span className="text-xs text-muted-foreground">{zone.plan}</span>
+                      )}
+                    </button>
+                    <div className="flex items-center gap-2">
+                      <span className="text-xs text-muted-foreground">
+                        {zone.snapshotAt !== null
+                          ? `Snapshot ${formatRelativeTime(fromUnix(zone.snapshotAt))}`
+                          : "Kein Snapshot"}
+                      </span>
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        onClick={() => setPending({ action: "save", zone })}
+                        title="Snapshot speichern"
+                      >
+                        <Camera className="h-4 w-4" />
+                      </Button>
+                      {zone.snapshotAt !== null && (
+                        <Button
+                          variant="ghost"
+                          size="icon"
+                          onClick={() => setPending({ action: "delete", zone })}
+                          title="Überwachung beenden"
+                        >
+                          <Trash2 className="h-4 w-4" />
+                        </Button>
+                      )}
+                    </div>
+                  </div>
+                  {openZone === zone.id && <ZoneDns secretId={secretId} zone={zone} />}
+                </div>
+              ))
+            )}
+
+            <Workers secretId={secretId} />
+          </>
+        )}
+      </CardContent>
+
+      {pending && secretId && (
+        <ConfirmAction
+          title={pending.action === "save" ? "DNS-Snapshot speichern" : "Überwachung beenden"}
+          description={
+            pending.action === "save" ? (
+              <>
+                Die aktuellen DNS-Records von <strong>{pending.zone.name}</strong> werden als
+                erwarteter Stand gespeichert. Spätere Abweichungen lösen DNS-Alerts aus.
+              </>
+            ) : (
+              <>
+                Der Snapshot von <strong>{pending.zone.name}</strong> wird gelöscht; DNS-Änderungen
+                werden nicht mehr erkannt.
+              </>
+            )
+          }
+          confirmLabel={pending.action === "save" ? "Speichern" : "Beenden"}
+          destructive={pending.action === "delete"}
+          pending={mutation.isPending}
+          error={mutation.error}
+          onConfirm={confirm}
+          onCancel={closeDialog}
+        />
+      )}
+    </Card>
+  );
+}
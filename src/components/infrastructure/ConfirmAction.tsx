@@ -0,0 +1,64 @@
+import { AlertTriangle, Loader2, X } from "lucide-react";
+import { Button } from "@/components/ui/button";
+
+interface ConfirmActionProps {
+  title: string;
+  description: React.ReactNode;
+  confirmLabel: string;
+  pending: boolean;
+  error?: unknown;
+  destructive?: boolean;
+  onConfirm: () => void;
+  onCancel: () => void;
+}
+
+// Confirmation for actions that change a running service (redeploy, ...)
+export function ConfirmAction({
+  title,
+  description,
+  confirmLabel,
+  pending,
+  error,
+  destructive,
+  onConfirm,
+  onCancel,
+}: ConfirmActionProps) {
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 p-4">
+      <div className="w-full max-w-md rounded-xl border bg-card p-6 shadow-2xl">
+        <div className="flex items-start justify-between">
+          <div className="flex items-center gap-3">
+            <div className="rounded-full bg-warning/10 p-2">
+              <AlertTriangle className="h-5 w-5 text-warning" />
+            </div>
+            <h3 className="text-lg font-semibold">{title}</h3>
+          </div>
+          <Button variant="ghost" size="sm" onClick={onCancel} disabled={pending}>
+            <X className="h-4 w-4" />
+          </Button>
+        </div>
+
+        <div className="mt-4 space-y-4">
+          <div className="text-sm text-muted-foreground">{description}</div>
+          <p className="text-xs text-muted-foreground">
+            Die Aktion wird im Audit-Log festgehalten.
+          </p>
+          {!!error && <p className="text-sm text-destructive">{String(error)}</p>}
+          <div className="flex justify-end gap-2">
+            <Button variant="outline" onClick={onCancel} disabled={pending}>
+              Abbrechen
+            </Button>
+            <Button
+              variant={destructive ? "destructive" : "default"}
+              onClick={onConfirm}
+              disabled={pending}
+            >
+              {pending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+              {confirmLabel}
+            </Button>
+          </div>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,235 @@
+import { useState } from "react";
+import { Database, Loader2 } from "lucide-react";
+import {
+  CartesianGrid,
+  Line,
+  LineChart,
+  ResponsiveContainer,
+  Tooltip,
+  XAxis,
+  YAxis,
+} from "recharts";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { ServiceKeySelect } from "@/components/infrastructure/ServiceKeySelect";
+import { useNeonOverview, useNeonStorageHistory } from "@/hooks/useInfrastructure";
+import type { LimitUsage, NeonEndpoint, NeonProject } from "@/lib/infrastructure";
+import { formatRelativeTime } from "@/lib/utils";
+
+const GIB = 1024 ** 3;
+
+function formatBytes(bytes: number): string {
+  if (bytes >= GIB) return `${(bytes / GIB).toFixed(2)} GiB`;
+  return `${(bytes / 1024 ** 2).toFixed(0)} MiB`;
+}
+
+function formatHours(seconds: number): string {
+  return `${(seconds / 3600).toFixed(1)} h`;
+}
+
+function formatLimitValue(usage: LimitUsage, value: number): string {
+  return usage.metric === "storage" ? formatBytes(value) : formatHours(value);
+}
+
+function limitColor(percent: number): string {
+  if (percent >= 90) return "bg-destructive";
+  if (percent >= 80) return "bg-warning";
+  return "bg-primary";
+}
+
+function endpointStatus(endpoint: NeonEndpoint): { label: string; color: string } {
+  if (endpoint.suspended) return { label: "Suspendiert", color: "bg-muted-foreground" };
+  if (endpoint.state === "active") return { label: "Aktiv", color: "bg-success" };
+  return { label: "Startet", color: "bg-warning" };
+}
+
+function LimitBar({ usage }: { usage: LimitUsage }) {
+  const percent = (usage.used / usage.limit) * 100;
+  return (
+    <div className="space-y-1">
+      <div className="flex justify-between text-xs">
+        <span>
+          {usage.resource} · {usage.metric === "storage" ? "Storage" : "Compute-Zeit"}
+        </span>
+        <span className="text-muted-foreground">
+          {formatLimitValue(usage, usage.used)} von {formatLimitValue(usage, usage.limit)} (
+          {percent.toFixed(0)}%)
+        </span>
+      </div>
+      <div className="h-1.5 overflow-hidden rounded-full bg-muted">
+        <div
+          className={`h-full ${limitColor(percent)}`}
+          style={{ width: `${Math.min(percent, 100)}%` }}
+        />
+      </div>
+    </div>
+  );
+}
+
+function StorageHistory({ secretId }: { secretId: string }) {
+  const { data: history = [] } = useNeonStorageHistory(secretId);
+  const chartData = history.map((day) => ({
+    date: new Date(day.date).toLocaleDateString("de-DE", { day: "2-digit", month: "2-digit" }),
+    storage: day.projects.reduce((sum, p) => sum + p.storageBytes, 0) / GIB,
+  }));
+
+  if (chartData.length < 2) {
+    return (
+      <p className="text-sm text-muted-foreground">
+        Der Storage-Verlauf entsteht, sobald an mehreren Tagen Daten abgerufen wurden.
+      </p>
+    );
+  }
+  return (
+    <ResponsiveContainer width="100%" height={180}>
+      <LineChart data={chartData}>
+        <CartesianGrid strokeDasharray="3 3" stroke="#262626" />
+        <XAxis dataKey="date" stroke="#a1a1a1" fontSize={12} />
+        <YAxis stroke="#a1a1a1" fontSize={12} />
+        <Tooltip
+          contentStyle={{
+            backgroundColor: "#141414",
+            border: "1px solid #262626",
+            borderRadius: "8px",
+          }}
+          formatter={(value) => [`${(value as number).toFixed(2)} GiB`, "Storage"]}
+        />
+        <Line
+          type="monotone"
+          dataKey="storage"
+          stroke="#22c55e"
+          strokeWidth={2}
+          dot={{ fill: "#22c55e" }}
+        />
+      </LineChart>
+    </ResponsiveContainer>
+  );
+}
+
+function ProjectDetails({ project }: { project: NeonProject }) {
+  return (
+    <div className="mt-3 space-y-4 border-t border-border pt-3">
+      <div className="space-y-2">
+        <p className="text-sm font-medium">Branches</p>
+        {project.branches.map((branch) => (
+          <div key={branch.id} className="flex items-center justify-between text-sm">
+            <span>
+              {branch.name}
+              {branch.default && <span className="ml-2 text-xs text-muted-foreground">Standard</span>}
+            </span>
+            <span className="text-xs text-muted-foreground">
+              {branch.logicalSizeBytes !== null && formatBytes(branch.logicalSizeBytes)}
+              {branch.updatedAt && ` · ${formatRelativeTime(branch.updatedAt)}`}
+            </span>
+          </div>
+        ))}
+      </div>
+
+      <div className="space-y-2">
+        <p className="text-sm font-medium">Compute-Endpoints</p>
+        {project.endpoints.length === 0 ? (
+          <p className="text-sm text-muted-foreground">Keine Endpoints.</p>
+        ) : (
+          project.endpoints.map((endpoint) => {
+            const status = endpointStatus(endpoint);
+            const branch = project.branches.find((b) => b.id === endpoint.branchId);
+            return (
+              <div key={endpoint.id} className="flex items-center justify-between gap-4 text-sm">
+                <div className="flex min-w-0 items-center gap-2">
+                  <div className={`h-2 w-2 shrink-0 rounded-full ${status.color}`} />
+                  <span className="truncate font-mono text-xs">{endpoint.id}</span>
+                  <span className="text-xs text-muted-foreground">
+                    {branch?.name ?? endpoint.branchId}
+                    {endpoint.kind === "read_only" && " · Read-only"}
+                  </span>
+                </div>
+                <span className="shrink-0 text-xs text-muted-foreground">
+                  {status.label}
+                  {endpoint.minCu !== null &&
+                    endpoint.maxCu !== null &&
+                    ` · ${endpoint.minCu}–${endpoint.maxCu} CU`}
+                  {endpoint.lastActive && ` · ${formatRelativeTime(endpoint.lastActive)}`}
+                </span>
+              </div>
+            );
+          })
+        )}
+      </div>
+    </div>
+  );
+}
+
+export function NeonPanel() {
+  const [secretId, setSecretId] = useState<string | null>(null);
+  const [openProject, setOpenProject] = useState<string | null>(null);
+  const { data: overview, isLoading, error } = useNeonOverview(secretId);
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between gap-4">
+        <CardTitle className="flex items-center gap-2">
+          <Database className="h-5 w-5" />
+          NeonDB
+          {overview?.plan.name && (
+            <span className="rounded bg-muted px-1.5 py-0.5 text-xs font-normal text-muted-foreground">
+              {overview.plan.name}
+            </span>
+          )}
+        </CardTitle>
+        <ServiceKeySelect service="neondb" value={secretId} onChange={setSecretId} />
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {!secretId ? null : isLoading ? (
+          <div className="flex items-center justify-center py-8">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : error ? (
+          <p className="text-sm text-destructive">{String(error)}</p>
+        ) : overview ? (
+          <>
+            {overview.limits.length > 0 && (
+              <div className="space-y-3 rounded-lg bg-muted/50 p-3">
+                {overview.limits.map((usage) => (
+                  <LimitBar key={`${usage.resource}-${usage.metric}`} usage={usage} />
+                ))}
+              </div>
+            )}
+
+            <StorageHistory secretId={secretId} />
+
+            {overview.projects.length === 0 ? (
+              <p className="py-8 text-center text-muted-foreground">Keine Projekte gefunden.</p>
+            ) : (
+              overview.projects.map((project) => {
+                const active = project.endpoints.filter((e) => !e.suspended).length;
+                return (
+                  <div key={project.id} className="rounded-lg border border-border p-3">
+                    <button
+                      className="flex w-full items-center justify-between gap-4 text-left"
+                      onClick={() => setOpenProject(openProject === project.id ? null : project.id)}
+                    >
+                      <div className="flex min-w-0 items-center gap-3">
+                        <div
+                          className={`h-2 w-2 shrink-0 rounded-full ${active > 0 ? "bg-success" : "bg-muted-foreground"}`}
+                        />
+                        <span className="font-medium">{project.name}</span>
+                        <span className="text-xs text-muted-foreground">
+                          {project.region}
+                          {project.pgVersion && ` · Postgres ${project.pgVersion}`}
+                        </span>
+                      </div>
+                      <span className="shrink-0 text-xs text-muted-foreground">
+                        {formatBytes(project.storageBytes)} · {formatHours(project.computeSeconds)}{" "}
+                        Compute · {active}/{project.endpoints.length} aktiv
+                      </span>
+                    </button>
+                    {openProject === project.id && <ProjectDetails project={project} />}
+                  </div>
+                );
+              })
+            )}
+          </>
+        ) : null}
+      </CardContent>
+    </Card>
+  );
+}
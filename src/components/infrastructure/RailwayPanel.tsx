@@ -0,0 +1,209 @@
+import { useState } from "react";
+import { ExternalLink, History, Loader2, RefreshCw, TrainFront } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { ConfirmAction } from "@/components/infrastructure/ConfirmAction";
+import { ServiceKeySelect } from "@/components/infrastructure/ServiceKeySelect";
+import {
+  useRailwayDeployments,
+  useRailwayProjects,
+  useRedeployRailwayService,
+} from "@/hooks/useInfrastructure";
+import type { RailwayDeployment, RailwayServiceInstance } from "@/lib/infrastructure";
+import { formatRelativeTime } from "@/lib/utils";
+
+// Railway deployment states grouped by colour
+function statusColor(status: string): string {
+  switch (status) {
+    case "SUCCESS":
+      return "bg-success";
+    case "FAILED":
+    case "CRASHED":
+      return "bg-destructive";
+    case "BUILDING":
+    case "DEPLOYING":
+    case "INITIALIZING":
+    case "QUEUED":
+    case "WAITING":
+      return "bg-warning";
+    default:
+      return "bg-muted-foreground";
+  }
+}
+
+function DeploymentLine({ deployment }: { deployment: RailwayDeployment }) {
+  return (
+    <div className="flex items-center justify-between gap-4 text-sm">
+      <div className="flex min-w-0 items-center gap-2">
+        <div className={`h-2 w-2 shrink-0 rounded-full ${statusColor(deployment.status)}`} />
+        <span className="font-mono text-xs">{deployment.status}</span>
+        {deployment.commitMessage && (
+          <span className="truncate text-muted-foreground">{deployment.commitMessage}</span>
+        )}
+      </div>
+      <span className="shrink-0 text-xs text-muted-foreground">
+        {deployment.branch && `${deployment.branch} · `}
+        {deployment.createdAt && formatRelativeTime(deployment.createdAt)}
+      </span>
+    </div>
+  );
+}
+
+interface InstanceRowProps {
+  secretId: string;
+  projectId: string;
+  instance: RailwayServiceInstance;
+  onRedeploy: () => void;
+}
+
+function InstanceRow({ secretId, projectId, instance, onRedeploy }: InstanceRowProps) {
+  const [showHistory, setShowHistory] = useState(false);
+  const { data: deployments = [], isLoading } = useRailwayDeployments(
+    secretId,
+    projectId,
+    showHistory ? instance : null
+  );
+  const latest = instance.latestDeployment;
+
+  return (
+    <div className="rounded-lg border border-border p-3">
+      <div className="flex items-center justify-between gap-4">
+        <div className="flex min-w-0 items-center gap-3">
+          <div
+            className={`h-2 w-2 shrink-0 rounded-full ${statusColor(latest?.status ?? "")}`}
+          />
+          <span className="font-medium">{instance.serviceName}</span>
+          <span className="text-xs text-muted-foreground">{instance.environmentName}</span>
+          {latest?.url && (
+            <a
+              href={latest.url}
+              target="_blank"
+              rel="noreferrer"
+              className="text-muted-foreground hover:text-foreground"
+            >
+              <ExternalLink className="h-3 w-3" />
+            </a>
+          )}
+        </div>
+        <div className="flex items-center gap-1">
+          <span className="mr-2 text-xs text-muted-foreground">
+            {latest
+              ? `${latest.status}${latest.createdAt ? ` · ${formatRelativeTime(latest.createdAt)}` : ""}`
+              : "Nie deployt"}
+          </span>
+          <Button
+            variant="ghost"
+            size="icon"
+            onClick={() => setShowHistory(!showHistory)}
+            title="Letzte Deployments"
+          >
+            <History className="h-4 w-4" />
+          </Button>
+          <Button
+            variant="ghost"
+            size="icon"
+            onClick={onRedeploy}
+            disabled={!latest}
+            title="Letztes Deployment neu starten"
+          >
+            <RefreshCw className="h-4 w-4" />
+          </Button>
+        </div>
+      </div>
+      {showHistory && (
+        <div className="mt-3 space-y-2 border-t border-border pt-3">
+          {isLoading ? (
+            <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />
+          ) : deployments.length === 0 ? (
+            <p className="text-sm text-muted-foreground">Keine Deployments.</p>
+          ) : (
+            deployments.map((deployment) => (
+              <DeploymentLine key={deployment.id} deployment={deployment} />
+            ))
+          )}
+        </div>
+      )}
+    </div>
+  );
+}
+
+export function RailwayPanel() {
+  const [secretId, setSecretId] = useState<string | null>(null);
+  const { data: projects = [], isLoading, error } = useRailwayProjects(secretId);
+  const redeployMutation = useRedeployRailwayService();
+  const [pending, setPending] = useState<RailwayServiceInstance | null>(null);
+
+  const closeDialog = () => {
+    setPending(null);
+    redeployMutation.reset();
+  };
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between gap-4">
+        <CardTitle className="flex items-center gap-2">
+          <TrainFront className="h-5 w-5" />
+          Railway
+        </CardTitle>
+        <ServiceKeySelect service="railway" value={secretId} onChange={setSecretId} />
+      </CardHeader>
+      <CardContent className="space-y-6">
+        {!secretId ? null : isLoading ? (
+          <div className="flex items-center justify-center py-8">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : error ? (
+          <p className="text-sm text-destructive">{String(error)}</p>
+        ) : projects.length === 0 ? (
+          <p className="py-8 text-center text-muted-foreground">Keine Projekte gefunden.</p>
+        ) : (
+          projects.map((project) => (
+            <div key={project.id} className="space-y-2">
+              <div className="flex items-baseline gap-2">
+                <h3 className="font-semibold">{project.name}</h3>
+                {project.description && (
+                  <span className="text-sm text-muted-foreground">{project.description}</span>
+                )}
+              </div>
+              {project.instances.length === 0 ? (
+                <p className="text-sm text-muted-foreground">Keine Services.</p>
+              ) : (
+                project.instances.map((instance) => (
+                  <InstanceRow
+                    key={`${instance.environmentId}:${instance.serviceId}`}
+                    secretId={secretId}
+                    projectId={project.id}
+                    instance={instance}
+                    onRedeploy={() => setPending(instance)}
+                  />
+                ))
+              )}
+            </div>
+          ))
+        )}
+      </CardContent>
+
+      {pending && secretId && (
+        <ConfirmAction
+          title="Redeploy starten"
+          description={
+            <>
+              Das letzte Deployment von <strong>{pending.serviceName}</strong> in{" "}
+              <strong>{pending.environmentName}</strong> wird neu gestartet.
+            </>
+          }
+          confirmLabel="Redeploy"
+          pending={redeployMutation.isPending}
+          error={redeployMutation.error}
+          onConfirm={() =>
+            redeployMutation.mutate(
+              { secretId, instance: pending },
+              { onSuccess: closeDialog }
+            )
+          }
+          onCancel={closeDialog}
+        />
+      )}
+    </Card>
+  );
+}
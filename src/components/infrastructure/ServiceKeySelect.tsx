@@ -0,0 +1,55 @@
+import { useEffect } from "react";
+import { Link } from "react-router-dom";
+import { Button } from "@/components/ui/button";
+import { useServiceKeys } from "@/hooks/useInfrastructure";
+import { INFRA_SERVICE_LABELS, type InfraService } from "@/lib/infrastructure";
+
+const selectClassName =
+  "flex h-9 w-full rounded-md border border-input bg-background px-2 py-1 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+interface ServiceKeySelectProps {
+  service: InfraService;
+  value: string | null;
+  onChange: (secretId: string | null) => void;
+}
+
+// Picks the token of a service; preselects the first one
+export function ServiceKeySelect({ service, value, onChange }: ServiceKeySelectProps) {
+  const { data: keys = [], isLoading } = useServiceKeys(service);
+
+  useEffect(() => {
+    if (!value && keys.length > 0) onChange(keys[0].id);
+  }, [keys, value, onChange]);
+
+  if (isLoading) return null;
+
+  if (keys.length === 0) {
+    return (
+      <div className="text-sm text-muted-foreground">
+        Kein {INFRA_SERVICE_LABELS[service]}-Token gespeichert. Lege ein Secret mit Provider „
+        {service}" an.
+        <Link to="/secrets">
+          <Button variant="link" size="sm">
+            Zu den Secrets
+          </Button>
+        </Link>
+      </div>
+    );
+  }
+
+  if (keys.length === 1) return null;
+
+  return (
+    <select
+      className={`${selectClassName} max-w-xs`}
+      value={value ?? ""}
+      onChange={(e) => onChange(e.target.value || null)}
+    >
+      {keys.map((key) => (
+        <option key={key.id} value={key.id}>
+          {key.name}
+        </option>
+      ))}
+    </select>
+  );
+}
@@ -0,0 +1,211 @@
+import { useState } from "react";
+import { Loader2, Pause, Play, Zap } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { ConfirmAction } from "@/components/infrastructure/ConfirmAction";
+import { ServiceKeySelect } from "@/components/infrastructure/ServiceKeySelect";
+import {
+  useSupabaseAction,
+  useSupabaseProjectStats,
+  useSupabaseProjects,
+} from "@/hooks/useInfrastructure";
+import type { SupabaseAction, SupabaseProject } from "@/lib/infrastructure";
+import { formatNumber } from "@/lib/utils";
+
+const TRANSITIONING = ["COMING_UP", "GOING_DOWN", "PAUSING", "RESTORING", "RESIZING", "UPGRADING"];
+
+function statusColor(status: string): string {
+  if (status === "ACTIVE_HEALTHY") return "bg-success";
+  if (status === "INACTIVE") return "bg-muted-foreground";
+  if (TRANSITIONING.includes(status)) return "bg-warning";
+  return "bg-destructive";
+}
+
+function statusLabel(status: string): string {
+  switch (status) {
+    case "ACTIVE_HEALTHY":
+      return "Aktiv";
+    case "ACTIVE_UNHEALTHY":
+      return "Aktiv, Störung";
+    case "INACTIVE":
+      return "Pausiert";
+    case "PAUSING":
+      return "Wird pausiert";
+    case "RESTORING":
+    case "COMING_UP":
+      return "Startet";
+    default:
+      return status;
+  }
+}
+
+function formatBytes(bytes: number): string {
+  if (bytes >= 1024 ** 3) return `${(bytes / 1024 ** 3).toFixed(2)} GiB`;
+  return `${(bytes / 1024 ** 2).toFixed(0)} MiB`;
+}
+
+const actionTexts: Record<SupabaseAction, { title: string; label: string; text: string }> = {
+  pause: {
+    title: "Projekt pausieren",
+    label: "Pausieren",
+    text: "wird pausiert. Datenbank und APIs sind bis zum Fortsetzen nicht erreichbar",
+  },
+  restore: {
+    title: "Projekt fortsetzen",
+    label: "Fortsetzen",
+    text: "wird wieder gestartet; das kann einige Minuten dauern",
+  },
+};
+
+function ProjectStats({ secretId, project }: { secretId: string; project: SupabaseProject }) {
+  const { data: stats, isLoading, error } = useSupabaseProjectStats(secretId, project.id);
+
+  if (isLoading) return <Loader2 className="mt-3 h-4 w-4 animate-spin text-muted-foreground" />;
+  if (error) return <p className="mt-3 text-sm text-destructive">{String(error)}</p>;
+  if (!stats) return null;
+
+  return (
+    <div className="mt-3 space-y-3 border-t border-border pt-3">
+      <div className="flex flex-wrap gap-x-6 gap-y-1 text-sm">
+        <span>
+          Datenbank:{" "}
+          <span className="font-medium">
+            {stats.dbSizeBytes !== null ? formatBytes(stats.dbSizeBytes) : "–"}
+          </span>
+        </span>
+        <span>
+          Auth-User:{" "}
+          <span className="font-medium">
+            {stats.authUsers !== null ? formatNumber(stats.authUsers) : "–"}
+          </span>
+        </span>
+        {project.dbVersion && (
+          <span className="text-muted-foreground">Postgres {project.dbVersion}</span>
+        )}
+      </div>
+      <div className="flex flex-wrap gap-2">
+        {stats.services.map((service) => (
+          <span
+            key={service.name}
+            className="flex items-center gap-1.5 rounded bg-muted px-2 py-0.5 text-xs"
+            title={service.error ?? service.status}
+          >
+            <span
+              className={`h-1.5 w-1.5 rounded-full ${service.healthy ? "bg-success" : "bg-destructive"}`}
+            />
+            {service.name}
+          </span>
+        ))}
+      </div>
+    </div>
+  );
+}
+
+export function SupabasePanel() {
+  const [secretId, setSecretId] = useState<string | null>(null);
+  const [openProject, setOpenProject] = useState<string | null>(null);
+  const [pending, setPending] = useState<{ action: SupabaseAction; project: SupabaseProject } | null>(
+    null
+  );
+  const { data: projects = [], isLoading, error } = useSupabaseProjects(secretId);
+  const actionMutation = useSupabaseAction();
+
+  const closeDialog = () => {
+    setPending(null);
+    actionMutation.reset();
+  };
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between gap-4">
+        <CardTitle className="flex items-center gap-2">
+          <Zap className="h-5 w-5" />
+          Supabase
+        </CardTitle>
+        <ServiceKeySelect service="supabase" value={secretId} onChange={setSecretId} />
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {!secretId ? null : isLoading ? (
+          <div className="flex items-center justify-center py-8">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : error ? (
+          <p className="text-sm text-destructive">{String(error)}</p>
+        ) : projects.length === 0 ? (
+          <p className="py-8 text-center text-muted-foreground">Keine Projekte gefunden.</p>
+        ) : (
+          projects.map((project) => {
+            const active = project.status.startsWith("ACTIVE");
+            return (
+              <div key={project.id} className="rounded-lg border border-border p-3">
+                <div className="flex items-center justify-between gap-4">
+                  <button
+                    className="flex min-w-0 items-center gap-3 text-left"
+                    onClick={() => setOpenProject(openProject === project.id ? null : project.id)}
+                    disabled={!active}
+                  >
+                    <div className={`h-2 w-2 shrink-0 rounded-full ${statusColor(project.status)}`} />
+                    <span className="font-medium">{project.name}</span>
+                    {project.region && (
+                      <span className="text-xs text-muted-foreground">{project.region}</span>
+                    )}
+                  </button>
+                  <div className="flex items-center gap-2">
+                    <span className="text-xs text-muted-foreground">
+                      {statusLabel(project.status)}
+                    </span>
+                    {active && (
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        onClick={() => setPending({ action: "pause", project })}
+                        title="Pausieren"
+                      >
+                        <Pause className="h-4 w-4" />
+                      </Button>
+                    )}
+                    {project.status === "INACTIVE" && (
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        onClick={() => setPending({ action: "restore", project })}
+                        title="Fortsetzen"
+                      >
+                        <Play className="h-4 w-4" />
+                      </Button>
+                    )}
+                  </div>
+                </div>
+                {active && openProject === project.id && (
+                  <ProjectStats secretId={secretId} project={project} />
+                )}
+              </div>
+            );
+          })
+        )}
+      </CardContent>
+
+      {pending && secretId && (
+        <ConfirmAction
+          title={actionTexts[pending.action].title}
+          description={
+            <>
+              Das Projekt <strong>{pending.project.name}</strong> {actionTexts[pending.action].text}.
+            </>
+          }
+          confirmLabel={actionTexts[pending.action].label}
+          destructive={pending.action === "pause"}
+          pending={actionMutation.isPending}
+          error={actionMutation.error}
+          onConfirm={() =>
+            actionMutation.mutate(
+              { action: pending.action, secretId, project: pending.project },
+              { onSuccess: closeDialog }
+            )
+          }
+          onCancel={closeDialog}
+        />
+      )}
+    </Card>
+  );
+}
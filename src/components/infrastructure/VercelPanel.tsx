@@ -0,0 +1,336 @@
+import { useState } from "react";
+import {
+  ArrowUpCircle,
+  ExternalLink,
+  Globe,
+  Loader2,
+  RefreshCw,
+  Triangle,
+  XCircle,
+} from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { ConfirmAction } from "@/components/infrastructure/ConfirmAction";
+import { ServiceKeySelect } from "@/components/infrastructure/ServiceKeySelect";
+import {
+  useVercelAction,
+  useVercelDeployments,
+  useVercelDomains,
+  useVercelProjects,
+  useVercelTeams,
+  useVercelUsage,
+} from "@/hooks/useInfrastructure";
+import type {
+  VercelAction,
+  VercelDeployment,
+  VercelDomain,
+  VercelProject,
+} from "@/lib/infrastructure";
+import { formatCurrency, formatRelativeTime } from "@/lib/utils";
+
+const selectClassName =
+  "flex h-9 w-full rounded-md border border-input bg-background px-2 py-1 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+const IN_PROGRESS = ["BUILDING", "QUEUED", "INITIALIZING"];
+
+function stateColor(state: string): string {
+  if (state === "READY") return "bg-success";
+  if (state === "ERROR") return "bg-destructive";
+  if (IN_PROGRESS.includes(state)) return "bg-warning";
+  return "bg-muted-foreground";
+}
+
+const actionTexts: Record<VercelAction, { title: string; label: string; text: string }> = {
+  redeploy: {
+    title: "Redeploy starten",
+    label: "Redeploy",
+    text: "wird als neues Deployment erneut gebaut",
+  },
+  promote: {
+    title: "Deployment promoten",
+    label: "Promoten",
+    text: "wird zum Production-Deployment",
+  },
+  cancel: {
+    title: "Build abbrechen",
+    label: "Abbrechen",
+    text: "wird abgebrochen",
+  },
+};
+
+function domainStatus(domain: VercelDomain): { label: string; className: string } {
+  if (!domain.verified) return { label: "Nicht verifiziert", className: "text-destructive" };
+  if (domain.misconfigured) return { label: "DNS falsch, kein SSL", className: "text-warning" };
+  if (domain.misconfigured === null) return { label: "Verifiziert", className: "text-success" };
+  return { label: "Verifiziert · SSL aktiv", className: "text-success" };
+}
+
+interface PendingAction {
+  action: VercelAction;
+  project: VercelProject;
+  deployment: VercelDeployment;
+}
+
+interface ProjectDetailsProps {
+  secretId: string;
+  teamId: string | null;
+  project: VercelProject;
+  onAction: (action: VercelAction, deployment: VercelDeployment) => void;
+}
+
+function ProjectDetails({ secretId, teamId, project, onAction }: ProjectDetailsProps) {
+  const { data: deployments = [], isLoading } = useVercelDeployments(
+    secretId,
+    teamId,
+    project.id
+  );
+  const { data: domains = [], isLoading: domainsLoading } = useVercelDomains(
+    secretId,
+    teamId,
+    project.id
+  );
+
+  return (
+    <div className="mt-3 space-y-4 border-t border-border pt-3">
+      <div className="space-y-2">
+        <p className="text-sm font-medium">Deployments</p>
+        {isLoading ? (
+          <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />
+        ) : (
+          deployments.map((deployment) => (
+            <div key={deployment.id} className="flex items-center justify-between gap-4 text-sm">
+              <div className="flex min-w-0 items-center gap-2">
+                <div className={`h-2 w-2 shrink-0 rounded-full ${stateColor(deployment.state)}`} />
+                <span className="font-mono text-xs">{deployment.state}</span>
+                <span className="text-xs text-muted-foreground">
+                  {deployment.target === "production" ? "Production" : "Preview"}
+                </span>
+                {deployment.commitMessage && (
+                  <span className="truncate text-muted-foreground">
+                    {deployment.commitMessage}
+                  </span>
+                )}
+              </div>
+              <div className="flex shrink-0 items-center gap-1">
+                <span className="mr-2 text-xs text-muted-foreground">
+                  {deployment.branch && `${deployment.branch} · `}
+                  {deployment.createdAt && formatRelativeTime(new Date(deployment.createdAt))}
+                </span>
+                {IN_PROGRESS.includes(deployment.state) ? (
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="text-destructive hover:text-destructive"
+                    onClick={() => onAction("cancel", deployment)}
+                    title="Build abbrechen"
+                  >
+                    <XCircle className="h-4 w-4" />
+                  </Button>
+                ) : (
+                  <>
+                    {deployment.state === "READY" && deployment.target !== "production" && (
+                      <Button
+                        variant="ghost"
+                        size="icon"
+                        onClick={() => onAction("promote", deployment)}
+                        title="Zu Production promoten"
+                      >
+                        <ArrowUpCircle className="h-4 w-4" />
+                      </Button>
+                    )}
+                    <Button
+                      variant="ghost"
+                      size="icon"
+                      onClick={() => onAction("redeploy", deployment)}
+                      title="Redeploy"
+                    >
+                      <RefreshCw className="h-4 w-4" />
+                    </Button>
+                  </>
+                )}
+              </div>
+            </div>
+          ))
+        )}
+      </div>
+
+      <div className="space-y-2">
+        <p className="text-sm font-medium">Domains</p>
+        {domainsLoading ? (
+          <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />
+        ) : domains.length === 0 ? (
+          <p className="text-sm text-muted-foreground">Keine Domains.</p>
+        ) : (
+          domains.map((domain) => {
+            const status = domainStatus(domain);
+            return (
+              <div key={domain.name} className="flex items-center justify-between text-sm">
+                <div className="flex items-center gap-2">
+                  <Globe className="h-3 w-3 text-muted-foreground" />
+                  <span>{domain.name}</span>
+                  {domain.redirect && (
+                    <span className="text-xs text-muted-foreground">→ {domain.redirect}</span>
+                  )}
+                </div>
+                <span className={`text-xs ${status.className}`}>{status.label}</span>
+              </div>
+            );
+          })
+        )}
+      </div>
+    </div>
+  );
+}
+
+export function VercelPanel() {
+  const [secretId, setSecretId] = useState<string | null>(null);
+  const [teamId, setTeamId] = useState<string | null>(null);
+  const [openProject, setOpenProject] = useState<string | null>(null);
+  const [pending, setPending] = useState<PendingAction | null>(null);
+  const { data: teams = [] } = useVercelTeams(secretId);
+  const { data: projects = [], isLoading, error } = useVercelProjects(secretId, teamId);
+  const { data: usage } = useVercelUsage(secretId, teamId);
+  const actionMutation = useVercelAction();
+
+  const closeDialog = () => {
+    setPending(null);
+    actionMutation.reset();
+  };
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between gap-4">
+        <CardTitle className="flex items-center gap-2">
+          <Triangle className="h-5 w-5" />
+          Vercel
+        </CardTitle>
+        <div className="flex items-center gap-2">
+          {teams.length > 0 && (
+            <select
+              className={`${selectClassName} max-w-xs`}
+              value={teamId ?? ""}
+              onChange={(e) => {
+                setTeamId(e.target.value || null);
+                setOpenProject(null);
+              }}
+            >
+              <option value="">Persönlicher Account</option>
+              {teams.map((team) => (
+                <option key={team.id} value={team.id}>
+                  {team.name}
+                </option>
+              ))}
+            </select>
+          )}
+          <ServiceKeySelect
+            service="vercel"
+            value={secretId}
+            onChange={(id) => {
+              setSecretId(id);
+              setTeamId(null);
+            }}
+          />
+        </div>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        {usage && (
+          <div className="flex flex-wrap items-baseline gap-x-4 gap-y-1 rounded-lg bg-muted/50 p-3 text-sm">
+            <span className="font-medium">
+              {formatCurrency(usage.totalUsd)} diesen Monat
+            </span>
+            {usage.services.slice(0, 4).map((service) => (
+              <span key={service.service} className="text-muted-foreground">
+                {service.service}: {formatCurrency(service.costUsd)}
+              </span>
+            ))}
+          </div>
+        )}
+
+        {!secretId ? null : isLoading ? (
+          <div className="flex items-center justify-center py-8">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : error ? (
+          <p className="text-sm text-destructive">{String(error)}</p>
+        ) : projects.length === 0 ? (
+          <p className="py-8 text-center text-muted-foreground">Keine Projekte gefunden.</p>
+        ) : (
+          projects.map((project) => {
+            const latest = project.latestDeployment;
+            return (
+              <div key={project.id} className="rounded-lg border border-border p-3">
+                <div className="flex items-center justify-between gap-4">
+                  <button
+                    className="flex min-w-0 items-center gap-3 text-left"
+                    onClick={() => setOpenProject(openProject === project.id ? null : project.id)}
+                  >
+                    <div
+                      className={`h-2 w-2 shrink-0 rounded-full ${stateColor(latest?.state ?? "")}`}
+                    />
+                    <span className="font-medium">{project.name}</span>
+                    {project.framework && (
+                      <span className="text-xs text-muted-foreground">{project.framework}</span>
+                    )}
+                  </button>
+                  <div className="flex items-center gap-2 text-xs text-muted-foreground">
+                    {latest
+                      ? `${latest.state}${latest.createdAt ? ` · ${formatRelativeTime(new Date(latest.createdAt))}` : ""}`
+                      : "Kein Deployment"}
+                    {project.productionDomains[0] && (
+                      <a
+                        href={`https://${project.productionDomains[0]}`}
+                        target="_blank"
+                        rel="noreferrer"
+                        className="hover:text-foreground"
+                      >
+                        <ExternalLink className="h-3 w-3" />
+                      </a>
+                    )}
+                  </div>
+                </div>
+                {openProject === project.id && (
+                  <ProjectDetails
+                    secretId={secretId}
+                    teamId={teamId}
+                    project={project}
+                    onAction={(action, deployment) => setPending({ action, project, deployment })}
+                  />
+                )}
+              </div>
+            );
+          })
+        )}
+      </CardContent>
+
+      {pending && secretId && (
+        <ConfirmAction
+          title={actionTexts[pending.action].title}
+          description={
+            <>
+              Das Deployment von <strong>{pending.project.name}</strong>
+              {pending.deployment.commitMessage && <> („{pending.deployment.commitMessage}")</>}{" "}
+              {actionTexts[pending.action].text}.
+            </>
+          }
+          confirmLabel={actionTexts[pending.action].label}
+          destructive={pending.action === "cancel"}
+          pending={actionMutation.isPending}
+          error={actionMutation.error}
+          onConfirm={() =>
+            actionMutation.mutate(
+              {
+                action: pending.action,
+                secretId,
+                teamId,
+                project: pending.project,
+                deployment: pending.deployment,
+              },
+              { onSuccess: closeDialog }
+            )
+          }
+          onCancel={closeDialog}
+        />
+      )}
+    </Card>
+  );
+}
@@ -0,0 +1,49 @@
+import { useState } from "react";
+import { AlertTriangle } from "lucide-react";
+import { Button } from "@/components/ui/button";
+
+interface RecoveryPhraseProps {
+  words: string[];
+  onDone: () => void;
+}
+
+// Shows a freshly generated phrase once. The words are not stored anywhere,
+// so the user has to confirm they wrote them down.
+export function RecoveryPhrase({ words, onDone }: RecoveryPhraseProps) {
+  const [confirmed, setConfirmed] = useState(false);
+
+  return (
+    <div className="space-y-4">
+      <div className="flex gap-2 rounded-lg bg-warning/10 p-3 text-sm text-warning">
+        <AlertTriangle className="h-4 w-4 shrink-0" />
+        <p>
+          Notiere diese 24 Wörter in der richtigen Reihenfolge und bewahre sie offline auf.
+          Mit ihnen lässt sich der Vault ohne Master-Passwort öffnen. Sie werden nur jetzt
+          angezeigt.
+        </p>
+      </div>
+
+      <ol className="grid grid-cols-3 gap-2 rounded-lg border border-border bg-muted/30 p-3 font-mono text-sm">
+        {words.map((word, index) => (
+          <li key={index} className="flex gap-2">
+            <span className="w-5 text-right text-muted-foreground">{index + 1}.</span>
+            <span>{word}</span>
+          </li>
+        ))}
+      </ol>
+
+      <label className="flex items-center gap-2 text-sm">
+        <input
+          type="checkbox"
+          checked={confirmed}
+          onChange={(e) => setConfirmed(e.target.checked)}
+        />
+        Ich habe die Wörter sicher notiert
+      </label>
+
+      <Button className="w-full" onClick={onDone} disabled={!confirmed}>
+        Weiter
+      </Button>
+    </div>
+  );
+}
@@ -0,0 +1,168 @@
+import { useState } from "react";
+import { FileInput, Loader2, Upload } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { useImportExternalSecrets, usePreviewSecretImport } from "@/hooks/useSecrets";
+import {
+  IMPORT_SOURCE_LABELS,
+  type ImportPreviewEntry,
+  type ImportSource,
+  type Secret,
+} from "@/lib/secrets";
+
+const selectClassName =
+  "flex h-9 w-full rounded-md border border-input bg-background px-2 py-1 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+interface Row extends ImportPreviewEntry {
+  selected: boolean;
+}
+
+export function ExternalImport() {
+  const previewMutation = usePreviewSecretImport();
+  const importMutation = useImportExternalSecrets();
+  const [source, setSource] = useState<ImportSource>("env");
+  const [path, setPath] = useState<string | null>(null);
+  const [rows, setRows] = useState<Row[]>([]);
+
+  const handlePreview = () => {
+    importMutation.reset();
+    previewMutation.mutate(source, {
+      onSuccess: (preview) => {
+        if (!preview) return;
+        setPath(preview.path);
+        // Existing names are left out unless picked explicitly
+        setRows(preview.entries.map((entry) => ({ ...entry, selected: !entry.exists })));
+      },
+    });
+  };
+
+  const update = (index: number, changes: Partial<Row>) => {
+    setRows(rows.map((row) => (row.index === index ? { ...row, ...changes } : row)));
+  };
+
+  const handleImport = () => {
+    if (!path) return;
+    const selections = rows
+      .filter((row) => row.selected)
+      .map(({ index, name, provider, category }) => ({ index, name, provider, category }));
+    importMutation.mutate(
+      { path, source, selections },
+      {
+        onSuccess: () => {
+          setPath(null);
+          setRows([]);
+        },
+      }
+    );
+  };
+
+  const selectedCount = rows.filter((row) => row.selected).length;
+
+  return (
+    <Card>
+      <CardHeader>
+        <CardTitle className="flex items-center gap-2">
+          <FileInput className="h-5 w-5" />
+          Aus anderen Tools importieren
+        </CardTitle>
+      </CardHeader>
+      <CardContent className="space-y-4">
+        <div className="flex items-end gap-2">
+          <div className="flex-1 space-y-2">
+            <label className="text-sm font-medium">Quelle</label>
+            <select
+              className={selectClassName}
+              value={source}
+              onChange={(e) => {
+                setSource(e.target.value as ImportSource);
+                setPath(null);
+                setRows([]);
+              }}
+            >
+              {Object.entries(IMPORT_SOURCE_LABELS).map(([value, label]) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <Button variant="outline" onClick={handlePreview} disabled={previewMutation.isPending}>
+            {previewMutation.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+            Datei wählen
+          </Button>
+        </div>
+
+        {path && (
+          <>
+            <p className="truncate text-xs text-muted-foreground">{path}</p>
+            {rows.length === 0 ? (
+              <p className="text-sm text-muted-foreground">Keine Einträge gefunden.</p>
+            ) : (
+              <div className="max-h-80 space-y-2 overflow-y-auto">
+                {rows.map((row) => (
+                  <div
+                    key={row.index}
+                    className="grid grid-cols-[auto_1fr_1fr_1fr_auto] items-center gap-2 text-sm"
+                  >
+                    <input
+                      type="checkbox"
+                      checked={row.selected}
+                      onChange={(e) => update(row.index, { selected: e.target.checked })}
+                    />
+                    <Input
+                      className="h-9"
+                      value={row.name}
+                      onChange={(e) => update(row.index, { name: e.target.value })}
+                    />
+                    <Input
+                      className="h-9"
+                      value={row.provider}
+                      onChange={(e) => update(row.index, { provider: e.target.value })}
+                    />
+                    <select
+                      className={selectClassName}
+                      value={row.category}
+                      onChange={(e) =>
+                        update(row.index, { category: e.target.value as Secret["category"] })
+                      }
+                    >
+                      <option value="llm">LLM</option>
+                      <option value="infrastructure">Infrastruktur</option>
+                      <option value="app">Anwendung</option>
+                    </select>
+                    <span className="font-mono text-xs text-muted-foreground">
+                      {row.hint}
+                      {row.exists && <span className="ml-2 text-warning">existiert</span>}
+                    </span>
+                  </div>
+                ))}
+              </div>
+            )}
+
+            <Button
+              onClick={handleImport}
+              disabled={importMutation.isPending || selectedCount === 0}
+            >
+              {importMutation.isPending ? (
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+              ) : (
+                <Upload className="mr-2 h-4 w-4" />
+              )}
+              {selectedCount} Secrets importieren
+            </Button>
+          </>
+        )}
+
+        {importMutation.data !== undefined && (
+          <p className="text-sm text-success">{importMutation.data} Secrets importiert.</p>
+        )}
+        {(previewMutation.error || importMutation.error) && (
+          <p className="text-sm text-destructive">
+            {String(previewMutation.error ?? importMutation.error)}
+          </p>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
@@ -0,0 +1,80 @@
+import { ArrowRight, CalendarClock, Loader2 } from "lucide-react";
+import { Link } from "react-router-dom";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { useSecretAges } from "@/hooks/useSecrets";
+import { ROTATION_STATUS_LABELS } from "@/lib/secrets";
+import { formatDate } from "@/lib/utils";
+
+const statusColors = {
+  ok: "bg-success",
+  dueSoon: "bg-warning",
+  overdue: "bg-destructive",
+  expired: "bg-destructive",
+};
+
+// Secrets that are due, overdue or expired, plus long unused ones
+export function RotationDue() {
+  const { data: ages = [], isLoading } = useSecretAges();
+  const due = ages.filter((age) => age.status !== "ok");
+  const stale = ages.filter((age) => age.status === "ok" && age.stale);
+
+  return (
+    <Card>
+      <CardHeader className="flex flex-row items-center justify-between">
+        <CardTitle className="flex items-center gap-2">
+          <CalendarClock className="h-5 w-5" />
+          Rotation fällig
+        </CardTitle>
+        <Link to="/secrets">
+          <Button variant="ghost" size="sm">
+            Secrets
+            <ArrowRight className="ml-1 h-4 w-4" />
+          </Button>
+        </Link>
+      </CardHeader>
+      <CardContent>
+        {isLoading ? (
+          <div className="flex items-center justify-center py-8">
+            <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+          </div>
+        ) : due.length === 0 && stale.length === 0 ? (
+          <div className="py-8 text-center text-muted-foreground">
+            Alle Keys sind innerhalb ihrer Rotationsfrist.
+          </div>
+        ) : (
+          <div className="space-y-4">
+            {due.map((age) => (
+              <div key={age.id} className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                  <div className={`h-2 w-2 rounded-full ${statusColors[age.status]}`} />
+                  <span className="font-medium">{age.name}</span>
+                  <span className="text-sm text-muted-foreground">{age.provider}</span>
+                </div>
+                <span className="text-sm text-muted-foreground">
+                  {ROTATION_STATUS_LABELS[age.status]} · {age.ageDays} Tage
+                  {age.dueAt && age.status === "dueSoon" &&
+                    ` · bis ${formatDate(new Date(age.dueAt * 1000))}`}
+                </span>
+              </div>
+            ))}
+            {stale.map((age) => (
+              <div key={age.id} className="flex items-center justify-between">
+                <div className="flex items-center gap-3">
+                  <div className="h-2 w-2 rounded-full bg-muted-foreground" />
+                  <span className="font-medium">{age.name}</span>
+                  <span className="text-sm text-muted-foreground">{age.provider}</span>
+                </div>
+                <span className="text-sm text-muted-foreground">
+                  {age.unusedDays === null
+                    ? "Nie benutzt"
+                    : `Seit ${age.unusedDays} Tagen unbenutzt`}
+                </span>
+              </div>
+            ))}
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  );
+}
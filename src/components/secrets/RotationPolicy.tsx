@@ -0,0 +1,173 @@
+import { useEffect, useState } from "react";
+import { CalendarClock, Loader2 } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import {
+  useRotationPolicies,
+  useSecretAges,
+  useSetRotationPolicies,
+  useSetSecretPolicy,
+} from "@/hooks/useSecrets";
+import { ROTATION_STATUS_LABELS, type RotationPolicies, type Secret } from "@/lib/secrets";
+
+const categoryLabels: Record<Secret["category"], string> = {
+  llm: "LLM",
+  infrastructure: "Infrastruktur",
+  app: "Anwendung",
+};
+
+const statusColors = {
+  ok: "text-success",
+  dueSoon: "text-warning",
+  overdue: "text-destructive",
+  expired: "text-destructive",
+};
+
+// Empty input means "no reminder" / "no expiry"
+function parseDays(value: string): number | null {
+  const days = parseInt(value, 10);
+  return Number.isFinite(days) && days > 0 ? days : null;
+}
+
+function toDateInput(timestamp: number | null): string {
+  return timestamp ? new Date(timestamp * 1000).toISOString().slice(0, 10) : "";
+}
+
+function fromDateInput(value: string): number | null {
+  return value ? Math.floor(new Date(`${value}T00:00:00`).getTime() / 1000) : null;
+}
+
+// Maximum age per category, shown in the settings
+export function CategoryRotationPolicies() {
+  const { data: policies } = useRotationPolicies();
+  const saveMutation = useSetRotationPolicies();
+  const [form, setForm] = useState<Record<Secret["category"], string>>({
+    llm: "",
+    infrastructure: "",
+    app: "",
+  });
+
+  useEffect(() => {
+    if (!policies) return;
+    setForm({
+      llm: policies.llm?.toString() ?? "",
+      infrastructure: policies.infrastructure?.toString() ?? "",
+      app: policies.app?.toString() ?? "",
+    });
+  }, [policies]);
+
+  const handleSave = () => {
+    const next: RotationPolicies = {
+      llm: parseDays(form.llm),
+      infrastructure: parseDays(form.infrastructure),
+      app: parseDays(form.app),
+    };
+    saveMutation.mutate(next);
+  };
+
+  return (
+    <div className="space-y-3">
+      <div>
+        <p className="font-medium">Rotations-Erinnerungen</p>
+        <p className="text-sm text-muted-foreground">
+          Maximales Alter in Tagen je Kategorie. Leer lassen, um nicht zu erinnern.
+        </p>
+      </div>
+      <div className="grid grid-cols-3 gap-3">
+        {(Object.keys(categoryLabels) as Secret["category"][]).map((category) => (
+          <div key={category} className="space-y-1">
+            <label className="text-sm text-muted-foreground">{categoryLabels[category]}</label>
+            <Input
+              type="number"
+              min={1}
+              value={form[category]}
+              onChange={(e) => setForm({ ...form, [category]: e.target.value })}
+            />
+          </div>
+        ))}
+      </div>
+      <Button variant="outline" onClick={handleSave} disabled={saveMutation.isPending}>
+        {saveMutation.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+        Speichern
+      </Button>
+      {saveMutation.error && (
+        <p className="text-sm text-destructive">{String(saveMutation.error)}</p>
+      )}
+    </div>
+  );
+}
+
+interface SecretRotationPolicyProps {
+  secretId: string;
+}
+
+// Own maximum age and expiry date of a single secret
+export function SecretRotationPolicy({ secretId }: SecretRotationPolicyProps) {
+  const { data: ages = [] } = useSecretAges();
+  const saveMutation = useSetSecretPolicy();
+  const age = ages.find((a) => a.id === secretId);
+  const [maxAge, setMaxAge] = useState("");
+  const [expiry, setExpiry] = useState("");
+
+  useEffect(() => {
+    if (!age) return;
+    setMaxAge(age.customPolicy && age.maxAgeDays ? age.maxAgeDays.toString() : "");
+    setExpiry(toDateInput(age.expiresAt));
+  }, [age?.customPolicy, age?.maxAgeDays, age?.expiresAt]);
+
+  if (!age) return null;
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center gap-2 text-sm">
+        <CalendarClock className="h-4 w-4 text-muted-foreground" />
+        <span>{age.ageDays} Tage alt</span>
+        <span className={statusColors[age.status]}>· {ROTATION_STATUS_LABELS[age.status]}</span>
+        {age.stale && (
+          <span className="text-muted-foreground">
+            · {age.unusedDays === null ? "nie benutzt" : `seit ${age.unusedDays} Tagen unbenutzt`}
+          </span>
+        )}
+      </div>
+      <div className="flex items-end gap-2">
+        <div className="space-y-1">
+          <label className="text-xs text-muted-foreground">Max. Alter (Tage)</label>
+          <Input
+            type="number"
+            min={1}
+            className="h-9 w-36"
+            placeholder={age.customPolicy ? "" : `Kategorie: ${age.maxAgeDays ?? "–"}`}
+            value={maxAge}
+            onChange={(e) => setMaxAge(e.target.value)}
+          />
+        </div>
+        <div className="space-y-1">
+          <label className="text-xs text-muted-foreground">Läuft ab am</label>
+          <Input
+            type="date"
+            className="h-9 w-40"
+            value={expiry}
+            onChange={(e) => setExpiry(e.target.value)}
+          />
+        </div>
+        <Button
+          variant="outline"
+          size="sm"
+          onClick={() =>
+            saveMutation.mutate({
+              secretId,
+              maxAgeDays: parseDays(maxAge),
+              expiresAt: fromDateInput(expiry),
+            })
+          }
+          disabled={saveMutation.isPending}
+        >
+          Übernehmen
+        </Button>
+      </div>
+      {saveMutation.error && (
+        <p className="text-sm text-destructive">{String(saveMutation.error)}</p>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,54 @@
+import { Loader2, RotateCcw } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { useRevertSecret, useSecretVersions } from "@/hooks/useSecrets";
+import { formatDate } from "@/lib/utils";
+
+interface SecretHistoryProps {
+  secretId: string;
+}
+
+// Earlier values of a secret, masked, with a one-click revert
+export function SecretHistory({ secretId }: SecretHistoryProps) {
+  const { data: versions = [], isLoading } = useSecretVersions(secretId);
+  const revertMutation = useRevertSecret();
+
+  if (isLoading) {
+    return <Loader2 className="h-4 w-4 animate-spin text-muted-foreground" />;
+  }
+
+  if (versions.length === 0) {
+    return (
+      <p className="text-sm text-muted-foreground">
+        Noch keine früheren Versionen. Beim Ändern des Werts bleibt der alte erhalten.
+      </p>
+    );
+  }
+
+  return (
+    <div className="space-y-2">
+      {versions.map((version) => (
+        <div key={version.id} className="flex items-center justify-between text-sm">
+          <div className="flex items-center gap-4">
+            <span className="font-medium">Version {version.version}</span>
+            <code className="font-mono text-muted-foreground">{version.hint}</code>
+            <span className="text-xs text-muted-foreground">
+              ersetzt am {formatDate(new Date(version.retiredAt * 1000))}
+            </span>
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={() => revertMutation.mutate({ secretId, versionId: version.id })}
+            disabled={revertMutation.isPending}
+          >
+            <RotateCcw className="mr-2 h-3 w-3" />
+            Wiederherstellen
+          </Button>
+        </div>
+      ))}
+      {revertMutation.error && (
+        <p className="text-sm text-destructive">{String(revertMutation.error)}</p>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,200 @@
+import { useState } from "react";
+import { Download, Loader2, Upload } from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { useExportSecrets, useImportSecrets, useSecrets } from "@/hooks/useSecrets";
+import { CONFLICT_STRATEGY_LABELS, type ConflictStrategy } from "@/lib/secrets";
+
+const selectClassName =
+  "flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+const MIN_PASSPHRASE_LENGTH = 8;
+
+export function SecretTransfer() {
+  const { data: secrets = [] } = useSecrets();
+  const exportMutation = useExportSecrets();
+  const importMutation = useImportSecrets();
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [exportPassphrase, setExportPassphrase] = useState("");
+  const [confirmPassphrase, setConfirmPassphrase] = useState("");
+  const [importPassphrase, setImportPassphrase] = useState("");
+  const [conflict, setConflict] = useState<ConflictStrategy>("skip");
+
+  const toggle = (id: string) => {
+    const next = new Set(selected);
+    if (next.has(id)) {
+      next.delete(id);
+    } else {
+      next.add(id);
+    }
+    setSelected(next);
+  };
+
+  const allSelected = secrets.length > 0 && selected.size === secrets.length;
+  const passphraseError =
+    exportPassphrase.length > 0 && exportPassphrase.length < MIN_PASSPHRASE_LENGTH
+      ? `Mindestens ${MIN_PASSPHRASE_LENGTH} Zeichen`
+      : confirmPassphrase && confirmPassphrase !== exportPassphrase
+        ? "Passphrasen stimmen nicht überein"
+        : null;
+
+  const handleExport = () => {
+    exportMutation.mutate(
+      { ids: [...selected], passphrase: exportPassphrase },
+      {
+        onSuccess: (result) => {
+          if (result) {
+            setExportPassphrase("");
+            setConfirmPassphrase("");
+          }
+        },
+      }
+    );
+  };
+
+  const handleImport = () => {
+    importMutation.mutate(
+      { passphrase: importPassphrase, conflict },
+      {
+        onSuccess: (result) => {
+          if (result) setImportPassphrase("");
+        },
+      }
+    );
+  };
+
+  return (
+    <div className="grid gap-6 md:grid-cols-2">
+      <Card>
+        <CardHeader>
+          <CardTitle className="flex items-center gap-2">
+            <Download className="h-5 w-5" />
+            Exportieren
+          </CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="max-h-48 space-y-1 overflow-y-auto rounded-lg border border-border p-2">
+            <label className="flex items-center gap-2 border-b border-border pb-1 text-sm font-medium">
+              <input
+                type="checkbox"
+                checked={allSelected}
+                onChange={() =>
+                  setSelected(allSelected ? new Set() : new Set(secrets.map((s) => s.id)))
+                }
+              />
+              Alle auswählen
+            </label>
+            {secrets.map((secret) => (
+              <label key={secret.id} className="flex items-center gap-2 text-sm">
+                <input
+                  type="checkbox"
+                  checked={selected.has(secret.id)}
+                  onChange={() => toggle(secret.id)}
+                />
+                {secret.name}
+                <span className="text-muted-foreground">{secret.provider}</span>
+              </label>
+            ))}
+          </div>
+
+          <div className="grid gap-2 md:grid-cols-2">
+            <Input
+              type="password"
+              placeholder="Passphrase"
+              value={exportPassphrase}
+              onChange={(e) => setExportPassphrase(e.target.value)}
+            />
+            <Input
+              type="password"
+              placeholder="Passphrase bestätigen"
+              value={confirmPassphrase}
+              onChange={(e) => setConfirmPassphrase(e.target.value)}
+            />
+          </div>
+          {passphraseError && <p className="text-sm text-destructive">{passphraseError}</p>}
+
+          <Button
+            onClick={handleExport}
+            disabled={
+              exportMutation.isPending ||
+              selected.size === 0 ||
+              exportPassphrase.length < MIN_PASSPHRASE_LENGTH ||
+              confirmPassphrase !== exportPassphrase
+            }
+          >
+            {exportMutation.isPending ? (
+              <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+            ) : (
+              <Download className="mr-2 h-4 w-4" />
+            )}
+            {selected.size} Secrets exportieren
+          </Button>
+          {exportMutation.data && (
+            <p className="text-sm text-muted-foreground">
+              {exportMutation.data.count} Secrets gespeichert in {exportMutation.data.path}
+            </p>
+          )}
+          {exportMutation.error && (
+            <p className="text-sm text-destructive">{String(exportMutation.error)}</p>
+          )}
+        </CardContent>
+      </Card>
+
+      <Card>
+        <CardHeader>
+          <CardTitle className="flex items-center gap-2">
+            <Upload className="h-5 w-5" />
+            Importieren
+          </CardTitle>
+        </CardHeader>
+        <CardContent className="space-y-4">
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Passphrase des Exports</label>
+            <Input
+              type="password"
+              value={importPassphrase}
+              onChange={(e) => setImportPassphrase(e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <label className="text-sm font-medium">Bei gleichem Namen und Provider</label>
+            <select
+              className={selectClassName}
+              value={conflict}
+              onChange={(e) => setConflict(e.target.value as ConflictStrategy)}
+            >
+              {Object.entries(CONFLICT_STRATEGY_LABELS).map(([value, label]) => (
+                <option key={value} value={value}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </div>
+
+          <Button
+            variant="outline"
+            onClick={handleImport}
+            disabled={importMutation.isPending || !importPassphrase}
+          >
+            {importMutation.isPending ? (
+              <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+            ) : (
+              <Upload className="mr-2 h-4 w-4" />
+            )}
+            Datei wählen und importieren
+          </Button>
+          {importMutation.data && (
+            <p className="text-sm text-muted-foreground">
+              {importMutation.data.imported} importiert, {importMutation.data.overwritten}{" "}
+              überschrieben, {importMutation.data.skipped} übersprungen
+            </p>
+          )}
+          {importMutation.error && (
+            <p className="text-sm text-destructive">{String(importMutation.error)}</p>
+          )}
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
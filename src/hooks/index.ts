@@ -2,3 +2,6 @@ export * from "./useSecrets";
 export * from "./useSettings";
 export * from "./useOpenAI";
 export * from "./useAuditLog";
+export * from "./useAlerts";
+export * from "./useRecovery";
+export * from "./useInfrastructure";
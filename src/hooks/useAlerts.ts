@@ -0,0 +1,80 @@
+import { useEffect } from "react";
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { listen } from "@tauri-apps/api/event";
+import {
+  checkAlertsNow,
+  createAlertRule,
+  deleteAlertRule,
+  listAlertEvents,
+  listAlertRules,
+  sendTestNotification,
+  updateAlertRule,
+  ALERT_EVENT,
+  type AlertRuleInput,
+} from "@/lib/alerts";
+
+// Rules and events, refreshed whenever the scheduler records an event
+export function useAlertRules() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen(ALERT_EVENT, () => {
+      queryClient.invalidateQueries({ queryKey: ["alerts"] });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: ["alerts", "rules"],
+    queryFn: listAlertRules,
+  });
+}
+
+export function useAlertEvents(limit = 50) {
+  return useQuery({
+    queryKey: ["alerts", "events", limit],
+    queryFn: () => listAlertEvents(undefined, limit),
+  });
+}
+
+export function useSaveAlertRule() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, rule }: { id?: string; rule: AlertRuleInput }) =>
+      id ? updateAlertRule(id, rule) : createAlertRule(rule),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["alerts"] });
+    },
+  });
+}
+
+export function useDeleteAlertRule() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: deleteAlertRule,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["alerts"] });
+    },
+  });
+}
+
+export function useCheckAlerts() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: checkAlertsNow,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["alerts"] });
+    },
+  });
+}
+
+export function useTestNotification() {
+  return useMutation({
+    mutationFn: sendTestNotification,
+  });
+}
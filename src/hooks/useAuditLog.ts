@@ -1,5 +1,15 @@
-import { useQuery } from "@tanstack/react-query";
-import { getAuditLog, getAuditLogCount } from "@/lib/audit";
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import {
+  exportAuditLog,
+  getAuditLog,
+  getAuditLogCount,
+  getAuditSyslog,
+  setAuditSyslog,
+  testAuditSyslog,
+  verifyAuditChain,
+  type AuditExportFormat,
+  type AuditFilter,
+} from "@/lib/audit";
 
 export function useAuditLog(options?: {
   limit?: number;
@@ -26,3 +36,48 @@ export function useAuditLogCount(options?: {
     queryFn: () => getAuditLogCount(options),
   });
 }
+
+export function useVerifyAuditChain() {
+  return useMutation({
+    mutationFn: verifyAuditChain,
+  });
+}
+
+export function useExportAuditLog() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ format, filter }: { format: AuditExportFormat; filter: AuditFilter }) =>
+      exportAuditLog(format, filter),
+    onSuccess: () => {
+      // The export is audited itself
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLogCount"] });
+    },
+  });
+}
+
+export function useAuditSyslog() {
+  return useQuery({
+    queryKey: ["auditSyslog"],
+    queryFn: getAuditSyslog,
+  });
+}
+
+export function useSaveAuditSyslog() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: setAuditSyslog,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["auditSyslog"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useTestAuditSyslog() {
+  return useMutation({
+    mutationFn: testAuditSyslog,
+  });
+}
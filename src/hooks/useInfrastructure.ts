@@ -0,0 +1,277 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import {
+  listRailwayDeployments,
+  listRailwayProjects,
+  listServiceKeys,
+  redeployRailwayService,
+  listVercelTeams,
+  listVercelProjects,
+  listVercelDeployments,
+  listVercelDomains,
+  getVercelUsage,
+  runVercelAction,
+  getNeonOverview,
+  getNeonStorageHistory,
+  listSupabaseProjects,
+  getSupabaseProjectStats,
+  runSupabaseAction,
+  listCloudflareAccounts,
+  listCloudflareZones,
+  getCloudflareDns,
+  saveDnsSnapshot,
+  deleteDnsSnapshot,
+  listCloudflareWorkers,
+  type CloudflareZone,
+  type InfraService,
+  type RailwayServiceInstance,
+  type SupabaseAction,
+  type SupabaseProject,
+  type VercelAction,
+  type VercelDeployment,
+  type VercelProject,
+} from "@/lib/infrastructure";
+
+const REFRESH_INTERVAL = 60_000;
+
+export function useServiceKeys(service: InfraService) {
+  return useQuery({
+    queryKey: ["infrastructure", service, "keys"],
+    queryFn: () => listServiceKeys(service),
+  });
+}
+
+export function useRailwayProjects(secretId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "railway", "projects", secretId],
+    queryFn: () => listRailwayProjects(secretId!),
+    enabled: !!secretId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useRailwayDeployments(
+  secretId: string | null,
+  projectId: string,
+  instance: RailwayServiceInstance | null
+) {
+  return useQuery({
+    queryKey: [
+      "infrastructure",
+      "railway",
+      "deployments",
+      secretId,
+      instance?.environmentId,
+      instance?.serviceId,
+    ],
+    queryFn: () =>
+      listRailwayDeployments(secretId!, projectId, instance!.environmentId, instance!.serviceId),
+    enabled: !!secretId && !!instance,
+  });
+}
+
+export function useRedeployRailwayService() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      secretId,
+      instance,
+    }: {
+      secretId: string;
+      instance: RailwayServiceInstance;
+    }) => redeployRailwayService(secretId, instance),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["infrastructure", "railway"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useVercelTeams(secretId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "vercel", "teams", secretId],
+    queryFn: () => listVercelTeams(secretId!),
+    enabled: !!secretId,
+  });
+}
+
+export function useVercelProjects(secretId: string | null, teamId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "vercel", "projects", secretId, teamId],
+    queryFn: () => listVercelProjects(secretId!, teamId),
+    enabled: !!secretId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useVercelDeployments(
+  secretId: string | null,
+  teamId: string | null,
+  projectId: string | null
+) {
+  return useQuery({
+    queryKey: ["infrastructure", "vercel", "deployments", secretId, teamId, projectId],
+    queryFn: () => listVercelDeployments(secretId!, teamId, projectId!),
+    enabled: !!secretId && !!projectId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useVercelDomains(
+  secretId: string | null,
+  teamId: string | null,
+  projectId: string | null
+) {
+  return useQuery({
+    queryKey: ["infrastructure", "vercel", "domains", secretId, teamId, projectId],
+    queryFn: () => listVercelDomains(secretId!, teamId, projectId!),
+    enabled: !!secretId && !!projectId,
+  });
+}
+
+export function useVercelUsage(secretId: string | null, teamId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "vercel", "usage", secretId, teamId],
+    queryFn: () => getVercelUsage(secretId!, teamId),
+    enabled: !!secretId,
+  });
+}
+
+export function useVercelAction() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      action,
+      secretId,
+      teamId,
+      project,
+      deployment,
+    }: {
+      action: VercelAction;
+      secretId: string;
+      teamId: string | null;
+      project: VercelProject;
+      deployment: VercelDeployment;
+    }) => runVercelAction(action, secretId, teamId, project, deployment),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["infrastructure", "vercel"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useNeonOverview(secretId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "neondb", "overview", secretId],
+    queryFn: () => getNeonOverview(secretId!),
+    enabled: !!secretId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useNeonStorageHistory(secretId: string | null, days = 30) {
+  return useQuery({
+    queryKey: ["infrastructure", "neondb", "history", secretId, days],
+    queryFn: () => getNeonStorageHistory(secretId!, days),
+    enabled: !!secretId,
+  });
+}
+
+export function useSupabaseProjects(secretId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "supabase", "projects", secretId],
+    queryFn: () => listSupabaseProjects(secretId!),
+    enabled: !!secretId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useSupabaseProjectStats(secretId: string | null, projectRef: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "supabase", "stats", secretId, projectRef],
+    queryFn: () => getSupabaseProjectStats(secretId!, projectRef!),
+    enabled: !!secretId && !!projectRef,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useSupabaseAction() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      action,
+      secretId,
+      project,
+    }: {
+      action: SupabaseAction;
+      secretId: string;
+      project: SupabaseProject;
+    }) => runSupabaseAction(action, secretId, project),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["infrastructure", "supabase"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useCloudflareAccounts(secretId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "cloudflare", "accounts", secretId],
+    queryFn: () => listCloudflareAccounts(secretId!),
+    enabled: !!secretId,
+  });
+}
+
+export function useCloudflareZones(secretId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "cloudflare", "zones", secretId],
+    queryFn: () => listCloudflareZones(secretId!),
+    enabled: !!secretId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useCloudflareDns(secretId: string | null, zoneId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "cloudflare", "dns", secretId, zoneId],
+    queryFn: () => getCloudflareDns(secretId!, zoneId!),
+    enabled: !!secretId && !!zoneId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
+
+export function useSaveDnsSnapshot() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ secretId, zone }: { secretId: string; zone: CloudflareZone }) =>
+      saveDnsSnapshot(secretId, zone),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["infrastructure", "cloudflare"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useDeleteDnsSnapshot() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (zoneId: string) => deleteDnsSnapshot(zoneId),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["infrastructure", "cloudflare"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useCloudflareWorkers(secretId: string | null, accountId: string | null) {
+  return useQuery({
+    queryKey: ["infrastructure", "cloudflare", "workers", secretId, accountId],
+    queryFn: () => listCloudflareWorkers(secretId!, accountId!),
+    enabled: !!secretId && !!accountId,
+    refetchInterval: REFRESH_INTERVAL,
+  });
+}
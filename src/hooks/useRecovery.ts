@@ -0,0 +1,21 @@
+import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { getRecoveryStatus, generateRecoveryPhrase } from "@/lib/recovery";
+
+export function useRecoveryStatus() {
+  return useQuery({
+    queryKey: ["recovery"],
+    queryFn: getRecoveryStatus,
+  });
+}
+
+export function useGenerateRecoveryPhrase() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: generateRecoveryPhrase,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["recovery"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
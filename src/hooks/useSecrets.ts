@@ -1,4 +1,6 @@
+import { useEffect } from "react";
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
+import { listen } from "@tauri-apps/api/event";
 import {
   getAllSecrets,
   getSecretById,
@@ -6,7 +8,22 @@ import {
   updateSecret,
   deleteSecret,
   searchSecrets,
-  type Secret,
+  exportSecrets,
+  importSecrets,
+  previewSecretImport,
+  importExternalSecrets,
+  listSecretVersions,
+  revertSecret,
+  listSecretAges,
+  getRotationPolicies,
+  setRotationPolicies,
+  setSecretPolicy,
+  ROTATION_EVENT,
+  type ConflictStrategy,
+  type ImportSelection,
+  type ImportSource,
+  type RotationPolicies,
+  type SecretInput,
 } from "@/lib/secrets";
 
 export function useSecrets() {
@@ -36,9 +53,7 @@ export function useCreateSecret() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: (
-      data: Omit<Secret, "id" | "createdAt" | "rotatedAt" | "lastUsedAt">
-    ) => createSecret(data),
+    mutationFn: (data: SecretInput) => createSecret(data),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ["secrets"] });
     },
@@ -54,11 +69,12 @@ export function useUpdateSecret() {
       data,
     }: {
       id: string;
-      data: Partial<Pick<Secret, "name" | "category" | "provider" | "value">>;
+      data: Partial<SecretInput>;
     }) => updateSecret(id, data),
     onSuccess: (_, variables) => {
       queryClient.invalidateQueries({ queryKey: ["secrets"] });
       queryClient.invalidateQueries({ queryKey: ["secrets", variables.id] });
+      queryClient.invalidateQueries({ queryKey: ["secretVersions", variables.id] });
     },
   });
 }
@@ -73,3 +89,140 @@ export function useDeleteSecret() {
     },
   });
 }
+
+export function useExportSecrets() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ ids, passphrase }: { ids: string[]; passphrase: string }) =>
+      exportSecrets(ids, passphrase),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useImportSecrets() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      passphrase,
+      conflict,
+    }: {
+      passphrase: string;
+      conflict: ConflictStrategy;
+    }) => importSecrets(passphrase, conflict),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["secrets"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function usePreviewSecretImport() {
+  return useMutation({
+    mutationFn: (source: ImportSource) => previewSecretImport(source),
+  });
+}
+
+export function useImportExternalSecrets() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      path,
+      source,
+      selections,
+    }: {
+      path: string;
+      source: ImportSource;
+      selections: ImportSelection[];
+    }) => importExternalSecrets(path, source, selections),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["secrets"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useSecretVersions(secretId: string | null) {
+  return useQuery({
+    queryKey: ["secretVersions", secretId],
+    queryFn: () => listSecretVersions(secretId!),
+    enabled: !!secretId,
+  });
+}
+
+export function useRevertSecret() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ secretId, versionId }: { secretId: string; versionId: string }) =>
+      revertSecret(secretId, versionId),
+    onSuccess: (_, variables) => {
+      queryClient.invalidateQueries({ queryKey: ["secrets"] });
+      queryClient.invalidateQueries({ queryKey: ["secretVersions", variables.secretId] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+// Refreshed whenever the background check finds a secret due
+export function useSecretAges() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    const unlisten = listen(ROTATION_EVENT, () => {
+      queryClient.invalidateQueries({ queryKey: ["secrets", "ages"] });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [queryClient]);
+
+  return useQuery({
+    queryKey: ["secrets", "ages"],
+    queryFn: listSecretAges,
+  });
+}
+
+export function useRotationPolicies() {
+  return useQuery({
+    queryKey: ["rotationPolicies"],
+    queryFn: getRotationPolicies,
+  });
+}
+
+export function useSetRotationPolicies() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (policies: RotationPolicies) => setRotationPolicies(policies),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["rotationPolicies"] });
+      queryClient.invalidateQueries({ queryKey: ["secrets", "ages"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
+
+export function useSetSecretPolicy() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      secretId,
+      maxAgeDays,
+      expiresAt,
+    }: {
+      secretId: string;
+      maxAgeDays: number | null;
+      expiresAt: number | null;
+    }) => setSecretPolicy(secretId, maxAgeDays, expiresAt),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["secrets", "ages"] });
+      queryClient.invalidateQueries({ queryKey: ["auditLog"] });
+    },
+  });
+}
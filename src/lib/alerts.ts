@@ -0,0 +1,107 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { DnsService, LimitService } from "@/lib/infrastructure";
+import type { UsageProvider } from "@/lib/usage";
+
+// Alert rules are evaluated by the Rust scheduler every few minutes while the
+// app is unlocked. State changes land in alert_events and as notifications.
+
+export type AlertKind = "cost" | "quota" | "status" | "limit" | "dns";
+export type AlertPeriod = "day" | "week" | "month";
+export type AlertChannel = "system" | "email" | "telegram" | "discord" | "slack" | "webhook";
+export type AlertState = "ok" | "firing";
+
+export interface AlertRuleInput {
+  name: string;
+  kind: AlertKind;
+  // null watches all providers; limit and dns rules take a service like "neondb"
+  provider: UsageProvider | LimitService | DnsService | null;
+  // USD for cost, tokens for quota, percent for limit, unused for status and dns
+  threshold: number | null;
+  period: AlertPeriod;
+  channel: AlertChannel;
+  // Plain settings of the channel; credentials are referenced by secret id,
+  // e.g. { botTokenSecretId, chatId } for Telegram
+  channelConfig: Record<string, unknown> | null;
+  enabled: boolean;
+}
+
+export interface AlertRule extends AlertRuleInput {
+  id: string;
+  state: AlertState;
+  lastValue: number | null;
+  lastCheckedAt: number | null; // Unix seconds
+  createdAt: number;
+}
+
+export interface AlertEvent {
+  id: string;
+  alertId: string;
+  alertName: string | null;
+  kind: "firing" | "resolved";
+  value: number | null;
+  message: string;
+  createdAt: number; // Unix seconds
+}
+
+export const ALERT_EVENT = "alert-event";
+
+export const ALERT_KIND_LABELS: Record<AlertKind, string> = {
+  cost: "Kosten",
+  quota: "Tokens",
+  status: "Provider-Status",
+  limit: "Plan-Limit",
+  dns: "DNS-Änderungen",
+};
+
+export const ALERT_CHANNEL_LABELS: Record<AlertChannel, string> = {
+  system: "Desktop",
+  email: "E-Mail (SMTP)",
+  telegram: "Telegram",
+  discord: "Discord",
+  slack: "Slack",
+  webhook: "Webhook",
+};
+
+// Secret provider holding the credential of each channel
+export const CHANNEL_SECRET_PROVIDERS: Partial<Record<AlertChannel, string>> = {
+  email: "smtp",
+  telegram: "telegram",
+  discord: "discord",
+  slack: "slack",
+  webhook: "webhook",
+};
+
+export const ALERT_PERIOD_LABELS: Record<AlertPeriod, string> = {
+  day: "Heute",
+  week: "7 Tage",
+  month: "30 Tage",
+};
+
+export async function listAlertRules(): Promise<AlertRule[]> {
+  return invoke<AlertRule[]>("list_alert_rules");
+}
+
+export async function createAlertRule(rule: AlertRuleInput): Promise<AlertRule> {
+  return invoke<AlertRule>("create_alert_rule", { rule });
+}
+
+export async function updateAlertRule(id: string, rule: AlertRuleInput): Promise<AlertRule> {
+  return invoke<AlertRule>("update_alert_rule", { id, rule });
+}
+
+export async function deleteAlertRule(id: string): Promise<void> {
+  await invoke("delete_alert_rule", { id });
+}
+
+export async function listAlertEvents(alertId?: string, limit?: number): Promise<AlertEvent[]> {
+  return invoke<AlertEvent[]>("list_alert_events", { alertId, limit });
+}
+
+export async function checkAlertsNow(): Promise<{ events: AlertEvent[]; checkedAt: number }> {
+  return invoke("check_alerts_now");
+}
+
+// Sends a test message through the saved channel of a rule
+export async function sendTestNotification(id: string): Promise<void> {
+  await invoke("send_test_notification", { id });
+}
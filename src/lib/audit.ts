@@ -1,4 +1,8 @@
-import { getDatabase, generateId } from "./database";
+import { invoke } from "@tauri-apps/api/core";
+
+// Entries are written by the Rust backend, which chains every row to the
+// previous one with an HMAC-SHA256 keyed by the vault key. Rows inserted or
+// changed behind its back show up in verifyAuditChain().
 
 export interface AuditLogEntry {
   id: string;
@@ -12,20 +16,20 @@ export interface AuditLogEntry {
 interface AuditLogRow {
   id: string;
   action: string;
-  resource_type: string | null;
-  resource_id: string | null;
+  resourceType: string | null;
+  resourceId: string | null;
   details: string | null;
-  created_at: number;
+  createdAt: number;
 }
 
 function rowToEntry(row: AuditLogRow): AuditLogEntry {
   return {
     id: row.id,
     action: row.action,
-    resourceType: row.resource_type || undefined,
-    resourceId: row.resource_id || undefined,
+    resourceType: row.resourceType || undefined,
+    resourceId: row.resourceId || undefined,
     details: row.details ? JSON.parse(row.details) : undefined,
-    createdAt: new Date(row.created_at * 1000),
+    createdAt: new Date(row.createdAt * 1000),
   };
 }
 
@@ -36,26 +40,37 @@ export async function logAudit(
   details?: Record<string, unknown>
 ): Promise<void> {
   try {
-    const db = await getDatabase();
-    const id = generateId();
-
-    await db.execute(
-      `INSERT INTO audit_log (id, action, resource_type, resource_id, details)
-       VALUES (?, ?, ?, ?, ?)`,
-      [
-        id,
+    await invoke("log_audit", {
+      entry: {
         action,
-        resourceType || null,
-        resourceId || null,
-        details ? JSON.stringify(details) : null,
-      ]
-    );
+        resourceType: resourceType || null,
+        resourceId: resourceId || null,
+        details: details ?? null,
+      },
+    });
   } catch (error) {
     // Don't throw on audit failures - just log to console
     console.error("Failed to write audit log:", error);
   }
 }
 
+function toFilter(options?: {
+  action?: string;
+  resourceType?: string;
+  startDate?: Date;
+  endDate?: Date;
+}) {
+  const seconds = (date?: Date) => (date ? Math.floor(date.getTime() / 1000) : null);
+  return {
+    startDate: seconds(options?.startDate),
+    endDate: seconds(options?.endDate),
+    action: options?.action || null,
+    resourceType: options?.resourceType || null,
+    resourceId: null,
+  };
+}
+
+// Newest first
 export async function getAuditLog(options?: {
   limit?: number;
   offset?: number;
@@ -64,41 +79,11 @@ export async function getAuditLog(options?: {
   startDate?: Date;
   endDate?: Date;
 }): Promise<AuditLogEntry[]> {
-  const db = await getDatabase();
-
-  const conditions: string[] = [];
-  const values: (string | number)[] = [];
-
-  if (options?.action) {
-    conditions.push("action = ?");
-    values.push(options.action);
-  }
-  if (options?.resourceType) {
-    conditions.push("resource_type = ?");
-    values.push(options.resourceType);
-  }
-  if (options?.startDate) {
-    conditions.push("created_at >= ?");
-    values.push(Math.floor(options.startDate.getTime() / 1000));
-  }
-  if (options?.endDate) {
-    conditions.push("created_at <= ?");
-    values.push(Math.floor(options.endDate.getTime() / 1000));
-  }
-
-  const whereClause =
-    conditions.length > 0 ? `WHERE ${conditions.join(" AND ")}` : "";
-
-  const limit = options?.limit || 100;
-  const offset = options?.offset || 0;
-
-  const rows = await db.select<AuditLogRow[]>(
-    `SELECT * FROM audit_log ${whereClause}
-     ORDER BY created_at DESC
-     LIMIT ? OFFSET ?`,
-    [...values, limit, offset]
-  );
-
+  const rows = await invoke<AuditLogRow[]>("list_audit_log", {
+    filter: toFilter(options),
+    limit: options?.limit || 100,
+    offset: options?.offset || 0,
+  });
   return rows.map(rowToEntry);
 }
 
@@ -108,49 +93,86 @@ export async function getAuditLogCount(options?: {
   startDate?: Date;
   endDate?: Date;
 }): Promise<number> {
-  const db = await getDatabase();
+  return invoke<number>("count_audit_log", { filter: toFilter(options) });
+}
+
+// Leaves a signed checkpoint behind so the remaining chain still verifies.
+// Fails if the chain is already broken.
+export async function clearOldAuditLogs(daysToKeep: number = 90): Promise<number> {
+  return invoke<number>("prune_audit_log", { daysToKeep });
+}
 
-  const conditions: string[] = [];
-  const values: (string | number)[] = [];
+export type AuditBreakReason = "modified" | "missing" | "unchained" | "checkpoint";
+
+export interface AuditChainReport {
+  intact: boolean;
+  checked: number;
+  checkpoint: { seq: number; pruned: number; createdAt: number } | null;
+  firstBroken: {
+    seq: number | null;
+    id: string | null;
+    action: string | null;
+    createdAt: number | null; // Unix seconds
+    reason: AuditBreakReason;
+  } | null;
+}
 
-  if (options?.action) {
-    conditions.push("action = ?");
-    values.push(options.action);
-  }
-  if (options?.resourceType) {
-    conditions.push("resource_type = ?");
-    values.push(options.resourceType);
-  }
-  if (options?.startDate) {
-    conditions.push("created_at >= ?");
-    values.push(Math.floor(options.startDate.getTime() / 1000));
-  }
-  if (options?.endDate) {
-    conditions.push("created_at <= ?");
-    values.push(Math.floor(options.endDate.getTime() / 1000));
-  }
+export const AUDIT_BREAK_LABELS: Record<AuditBreakReason, string> = {
+  modified: "Eintrag wurde nachträglich verändert",
+  missing: "Vorherige Einträge fehlen oder wurden umsortiert",
+  unchained: "Eintrag wurde an der Hash-Kette vorbei eingefügt",
+  checkpoint: "Signatur des Löschpunkts ist ungültig",
+};
 
-  const whereClause =
-    conditions.length > 0 ? `WHERE ${conditions.join(" AND ")}` : "";
+export type AuditExportFormat = "jsonl" | "csv" | "cef";
 
-  const result = await db.select<{ count: number }[]>(
-    `SELECT COUNT(*) as count FROM audit_log ${whereClause}`,
-    values
-  );
+export const AUDIT_EXPORT_FORMAT_LABELS: Record<AuditExportFormat, string> = {
+  jsonl: "JSON Lines",
+  csv: "CSV",
+  cef: "CEF (SIEM)",
+};
 
-  return result[0]?.count || 0;
+export interface AuditFilter {
+  startDate?: Date;
+  endDate?: Date;
+  action?: string;
+  resourceType?: string;
+  resourceId?: string;
 }
 
-export async function clearOldAuditLogs(daysToKeep: number = 90): Promise<number> {
-  const db = await getDatabase();
-  const cutoff = Math.floor(Date.now() / 1000) - daysToKeep * 24 * 60 * 60;
+// Opens a save dialog in the backend; null if the user cancelled it
+export async function exportAuditLog(
+  format: AuditExportFormat,
+  filter: AuditFilter = {}
+): Promise<{ path: string; count: number } | null> {
+  return invoke("export_audit_log", {
+    format,
+    filter: { ...toFilter(filter), resourceId: filter.resourceId || null },
+  });
+}
+
+// New entries are forwarded as RFC 5424 messages with a CEF body
+export interface SyslogConfig {
+  enabled: boolean;
+  host: string;
+  port: number;
+  protocol: "udp" | "tcp";
+}
 
-  const result = await db.execute(
-    "DELETE FROM audit_log WHERE created_at < ?",
-    [cutoff]
-  );
+export async function getAuditSyslog(): Promise<SyslogConfig | null> {
+  return invoke<SyslogConfig | null>("get_audit_syslog");
+}
+
+export async function setAuditSyslog(config: SyslogConfig): Promise<void> {
+  await invoke("set_audit_syslog", { config });
+}
+
+export async function testAuditSyslog(config: SyslogConfig): Promise<void> {
+  await invoke("test_audit_syslog", { config });
+}
 
-  return result.rowsAffected;
+export async function verifyAuditChain(): Promise<AuditChainReport> {
+  return invoke<AuditChainReport>("verify_audit_chain");
 }
 
 // Pre-defined action types for consistency
@@ -164,11 +186,31 @@ export const AuditActions = {
   SECRET_UPDATED: "secret_updated",
   SECRET_DELETED: "secret_deleted",
   SECRET_ACCESSED: "secret_accessed",
+  SECRET_REVEALED: "secret_revealed",
   SECRET_COPIED: "secret_copied",
+  SECRET_ROTATED: "secret_rotated",
+  SECRET_REVERTED: "secret_reverted",
+  SECRETS_EXPORTED: "secrets_exported",
+  SECRETS_IMPORTED: "secrets_imported",
 
   // Settings
   SETTINGS_UPDATED: "settings_updated",
   DATA_PATH_CHANGED: "data_path_changed",
+  SCHEMA_MIGRATED: "schema_migrated",
+  AUDIT_PRUNED: "audit_pruned",
+
+  // Recovery
+  RECOVERY_PHRASE_CREATED: "recovery_phrase_created",
+  VAULT_RECOVERED: "vault_recovered",
+
+  // Infrastructure
+  DEPLOYMENT_REDEPLOYED: "deployment_redeployed",
+  DEPLOYMENT_PROMOTED: "deployment_promoted",
+  DEPLOYMENT_CANCELLED: "deployment_cancelled",
+  PROJECT_PAUSED: "project_paused",
+  PROJECT_RESTORED: "project_restored",
+  DNS_SNAPSHOT_SAVED: "dns_snapshot_saved",
+  DNS_SNAPSHOT_DELETED: "dns_snapshot_deleted",
 
   // API
   API_CALL: "api_call",
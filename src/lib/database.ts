@@ -1,78 +1,22 @@
-import Database from "@tauri-apps/plugin-sql";
+import { invoke } from "@tauri-apps/api/core";
 
-let db: Database | null = null;
-
-// Database schema
-const SCHEMA = `
--- App-Einstellungen
-CREATE TABLE IF NOT EXISTS settings (
-  key TEXT PRIMARY KEY,
-  value TEXT NOT NULL,
-  updated_at INTEGER DEFAULT (unixepoch())
-);
-
--- Gespeicherte API-Keys
-CREATE TABLE IF NOT EXISTS secrets (
-  id TEXT PRIMARY KEY,
-  name TEXT NOT NULL,
-  category TEXT NOT NULL,
-  provider TEXT NOT NULL,
-  value TEXT NOT NULL,
-  created_at INTEGER DEFAULT (unixepoch()),
-  rotated_at INTEGER,
-  last_used_at INTEGER
-);
-
--- Provider-Konfigurationen
-CREATE TABLE IF NOT EXISTS providers (
-  id TEXT PRIMARY KEY,
-  type TEXT NOT NULL,
-  name TEXT NOT NULL,
-  enabled INTEGER DEFAULT 1,
-  config TEXT,
-  secret_id TEXT REFERENCES secrets(id),
-  created_at INTEGER DEFAULT (unixepoch())
-);
-
--- Gecachte Usage-Daten
-CREATE TABLE IF NOT EXISTS usage_cache (
-  id TEXT PRIMARY KEY,
-  provider_id TEXT NOT NULL,
-  date TEXT NOT NULL,
-  data TEXT NOT NULL,
-  fetched_at INTEGER DEFAULT (unixepoch())
-);
-
--- Audit Log
-CREATE TABLE IF NOT EXISTS audit_log (
-  id TEXT PRIMARY KEY,
-  action TEXT NOT NULL,
-  resource_type TEXT,
-  resource_id TEXT,
-  details TEXT,
-  created_at INTEGER DEFAULT (unixepoch())
-);
+// Handle to the Rust-owned SQLCipher connection (see src-tauri/src/db). The
+// webview has no SQL access; data goes through the typed commands in lib/*.
+export class Database {
+  async close(): Promise<void> {
+    await invoke("close_database");
+  }
+}
 
--- Alert-Konfigurationen
-CREATE TABLE IF NOT EXISTS alerts (
-  id TEXT PRIMARY KEY,
-  name TEXT NOT NULL,
-  type TEXT,
-  provider_id TEXT,
-  threshold REAL,
-  channel TEXT,
-  channel_config TEXT,
-  enabled INTEGER DEFAULT 1,
-  created_at INTEGER DEFAULT (unixepoch())
-);
+let db: Database | null = null;
 
--- Indizes
-CREATE INDEX IF NOT EXISTS idx_secrets_category ON secrets(category);
-CREATE INDEX IF NOT EXISTS idx_secrets_provider ON secrets(provider);
-CREATE INDEX IF NOT EXISTS idx_usage_cache_provider_date ON usage_cache(provider_id, date);
-CREATE INDEX IF NOT EXISTS idx_audit_log_created ON audit_log(created_at);
-CREATE INDEX IF NOT EXISTS idx_audit_log_action ON audit_log(action);
-`;
+export interface DatabaseStatus {
+  path: string;
+  exists: boolean;
+  encrypted: boolean;
+  passwordSet: boolean;
+  unlocked: boolean;
+}
 
 export async function initializeDataDirectory(): Promise<string> {
   const dataPath = await getDataPath();
@@ -101,19 +45,13 @@ export async function getDatabase(): Promise<Database> {
 
   // Ensure directory exists first
   const dataPath = await initializeDataDirectory();
-  
-  // Use a simple path for SQLite
-  const dbPath = `sqlite:${dataPath}/panoptic.db`;
 
-  console.log("Opening database at:", dbPath);
-  
-  try {
-    db = await Database.load(dbPath);
+  console.log("Opening database at:", `${dataPath}/panoptic.db`);
 
-    // Initialize schema
-    await db.execute(SCHEMA);
-    
-    console.log("Database initialized successfully");
+  try {
+    // Reopens with the key of the current session - fails while locked
+    await invoke("open_database", { dataPath });
+    db = new Database();
   } catch (error) {
     console.error("Database error:", error);
     throw error;
@@ -122,6 +60,26 @@ export async function getDatabase(): Promise<Database> {
   return db;
 }
 
+// Status of the database file before unlocking (exists, encrypted, password set)
+export async function getDatabaseStatus(): Promise<DatabaseStatus> {
+  const dataPath = await initializeDataDirectory();
+  return invoke<DatabaseStatus>("database_status", { dataPath });
+}
+
+// Open (and on first use create or encrypt) the database with the master password
+export async function unlockDatabase(password: string): Promise<void> {
+  const dataPath = await initializeDataDirectory();
+  await invoke("unlock_database", { dataPath, password });
+  db = new Database();
+}
+
+// Lock the vault: the Rust backend drops the key, closes the connection and
+// emits "app-locked"
+export async function lockDatabase(): Promise<void> {
+  await invoke("lock_vault");
+  db = null;
+}
+
 export async function getDataPath(): Promise<string> {
   // Try to get from localStorage first (for quick access)
   const cachedPath = localStorage.getItem("panoptic_data_path");
@@ -168,13 +126,11 @@ export async function initializeDatabase(): Promise<{ success: boolean; error?:
   try {
     console.log("Initializing database...");
     
-    // This will create directory, db file, and tables if needed
-    const database = await getDatabase();
-    
-    // Verify tables exist by running a simple query
-    await database.select("SELECT COUNT(*) as count FROM settings");
+    // This will create the directory and inspect the db file - the database
+    // itself is opened by unlockDatabase() once the master password is known
+    const status = await getDatabaseStatus();
     
-    console.log("Database initialized successfully");
+    console.log("Database status:", status);
     return { success: true };
   } catch (error) {
     const errorMessage = error instanceof Error ? error.message : String(error);
@@ -202,39 +158,8 @@ export async function getDatabaseInfoAt(path: string): Promise<{
   settingsCount: number;
 }> {
   try {
-    const { exists } = await import("@tauri-apps/plugin-fs");
-    const dbFile = `${path}/panoptic.db`;
-    const dbExists = await exists(dbFile);
-    
-    if (!dbExists) {
-      return { path, exists: false, secretsCount: 0, settingsCount: 0 };
-    }
-    
-    // Open temporary connection to the target database
-    const Database = (await import("@tauri-apps/plugin-sql")).default;
-    const tempDb = await Database.load(`sqlite:${dbFile}`);
-    
-    try {
-      const secrets = await tempDb.select<{ count: number }[]>(
-        "SELECT COUNT(*) as count FROM secrets"
-      );
-      const settings = await tempDb.select<{ count: number }[]>(
-        "SELECT COUNT(*) as count FROM settings"
-      );
-      
-      await tempDb.close();
-      
-      return {
-        path,
-        exists: true,
-        secretsCount: secrets[0]?.count || 0,
-        settingsCount: settings[0]?.count || 0,
-      };
-    } catch (queryError) {
-      // Database exists but might not have tables yet
-      await tempDb.close();
-      return { path, exists: true, secretsCount: 0, settingsCount: 0 };
-    }
+    // Rust opens the target with the current key (or read-only if not yet encrypted)
+    return await invoke("database_info_at", { dataPath: path });
   } catch (error) {
     console.error("Error getting database info at path:", error);
     return { path, exists: false, secretsCount: 0, settingsCount: 0 };
@@ -256,20 +181,8 @@ export async function getDatabaseInfo(): Promise<{
       return { path, exists: false, secretsCount: 0, settingsCount: 0 };
     }
     
-    const database = await getDatabase();
-    const secrets = await database.select<{ count: number }[]>(
-      "SELECT COUNT(*) as count FROM secrets"
-    );
-    const settings = await database.select<{ count: number }[]>(
-      "SELECT COUNT(*) as count FROM settings"
-    );
-    
-    return {
-      path,
-      exists: true,
-      secretsCount: secrets[0]?.count || 0,
-      settingsCount: settings[0]?.count || 0,
-    };
+    await getDatabase();
+    return await invoke("database_info_at", { dataPath: path });
   } catch (error) {
     console.error("Error getting database info:", error);
     const path = await getDataPath();
@@ -281,6 +194,8 @@ export async function getDatabaseInfo(): Promise<{
 export async function switchToExistingDatabase(
   newPath: string
 ): Promise<{ success: boolean; error?: string }> {
+  const previousPath = await getDataPath();
+
   try {
     const { exists } = await import("@tauri-apps/plugin-fs");
     const dbFile = `${newPath}/panoptic.db`;
@@ -297,15 +212,17 @@ export async function switchToExistingDatabase(
     // Update the stored path
     localStorage.setItem("panoptic_data_path", newPath);
     
-    // Verify we can open the new database
-    const database = await getDatabase();
-    await database.select("SELECT 1");
+    // Verify we can open the new database (it must use the same master password)
+    await getDatabase();
     
     console.log("Switched to existing database at:", newPath);
     return { success: true };
   } catch (error) {
     const errorMessage = error instanceof Error ? error.message : String(error);
     console.error("Failed to switch database:", errorMessage);
+    // Fall back to the previous location so the app keeps working
+    localStorage.setItem("panoptic_data_path", previousPath);
+    db = null;
     return { success: false, error: errorMessage };
   }
 }
@@ -365,6 +282,12 @@ export async function migrateDatabase(
     if (await exists(shmFile)) {
       await copyFile(shmFile, `${newPath}/panoptic.db-shm`);
     }
+
+    // The sealed vault key for the recovery phrase belongs to this database
+    const recoveryFile = `${currentPath}/panoptic.recovery`;
+    if (await exists(recoveryFile)) {
+      await copyFile(recoveryFile, `${newPath}/panoptic.recovery`);
+    }
     
     // Update the stored path
     localStorage.setItem("panoptic_data_path", newPath);
@@ -0,0 +1,402 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// Infrastructure services (see src-tauri/src/infra). Like the LLM providers,
+// every call only passes the id of a stored token.
+export type InfraService = "railway" | "vercel" | "neondb" | "supabase" | "cloudflare";
+
+export const INFRA_SERVICE_LABELS: Record<InfraService, string> = {
+  railway: "Railway",
+  vercel: "Vercel",
+  neondb: "NeonDB",
+  supabase: "Supabase",
+  cloudflare: "Cloudflare",
+};
+
+// Services whose plan limits "limit" alert rules can watch
+export type LimitService = "neondb";
+
+export const LIMIT_SERVICE_LABELS: Record<LimitService, string> = {
+  neondb: "NeonDB",
+};
+
+// Services whose DNS "dns" alert rules compare against saved snapshots
+export type DnsService = "cloudflare";
+
+export const DNS_SERVICE_LABELS: Record<DnsService, string> = {
+  cloudflare: "Cloudflare",
+};
+
+export interface LimitUsage {
+  service: LimitService;
+  resource: string; // project, branch or "Konto"
+  metric: "storage" | "computeTime"; // bytes or seconds
+  used: number;
+  limit: number;
+}
+
+export interface ServiceKey {
+  id: string;
+  name: string;
+  provider: string;
+}
+
+export async function listServiceKeys(service: InfraService): Promise<ServiceKey[]> {
+  return invoke<ServiceKey[]>("list_service_keys", { service });
+}
+
+// Railway
+export interface RailwayDeployment {
+  id: string;
+  status: string; // SUCCESS, FAILED, CRASHED, BUILDING, DEPLOYING, ...
+  createdAt: string | null;
+  url: string | null;
+  commitMessage: string | null;
+  branch: string | null;
+}
+
+export interface RailwayServiceInstance {
+  serviceId: string;
+  serviceName: string;
+  icon: string | null;
+  environmentId: string;
+  environmentName: string;
+  latestDeployment: RailwayDeployment | null;
+}
+
+export interface RailwayProject {
+  id: string;
+  name: string;
+  description: string | null;
+  updatedAt: string | null;
+  environments: { id: string; name: string }[];
+  instances: RailwayServiceInstance[];
+}
+
+export async function listRailwayProjects(secretId: string): Promise<RailwayProject[]> {
+  return invoke<RailwayProject[]>("list_railway_projects", { secretId });
+}
+
+export async function listRailwayDeployments(
+  secretId: string,
+  projectId: string,
+  environmentId: string,
+  serviceId: string
+): Promise<RailwayDeployment[]> {
+  return invoke<RailwayDeployment[]>("list_railway_deployments", {
+    secretId,
+    projectId,
+    environmentId,
+    serviceId,
+  });
+}
+
+// Redeploys the latest deployment; written to the audit log
+export async function redeployRailwayService(
+  secretId: string,
+  instance: Pick<RailwayServiceInstance, "environmentId" | "serviceId" | "serviceName">
+): Promise<void> {
+  return invoke("redeploy_railway_service", {
+    secretId,
+    environmentId: instance.environmentId,
+    serviceId: instance.serviceId,
+    serviceName: instance.serviceName,
+  });
+}
+
+// Vercel. teamId null means the personal account of the token; timestamps
+// are milliseconds.
+export interface VercelTeam {
+  id: string;
+  name: string;
+  slug: string;
+}
+
+export interface VercelDeployment {
+  id: string;
+  url: string | null;
+  state: string; // READY, ERROR, BUILDING, QUEUED, INITIALIZING, CANCELED
+  target: string | null; // "production", null for previews
+  createdAt: number | null;
+  commitMessage: string | null;
+  branch: string | null;
+  creator: string | null;
+}
+
+export interface VercelProject {
+  id: string;
+  name: string;
+  framework: string | null;
+  updatedAt: number | null;
+  productionDomains: string[];
+  latestDeployment: VercelDeployment | null;
+}
+
+export interface VercelDomain {
+  name: string;
+  verified: boolean;
+  // true if DNS doesn't point to Vercel (no certificate), null if unchecked
+  misconfigured: boolean | null;
+  redirect: string | null;
+}
+
+export interface VercelUsage {
+  from: string;
+  to: string;
+  totalUsd: number;
+  services: { service: string; costUsd: number }[];
+}
+
+export async function listVercelTeams(secretId: string): Promise<VercelTeam[]> {
+  return invoke<VercelTeam[]>("list_vercel_teams", { secretId });
+}
+
+export async function listVercelProjects(
+  secretId: string,
+  teamId: string | null
+): Promise<VercelProject[]> {
+  return invoke<VercelProject[]>("list_vercel_projects", { secretId, teamId });
+}
+
+export async function listVercelDeployments(
+  secretId: string,
+  teamId: string | null,
+  projectId: string
+): Promise<VercelDeployment[]> {
+  return invoke<VercelDeployment[]>("list_vercel_deployments", { secretId, teamId, projectId });
+}
+
+export async function listVercelDomains(
+  secretId: string,
+  teamId: string | null,
+  projectId: string
+): Promise<VercelDomain[]> {
+  return invoke<VercelDomain[]>("list_vercel_domains", { secretId, teamId, projectId });
+}
+
+// null if the plan has no billing API
+export async function getVercelUsage(
+  secretId: string,
+  teamId: string | null
+): Promise<VercelUsage | null> {
+  return invoke<VercelUsage | null>("get_vercel_usage", { secretId, teamId });
+}
+
+export type VercelAction = "redeploy" | "promote" | "cancel";
+
+// All three are written to the audit log
+export async function runVercelAction(
+  action: VercelAction,
+  secretId: string,
+  teamId: string | null,
+  project: Pick<VercelProject, "id" | "name">,
+  deployment: Pick<VercelDeployment, "id" | "target">
+): Promise<void> {
+  const args = { secretId, teamId, deploymentId: deployment.id, projectName: project.name };
+  switch (action) {
+    case "redeploy":
+      await invoke("redeploy_vercel_deployment", { ...args, target: deployment.target });
+      break;
+    case "promote":
+      await invoke("promote_vercel_deployment", { ...args, projectId: project.id });
+      break;
+    case "cancel":
+      await invoke("cancel_vercel_deployment", args);
+      break;
+  }
+}
+
+// NeonDB. Consumption is counted within the current billing period; every
+// overview request also stores a daily snapshot for the storage history.
+export interface NeonBranch {
+  id: string;
+  name: string;
+  default: boolean;
+  state: string | null; // ready, init
+  logicalSizeBytes: number | null;
+  updatedAt: string | null;
+}
+
+export interface NeonEndpoint {
+  id: string;
+  branchId: string;
+  kind: string; // read_write, read_only
+  state: string; // active, idle, init
+  suspended: boolean;
+  host: string | null;
+  minCu: number | null;
+  maxCu: number | null;
+  lastActive: string | null;
+}
+
+export interface NeonProject {
+  id: string;
+  name: string;
+  region: string | null;
+  pgVersion: number | null;
+  storageBytes: number;
+  branchSizeLimitBytes: number | null;
+  computeSeconds: number;
+  computeLimitSeconds: number | null;
+  activeSeconds: number;
+  writtenBytes: number;
+  transferBytes: number;
+  periodEnd: string | null;
+  branches: NeonBranch[];
+  endpoints: NeonEndpoint[];
+}
+
+export interface NeonPlan {
+  name: string | null;
+  projectsLimit: number | null;
+  branchesLimit: number | null;
+  activeSecondsLimit: number | null;
+}
+
+export interface NeonOverview {
+  plan: NeonPlan;
+  projects: NeonProject[];
+  limits: LimitUsage[];
+}
+
+export interface NeonHistoryDay {
+  date: string;
+  projects: { id: string; name: string; storageBytes: number; computeSeconds: number }[];
+}
+
+export async function getNeonOverview(secretId: string): Promise<NeonOverview> {
+  return invoke<NeonOverview>("get_neon_overview", { secretId });
+}
+
+export async function getNeonStorageHistory(
+  secretId: string,
+  days: number
+): Promise<NeonHistoryDay[]> {
+  return invoke<NeonHistoryDay[]>("get_neon_storage_history", { secretId, days });
+}
+
+// Supabase. A project is addressed by its ref, which is its id.
+export interface SupabaseProject {
+  id: string;
+  name: string;
+  organizationId: string | null;
+  region: string | null;
+  // ACTIVE_HEALTHY, ACTIVE_UNHEALTHY, COMING_UP, INACTIVE (paused), PAUSING, RESTORING, ...
+  status: string;
+  createdAt: string | null;
+  dbHost: string | null;
+  dbVersion: string | null;
+}
+
+export interface SupabaseServiceHealth {
+  name: string; // auth, db, rest, storage, realtime
+  healthy: boolean;
+  status: string;
+  error: string | null;
+}
+
+export interface SupabaseProjectStats {
+  services: SupabaseServiceHealth[];
+  dbSizeBytes: number | null; // null if the token may not run queries
+  authUsers: number | null;
+}
+
+export async function listSupabaseProjects(secretId: string): Promise<SupabaseProject[]> {
+  return invoke<SupabaseProject[]>("list_supabase_projects", { secretId });
+}
+
+export async function getSupabaseProjectStats(
+  secretId: string,
+  projectRef: string
+): Promise<SupabaseProjectStats> {
+  return invoke<SupabaseProjectStats>("get_supabase_project_stats", { secretId, projectRef });
+}
+
+export type SupabaseAction = "pause" | "restore";
+
+// Both are written to the audit log
+export async function runSupabaseAction(
+  action: SupabaseAction,
+  secretId: string,
+  project: Pick<SupabaseProject, "id" | "name">
+): Promise<void> {
+  const command = action === "pause" ? "pause_supabase_project" : "restore_supabase_project";
+  await invoke(command, { secretId, projectRef: project.id, projectName: project.name });
+}
+
+// Cloudflare. Workers counts cover the last 24 hours.
+export interface CloudflareAccount {
+  id: string;
+  name: string;
+}
+
+export interface CloudflareZone {
+  id: string;
+  name: string;
+  status: string; // active, pending, initializing, moved, ...
+  paused: boolean;
+  plan: string | null;
+  accountId: string | null;
+  snapshotAt: number | null; // Unix seconds, null without a DNS snapshot
+}
+
+export interface DnsRecord {
+  id: string;
+  recordType: string;
+  name: string;
+  content: string;
+  proxied: boolean;
+  ttl: number; // 1 is automatic
+  priority: number | null;
+  comment: string | null;
+}
+
+export interface DnsChange {
+  kind: "added" | "removed" | "changed";
+  before: DnsRecord | null;
+  after: DnsRecord | null;
+}
+
+export interface DnsOverview {
+  records: DnsRecord[];
+  snapshotAt: number | null;
+  changes: DnsChange[]; // empty without a snapshot
+}
+
+export interface CloudflareWorker {
+  name: string;
+  createdOn: string | null;
+  modifiedOn: string | null;
+  requests: number | null; // null if the token may not read analytics
+  errors: number | null;
+}
+
+export async function listCloudflareAccounts(secretId: string): Promise<CloudflareAccount[]> {
+  return invoke<CloudflareAccount[]>("list_cloudflare_accounts", { secretId });
+}
+
+export async function listCloudflareZones(secretId: string): Promise<CloudflareZone[]> {
+  return invoke<CloudflareZone[]>("list_cloudflare_zones", { secretId });
+}
+
+export async function getCloudflareDns(secretId: string, zoneId: string): Promise<DnsOverview> {
+  return invoke<DnsOverview>("get_cloudflare_dns", { secretId, zoneId });
+}
+
+// Accepts the current records; later changes are flagged against them
+export async function saveDnsSnapshot(
+  secretId: string,
+  zone: Pick<CloudflareZone, "id" | "name">
+): Promise<number> {
+  return invoke<number>("save_dns_snapshot", { secretId, zoneId: zone.id, zoneName: zone.name });
+}
+
+export async function deleteDnsSnapshot(zoneId: string): Promise<void> {
+  return invoke("delete_dns_snapshot", { zoneId });
+}
+
+export async function listCloudflareWorkers(
+  secretId: string,
+  accountId: string
+): Promise<CloudflareWorker[]> {
+  return invoke<CloudflareWorker[]>("list_cloudflare_workers", { secretId, accountId });
+}
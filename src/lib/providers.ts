@@ -0,0 +1,54 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// A stored provider key without its value. The value stays in the Rust
+// backend, which attaches it to requests made through providerRequest().
+export interface ProviderKey {
+  id: string;
+  name: string;
+  category: string;
+  provider: string;
+  keyKind: string; // e.g. "admin", "project", "api_key", "unknown"
+}
+
+export interface ProviderResponse<T> {
+  ok: boolean;
+  status: number;
+  body: T; // Parsed JSON, or the raw text if the response is not JSON
+}
+
+type QueryValue = string | number | undefined;
+
+export async function listProviderKeys(provider: string): Promise<ProviderKey[]> {
+  return invoke<ProviderKey[]>("list_provider_keys", { provider });
+}
+
+// Authenticated request to the provider of the given secret. Only the path is
+// passed; the backend resolves the host and the auth header (Bearer,
+// x-api-key or ?key=).
+export async function providerRequest<T = any>(
+  secretId: string,
+  method: string,
+  path: string,
+  query: Record<string, QueryValue> = {}
+): Promise<ProviderResponse<T>> {
+  const params: Record<string, string> = {};
+  for (const [key, value] of Object.entries(query)) {
+    if (value !== undefined) params[key] = String(value);
+  }
+
+  const response = await invoke<{ status: number; body: T }>("provider_request", {
+    secretId,
+    method,
+    path,
+    query: params,
+  });
+  return {
+    ...response,
+    ok: response.status >= 200 && response.status < 300,
+  };
+}
+
+// Short error text for logs and diagnosis results
+export function responseText(body: unknown): string {
+  return typeof body === "string" ? body : JSON.stringify(body);
+}
@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+import { initializeDataDirectory } from "./database";
+
+// Recovery phrase for a forgotten master password (see src-tauri/src/recovery.rs).
+// The backend only keeps the vault key sealed to the phrase in
+// panoptic.recovery next to the database.
+
+export interface RecoveryStatus {
+  configured: boolean;
+  createdAt: number | null;
+}
+
+export async function getRecoveryStatus(): Promise<RecoveryStatus> {
+  const dataPath = await initializeDataDirectory();
+  return invoke<RecoveryStatus>("recovery_status", { dataPath });
+}
+
+// Returns the 24 words. They are shown once and never stored; a previous
+// phrase stops working.
+export async function generateRecoveryPhrase(): Promise<string[]> {
+  return invoke<string[]>("generate_recovery_phrase");
+}
+
+// Unlocks the vault with the phrase and replaces the master password
+export async function recoverWithPhrase(phrase: string, newPassword: string): Promise<void> {
+  const dataPath = await initializeDataDirectory();
+  await invoke("recover_with_phrase", { dataPath, phrase, newPassword });
+}
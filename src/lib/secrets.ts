@@ -1,12 +1,14 @@
-import { getDatabase, generateId } from "./database";
+import { invoke } from "@tauri-apps/api/core";
 import { logAudit } from "./audit";
 
+// The value never leaves the backend unasked: hint is masked (prefix and
+// last four characters), revealSecret() returns the plaintext
 export interface Secret {
   id: string;
   name: string;
   category: "llm" | "infrastructure" | "app";
   provider: string;
-  value: string;
+  hint: string;
   createdAt: Date;
   rotatedAt?: Date;
   lastUsedAt?: Date;
@@ -17,7 +19,7 @@ interface SecretRow {
   name: string;
   category: string;
   provider: string;
-  value: string;
+  hint: string;
   created_at: number;
   rotated_at: number | null;
   last_used_at: number | null;
@@ -29,158 +31,257 @@ function rowToSecret(row: SecretRow): Secret {
     name: row.name,
     category: row.category as Secret["category"],
     provider: row.provider,
-    value: row.value,
+    hint: row.hint,
     createdAt: new Date(row.created_at * 1000),
     rotatedAt: row.rotated_at ? new Date(row.rotated_at * 1000) : undefined,
     lastUsedAt: row.last_used_at ? new Date(row.last_used_at * 1000) : undefined,
   };
 }
 
-export async function getAllSecrets(): Promise<Secret[]> {
-  const db = await getDatabase();
-  const rows = await db.select<SecretRow[]>(
-    "SELECT * FROM secrets ORDER BY created_at DESC"
-  );
+// Secrets are read through dedicated commands that refuse to run while the
+// vault is locked
+interface SecretFilter {
+  category?: Secret["category"];
+  provider?: string;
+  query?: string;
+}
+
+async function listSecrets(filter: SecretFilter = {}): Promise<Secret[]> {
+  const rows = await invoke<SecretRow[]>("list_secrets", { ...filter });
   return rows.map(rowToSecret);
 }
 
-export async function getSecretById(id: string): Promise<Secret | null> {
-  const db = await getDatabase();
-  const rows = await db.select<SecretRow[]>(
-    "SELECT * FROM secrets WHERE id = ?",
-    [id]
-  );
-  if (rows.length === 0) return null;
+export async function getAllSecrets(): Promise<Secret[]> {
+  return listSecrets();
+}
 
-  // Update last_used_at
-  await db.execute(
-    "UPDATE secrets SET last_used_at = unixepoch() WHERE id = ?",
-    [id]
-  );
+export async function getSecretById(id: string): Promise<Secret | null> {
+  // Also updates last_used_at
+  const row = await invoke<SecretRow | null>("get_secret", { id });
+  if (!row) return null;
 
-  await logAudit("secret_accessed", "secret", id, { name: rows[0].name });
-  return rowToSecret(rows[0]);
+  await logAudit("secret_accessed", "secret", id, { name: row.name });
+  return rowToSecret(row);
 }
 
 export async function getSecretsByCategory(
   category: Secret["category"]
 ): Promise<Secret[]> {
-  const db = await getDatabase();
-  const rows = await db.select<SecretRow[]>(
-    "SELECT * FROM secrets WHERE category = ? ORDER BY created_at DESC",
-    [category]
-  );
-  return rows.map(rowToSecret);
+  return listSecrets({ category });
 }
 
 export async function getSecretsByProvider(provider: string): Promise<Secret[]> {
-  const db = await getDatabase();
-  // Case-insensitive search using LOWER()
-  const rows = await db.select<SecretRow[]>(
-    "SELECT * FROM secrets WHERE LOWER(provider) = LOWER(?) ORDER BY created_at DESC",
-    [provider]
-  );
-  return rows.map(rowToSecret);
+  // Case-insensitive match in the backend
+  return listSecrets({ provider });
+}
+
+// Audited on every call
+export async function revealSecret(id: string): Promise<string> {
+  return invoke<string>("reveal_secret", { id });
+}
+
+// Copies the value in Rust, so it never passes through the webview. Returns
+// the seconds until the clipboard is cleared.
+export async function copySecretToClipboard(id: string, ttl?: number): Promise<number> {
+  return invoke<number>("copy_secret_to_clipboard", { secretId: id, ttl });
+}
+
+// Encrypted bundles for moving secrets between machines (see
+// src-tauri/src/secrets/bundle.rs). The file dialogs open in Rust; both
+// resolve to null when the dialog is cancelled.
+export type ConflictStrategy = "skip" | "overwrite" | "keepBoth";
+
+export const CONFLICT_STRATEGY_LABELS: Record<ConflictStrategy, string> = {
+  skip: "Überspringen",
+  overwrite: "Überschreiben",
+  keepBoth: "Beide behalten",
+};
+
+export interface SecretBundleExport {
+  path: string;
+  count: number;
+}
+
+export interface SecretImportSummary {
+  imported: number;
+  overwritten: number;
+  skipped: number;
+}
+
+export async function exportSecrets(
+  ids: string[],
+  passphrase: string
+): Promise<SecretBundleExport | null> {
+  return invoke<SecretBundleExport | null>("export_secrets", { ids, passphrase });
+}
+
+export async function importSecrets(
+  passphrase: string,
+  conflict: ConflictStrategy
+): Promise<SecretImportSummary | null> {
+  return invoke<SecretImportSummary | null>("import_secrets", { passphrase, conflict });
+}
+
+// Import from other tools (see src-tauri/src/secrets/import.rs). The preview
+// only contains masked hints; the values stay in Rust.
+export type ImportSource = "env" | "onePassword" | "bitwarden";
+
+export const IMPORT_SOURCE_LABELS: Record<ImportSource, string> = {
+  env: ".env-Datei",
+  onePassword: "1Password (CSV)",
+  bitwarden: "Bitwarden (JSON, unverschlüsselt)",
+};
+
+export interface ImportPreviewEntry {
+  index: number;
+  name: string;
+  provider: string;
+  category: Secret["category"];
+  hint: string;
+  exists: boolean;
+}
+
+export interface ImportPreview {
+  path: string;
+  entries: ImportPreviewEntry[];
+}
+
+export type ImportSelection = Pick<ImportPreviewEntry, "index" | "name" | "provider" | "category">;
+
+export async function previewSecretImport(source: ImportSource): Promise<ImportPreview | null> {
+  return invoke<ImportPreview | null>("preview_secret_import", { source });
 }
 
-export async function createSecret(
-  data: Omit<Secret, "id" | "createdAt" | "rotatedAt" | "lastUsedAt">
-): Promise<Secret> {
-  const db = await getDatabase();
-  const id = generateId();
-  
-  // Normalize provider to lowercase for consistency
-  const normalizedProvider = data.provider.toLowerCase();
+// Returns the number of imported secrets
+export async function importExternalSecrets(
+  path: string,
+  source: ImportSource,
+  selections: ImportSelection[]
+): Promise<number> {
+  return invoke<number>("import_external_secrets", { path, source, selections });
+}
 
-  await db.execute(
-    `INSERT INTO secrets (id, name, category, provider, value) 
-     VALUES (?, ?, ?, ?, ?)`,
-    [id, data.name, data.category, normalizedProvider, data.value]
-  );
+export interface SecretInput {
+  name: string;
+  category: Secret["category"];
+  provider: string;
+  value: string;
+}
 
-  await logAudit("secret_created", "secret", id, {
+// Writes are validated and audited in the backend
+export async function createSecret(data: SecretInput): Promise<Secret> {
+  const row = await invoke<SecretRow>("create_secret", {
     name: data.name,
     category: data.category,
     provider: data.provider,
+    value: data.value,
   });
+  return rowToSecret(row);
+}
 
-  const created = await getSecretById(id);
-  if (!created) throw new Error("Failed to create secret");
-  return created;
-}
-
-export async function updateSecret(
-  id: string,
-  data: Partial<Pick<Secret, "name" | "category" | "provider" | "value">>
-): Promise<Secret> {
-  const db = await getDatabase();
-
-  const updates: string[] = [];
-  const values: (string | number)[] = [];
-
-  if (data.name !== undefined) {
-    updates.push("name = ?");
-    values.push(data.name);
-  }
-  if (data.category !== undefined) {
-    updates.push("category = ?");
-    values.push(data.category);
-  }
-  if (data.provider !== undefined) {
-    updates.push("provider = ?");
-    values.push(data.provider.toLowerCase()); // Normalize to lowercase
-  }
-  if (data.value !== undefined) {
-    updates.push("value = ?");
-    updates.push("rotated_at = unixepoch()");
-    values.push(data.value);
-  }
-
-  if (updates.length === 0) {
-    const existing = await getSecretById(id);
-    if (!existing) throw new Error("Secret not found");
-    return existing;
-  }
-
-  values.push(id);
-  await db.execute(
-    `UPDATE secrets SET ${updates.join(", ")} WHERE id = ?`,
-    values
-  );
-
-  await logAudit("secret_updated", "secret", id, { fields: Object.keys(data) });
-
-  const updated = await getSecretById(id);
-  if (!updated) throw new Error("Secret not found");
-  return updated;
+// A new value is a rotation: the backend checks it with the provider and
+// keeps the old one as a version. An empty or unchanged value is ignored.
+export async function updateSecret(id: string, data: Partial<SecretInput>): Promise<Secret> {
+  const row = await invoke<SecretRow>("update_secret", {
+    id,
+    name: data.name ?? null,
+    category: data.category ?? null,
+    provider: data.provider ?? null,
+    value: data.value || null,
+  });
+  return rowToSecret(row);
 }
 
-export async function deleteSecret(id: string): Promise<void> {
-  const db = await getDatabase();
+// Rotation with version history (see src-tauri/src/secrets/versions.rs)
+export interface SecretVersion {
+  id: string;
+  version: number;
+  hint: string;
+  createdAt: number | null;
+  retiredAt: number;
+}
 
-  // Get secret info for audit log before deletion
-  const rows = await db.select<SecretRow[]>(
-    "SELECT * FROM secrets WHERE id = ?",
-    [id]
-  );
+export interface RotationResult {
+  version: number;
+  // Diagnosis of the new key, null if the provider can't be checked
+  validation: { valid: boolean; error: string | null } | null;
+}
 
-  if (rows.length > 0) {
-    await db.execute("DELETE FROM secrets WHERE id = ?", [id]);
-    await logAudit("secret_deleted", "secret", id, {
-      name: rows[0].name,
-      provider: rows[0].provider,
-    });
-  }
+export async function listSecretVersions(secretId: string): Promise<SecretVersion[]> {
+  return invoke<SecretVersion[]>("list_secret_versions", { secretId });
+}
+
+// Fails without changing anything if the provider rejects the new key
+export async function rotateSecret(
+  secretId: string,
+  value: string,
+  skipValidation = false
+): Promise<RotationResult> {
+  return invoke<RotationResult>("rotate_secret", { secretId, value, skipValidation });
+}
+
+export async function revertSecret(secretId: string, versionId: string): Promise<void> {
+  await invoke("revert_secret", { secretId, versionId });
+}
+
+export async function deleteSecret(id: string): Promise<void> {
+  await invoke("delete_secret", { id });
 }
 
 export async function searchSecrets(query: string): Promise<Secret[]> {
-  const db = await getDatabase();
-  const searchTerm = `%${query.toLowerCase()}%`;
-  const rows = await db.select<SecretRow[]>(
-    `SELECT * FROM secrets 
-     WHERE LOWER(name) LIKE ? OR LOWER(provider) LIKE ?
-     ORDER BY created_at DESC`,
-    [searchTerm, searchTerm]
-  );
-  return rows.map(rowToSecret);
+  return listSecrets({ query });
+}
+
+// Rotation reminders (see src-tauri/src/secrets/policies.rs). The maximum age
+// per category can be overridden per secret; expiresAt is a hard deadline.
+export const ROTATION_EVENT = "secret-rotation";
+
+export type RotationStatus = "ok" | "dueSoon" | "overdue" | "expired";
+
+export const ROTATION_STATUS_LABELS: Record<RotationStatus, string> = {
+  ok: "Aktuell",
+  dueSoon: "Bald fällig",
+  overdue: "Überfällig",
+  expired: "Abgelaufen",
+};
+
+export interface SecretAge {
+  id: string;
+  name: string;
+  category: Secret["category"];
+  provider: string;
+  ageDays: number;
+  // null if the secret was never used
+  unusedDays: number | null;
+  stale: boolean;
+  maxAgeDays: number | null;
+  customPolicy: boolean;
+  expiresAt: number | null;
+  dueAt: number | null;
+  status: RotationStatus;
+}
+
+// Maximum age in days per category, null disables the reminder
+export type RotationPolicies = Record<Secret["category"], number | null>;
+
+// Most urgent first, then oldest first
+export async function listSecretAges(): Promise<SecretAge[]> {
+  return invoke<SecretAge[]>("list_secret_ages");
+}
+
+export async function getRotationPolicies(): Promise<RotationPolicies> {
+  return invoke<RotationPolicies>("get_rotation_policies");
+}
+
+export async function setRotationPolicies(policies: RotationPolicies): Promise<void> {
+  return invoke("set_rotation_policies", { policies });
+}
+
+// null for maxAgeDays falls back to the category policy
+export async function setSecretPolicy(
+  secretId: string,
+  maxAgeDays: number | null,
+  expiresAt: number | null
+): Promise<void> {
+  return invoke("set_secret_policy", { secretId, maxAgeDays, expiresAt });
 }
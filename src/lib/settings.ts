@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import { getDatabase, setDataPath as setDbPath, getDataPath, migrateDatabase } from "./database";
 import { logAudit, AuditActions } from "./audit";
 
@@ -9,7 +10,6 @@ export interface AppSettings {
   autoLockMinutes: number;
   theme: "dark" | "light" | "system";
   notificationsEnabled: boolean;
-  masterPasswordHash?: string;
   setupCompleted: boolean;
 }
 
@@ -21,22 +21,26 @@ const DEFAULT_SETTINGS: AppSettings = {
   setupCompleted: false,
 };
 
+// Only the settings above pass the backend; the rest belong to Rust commands
+async function loadSettings(): Promise<Record<string, string>> {
+  await getDatabase();
+  return invoke<Record<string, string>>("list_settings");
+}
+
+async function saveSetting(key: keyof AppSettings, value: string): Promise<void> {
+  await invoke("save_setting", { key, value });
+}
+
 export async function getSetting<K extends keyof AppSettings>(
   key: K
 ): Promise<AppSettings[K]> {
   try {
-    const db = await getDatabase();
-    const rows = await db.select<{ value: string }[]>(
-      "SELECT value FROM settings WHERE key = ?",
-      [key]
-    );
-
-    if (rows.length === 0) {
+    const value = (await loadSettings())[key];
+    if (value === undefined) {
       return DEFAULT_SETTINGS[key];
     }
 
     // Parse JSON for complex types
-    const value = rows[0].value;
     try {
       return JSON.parse(value);
     } catch {
@@ -51,16 +55,10 @@ export async function setSetting<K extends keyof AppSettings>(
   key: K,
   value: AppSettings[K]
 ): Promise<void> {
-  const db = await getDatabase();
   const stringValue =
     typeof value === "string" ? value : JSON.stringify(value);
 
-  await db.execute(
-    `INSERT INTO settings (key, value, updated_at) 
-     VALUES (?, ?, unixepoch())
-     ON CONFLICT(key) DO UPDATE SET value = ?, updated_at = unixepoch()`,
-    [key, stringValue, stringValue]
-  );
+  await saveSetting(key, stringValue);
 
   await logAudit(AuditActions.SETTINGS_UPDATED, "settings", key, {
     newValue: value,
@@ -71,17 +69,13 @@ export async function getAllSettings(): Promise<AppSettings> {
   const settings = { ...DEFAULT_SETTINGS };
 
   try {
-    const db = await getDatabase();
-    const rows = await db.select<{ key: string; value: string }[]>(
-      "SELECT key, value FROM settings"
-    );
+    const rows = await loadSettings();
 
-    for (const row of rows) {
-      const key = row.key as keyof AppSettings;
+    for (const [key, value] of Object.entries(rows)) {
       try {
-        (settings as Record<string, unknown>)[key] = JSON.parse(row.value);
+        (settings as Record<string, unknown>)[key] = JSON.parse(value);
       } catch {
-        (settings as Record<string, unknown>)[key] = row.value;
+        (settings as Record<string, unknown>)[key] = value;
       }
     }
   } catch {
@@ -123,14 +117,8 @@ export async function updateDataPath(
     await setDbPath(newPath);
     
     // Re-initialize and save the setting
-    const db = await getDatabase();
-    const stringValue = newPath;
-    await db.execute(
-      `INSERT INTO settings (key, value, updated_at) 
-       VALUES (?, ?, unixepoch())
-       ON CONFLICT(key) DO UPDATE SET value = ?, updated_at = unixepoch()`,
-      ["dataPath", stringValue, stringValue]
-    );
+    await getDatabase();
+    await saveSetting("dataPath", newPath);
 
     await logAudit(AuditActions.DATA_PATH_CHANGED, "settings", "dataPath", {
       oldPath,
@@ -157,27 +145,13 @@ export async function completeSetup(): Promise<void> {
   await setSetting("setupCompleted", true);
 }
 
-// Simple password hashing (in production, use a proper KDF like Argon2)
-export async function hashPassword(password: string): Promise<string> {
-  const encoder = new TextEncoder();
-  const data = encoder.encode(password + "panoptic-salt-v1");
-  const hashBuffer = await crypto.subtle.digest("SHA-256", data);
-  const hashArray = Array.from(new Uint8Array(hashBuffer));
-  return hashArray.map((b) => b.toString(16).padStart(2, "0")).join("");
-}
-
+// Master password hashing happens in Rust (Argon2id, see src-tauri/src/auth.rs).
+// Old SHA-256 hashes are upgraded there on the next successful unlock.
 export async function verifyPassword(password: string): Promise<boolean> {
-  const storedHash = await getSetting("masterPasswordHash");
-  if (!storedHash) {
-    // No password set yet - first time setup
-    return true;
-  }
-
-  const inputHash = await hashPassword(password);
-  return inputHash === storedHash;
+  return invoke<boolean>("verify_master_password", { password });
 }
 
 export async function setMasterPassword(password: string): Promise<void> {
-  const hash = await hashPassword(password);
-  await setSetting("masterPasswordHash", hash);
+  await invoke("set_master_password", { password });
+  await logAudit(AuditActions.SETTINGS_UPDATED, "settings", "masterPasswordHash");
 }
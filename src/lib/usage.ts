@@ -0,0 +1,178 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type UsageProvider = "openai" | "anthropic" | "google";
+
+export interface DailyUsage {
+  date: string;
+  inputTokens: number;
+  outputTokens: number;
+  totalTokens: number;
+  requests: number;
+  costUsd: number;
+  estimated: boolean; // Part of costUsd is estimated from the price table
+}
+
+export interface ProjectInfo {
+  id: string;
+  name: string;
+}
+
+export interface ProjectUsage {
+  project: ProjectInfo;
+  provider: UsageProvider;
+  inputTokens: number;
+  outputTokens: number;
+  totalTokens: number;
+  requests: number;
+  costUsd: number;
+  costToday: number;
+  costWeek: number;
+  costMonth: number;
+  estimated: boolean;
+  dailyUsage: DailyUsage[]; // Newest first
+}
+
+export const PROVIDER_LABELS: Record<UsageProvider, string> = {
+  openai: "OpenAI",
+  anthropic: "Anthropic",
+  google: "Gemini",
+};
+
+// Class of a failed provider request, set by the Rust HTTP client
+export type ApiErrorKind = "auth" | "permission" | "rateLimited" | "notFound" | "network" | "api";
+
+export const API_ERROR_HINTS: Record<ApiErrorKind, string> = {
+  auth: "Der API Key ist ungültig oder abgelaufen.",
+  permission: "Dem Key fehlen Rechte – für Usage-Daten wird ein Admin Key benötigt.",
+  rateLimited: "Rate-Limit des Providers erreicht. Versuche es später erneut.",
+  notFound: "Der Endpunkt wurde nicht gefunden.",
+  network: "Der Provider ist nicht erreichbar. Prüfe die Internetverbindung.",
+  api: "Der Provider hat einen Fehler gemeldet.",
+};
+
+export interface ProviderStatus {
+  provider: UsageProvider;
+  keys: number;
+  error: string | null;
+  errorKind: ApiErrorKind | null;
+  lastSyncedAt: number | null; // Unix seconds
+}
+
+export interface UsageSummary {
+  today: DailyUsage;
+  thisWeek: DailyUsage[];
+  thisMonth: DailyUsage[];
+  totalCostToday: number;
+  totalCostWeek: number;
+  totalCostMonth: number;
+  projects: ProjectUsage[];
+  providers: ProviderStatus[];
+  priceVersion: string | null; // Set when any cost is estimated
+}
+
+export interface KeyDiagnosis {
+  keyName: string;
+  valid: boolean;
+  keyType: string;
+  organization: string | null;
+  projects: ProjectInfo[];
+  models: string[];
+  error: string | null;
+  errorKind: ApiErrorKind | null;
+}
+
+export interface ProviderDiagnosis {
+  keys: KeyDiagnosis[];
+  totalKeys: number;
+  validKeys: number;
+  totalProjects: number;
+}
+
+export interface Diagnoses {
+  openai: ProviderDiagnosis;
+  anthropic: ProviderDiagnosis;
+  gemini: ProviderDiagnosis;
+}
+
+// Usage and costs of the last 30 days. Rust serves settled days from
+// usage_cache and only fetches open or missing days.
+export async function getUsageSummary(): Promise<UsageSummary> {
+  return invoke<UsageSummary>("get_usage_summary");
+}
+
+export async function diagnoseProviders(): Promise<Diagnoses> {
+  return invoke<Diagnoses>("diagnose_providers");
+}
+
+// Monthly totals from usage_cache, including backfilled months
+export interface MonthlyUsage {
+  month: string; // YYYY-MM
+  provider: UsageProvider;
+  inputTokens: number;
+  outputTokens: number;
+  requests: number;
+  costUsd: number;
+  estimated: boolean;
+}
+
+export async function getUsageHistory(months: number): Promise<MonthlyUsage[]> {
+  return invoke<MonthlyUsage[]>("get_usage_history", { months });
+}
+
+export interface BackfillJob {
+  secretId: string;
+  provider: UsageProvider;
+  keyName: string;
+  oldestMonth: string; // First day of the oldest month
+  nextMonth: string; // First day of the next month to fetch
+  totalMonths: number;
+  completedMonths: number;
+  status: "running" | "cancelled" | "failed" | "done";
+  error: string | null;
+  active: boolean; // false for running jobs that were interrupted
+}
+
+export const BACKFILL_EVENT = "usage-backfill";
+
+// Starts a backfill for the key, or resumes its unfinished one of the same
+// range. Progress arrives as BACKFILL_EVENT events.
+export async function startUsageBackfill(secretId: string, months: number): Promise<BackfillJob> {
+  return invoke<BackfillJob>("start_usage_backfill", { secretId, months });
+}
+
+export async function cancelUsageBackfill(secretId: string): Promise<void> {
+  await invoke("cancel_usage_backfill", { secretId });
+}
+
+export async function listUsageBackfills(): Promise<BackfillJob[]> {
+  return invoke<BackfillJob[]>("list_usage_backfills");
+}
+
+// Prices in USD per million tokens, used to estimate costs for usage without
+// billed costs, e.g. lagging Anthropic cost reports. Gemini reports no usage.
+export interface ModelPrice {
+  provider: UsageProvider;
+  model: string; // Prefix of the matching model names
+  input: number;
+  cachedInput?: number;
+  output: number;
+  batchInput?: number;
+  batchOutput?: number;
+}
+
+export interface PriceTable {
+  version: string;
+  prices: (ModelPrice & { overridden: boolean })[];
+}
+
+export async function getPriceTable(): Promise<PriceTable> {
+  return invoke<PriceTable>("get_price_table");
+}
+
+export async function setPriceOverride(price: ModelPrice): Promise<void> {
+  await invoke("set_price_override", { price });
+}
+
+export async function removePriceOverride(provider: UsageProvider, model: string): Promise<void> {
+  await invoke("remove_price_override", { provider, model });
+}
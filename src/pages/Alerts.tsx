@@ -0,0 +1,459 @@
+import { useState } from "react";
+import {
+  Bell,
+  BellOff,
+  CheckCircle,
+  Edit,
+  Loader2,
+  Plus,
+  RefreshCw,
+  Send,
+  Trash2,
+  TriangleAlert,
+} from "lucide-react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { ChannelFields } from "@/components/alerts/ChannelFields";
+import {
+  useAlertEvents,
+  useAlertRules,
+  useCheckAlerts,
+  useDeleteAlertRule,
+  useSaveAlertRule,
+  useTestNotification,
+} from "@/hooks/useAlerts";
+import {
+  ALERT_CHANNEL_LABELS,
+  ALERT_KIND_LABELS,
+  ALERT_PERIOD_LABELS,
+  type AlertChannel,
+  type AlertKind,
+  type AlertPeriod,
+  type AlertRule,
+  type AlertRuleInput,
+} from "@/lib/alerts";
+import {
+  DNS_SERVICE_LABELS,
+  LIMIT_SERVICE_LABELS,
+  type DnsService,
+  type LimitService,
+} from "@/lib/infrastructure";
+import { PROVIDER_LABELS, type UsageProvider } from "@/lib/usage";
+import { formatCurrency, formatNumber, formatRelativeTime } from "@/lib/utils";
+
+const selectClassName =
+  "flex h-10 w-full rounded-md border border-input bg-background px-3 py-2 text-sm ring-offset-background focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2";
+
+const emptyRule: AlertRuleInput = {
+  name: "",
+  kind: "cost",
+  provider: null,
+  threshold: 10,
+  period: "day",
+  channel: "system",
+  channelConfig: null,
+  enabled: true,
+};
+
+function formatValue(kind: AlertKind, value: number | null): string {
+  if (value === null) return "–";
+  switch (kind) {
+    case "cost":
+      return formatCurrency(value);
+    case "quota":
+      return `${formatNumber(value)} Tokens`;
+    case "status":
+      return value === 0 ? "OK" : `${value} mit Fehler`;
+    case "limit":
+      return `${value.toFixed(0)} %`;
+    case "dns":
+      return value === 0 ? "OK" : `${value} Änderungen`;
+  }
+}
+
+// Limit and DNS rules watch infrastructure services instead of LLM providers
+function watchesServices(kind: AlertKind): boolean {
+  return kind === "limit" || kind === "dns";
+}
+
+function scopeOptions(kind: AlertKind): Record<string, string> {
+  if (kind === "limit") return LIMIT_SERVICE_LABELS;
+  if (kind === "dns") return DNS_SERVICE_LABELS;
+  return PROVIDER_LABELS;
+}
+
+// Status and DNS rules fire on any failure or change
+function hasThreshold(kind: AlertKind): boolean {
+  return kind !== "status" && kind !== "dns";
+}
+
+function describeRule(rule: AlertRuleInput): string {
+  const options = scopeOptions(rule.kind);
+  const scope = rule.provider
+    ? (options[rule.provider] ?? rule.provider)
+    : watchesServices(rule.kind)
+      ? "Alle Dienste"
+      : "Alle Provider";
+  const channel = ALERT_CHANNEL_LABELS[rule.channel];
+  if (rule.kind === "status") return `${scope} · Anfragen schlagen fehl · ${channel}`;
+  if (rule.kind === "dns") return `${scope} · DNS weicht vom Snapshot ab · ${channel}`;
+  if (rule.kind === "limit") return `${scope} · Plan-Limit ≥ ${formatValue(rule.kind, rule.threshold)} · ${channel}`;
+  return `${scope} · ${ALERT_KIND_LABELS[rule.kind]} ${ALERT_PERIOD_LABELS[rule.period]} ≥ ${formatValue(rule.kind, rule.threshold)} · ${channel}`;
+}
+
+export function Alerts() {
+  const { data: rules = [], isLoading } = useAlertRules();
+  const { data: events = [] } = useAlertEvents();
+  const saveMutation = useSaveAlertRule();
+  const deleteMutation = useDeleteAlertRule();
+  const checkMutation = useCheckAlerts();
+  const testMutation = useTestNotification();
+
+  const [form, setForm] = useState<AlertRuleInput | null>(null);
+  const [editingId, setEditingId] = useState<string | undefined>();
+  const [deleteConfirmId, setDeleteConfirmId] = useState<string | null>(null);
+
+  const startEdit = (rule: AlertRule) => {
+    const { id, state: _state, lastValue: _value, lastCheckedAt: _checked, createdAt: _created, ...input } = rule;
+    setEditingId(id);
+    setForm(input);
+  };
+
+  const closeForm = () => {
+    setForm(null);
+    setEditingId(undefined);
+    saveMutation.reset();
+  };
+
+  const handleSubmit = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!form) return;
+    await saveMutation.mutateAsync({
+      id: editingId,
+      rule: { ...form, threshold: hasThreshold(form.kind) ? form.threshold : null },
+    });
+    closeForm();
+  };
+
+  const toggle = (rule: AlertRule) => {
+    const { id, state: _state, lastValue: _value, lastCheckedAt: _checked, createdAt: _created, ...input } = rule;
+    saveMutation.mutate({ id, rule: { ...input, enabled: !rule.enabled } });
+  };
+
+  const handleDelete = async (id: string) => {
+    await deleteMutation.mutateAsync(id);
+    setDeleteConfirmId(null);
+  };
+
+  return (
+    <div className="space-y-8">
+      {/* Header */}
+      <div className="flex items-center justify-between">
+        <div>
+          <h1 className="text-3xl font-bold tracking-tight">Alerts</h1>
+          <p className="mt-1 text-muted-foreground">
+            Benachrichtigungen bei Kostenspitzen, Token-Verbrauch und Provider-Fehlern.
+          </p>
+        </div>
+        <div className="flex gap-2">
+          <Button
+            variant="outline"
+            onClick={() => checkMutation.mutate()}
+            disabled={checkMutation.isPending}
+          >
+            <RefreshCw className={`mr-2 h-4 w-4 ${checkMutation.isPending ? "animate-spin" : ""}`} />
+            Jetzt prüfen
+          </Button>
+          <Button onClick={() => setForm(emptyRule)} disabled={form !== null}>
+            <Plus className="mr-2 h-4 w-4" />
+            Neue Regel
+          </Button>
+        </div>
+      </div>
+
+      {checkMutation.error && (
+        <p className="text-sm text-destructive">{String(checkMutation.error)}</p>
+      )}
+
+      {/* Add/Edit Form */}
+      {form && (
+        <Card>
+          <CardHeader>
+            <CardTitle>{editingId ? "Regel bearbeiten" : "Neue Regel"}</CardTitle>
+          </CardHeader>
+          <CardContent>
+            <form onSubmit={handleSubmit} className="space-y-4">
+              <div className="grid gap-4 md:grid-cols-2">
+                <div className="space-y-2">
+                  <label className="text-sm font-medium">Name</label>
+                  <Input
+                    placeholder="z.B. OpenAI Tageslimit"
+                    value={form.name}
+                    onChange={(e) => setForm({ ...form, name: e.target.value })}
+                    required
+                  />
+                </div>
+                <div className="space-y-2">
+                  <label className="text-sm font-medium">
+                    {watchesServices(form.kind) ? "Dienst" : "Provider"}
+                  </label>
+                  <select
+                    className={selectClassName}
+                    value={form.provider ?? ""}
+                    onChange={(e) =>
+                      setForm({
+                        ...form,
+                        provider: (e.target.value || null) as
+                          | UsageProvider
+                          | LimitService
+                          | DnsService
+                          | null,
+                      })
+                    }
+                  >
+                    <option value="">
+                      {watchesServices(form.kind) ? "Alle Dienste" : "Alle Provider"}
+                    </option>
+                    {Object.entries(scopeOptions(form.kind)).map(([value, label]) => (
+                      <option key={value} value={value}>
+                        {label}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+              </div>
+
+              <div className="grid gap-4 md:grid-cols-3">
+                <div className="space-y-2">
+                  <label className="text-sm font-medium">Typ</label>
+                  <select
+                    className={selectClassName}
+                    value={form.kind}
+                    onChange={(e) => {
+                      const kind = e.target.value as AlertKind;
+                      // Providers and services are not interchangeable
+                      const switched = scopeOptions(kind) !== scopeOptions(form.kind);
+                      setForm({
+                        ...form,
+                        kind,
+                        provider: switched ? null : form.provider,
+                        threshold: kind === "limit" ? 80 : form.threshold,
+                      });
+                    }}
+                  >
+                    {Object.entries(ALERT_KIND_LABELS).map(([value, label]) => (
+                      <option key={value} value={value}>
+                        {label}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+                {hasThreshold(form.kind) && (
+                  <>
+                    {form.kind !== "limit" && (
+                      <div className="space-y-2">
+                        <label className="text-sm font-medium">Zeitraum</label>
+                        <select
+                          className={selectClassName}
+                          value={form.period}
+                          onChange={(e) => setForm({ ...form, period: e.target.value as AlertPeriod })}
+                        >
+                          {Object.entries(ALERT_PERIOD_LABELS).map(([value, label]) => (
+                            <option key={value} value={value}>
+                              {label}
+                            </option>
+                          ))}
+                        </select>
+                      </div>
+                    )}
+                    <div className="space-y-2">
+                      <label className="text-sm font-medium">
+                        Schwellwert{" "}
+                        {form.kind === "cost" ? "(USD)" : form.kind === "limit" ? "(%)" : "(Tokens)"}
+                      </label>
+                      <Input
+                        type="number"
+                        min={0}
+                        step={form.kind === "cost" ? "0.01" : form.kind === "limit" ? "1" : "1000"}
+                        value={form.threshold ?? ""}
+                        onChange={(e) =>
+                          setForm({ ...form, threshold: e.target.value === "" ? null : Number(e.target.value) })
+                        }
+                        required
+                      />
+                    </div>
+                  </>
+                )}
+              </div>
+
+              <div className="grid gap-4 md:grid-cols-3">
+                <div className="space-y-2">
+                  <label className="text-sm font-medium">Kanal</label>
+                  <select
+                    className={selectClassName}
+                    value={form.channel}
+                    onChange={(e) =>
+                      setForm({ ...form, channel: e.target.value as AlertChannel, channelConfig: null })
+                    }
+                  >
+                    {Object.entries(ALERT_CHANNEL_LABELS).map(([value, label]) => (
+                      <option key={value} value={value}>
+                        {label}
+                      </option>
+                    ))}
+                  </select>
+                </div>
+              </div>
+
+              <ChannelFields
+                channel={form.channel}
+                config={form.channelConfig}
+                onChange={(channelConfig) => setForm({ ...form, channelConfig })}
+              />
+
+              {saveMutation.error && (
+                <p className="text-sm text-destructive">{String(saveMutation.error)}</p>
+              )}
+
+              <div className="flex gap-2">
+                <Button type="submit" disabled={saveMutation.isPending}>
+                  {saveMutation.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                  {editingId ? "Speichern" : "Erstellen"}
+                </Button>
+                <Button type="button" variant="outline" onClick={closeForm}>
+                  Abbrechen
+                </Button>
+              </div>
+            </form>
+          </CardContent>
+        </Card>
+      )}
+
+      {/* Rules */}
+      <Card>
+        <CardHeader>
+          <CardTitle className="flex items-center gap-2">
+            <Bell className="h-5 w-5" />
+            Regeln
+          </CardTitle>
+        </CardHeader>
+        <CardContent>
+          {isLoading ? (
+            <div className="flex justify-center py-6">
+              <Loader2 className="h-6 w-6 animate-spin text-muted-foreground" />
+            </div>
+          ) : rules.length === 0 ? (
+            <p className="text-sm text-muted-foreground">
+              Noch keine Regeln. Lege z.B. ein Tageslimit für deine LLM-Kosten an.
+            </p>
+          ) : (
+            <div className="space-y-2">
+              {rules.map((rule) => (
+                <div
+                  key={rule.id}
+                  className={`flex items-center justify-between rounded-lg border p-4 ${
+                    rule.state === "firing" && rule.enabled ? "border-destructive/50" : "border-border"
+                  }`}
+                >
+                  <div className="flex items-center gap-3">
+                    {!rule.enabled ? (
+                      <BellOff className="h-5 w-5 text-muted-foreground" />
+                    ) : rule.state === "firing" ? (
+                      <TriangleAlert className="h-5 w-5 text-destructive" />
+                    ) : (
+                      <CheckCircle className="h-5 w-5 text-success" />
+                    )}
+                    <div>
+                      <p className="font-medium">{rule.name}</p>
+                      <p className="text-xs text-muted-foreground">{describeRule(rule)}</p>
+                    </div>
+                  </div>
+                  <div className="flex items-center gap-4">
+                    <div className="text-right text-xs text-muted-foreground">
+                      <p className="font-semibold text-foreground">
+                        {formatValue(rule.kind, rule.lastValue)}
+                      </p>
+                      <p>
+                        {rule.lastCheckedAt
+                          ? formatRelativeTime(new Date(rule.lastCheckedAt * 1000))
+                          : "noch nicht geprüft"}
+                      </p>
+                    </div>
+                    <Button
+                      variant="ghost"
+                      size="sm"
+                      onClick={() => testMutation.mutate(rule.id)}
+                      disabled={testMutation.isPending}
+                      title="Testnachricht über den gespeicherten Kanal senden"
+                    >
+                      {testMutation.isPending && testMutation.variables === rule.id ? (
+                        <Loader2 className="h-4 w-4 animate-spin" />
+                      ) : (
+                        <Send className="h-4 w-4" />
+                      )}
+                    </Button>
+                    <Button variant="ghost" size="sm" onClick={() => toggle(rule)}>
+                      {rule.enabled ? "Pausieren" : "Aktivieren"}
+                    </Button>
+                    <Button variant="ghost" size="sm" onClick={() => startEdit(rule)}>
+                      <Edit className="h-4 w-4" />
+                    </Button>
+                    {deleteConfirmId === rule.id ? (
+                      <Button variant="destructive" size="sm" onClick={() => handleDelete(rule.id)}>
+                        Löschen?
+                      </Button>
+                    ) : (
+                      <Button variant="ghost" size="sm" onClick={() => setDeleteConfirmId(rule.id)}>
+                        <Trash2 className="h-4 w-4" />
+                      </Button>
+                    )}
+                  </div>
+                </div>
+              ))}
+            </div>
+          )}
+          {testMutation.isSuccess && (
+            <p className="mt-3 text-sm text-success">Testnachricht versendet.</p>
+          )}
+          {testMutation.error && (
+            <p className="mt-3 text-sm text-destructive">{String(testMutation.error)}</p>
+          )}
+        </CardContent>
+      </Card>
+
+      {/* History */}
+      <Card>
+        <CardHeader>
+          <CardTitle>Verlauf</CardTitle>
+        </CardHeader>
+        <CardContent>
+          {events.length === 0 ? (
+            <p className="text-sm text-muted-foreground">Bisher wurde kein Alert ausgelöst.</p>
+          ) : (
+            <div className="space-y-2">
+              {events.map((event) => (
+                <div key={event.id} className="flex items-start gap-3 border-b border-border/50 pb-2 text-sm">
+                  {event.kind === "firing" ? (
+                    <TriangleAlert className="mt-0.5 h-4 w-4 text-destructive" />
+                  ) : (
+                    <CheckCircle className="mt-0.5 h-4 w-4 text-success" />
+                  )}
+                  <div className="flex-1">
+                    <p className="font-medium">
+                      {event.kind === "firing" ? "Ausgelöst" : "Entwarnung"}: {event.alertName ?? "Gelöschte Regel"}
+                    </p>
+                    <p className="text-xs text-muted-foreground">{event.message}</p>
+                  </div>
+                  <span className="text-xs text-muted-foreground">
+                    {formatRelativeTime(new Date(event.createdAt * 1000))}
+                  </span>
+                </div>
+              ))}
+            </div>
+          )}
+        </CardContent>
+      </Card>
+    </div>
+  );
+}
@@ -4,14 +4,23 @@ import {
   KeyRound,
   Settings,
   Globe,
+  Database,
   Loader2,
   ChevronLeft,
   ChevronRight,
+  ShieldCheck,
+  ShieldAlert,
+  Download,
+  LifeBuoy,
+  Server,
 } from "lucide-react";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
-import { useAuditLog, useAuditLogCount } from "@/hooks/useAuditLog";
-import { formatRelativeTime } from "@/lib/utils";
+import { AuditExport } from "@/components/audit/AuditExport";
+import { SyslogSettings } from "@/components/audit/SyslogSettings";
+import { useAuditLog, useAuditLogCount, useVerifyAuditChain } from "@/hooks/useAuditLog";
+import { AUDIT_BREAK_LABELS } from "@/lib/audit";
+import { formatDate, formatRelativeTime } from "@/lib/utils";
 
 const PAGE_SIZE = 20;
 
@@ -20,13 +29,30 @@ const actionIcons: Record<string, React.ReactNode> = {
   secret_updated: <KeyRound className="h-4 w-4 text-warning" />,
   secret_deleted: <KeyRound className="h-4 w-4 text-destructive" />,
   secret_accessed: <KeyRound className="h-4 w-4 text-primary" />,
+  secret_revealed: <KeyRound className="h-4 w-4 text-warning" />,
   secret_copied: <KeyRound className="h-4 w-4 text-muted-foreground" />,
+  secret_rotated: <KeyRound className="h-4 w-4 text-warning" />,
+  secret_reverted: <KeyRound className="h-4 w-4 text-warning" />,
+  secrets_exported: <Download className="h-4 w-4 text-warning" />,
+  secrets_imported: <KeyRound className="h-4 w-4 text-success" />,
   settings_updated: <Settings className="h-4 w-4 text-primary" />,
   data_path_changed: <Settings className="h-4 w-4 text-warning" />,
   api_call: <Globe className="h-4 w-4 text-success" />,
   api_error: <Globe className="h-4 w-4 text-destructive" />,
   app_unlocked: <ScrollText className="h-4 w-4 text-success" />,
   app_locked: <ScrollText className="h-4 w-4 text-muted-foreground" />,
+  schema_migrated: <Database className="h-4 w-4 text-primary" />,
+  audit_pruned: <Database className="h-4 w-4 text-muted-foreground" />,
+  data_exported: <Download className="h-4 w-4 text-warning" />,
+  deployment_redeployed: <Server className="h-4 w-4 text-warning" />,
+  deployment_promoted: <Server className="h-4 w-4 text-warning" />,
+  deployment_cancelled: <Server className="h-4 w-4 text-destructive" />,
+  project_paused: <Server className="h-4 w-4 text-destructive" />,
+  project_restored: <Server className="h-4 w-4 text-warning" />,
+  dns_snapshot_saved: <Globe className="h-4 w-4 text-primary" />,
+  dns_snapshot_deleted: <Globe className="h-4 w-4 text-muted-foreground" />,
+  recovery_phrase_created: <LifeBuoy className="h-4 w-4 text-primary" />,
+  vault_recovered: <LifeBuoy className="h-4 w-4 text-warning" />,
 };
 
 const actionLabels: Record<string, string> = {
@@ -34,13 +60,30 @@ const actionLabels: Record<string, string> = {
   secret_updated: "Secret aktualisiert",
   secret_deleted: "Secret gelöscht",
   secret_accessed: "Secret abgerufen",
+  secret_revealed: "Secret angezeigt",
   secret_copied: "Secret kopiert",
+  secret_rotated: "Secret rotiert",
+  secret_reverted: "Secret-Version wiederhergestellt",
+  secrets_exported: "Secrets exportiert",
+  secrets_imported: "Secrets importiert",
   settings_updated: "Einstellungen geändert",
   data_path_changed: "Datenpfad geändert",
   api_call: "API-Aufruf",
   api_error: "API-Fehler",
   app_unlocked: "App entsperrt",
   app_locked: "App gesperrt",
+  schema_migrated: "Datenbank migriert",
+  audit_pruned: "Alte Einträge gelöscht",
+  data_exported: "Daten exportiert",
+  deployment_redeployed: "Redeploy gestartet",
+  deployment_promoted: "Deployment promotet",
+  deployment_cancelled: "Deployment abgebrochen",
+  project_paused: "Projekt pausiert",
+  project_restored: "Projekt fortgesetzt",
+  dns_snapshot_saved: "DNS-Snapshot gespeichert",
+  dns_snapshot_deleted: "DNS-Snapshot gelöscht",
+  recovery_phrase_created: "Wiederherstellungsphrase erzeugt",
+  vault_recovered: "Vault wiederhergestellt",
 };
 
 export function AuditLog() {
@@ -52,19 +95,75 @@ export function AuditLog() {
     offset,
   });
   const { data: totalCount = 0 } = useAuditLogCount();
+  const verifyMutation = useVerifyAuditChain();
+  const report = verifyMutation.data;
 
   const totalPages = Math.ceil(totalCount / PAGE_SIZE);
 
   return (
     <div className="space-y-8">
       {/* Header */}
-      <div>
-        <h1 className="text-3xl font-bold tracking-tight">Audit Log</h1>
-        <p className="mt-1 text-muted-foreground">
-          Vollständige Nachvollziehbarkeit aller Aktionen.
-        </p>
+      <div className="flex items-center justify-between">
+        <div>
+          <h1 className="text-3xl font-bold tracking-tight">Audit Log</h1>
+          <p className="mt-1 text-muted-foreground">
+            Vollständige Nachvollziehbarkeit aller Aktionen.
+          </p>
+        </div>
+        <Button
+          variant="outline"
+          onClick={() => verifyMutation.mutate()}
+          disabled={verifyMutation.isPending}
+        >
+          {verifyMutation.isPending ? (
+            <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+          ) : (
+            <ShieldCheck className="mr-2 h-4 w-4" />
+          )}
+          Integrität prüfen
+        </Button>
       </div>
 
+      {/* Chain verification */}
+      {verifyMutation.error && (
+        <p className="text-sm text-destructive">{String(verifyMutation.error)}</p>
+      )}
+      {report &&
+        (report.intact ? (
+          <div className="flex items-center gap-3 rounded-lg border border-success/50 p-4 text-sm">
+            <ShieldCheck className="h-5 w-5 text-success" />
+            <div>
+              <p className="font-medium">Hash-Kette intakt</p>
+              <p className="text-muted-foreground">
+                {report.checked} Einträge geprüft
+                {report.checkpoint &&
+                  ` · ${report.checkpoint.pruned} ältere Einträge am ${formatDate(
+                    new Date(report.checkpoint.createdAt * 1000)
+                  )} gelöscht (signierter Löschpunkt)`}
+              </p>
+            </div>
+          </div>
+        ) : (
+          report.firstBroken && (
+            <div className="flex items-center gap-3 rounded-lg border border-destructive/50 p-4 text-sm">
+              <ShieldAlert className="h-5 w-5 text-destructive" />
+              <div>
+                <p className="font-medium">
+                  Hash-Kette unterbrochen: {AUDIT_BREAK_LABELS[report.firstBroken.reason]}
+                </p>
+                <p className="text-muted-foreground">
+                  {report.firstBroken.seq !== null && `Eintrag #${report.firstBroken.seq}`}
+                  {report.firstBroken.action &&
+                    ` · ${actionLabels[report.firstBroken.action] || report.firstBroken.action}`}
+                  {report.firstBroken.createdAt !== null &&
+                    ` · ${formatDate(new Date(report.firstBroken.createdAt * 1000))}`}
+                  {` · ${report.checked} Einträge davor sind intakt`}
+                </p>
+              </div>
+            </div>
+          )
+        ))}
+
       {/* Stats */}
       <div className="grid gap-4 md:grid-cols-3">
         <Card>
@@ -205,11 +304,15 @@ export function AuditLog() {
         </CardContent>
       </Card>
 
+      <AuditExport actionLabels={actionLabels} />
+      <SyslogSettings />
+
       {/* Info */}
       <div className="rounded-lg border border-border bg-card/50 p-4 text-sm text-muted-foreground">
         <p>
           📋 Audit-Logs werden automatisch nach 90 Tagen gelöscht. Alle
-          Secret-Zugriffe und Änderungen werden protokolliert.
+          Secret-Zugriffe und Änderungen werden protokolliert und per Hash-Kette
+          gegen nachträgliche Änderungen gesichert.
         </p>
       </div>
     </div>
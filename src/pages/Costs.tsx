@@ -25,22 +25,17 @@ import {
 } from "recharts";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
+import { EstimatedBadge } from "@/components/costs/EstimatedBadge";
+import { PriceTable } from "@/components/costs/PriceTable";
+import { UsageHistory } from "@/components/costs/UsageHistory";
 import { useCombinedLLMUsage } from "@/hooks/useOpenAI";
-import { formatCurrency, formatNumber } from "@/lib/utils";
-import { diagnoseOpenAIKey, type OpenAIDiagnosis } from "@/lib/openai";
-import { diagnoseAnthropicKey, type AnthropicDiagnosis } from "@/lib/anthropic";
-import { diagnoseGeminiKey, type GeminiDiagnosis } from "@/lib/gemini";
-
-interface CombinedDiagnosis {
-  openai: OpenAIDiagnosis | null;
-  anthropic: AnthropicDiagnosis | null;
-  gemini: GeminiDiagnosis | null;
-}
+import { formatCurrency, formatNumber, formatRelativeTime } from "@/lib/utils";
+import { API_ERROR_HINTS, diagnoseProviders, PROVIDER_LABELS, type Diagnoses } from "@/lib/usage";
 
 export function Costs() {
   const [timeRange, setTimeRange] = useState<"week" | "month">("week");
   const [showDebug, setShowDebug] = useState(false);
-  const [diagnosis, setDiagnosis] = useState<CombinedDiagnosis | null>(null);
+  const [diagnosis, setDiagnosis] = useState<Diagnoses | null>(null);
   const [diagnosing, setDiagnosing] = useState(false);
   const [selectedProjectId, setSelectedProjectId] = useState<string | null>(null);
   const { data: usage, isLoading, error, refetch, isFetching } = useCombinedLLMUsage();
@@ -48,16 +43,7 @@ export function Costs() {
   const runDiagnosis = async () => {
     setDiagnosing(true);
     try {
-      const [openaiResult, anthropicResult, geminiResult] = await Promise.all([
-        diagnoseOpenAIKey(),
-        diagnoseAnthropicKey(),
-        diagnoseGeminiKey(),
-      ]);
-      setDiagnosis({
-        openai: openaiResult,
-        anthropic: anthropicResult,
-        gemini: geminiResult,
-      });
+      setDiagnosis(await diagnoseProviders());
     } catch (e) {
       console.error("Diagnosis failed:", e);
     } finally {
@@ -92,6 +78,13 @@ export function Costs() {
   const displayCostToday = selectedProject ? selectedProject.costToday : (usage?.totalCostToday || 0);
   const displayCostWeek = selectedProject ? selectedProject.costWeek : (usage?.totalCostWeek || 0);
   const displayCostMonth = selectedProject ? selectedProject.costMonth : (usage?.totalCostMonth || 0);
+  const estimatedToday = selectedProject ? selectedProject.estimated : !!usage?.today.estimated;
+  const estimatedWeek = selectedProject
+    ? selectedProject.estimated
+    : !!usage?.thisWeek.some((d) => d.estimated);
+  const estimatedMonth = selectedProject
+    ? selectedProject.estimated
+    : !!usage?.thisMonth.some((d) => d.estimated);
   const displayTokensToday = selectedProject ? selectedProject.totalTokens : allProjectsTotals.totalTokens;
   const displayInputTokensToday = selectedProject ? selectedProject.inputTokens : allProjectsTotals.inputTokens;
   const displayOutputTokensToday = selectedProject ? selectedProject.outputTokens : allProjectsTotals.outputTokens;
@@ -146,6 +139,20 @@ export function Costs() {
           <p className="mt-1 text-muted-foreground">
             Übersicht und Analyse deiner API-Kosten.
           </p>
+          {usage && usage.providers.some((p) => p.keys > 0) && (
+            <p className="mt-1 text-xs text-muted-foreground">
+              Zuletzt synchronisiert:{" "}
+              {usage.providers
+                .filter((p) => p.keys > 0)
+                .map(
+                  (p) =>
+                    `${PROVIDER_LABELS[p.provider]} ${
+                      p.lastSyncedAt ? formatRelativeTime(new Date(p.lastSyncedAt * 1000)) : "nie"
+                    }`
+                )
+                .join(" · ")}
+            </p>
+          )}
         </div>
         <div className="flex gap-2">
           <Button
@@ -300,7 +307,7 @@ export function Costs() {
                   </div>
                   <h3 className="font-semibold">Anthropic (Claude)</h3>
                   <span className="text-xs text-muted-foreground">
-                    {diagnosis.anthropic.validKeys}/{diagnosis.anthropic.totalKeys} Keys · {diagnosis.anthropic.totalWorkspaces} Workspaces
+                    {diagnosis.anthropic.validKeys}/{diagnosis.anthropic.totalKeys} Keys · {diagnosis.anthropic.totalProjects} Workspaces
                   </span>
                 </div>
                 <div className="space-y-2">
@@ -336,7 +343,7 @@ export function Costs() {
                             </div>
                           </div>
                         </div>
-                        <p className="text-xs">{keyDiag.workspaces.length} Workspaces</p>
+                        <p className="text-xs">{keyDiag.projects.length} Workspaces</p>
                       </div>
                       {keyDiag.error && (
                         <div className="mt-2 rounded bg-warning/10 px-2 py-1 text-xs text-warning">
@@ -386,7 +393,7 @@ export function Costs() {
                               <code className="rounded bg-blue-500/20 px-1 py-0.5 text-[10px] text-blue-500">
                                 {keyDiag.keyType}
                               </code>
-                              {keyDiag.projectId && <span>{keyDiag.projectId}</span>}
+                              {keyDiag.organization && <span>{keyDiag.organization}</span>}
                             </div>
                           </div>
                         </div>
@@ -417,7 +424,8 @@ export function Costs() {
                 {diagnosis.gemini.validKeys > 0 && (
                   <div className="rounded-lg border border-blue-500/20 bg-blue-500/5 p-2 text-xs text-blue-400">
                     <strong>Hinweis:</strong> Google AI Studio bietet leider keine öffentliche Usage API. 
-                    Verbrauchsdaten sind nur im Dashboard unter aistudio.google.com einsehbar.
+                    Verbrauchsdaten sind nur im Dashboard unter aistudio.google.com einsehbar, und
+                    Gemini-Kosten können auch nicht geschätzt werden.
                   </div>
                 )}
               </div>
@@ -453,14 +461,31 @@ export function Costs() {
               Fehler beim Laden der Daten
             </p>
             <p className="text-sm text-muted-foreground">
-              {error instanceof Error
-                ? error.message
-                : "Stelle sicher, dass ein OpenAI Admin Key in den Secrets gespeichert ist."}
+              {error instanceof Error ? error.message : String(error)}
             </p>
           </div>
         </div>
       )}
 
+      {/* Provider errors: the other providers are still shown */}
+      {usage?.providers
+        .filter((p) => p.error)
+        .map((p) => (
+          <div
+            key={p.provider}
+            className="flex items-center gap-3 rounded-lg border border-warning/50 bg-warning/10 p-4"
+          >
+            <AlertCircle className="h-5 w-5 text-warning" />
+            <div>
+              <p className="font-medium">
+                {PROVIDER_LABELS[p.provider]}: Offline-Daten aus dem Cache
+              </p>
+              {p.errorKind && <p className="text-sm">{API_ERROR_HINTS[p.errorKind]}</p>}
+              <p className="text-sm text-muted-foreground">{p.error}</p>
+            </div>
+          </div>
+        ))}
+
       {/* Loading State */}
       {isLoading && (
         <div className="flex items-center justify-center py-12">
@@ -483,6 +508,7 @@ export function Costs() {
               <CardContent>
                 <div className="text-2xl font-bold">
                   {formatCurrency(displayCostToday)}
+                  {estimatedToday && <EstimatedBadge version={usage?.priceVersion} />}
                 </div>
                 <div className="mt-1 flex items-center text-xs">
                   {!selectedProject && costTrend !== 0 && (
@@ -517,6 +543,7 @@ export function Costs() {
               <CardContent>
                 <div className="text-2xl font-bold">
                   {formatCurrency(displayCostWeek)}
+                  {estimatedWeek && <EstimatedBadge version={usage?.priceVersion} />}
                 </div>
                 <p className="mt-1 text-xs text-muted-foreground">
                   Letzte 7 Tage
@@ -535,6 +562,7 @@ export function Costs() {
               <CardContent>
                 <div className="text-2xl font-bold">
                   {formatCurrency(displayCostMonth)}
+                  {estimatedMonth && <EstimatedBadge version={usage?.priceVersion} />}
                 </div>
                 <p className="mt-1 text-xs text-muted-foreground">
                   Letzte 30 Tage
@@ -653,6 +681,9 @@ export function Costs() {
                             <p className={`font-semibold ${isSelected ? "text-primary" : "text-primary"}`}>
                               {formatCurrency(projectUsage.costMonth)}
                             </p>
+                            {projectUsage.estimated && (
+                              <p className="text-xs text-muted-foreground">geschätzt</p>
+                            )}
                           </div>
                         </div>
                       </div>
@@ -819,6 +850,10 @@ export function Costs() {
         </>
       )}
 
+      <UsageHistory />
+
+      <PriceTable />
+
       {/* Info */}
       <div className="rounded-lg border border-border bg-card/50 p-4 text-sm text-muted-foreground">
         <p>
@@ -11,14 +11,16 @@ import {
 import { Link } from "react-router-dom";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
+import { EstimatedBadge } from "@/components/costs/EstimatedBadge";
+import { RotationDue } from "@/components/secrets/RotationDue";
 import { formatCurrency } from "@/lib/utils";
 import { useSecrets } from "@/hooks/useSecrets";
-import { useOpenAIUsage } from "@/hooks/useOpenAI";
+import { useUsageSummary } from "@/hooks/useOpenAI";
 import { useAuditLog } from "@/hooks/useAuditLog";
 
 export function Dashboard() {
   const { data: secrets = [], isLoading: secretsLoading } = useSecrets();
-  const { data: usage, isLoading: usageLoading, error: usageError } = useOpenAIUsage();
+  const { data: usage, isLoading: usageLoading, error: usageError } = useUsageSummary();
   const { data: recentActivity = [], isLoading: activityLoading } = useAuditLog({
     limit: 5,
   });
@@ -66,6 +68,7 @@ export function Dashboard() {
               <>
                 <div className="text-2xl font-bold">
                   {formatCurrency(usage?.totalCostToday || 0)}
+                  {usage?.today.estimated && <EstimatedBadge version={usage.priceVersion} />}
                 </div>
                 {costTrend !== 0 && (
                   <div className="mt-1 flex items-center text-xs">
@@ -106,6 +109,7 @@ export function Dashboard() {
               <>
                 <div className="text-2xl font-bold">
                   {formatCurrency(usage?.totalCostMonth || 0)}
+                  {usage?.priceVersion && <EstimatedBadge version={usage.priceVersion} />}
                 </div>
                 <p className="mt-1 text-xs text-muted-foreground">
                   Letzte 30 Tage
@@ -270,6 +274,9 @@ export function Dashboard() {
         </Card>
       </div>
 
+      {/* Rotation reminders */}
+      <RotationDue />
+
       {/* Quick Actions */}
       <Card>
         <CardHeader>
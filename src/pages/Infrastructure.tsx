@@ -0,0 +1,24 @@
+import { CloudflarePanel } from "@/components/infrastructure/CloudflarePanel";
+import { NeonPanel } from "@/components/infrastructure/NeonPanel";
+import { RailwayPanel } from "@/components/infrastructure/RailwayPanel";
+import { SupabasePanel } from "@/components/infrastructure/SupabasePanel";
+import { VercelPanel } from "@/components/infrastructure/VercelPanel";
+
+export function Infrastructure() {
+  return (
+    <div className="space-y-8">
+      <div>
+        <h1 className="text-3xl font-bold tracking-tight">Infrastruktur</h1>
+        <p className="mt-1 text-muted-foreground">
+          Status und Verwaltung deiner Cloud-Services.
+        </p>
+      </div>
+
+      <RailwayPanel />
+      <VercelPanel />
+      <NeonPanel />
+      <SupabasePanel />
+      <CloudflarePanel />
+    </div>
+  );
+}
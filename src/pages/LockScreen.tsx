@@ -3,8 +3,11 @@ import { KeyRound, AlertCircle, Loader2 } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
 import { Card, CardContent } from "@/components/ui/card";
-import { verifyPassword, setMasterPassword, getSetting } from "@/lib/settings";
+import { RecoveryPhrase } from "@/components/recovery/RecoveryPhrase";
+import { setMasterPassword } from "@/lib/settings";
+import { getDatabaseStatus, unlockDatabase } from "@/lib/database";
 import { logAudit, AuditActions } from "@/lib/audit";
+import { generateRecoveryPhrase, getRecoveryStatus, recoverWithPhrase } from "@/lib/recovery";
 
 interface LockScreenProps {
   onUnlock: () => void;
@@ -16,22 +19,37 @@ export function LockScreen({ onUnlock }: LockScreenProps) {
   const [error, setError] = useState<string | null>(null);
   const [isLoading, setIsLoading] = useState(false);
   const [isFirstTime, setIsFirstTime] = useState<boolean | null>(null);
+  const [canRecover, setCanRecover] = useState(false);
+  const [isRecovering, setIsRecovering] = useState(false);
+  const [recoveryInput, setRecoveryInput] = useState("");
+  const [newPhrase, setNewPhrase] = useState<string[] | null>(null);
 
-  // Check if this is first time setup
+  // Check if this is first time setup (the encrypted database can't be read yet)
   useState(() => {
-    getSetting("masterPasswordHash").then((hash) => {
-      setIsFirstTime(!hash);
-    });
+    getDatabaseStatus()
+      .then((status) => setIsFirstTime(!status.passwordSet))
+      .catch(() => setIsFirstTime(true));
+    getRecoveryStatus()
+      .then((status) => setCanRecover(status.configured))
+      .catch(() => setCanRecover(false));
   });
 
+  function toggleRecovery() {
+    setIsRecovering(!isRecovering);
+    setError(null);
+    setPassword("");
+    setConfirmPassword("");
+    setRecoveryInput("");
+  }
+
   async function handleSubmit(e: React.FormEvent) {
     e.preventDefault();
     setError(null);
     setIsLoading(true);
 
     try {
-      if (isFirstTime) {
-        // First time setup - create password
+      if (isFirstTime || isRecovering) {
+        // First time setup or recovery - create password
         if (password.length < 4) {
           setError("Passwort muss mindestens 4 Zeichen haben");
           setIsLoading(false);
@@ -43,24 +61,32 @@ export function LockScreen({ onUnlock }: LockScreenProps) {
           return;
         }
 
+        if (isRecovering) {
+          // Opens the vault with the key sealed to the phrase and re-encrypts
+          // it with the new password
+          await recoverWithPhrase(recoveryInput, password);
+          onUnlock();
+          return;
+        }
+
+        // Creates the encrypted database with a key derived from the password
+        await unlockDatabase(password);
         await setMasterPassword(password);
         await logAudit(AuditActions.APP_UNLOCKED, "app", "setup", {
           firstTime: true,
         });
-        onUnlock();
+        // The phrase is shown before entering the app
+        setNewPhrase(await generateRecoveryPhrase());
       } else {
-        // Verify existing password
-        const isValid = await verifyPassword(password);
-        if (isValid) {
-          await logAudit(AuditActions.APP_UNLOCKED, "app", "login");
-          onUnlock();
-        } else {
-          setError("Falsches Passwort");
-        }
+        // Decrypting the database verifies the password (plaintext
+        // databases are checked against the old hash and encrypted once)
+        await unlockDatabase(password);
+        await logAudit(AuditActions.APP_UNLOCKED, "app", "login");
+        onUnlock();
       }
     } catch (err) {
       console.error("Auth error:", err);
-      setError("Ein Fehler ist aufgetreten");
+      setError(typeof err === "string" ? err : "Ein Fehler ist aufgetreten");
     }
 
     setIsLoading(false);
@@ -94,7 +120,15 @@ export function LockScreen({ onUnlock }: LockScreenProps) {
           <CardContent className="pt-6">
             {isFirstTime && (
               <div className="mb-4 rounded-lg bg-primary/10 p-3 text-sm text-primary">
-                👋 Willkommen! Erstelle ein Master-Passwort für deine App.
+                {newPhrase
+                  ? "Dein Vault ist eingerichtet. Das ist deine Wiederherstellungsphrase."
+                  : "👋 Willkommen! Erstelle ein Master-Passwort für deine App."}
+              </div>
+            )}
+
+            {isRecovering && (
+              <div className="mb-4 rounded-lg bg-primary/10 p-3 text-sm text-primary">
+                Gib deine 24 Wörter ein und wähle ein neues Master-Passwort.
               </div>
             )}
 
@@ -105,57 +139,97 @@ export function LockScreen({ onUnlock }: LockScreenProps) {
               </div>
             )}
 
-            <form onSubmit={handleSubmit} className="space-y-4">
-              <div className="space-y-2">
-                <label
-                  htmlFor="password"
-                  className="text-sm font-medium text-foreground"
-                >
-                  {isFirstTime ? "Neues Master-Passwort" : "Master-Passwort"}
-                </label>
-                <div className="relative">
-                  <KeyRound className="absolute left-3 top-1/2 h-4 w-4 -translate-y-1/2 text-muted-foreground" />
-                  <Input
-                    id="password"
-                    type="password"
-                    value={password}
-                    onChange={(e) => setPassword(e.target.value)}
-                    placeholder="••••••••••••"
-                    className="pl-10"
-                    autoFocus
-                    disabled={isLoading}
-                  />
-                </div>
-              </div>
+            {newPhrase ? (
+              <RecoveryPhrase words={newPhrase} onDone={onUnlock} />
+            ) : (
+              <form onSubmit={handleSubmit} className="space-y-4">
+                {isRecovering && (
+                  <div className="space-y-2">
+                    <label
+                      htmlFor="recoveryPhrase"
+                      className="text-sm font-medium text-foreground"
+                    >
+                      Wiederherstellungsphrase
+                    </label>
+                    <textarea
+                      id="recoveryPhrase"
+                      value={recoveryInput}
+                      onChange={(e) => setRecoveryInput(e.target.value)}
+                      rows={4}
+                      spellCheck={false}
+                      autoComplete="off"
+                      autoFocus
+                      disabled={isLoading}
+                      className="flex w-full rounded-md border border-input bg-background px-3 py-2 font-mono text-sm ring-offset-background placeholder:text-muted-foreground focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2"
+                    />
+                  </div>
+                )}
 
-              {isFirstTime && (
                 <div className="space-y-2">
                   <label
-                    htmlFor="confirmPassword"
+                    htmlFor="password"
                     className="text-sm font-medium text-foreground"
                   >
-                    Passwort bestätigen
+                    {isFirstTime || isRecovering ? "Neues Master-Passwort" : "Master-Passwort"}
                   </label>
                   <div className="relative">
                     <KeyRound className="absolute left-3 top-1/2 h-4 w-4 -translate-y-1/2 text-muted-foreground" />
                     <Input
-                      id="confirmPassword"
+                      id="password"
                       type="password"
-                      value={confirmPassword}
-                      onChange={(e) => setConfirmPassword(e.target.value)}
+                      value={password}
+                      onChange={(e) => setPassword(e.target.value)}
                       placeholder="••••••••••••"
                       className="pl-10"
+                      autoFocus={!isRecovering}
                       disabled={isLoading}
                     />
                   </div>
                 </div>
-              )}
 
-              <Button type="submit" className="w-full" disabled={isLoading}>
-                {isLoading && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
-                {isFirstTime ? "Passwort erstellen" : "Entsperren"}
-              </Button>
-            </form>
+                {(isFirstTime || isRecovering) && (
+                  <div className="space-y-2">
+                    <label
+                      htmlFor="confirmPassword"
+                      className="text-sm font-medium text-foreground"
+                    >
+                      Passwort bestätigen
+                    </label>
+                    <div className="relative">
+                      <KeyRound className="absolute left-3 top-1/2 h-4 w-4 -translate-y-1/2 text-muted-foreground" />
+                      <Input
+                        id="confirmPassword"
+                        type="password"
+                        value={confirmPassword}
+                        onChange={(e) => setConfirmPassword(e.target.value)}
+                        placeholder="••••••••••••"
+                        className="pl-10"
+                        disabled={isLoading}
+                      />
+                    </div>
+                  </div>
+                )}
+
+                <Button type="submit" className="w-full" disabled={isLoading}>
+                  {isLoading && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                  {isFirstTime
+                    ? "Passwort erstellen"
+                    : isRecovering
+                      ? "Wiederherstellen"
+                      : "Entsperren"}
+                </Button>
+
+                {!isFirstTime && canRecover && (
+                  <button
+                    type="button"
+                    onClick={toggleRecovery}
+                    className="w-full text-center text-sm text-muted-foreground hover:text-foreground"
+                  >
+                    {isRecovering ? "Zurück zur Anmeldung" : "Passwort vergessen?"}
+                  </button>
+                )}
+              </form>
+            )}
 
             {/* Info about biometric auth coming soon */}
             <div className="mt-4 rounded-lg bg-muted/50 p-3 text-center text-xs text-muted-foreground">
@@ -12,19 +12,24 @@ import {
   X,
   Check,
   Loader2,
+  ArrowDownUp,
+  History,
 } from "lucide-react";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
-import { maskSecret, formatRelativeTime } from "@/lib/utils";
+import { ExternalImport } from "@/components/secrets/ExternalImport";
+import { SecretHistory } from "@/components/secrets/SecretHistory";
+import { SecretRotationPolicy } from "@/components/secrets/RotationPolicy";
+import { SecretTransfer } from "@/components/secrets/SecretTransfer";
+import { formatRelativeTime } from "@/lib/utils";
 import {
   useSearchSecrets,
   useCreateSecret,
   useUpdateSecret,
   useDeleteSecret,
 } from "@/hooks/useSecrets";
-import { logAudit, AuditActions } from "@/lib/audit";
-import type { Secret } from "@/lib/secrets";
+import { copySecretToClipboard, revealSecret, type Secret } from "@/lib/secrets";
 
 const categoryLabels = {
   llm: "LLM",
@@ -53,6 +58,11 @@ const providerOptions = [
   { value: "github", label: "GitHub", category: "app" },
   { value: "stripe", label: "Stripe", category: "app" },
   { value: "resend", label: "Resend", category: "app" },
+  { value: "smtp", label: "SMTP (Alert-E-Mails)", category: "app" },
+  { value: "telegram", label: "Telegram Bot", category: "app" },
+  { value: "discord", label: "Discord Webhook", category: "app" },
+  { value: "slack", label: "Slack Webhook", category: "app" },
+  { value: "webhook", label: "Webhook Signing Key", category: "app" },
   { value: "other", label: "Sonstiger", category: "app" },
 ];
 
@@ -72,63 +82,52 @@ const emptyFormData: SecretFormData = {
 
 export function Secrets() {
   const [searchQuery, setSearchQuery] = useState("");
-  const [visibleSecrets, setVisibleSecrets] = useState<Set<string>>(new Set());
+  // Revealed plaintexts by secret id, dropped again after 30 seconds
+  const [visibleSecrets, setVisibleSecrets] = useState<Map<string, string>>(new Map());
   const [copiedId, setCopiedId] = useState<string | null>(null);
   const [showAddForm, setShowAddForm] = useState(false);
   const [editingId, setEditingId] = useState<string | null>(null);
   const [formData, setFormData] = useState<SecretFormData>(emptyFormData);
   const [deleteConfirmId, setDeleteConfirmId] = useState<string | null>(null);
+  const [showTransfer, setShowTransfer] = useState(false);
+  const [historyId, setHistoryId] = useState<string | null>(null);
 
   const { data: secrets = [], isLoading } = useSearchSecrets(searchQuery);
   const createMutation = useCreateSecret();
   const updateMutation = useUpdateSecret();
   const deleteMutation = useDeleteSecret();
 
-  const toggleVisibility = (id: string) => {
-    setVisibleSecrets((prev) => {
-      const newSet = new Set(prev);
-      if (newSet.has(id)) {
-        newSet.delete(id);
-      } else {
-        newSet.add(id);
-        // Auto-hide after 30 seconds
-        setTimeout(() => {
-          setVisibleSecrets((current) => {
-            const updated = new Set(current);
-            updated.delete(id);
-            return updated;
-          });
-        }, 30000);
-      }
-      return newSet;
+  const hideSecret = (id: string) => {
+    setVisibleSecrets((current) => {
+      const updated = new Map(current);
+      updated.delete(id);
+      return updated;
     });
   };
 
-  const copyToClipboard = async (secret: Secret) => {
+  // Every reveal is audited in the backend
+  const toggleVisibility = async (id: string) => {
+    if (visibleSecrets.has(id)) {
+      hideSecret(id);
+      return;
+    }
     try {
-      const { writeText } = await import(
-        "@tauri-apps/plugin-clipboard-manager"
-      );
-      await writeText(secret.value);
-      setCopiedId(secret.id);
-      await logAudit(AuditActions.SECRET_COPIED, "secret", secret.id, {
-        name: secret.name,
-      });
-      setTimeout(() => setCopiedId(null), 2000);
+      const value = await revealSecret(id);
+      setVisibleSecrets((current) => new Map(current).set(id, value));
+      setTimeout(() => hideSecret(id), 30000);
+    } catch (error) {
+      console.error("Failed to reveal secret:", error);
+    }
+  };
 
-      // Clear clipboard after 30 seconds
-      setTimeout(async () => {
-        try {
-          await writeText("");
-        } catch {
-          // Ignore
-        }
-      }, 30000);
-    } catch {
-      // Fallback for development
-      await navigator.clipboard.writeText(secret.value);
+  // The backend writes the value and clears it again after 30 seconds
+  const copyToClipboard = async (secret: Secret) => {
+    try {
+      await copySecretToClipboard(secret.id);
       setCopiedId(secret.id);
       setTimeout(() => setCopiedId(null), 2000);
+    } catch (error) {
+      console.error("Failed to copy secret:", error);
     }
   };
 
@@ -136,10 +135,15 @@ export function Secrets() {
     e.preventDefault();
 
     if (editingId) {
-      await updateMutation.mutateAsync({
-        id: editingId,
-        data: formData,
-      });
+      try {
+        await updateMutation.mutateAsync({
+          id: editingId,
+          data: formData,
+        });
+      } catch {
+        // A rejected new key keeps the form open, the error is shown below
+        return;
+      }
       setEditingId(null);
     } else {
       await createMutation.mutateAsync(formData);
@@ -154,7 +158,7 @@ export function Secrets() {
       name: secret.name,
       category: secret.category,
       provider: secret.provider,
-      value: secret.value,
+      value: "",
     });
     setEditingId(secret.id);
     setShowAddForm(false);
@@ -181,12 +185,26 @@ export function Secrets() {
             Sichere Verwaltung deiner API-Keys und Credentials.
           </p>
         </div>
-        <Button onClick={() => setShowAddForm(true)} disabled={showAddForm}>
-          <Plus className="mr-2 h-4 w-4" />
-          Neuer Key
-        </Button>
+        <div className="flex gap-2">
+          <Button variant="outline" onClick={() => setShowTransfer(!showTransfer)}>
+            <ArrowDownUp className="mr-2 h-4 w-4" />
+            Import/Export
+          </Button>
+          <Button onClick={() => setShowAddForm(true)} disabled={showAddForm}>
+            <Plus className="mr-2 h-4 w-4" />
+            Neuer Key
+          </Button>
+        </div>
       </div>
 
+      {/* Encrypted Import/Export */}
+      {showTransfer && (
+        <div className="space-y-6">
+          <SecretTransfer />
+          <ExternalImport />
+        </div>
+      )}
+
       {/* Add/Edit Form */}
       {(showAddForm || editingId) && (
         <Card>
@@ -268,15 +286,27 @@ export function Secrets() {
                 <label className="text-sm font-medium">API Key / Secret</label>
                 <Input
                   type="password"
-                  placeholder="sk-..."
+                  placeholder={editingId ? "Leer lassen, um den Wert zu behalten" : "sk-..."}
                   value={formData.value}
                   onChange={(e) =>
                     setFormData((prev) => ({ ...prev, value: e.target.value }))
                   }
-                  required
+                  required={!editingId}
                 />
+                {editingId && (
+                  <p className="text-xs text-muted-foreground">
+                    Ein neuer Wert wird vorher beim Provider geprüft; der alte
+                    bleibt als Version erhalten.
+                  </p>
+                )}
               </div>
 
+              {updateMutation.error && (
+                <p className="text-sm text-destructive">
+                  {String(updateMutation.error)}
+                </p>
+              )}
+
               <div className="flex gap-2">
                 <Button
                   type="submit"
@@ -327,106 +357,123 @@ export function Secrets() {
               {secrets.map((secret) => (
                 <div
                   key={secret.id}
-                  className="flex items-center justify-between rounded-lg border border-border bg-secondary/30 p-4"
+                  className="rounded-lg border border-border bg-secondary/30 p-4"
                 >
-                  <div className="flex items-center gap-4">
-                    <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-secondary">
-                      <KeyRound className="h-5 w-5 text-muted-foreground" />
-                    </div>
-                    <div>
-                      <div className="flex items-center gap-2">
-                        <span className="font-medium">{secret.name}</span>
-                        <span
-                          className={`rounded-full border px-2 py-0.5 text-xs ${categoryColors[secret.category]}`}
-                        >
-                          {categoryLabels[secret.category]}
-                        </span>
-                      </div>
-                      <div className="mt-1 flex items-center gap-4 text-sm text-muted-foreground">
-                        <code className="font-mono">
-                          {visibleSecrets.has(secret.id)
-                            ? secret.value
-                            : maskSecret(secret.value)}
-                        </code>
+                  <div className="flex items-center justify-between">
+                    <div className="flex items-center gap-4">
+                      <div className="flex h-10 w-10 items-center justify-center rounded-lg bg-secondary">
+                        <KeyRound className="h-5 w-5 text-muted-foreground" />
                       </div>
-                      {secret.lastUsedAt && (
-                        <div className="mt-1 flex items-center gap-1 text-xs text-muted-foreground">
-                          <Clock className="h-3 w-3" />
-                          Zuletzt verwendet:{" "}
-                          {formatRelativeTime(secret.lastUsedAt)}
+                      <div>
+                        <div className="flex items-center gap-2">
+                          <span className="font-medium">{secret.name}</span>
+                          <span
+                            className={`rounded-full border px-2 py-0.5 text-xs ${categoryColors[secret.category]}`}
+                          >
+                            {categoryLabels[secret.category]}
+                          </span>
+                        </div>
+                        <div className="mt-1 flex items-center gap-4 text-sm text-muted-foreground">
+                          <code className="font-mono">
+                            {visibleSecrets.get(secret.id) ?? secret.hint}
+                          </code>
                         </div>
+                        {secret.lastUsedAt && (
+                          <div className="mt-1 flex items-center gap-1 text-xs text-muted-foreground">
+                            <Clock className="h-3 w-3" />
+                            Zuletzt verwendet:{" "}
+                            {formatRelativeTime(secret.lastUsedAt)}
+                          </div>
+                        )}
+                      </div>
+                    </div>
+
+                    <div className="flex items-center gap-2">
+                      {deleteConfirmId === secret.id ? (
+                        <>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() => handleDelete(secret.id)}
+                            className="text-destructive hover:text-destructive"
+                            disabled={deleteMutation.isPending}
+                          >
+                            <Check className="h-4 w-4" />
+                          </Button>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() => setDeleteConfirmId(null)}
+                          >
+                            <X className="h-4 w-4" />
+                          </Button>
+                        </>
+                      ) : (
+                        <>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() => toggleVisibility(secret.id)}
+                            title={
+                              visibleSecrets.has(secret.id)
+                                ? "Verstecken"
+                                : "Anzeigen"
+                            }
+                          >
+                            {visibleSecrets.has(secret.id) ? (
+                              <EyeOff className="h-4 w-4" />
+                            ) : (
+                              <Eye className="h-4 w-4" />
+                            )}
+                          </Button>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() => copyToClipboard(secret)}
+                            title="Kopieren (30s)"
+                          >
+                            <Copy
+                              className={`h-4 w-4 ${copiedId === secret.id ? "text-success" : ""}`}
+                            />
+                          </Button>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() =>
+                              setHistoryId(historyId === secret.id ? null : secret.id)
+                            }
+                            title="Versionen"
+                          >
+                            <History className="h-4 w-4" />
+                          </Button>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            onClick={() => startEdit(secret)}
+                            title="Bearbeiten"
+                          >
+                            <Edit className="h-4 w-4" />
+                          </Button>
+                          <Button
+                            variant="ghost"
+                            size="icon"
+                            className="text-destructive hover:text-destructive"
+                            onClick={() => setDeleteConfirmId(secret.id)}
+                            title="Löschen"
+                          >
+                            <Trash2 className="h-4 w-4" />
+                          </Button>
+                        </>
                       )}
                     </div>
                   </div>
 
-                  <div className="flex items-center gap-2">
-                    {deleteConfirmId === secret.id ? (
-                      <>
-                        <Button
-                          variant="ghost"
-                          size="icon"
-                          onClick={() => handleDelete(secret.id)}
-                          className="text-destructive hover:text-destructive"
-                          disabled={deleteMutation.isPending}
-                        >
-                          <Check className="h-4 w-4" />
-                        </Button>
-                        <Button
-                          variant="ghost"
-                          size="icon"
-                          onClick={() => setDeleteConfirmId(null)}
-                        >
-                          <X className="h-4 w-4" />
-                        </Button>
-                      </>
-                    ) : (
-                      <>
-                        <Button
-                          variant="ghost"
-                          size="icon"
-                          onClick={() => toggleVisibility(secret.id)}
-                          title={
-                            visibleSecrets.has(secret.id)
-                              ? "Verstecken"
-                              : "Anzeigen"
-                          }
-                        >
-                          {visibleSecrets.has(secret.id) ? (
-                            <EyeOff className="h-4 w-4" />
-                          ) : (
-                            <Eye className="h-4 w-4" />
-                          )}
-                        </Button>
-                        <Button
-                          variant="ghost"
-                          size="icon"
-                          onClick={() => copyToClipboard(secret)}
-                          title="Kopieren (30s)"
-                        >
-                          <Copy
-                            className={`h-4 w-4 ${copiedId === secret.id ? "text-success" : ""}`}
-                          />
-                        </Button>
-                        <Button
-                          variant="ghost"
-                          size="icon"
-                          onClick={() => startEdit(secret)}
-                          title="Bearbeiten"
-                        >
-                          <Edit className="h-4 w-4" />
-                        </Button>
-                        <Button
-                          variant="ghost"
-                          size="icon"
-                          className="text-destructive hover:text-destructive"
-                          onClick={() => setDeleteConfirmId(secret.id)}
-                          title="Löschen"
-                        >
-                          <Trash2 className="h-4 w-4" />
-                        </Button>
-                      </>
-                    )}
-                  </div>
+                  {historyId === secret.id && (
+                    <div className="mt-4 space-y-4 border-t border-border pt-4">
+                      <SecretRotationPolicy secretId={secret.id} />
+                      <SecretHistory secretId={secret.id} />
+                    </div>
+                  )}
                 </div>
               ))}
 
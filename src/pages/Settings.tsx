@@ -12,6 +12,7 @@ import {
   HardDrive,
   ArrowRight,
   X,
+  LifeBuoy,
 } from "lucide-react";
 import {
   Card,
@@ -22,7 +23,11 @@ import {
 } from "@/components/ui/card";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
+import { RecoveryPhrase } from "@/components/recovery/RecoveryPhrase";
+import { CategoryRotationPolicies } from "@/components/secrets/RotationPolicy";
 import { useSettings, useUpdateSetting, useUpdateDataPath, useDatabaseInfo, useGetDatabaseInfoAt, useSwitchToExistingDatabase } from "@/hooks/useSettings";
+import { useRecoveryStatus, useGenerateRecoveryPhrase } from "@/hooks/useRecovery";
+import { formatDate } from "@/lib/utils";
 
 export function Settings() {
   const { data: settings, isLoading } = useSettings();
@@ -31,6 +36,8 @@ export function Settings() {
   const { data: dbInfo, refetch: refetchDbInfo } = useDatabaseInfo();
   const getTargetDbInfo = useGetDatabaseInfoAt();
   const switchToExisting = useSwitchToExistingDatabase();
+  const { data: recovery } = useRecoveryStatus();
+  const generatePhrase = useGenerateRecoveryPhrase();
 
   const [localDataPath, setLocalDataPath] = useState("");
   const [localAutoLock, setLocalAutoLock] = useState(5);
@@ -43,6 +50,14 @@ export function Settings() {
   const [targetDbInfo, setTargetDbInfo] = useState<{ exists: boolean; secretsCount: number } | null>(null);
   const [pendingPath, setPendingPath] = useState<string | null>(null);
 
+  // Recovery phrase dialog state
+  const [showRecoveryDialog, setShowRecoveryDialog] = useState(false);
+
+  function closeRecoveryDialog() {
+    setShowRecoveryDialog(false);
+    generatePhrase.reset();
+  }
+
   useEffect(() => {
     detectPlatform();
   }, []);
@@ -374,6 +389,59 @@ export function Settings() {
         </div>
       )}
 
+      {/* Recovery Phrase Dialog */}
+      {showRecoveryDialog && (
+        <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 p-4">
+          <div className="w-full max-w-md rounded-xl border bg-card p-6 shadow-2xl">
+            <div className="flex items-start justify-between">
+              <div className="flex items-center gap-3">
+                <div className="rounded-full bg-primary/10 p-2">
+                  <LifeBuoy className="h-5 w-5 text-primary" />
+                </div>
+                <h3 className="text-lg font-semibold">Wiederherstellungsphrase</h3>
+              </div>
+              {!generatePhrase.data && (
+                <Button variant="ghost" size="sm" onClick={closeRecoveryDialog}>
+                  <X className="h-4 w-4" />
+                </Button>
+              )}
+            </div>
+
+            <div className="mt-4 space-y-4">
+              {generatePhrase.data ? (
+                <RecoveryPhrase words={generatePhrase.data} onDone={closeRecoveryDialog} />
+              ) : (
+                <>
+                  {recovery?.configured && (
+                    <div className="flex items-start gap-2 rounded-lg bg-warning/10 p-3 text-sm text-warning">
+                      <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0" />
+                      <div>
+                        Die bisherige Phrase wird sofort ungültig. Vernichte alte Notizen
+                        erst, wenn du die neue sicher aufbewahrt hast.
+                      </div>
+                    </div>
+                  )}
+                  {generatePhrase.error && (
+                    <div className="flex items-start gap-2 rounded-lg bg-destructive/10 p-3 text-sm text-destructive">
+                      <AlertTriangle className="mt-0.5 h-4 w-4 shrink-0" />
+                      <div>{String(generatePhrase.error)}</div>
+                    </div>
+                  )}
+                  <Button
+                    onClick={() => generatePhrase.mutate()}
+                    disabled={generatePhrase.isPending}
+                    className="w-full"
+                  >
+                    {generatePhrase.isPending && <Loader2 className="mr-2 h-4 w-4 animate-spin" />}
+                    Neue Phrase erzeugen
+                  </Button>
+                </>
+              )}
+            </div>
+          </div>
+        </div>
+      )}
+
       {/* Security */}
       <Card>
         <CardHeader>
@@ -451,6 +519,20 @@ export function Settings() {
             </div>
             <Button variant="outline">Ändern</Button>
           </div>
+
+          <div className="flex items-center justify-between">
+            <div>
+              <p className="font-medium">Wiederherstellungsphrase</p>
+              <p className="text-sm text-muted-foreground">
+                {recovery?.configured && recovery.createdAt
+                  ? `Erzeugt am ${formatDate(new Date(recovery.createdAt * 1000))}`
+                  : "Nicht eingerichtet – ohne Phrase ist ein vergessenes Passwort endgültig"}
+              </p>
+            </div>
+            <Button variant="outline" onClick={() => setShowRecoveryDialog(true)}>
+              {recovery?.configured ? "Neu erzeugen" : "Einrichten"}
+            </Button>
+          </div>
         </CardContent>
       </Card>
 
@@ -496,6 +578,7 @@ export function Settings() {
             </div>
             <Button variant="outline">Konfigurieren</Button>
           </div>
+          <CategoryRotationPolicies />
         </CardContent>
       </Card>
 